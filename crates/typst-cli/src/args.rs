@@ -116,6 +116,13 @@ pub struct CompileCommand {
     /// apart from file names and line numbers.
     #[arg(long = "timings", value_name = "OUTPUT_JSON")]
     pub timings: Option<Option<PathBuf>>,
+
+    /// Prints how long evaluating each imported file took, slowest first
+    ///
+    /// In watch mode, files that did not need to be evaluated again are marked
+    /// as cached.
+    #[arg(long = "profile-imports")]
+    pub profile_imports: bool,
 }
 
 /// Initializes a new project from a template
@@ -191,6 +198,14 @@ pub struct SharedArgs {
     )]
     pub inputs: Vec<(String, String)>,
 
+    /// Promotes certain warnings to errors
+    #[clap(long)]
+    pub strict: bool,
+
+    /// Enables warnings for code that is valid, but likely a mistake
+    #[clap(long)]
+    pub pedantic: bool,
+
     /// Common font arguments
     #[clap(flatten)]
     pub font_args: FontArgs,
@@ -9,6 +9,7 @@ use ecow::{eco_format, eco_vec, EcoString, EcoVec};
 use parking_lot::RwLock;
 use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
 use typst::diag::{bail, FileError, Severity, SourceDiagnostic, StrResult, Warned};
+use typst::engine::ImportProfile;
 use typst::foundations::{Datetime, Smart};
 use typst::layout::{Frame, PageRanges};
 use typst::model::Document;
@@ -111,7 +112,11 @@ pub fn compile_once(
         return Ok(());
     }
 
-    let Warned { output, warnings } = typst::compile(world);
+    let (Warned { output, warnings }, imports) = if command.profile_imports {
+        typst::compile_with_import_profile(world)
+    } else {
+        (typst::compile(world), vec![])
+    };
 
     match output {
         // Export the PDF / PNG.
@@ -157,6 +162,11 @@ pub fn compile_once(
         }
     }
 
+    if command.profile_imports {
+        print_import_profile(&imports)
+            .map_err(|err| eco_format!("failed to print import profile ({err})"))?;
+    }
+
     Ok(())
 }
 
@@ -528,6 +538,52 @@ fn hint_invalid_main_file(
     eco_vec![diagnostic]
 }
 
+/// Print the profiled imports to the terminal, slowest first.
+///
+/// Imports performed by other imported files are followed by the file that
+/// performed them, so that a file that is imported from multiple places can
+/// be seen to be evaluated only once.
+fn print_import_profile(imports: &[ImportProfile]) -> io::Result<()> {
+    fn flatten<'a>(
+        imports: &'a [ImportProfile],
+        importer: Option<&'a ImportProfile>,
+        out: &mut Vec<(&'a ImportProfile, Option<&'a ImportProfile>)>,
+    ) {
+        for import in imports {
+            out.push((import, importer));
+            flatten(&import.children, Some(import), out);
+        }
+    }
+
+    fn name(import: &ImportProfile) -> String {
+        let path = import.id.vpath().as_rootless_path().display();
+        match import.id.package() {
+            Some(spec) => format!("{spec}/{path}"),
+            None => path.to_string(),
+        }
+    }
+
+    let mut flat = vec![];
+    flatten(imports, None, &mut flat);
+    flat.sort_by_key(|(import, _)| std::cmp::Reverse(import.duration));
+
+    let mut out = terminal::out();
+    writeln!(out, "imports:")?;
+    for (import, importer) in flat {
+        let mut label = name(import);
+        if let Some(importer) = importer {
+            label.push_str(&format!(" (from {})", name(importer)));
+        }
+        if import.cached {
+            writeln!(out, "  {:>10}  {label}", "cached")?;
+        } else {
+            writeln!(out, "  {:>10.2?}  {label}", import.duration)?;
+        }
+    }
+
+    Ok(())
+}
+
 /// Print diagnostic messages to the terminal.
 pub fn print_diagnostics(
     world: &SystemWorld,
@@ -1,5 +1,5 @@
 use std::collections::HashMap;
-use std::io::Read;
+use std::io::{Read, Write};
 use std::path::{Path, PathBuf};
 use std::sync::OnceLock;
 use std::{fmt, fs, io, mem};
@@ -10,6 +10,7 @@ use once_cell::sync::Lazy;
 use parking_lot::Mutex;
 use typst::diag::{FileError, FileResult};
 use typst::foundations::{Bytes, Datetime, Dict, IntoValue};
+use typst::loading::DirEntry;
 use typst::syntax::{FileId, Source, VirtualPath};
 use typst::text::{Font, FontBook};
 use typst::utils::LazyHash;
@@ -107,7 +108,11 @@ impl SystemWorld {
                 .map(|(k, v)| (k.as_str().into(), v.as_str().into_value()))
                 .collect();
 
-            Library::builder().with_inputs(inputs).build()
+            Library::builder()
+                .with_inputs(inputs)
+                .with_strict(command.strict)
+                .with_pedantic(command.pedantic)
+                .build()
         };
 
         let mut searcher = FontSearcher::new();
@@ -229,6 +234,21 @@ impl World for SystemWorld {
             with_offset.day().try_into().ok()?,
         )
     }
+
+    fn list(&self, id: FileId) -> Option<FileResult<Vec<DirEntry>>> {
+        Some(list(id, &self.root, &self.package_storage))
+    }
+
+    fn cache_read(&self, key: &str) -> Option<Bytes> {
+        fs::read(cache_path(key)?).ok().map(Bytes::from)
+    }
+
+    fn cache_write(&self, key: &str, data: Bytes) {
+        if let Some(path) = cache_path(key) {
+            // Failures are ignored since the cache is only an optimization.
+            let _ = write_atomically(&path, &data);
+        }
+    }
 }
 
 impl SystemWorld {
@@ -387,6 +407,28 @@ fn system_path(
     id.vpath().resolve(root).ok_or(FileError::AccessDenied)
 }
 
+/// Lists the entries of the directory at a `FileId`.
+///
+/// Symbolic links are not followed, so a link to a directory is listed as a
+/// file.
+fn list(
+    id: FileId,
+    project_root: &Path,
+    package_storage: &PackageStorage,
+) -> FileResult<Vec<DirEntry>> {
+    let path = system_path(project_root, id, package_storage)?;
+    let f = |e| FileError::from_io(e, &path);
+    let mut entries = vec![];
+    for entry in fs::read_dir(&path).map_err(f)? {
+        let entry = entry.map_err(f)?;
+        entries.push(DirEntry {
+            name: entry.file_name().to_string_lossy().into(),
+            is_dir: entry.file_type().map_err(f)?.is_dir(),
+        });
+    }
+    Ok(entries)
+}
+
 /// Reads a file from a `FileId`.
 ///
 /// If the ID represents stdin it will read from standard input,
@@ -403,6 +445,25 @@ fn read(
     }
 }
 
+/// The path at which data persisted under a key is stored.
+fn cache_path(key: &str) -> Option<PathBuf> {
+    dirs::cache_dir().map(|dir| dir.join("typst/compiler").join(key))
+}
+
+/// Writes a file such that concurrent readers never observe it half-written.
+///
+/// The data is first written to a uniquely named temporary file in the same
+/// directory, so that concurrent writers don't interfere, and then renamed into
+/// place.
+fn write_atomically(path: &Path, data: &[u8]) -> io::Result<()> {
+    let dir = path.parent().unwrap_or(Path::new("."));
+    fs::create_dir_all(dir)?;
+    let mut tmp = tempfile::NamedTempFile::new_in(dir)?;
+    tmp.write_all(data)?;
+    tmp.persist(path)?;
+    Ok(())
+}
+
 /// Read a file from disk.
 fn read_from_disk(path: &Path) -> FileResult<Vec<u8>> {
     let f = |e| FileError::from_io(e, path);
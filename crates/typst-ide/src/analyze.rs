@@ -113,3 +113,88 @@ pub fn analyze_labels(document: &Document) -> (Vec<(Label, Option<EcoString>)>,
 
     (output, split)
 }
+
+#[cfg(test)]
+mod tests {
+    use typst::engine::DefinitionKind;
+    use typst::foundations::StyleChain;
+    use typst::syntax::{FileId, LinkedNode, Side, Span, VirtualPath};
+    use typst::text::TextElem;
+    use typst::visualize::{Color, Paint};
+
+    use super::analyze_expr;
+    use crate::tests::TestWorld;
+
+    #[track_caller]
+    fn fill_at(text: &str, cursor: usize) -> Option<Paint> {
+        let world = TestWorld::new(text);
+        let root = LinkedNode::new(world.main.root());
+        let leaf = root.leaf_at(cursor, Side::Before).unwrap();
+        let (_, styles) = analyze_expr(&world, &leaf).into_iter().next().unwrap();
+        styles.map(|styles| TextElem::fill_in(StyleChain::new(&styles)))
+    }
+
+    #[test]
+    fn test_analyze_expr_styles() {
+        let red = Some(Paint::Solid(Color::RED));
+        assert_eq!(fill_at("#let x = [a]\n#x", 15), None);
+        assert_eq!(fill_at("#set text(red)\n#let x = [a]\n#x", 30), red);
+        assert_eq!(fill_at("#{ set text(red); let x = 1; x }", 30), red);
+    }
+
+    #[test]
+    fn test_analyze_shadowing() {
+        let text = "#let x = 1\n#let f(x) = x\n#let y = { let x = 2; x }\n#f(3) #x";
+        let world = TestWorld::new(text);
+        let analysis = typst::analyze(&world, world.main.id());
+        let offset = |span: Span| world.main.range(span).unwrap().start;
+
+        let definitions: Vec<_> = analysis
+            .definitions
+            .iter()
+            .map(|def| (def.name.as_str(), offset(def.span), def.kind))
+            .collect();
+        assert!(definitions.contains(&("x", 5, DefinitionKind::Variable)));
+        assert!(definitions.contains(&("x", 18, DefinitionKind::Param)));
+        assert!(definitions.contains(&("x", 40, DefinitionKind::Variable)));
+
+        let usages: Vec<_> = analysis
+            .usages
+            .iter()
+            .map(|usage| {
+                (usage.name.as_str(), offset(usage.span), usage.definition.map(offset))
+            })
+            .collect();
+        assert!(usages.contains(&("x", 23, Some(18))));
+        assert!(usages.contains(&("x", 47, Some(40))));
+        assert!(usages.contains(&("x", 58, Some(5))));
+        assert!(usages.contains(&("f", 52, Some(16))));
+    }
+
+    #[test]
+    fn test_analyze_imports() {
+        let text = "#import calc: pow\n#pow(2, 3)";
+        let world = TestWorld::new(text);
+        let analysis = typst::analyze(&world, world.main.id());
+        let offset = |span: Span| world.main.range(span).unwrap().start;
+
+        let definition = &analysis.definitions[0];
+        assert_eq!(definition.name, "pow");
+        assert_eq!(definition.kind, DefinitionKind::Import);
+        assert_eq!(offset(definition.span), 14);
+
+        let usage = analysis.usages.iter().find(|usage| usage.name == "pow").unwrap();
+        assert_eq!(usage.definition, Some(definition.span));
+        let calc = analysis.usages.iter().find(|usage| usage.name == "calc").unwrap();
+        assert_eq!(calc.definition, None);
+    }
+
+    #[test]
+    fn test_analyze_disabled() {
+        let world = TestWorld::new("#let x = 1\n#x");
+        let other = FileId::new(None, VirtualPath::new("other.typ"));
+        let analysis = typst::analyze(&world, other);
+        assert!(analysis.definitions.is_empty());
+        assert!(analysis.usages.is_empty());
+    }
+}
@@ -665,7 +665,7 @@ fn complete_params(ctx: &mut CompletionContext) -> bool {
         let set = matches!(expr, ast::Expr::Set(_));
         if let Some(callee) = match expr {
             ast::Expr::FuncCall(call) => Some(call.callee()),
-            ast::Expr::Set(set) => Some(set.target()),
+            ast::Expr::Set(set) => set.targets().next(),
             _ => None,
         };
         then {
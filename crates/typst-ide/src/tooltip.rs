@@ -181,7 +181,7 @@ fn named_param_tooltip(world: &dyn World, leaf: &LinkedNode) -> Option<Tooltip>
         if let Some(expr) = grand_grand.cast::<ast::Expr>();
         if let Some(ast::Expr::Ident(callee)) = match expr {
             ast::Expr::FuncCall(call) => Some(call.callee()),
-            ast::Expr::Set(set) => Some(set.target()),
+            ast::Expr::Set(set) => set.targets().next(),
             _ => None,
         };
 
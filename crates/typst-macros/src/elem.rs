@@ -942,6 +942,21 @@ fn create_fields_impl(element: &Elem) -> TokenStream {
             }
         });
 
+    // Sets fields from values. Synthesized fields are computed by the
+    // element itself and can't be set.
+    let set_field_arms =
+        visible_non_ghost().filter(|field| !field.synthesized).map(|field| {
+            let Field { enum_ident, ident, .. } = field;
+
+            let expr = if field.required {
+                quote! { self.#ident = value.cast()? }
+            } else {
+                quote! { self.#ident = Some(value.cast()?) }
+            };
+
+            quote! { Fields::#enum_ident => #expr }
+        });
+
     // Creation of the `fields` dictionary for inherent fields.
     let field_inserts = visible_non_ghost().map(|field| {
         let Field { ident, name, .. } = field;
@@ -1015,6 +1030,23 @@ fn create_fields_impl(element: &Elem) -> TokenStream {
                #(#materializes)*
             }
 
+            fn set_field(
+                &mut self,
+                id: u8,
+                value: #foundations::Value,
+            ) -> ::typst::diag::HintedStrResult<()> {
+                let Ok(id) = Fields::try_from(id) else {
+                    ::typst::diag::bail!("unknown field");
+                };
+
+                match id {
+                    #(#set_field_arms,)*
+                    _ => ::typst::diag::bail!("unknown field"),
+                }
+
+                Ok(())
+            }
+
             fn fields(&self) -> #foundations::Dict {
                 let mut fields = #foundations::Dict::new();
                 #(#field_inserts)*
@@ -28,6 +28,8 @@ struct Func {
     constructor: bool,
     /// A list of alternate search terms for this element.
     keywords: Vec<String>,
+    /// The fixed set of keys of the dictionary this function returns.
+    keys: Vec<String>,
     /// The parent type of this function.
     ///
     /// Used for functions in a scope.
@@ -110,6 +112,8 @@ pub struct Meta {
     pub constructor: bool,
     /// A list of alternate search terms for this element.
     pub keywords: Vec<String>,
+    /// The fixed set of keys of the dictionary this function returns.
+    pub keys: Vec<String>,
     /// The parent type of this function.
     ///
     /// Used for functions in a scope.
@@ -125,6 +129,7 @@ impl Parse for Meta {
             title: parse_string::<kw::title>(input)?,
             constructor: parse_flag::<kw::constructor>(input)?,
             keywords: parse_string_array::<kw::keywords>(input)?,
+            keys: parse_string_array::<kw::keys>(input)?,
             parent: parse_key_value::<kw::parent, _>(input)?,
         })
     }
@@ -159,6 +164,7 @@ fn parse(stream: TokenStream, item: &syn::ItemFn) -> Result<Func> {
         scope: meta.scope,
         constructor: meta.constructor,
         keywords: meta.keywords,
+        keys: meta.keys,
         parent: meta.parent,
         contextual: meta.contextual,
         docs,
@@ -289,6 +295,7 @@ fn create_func_data(func: &Func) -> TokenStream {
         title,
         docs,
         keywords,
+        keys,
         returns,
         scope,
         parent,
@@ -319,6 +326,7 @@ fn create_func_data(func: &Func) -> TokenStream {
             title: #title,
             docs: #docs,
             keywords: &[#(#keywords),*],
+            keys: &[#(#keys),*],
             contextual: #contextual,
             scope: #foundations::Lazy::new(|| #scope),
             params: #foundations::Lazy::new(|| ::std::vec![#(#params),*]),
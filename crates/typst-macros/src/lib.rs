@@ -47,6 +47,9 @@ use syn::DeriveInput;
 /// - `title`: The functions's title case name (e.g. `Minimum`). Defaults to the
 ///   normal name in title case.
 /// - `keywords = [..]`: A list of alternate search terms for this function.
+/// - `keys = [..]`: The keys of the dictionary the function returns, for
+///   functions that always return a dictionary with the same keys. Used to
+///   hint at the available keys when destructuring the result fails.
 /// - `constructor`: Indicates that the function is a constructor.
 ///
 /// # Arguments
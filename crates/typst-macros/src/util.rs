@@ -260,5 +260,6 @@ pub mod kw {
     syn::custom_keyword!(cast);
     syn::custom_keyword!(constructor);
     syn::custom_keyword!(keywords);
+    syn::custom_keyword!(keys);
     syn::custom_keyword!(parent);
 }
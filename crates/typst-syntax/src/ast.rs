@@ -1594,6 +1594,19 @@ impl<'a> Args<'a> {
             .find(|n| !n.kind().is_trivia())
             .is_some_and(|n| n.kind() == SyntaxKind::Comma)
     }
+
+    /// The content blocks after the parentheses: `[b]` and `[c]` in
+    /// `f(a)[b][c]`. If there are no parentheses, all arguments are trailing
+    /// content blocks.
+    ///
+    /// They are also part of [`items`](Self::items) as positional arguments.
+    pub fn trailing(self) -> impl Iterator<Item = ContentBlock<'a>> {
+        let parenthesized = self.0.children().any(|n| n.kind() == SyntaxKind::RightParen);
+        self.0
+            .children()
+            .skip_while(move |n| parenthesized && n.kind() != SyntaxKind::RightParen)
+            .filter_map(SyntaxNode::cast)
+    }
 }
 
 /// An argument to a function call.
@@ -1870,11 +1883,42 @@ node! {
 }
 
 impl<'a> SetRule<'a> {
-    /// The function to set style properties for.
+    /// The function to set style properties for, or a parenthesized list of
+    /// functions: `(heading, figure)`.
     pub fn target(self) -> Expr<'a> {
         self.0.cast_first_match().unwrap_or_default()
     }
 
+    /// The functions to set style properties for.
+    pub fn targets(self) -> impl Iterator<Item = Expr<'a>> {
+        self.rules().map(|(target, _)| target)
+    }
+
+    /// The functions to set style properties for, each with its own style
+    /// properties in a group of set rules: `(heading(numbering: "1."), list())`.
+    pub fn rules(self) -> impl Iterator<Item = (Expr<'a>, Option<Args<'a>>)> {
+        let target = self.target();
+        let list = match target {
+            Expr::Array(array) => Some(array.to_untyped().children()),
+            _ => Option::None,
+        };
+        let single = list.is_none().then_some(target);
+        list.into_iter()
+            .flatten()
+            .filter_map(SyntaxNode::cast)
+            .chain(single)
+            .map(|target| match target {
+                Expr::FuncCall(call) => (call.callee(), Some(call.args())),
+                target => (target, Option::None),
+            })
+    }
+
+    /// Whether the targets have their own style properties instead of sharing
+    /// the ones in [`args`](Self::args).
+    pub fn grouped(self) -> bool {
+        self.rules().any(|(_, args)| args.is_some())
+    }
+
     /// The style properties to set.
     pub fn args(self) -> Args<'a> {
         self.0.cast_last_match().unwrap_or_default()
@@ -1949,14 +1993,23 @@ impl<'a> Conditional<'a> {
 }
 
 node! {
-    /// A while loop: `while x { y }`.
+    /// A while loop: `while x { y }`, `while <outer> x { y }`.
     WhileLoop
 }
 
 impl<'a> WhileLoop<'a> {
+    /// The label that `break` and `continue` can target the loop with.
+    pub fn label(self) -> Option<Label<'a>> {
+        self.0.cast_first_match()
+    }
+
     /// The condition which selects whether to evaluate the body.
     pub fn condition(self) -> Expr<'a> {
-        self.0.cast_first_match().unwrap_or_default()
+        self.0
+            .children()
+            .filter_map(SyntaxNode::cast)
+            .nth(usize::from(self.label().is_some()))
+            .unwrap_or_default()
     }
 
     /// The expression to evaluate while the condition is true.
@@ -1966,14 +2019,23 @@ impl<'a> WhileLoop<'a> {
 }
 
 node! {
-    /// A for loop: `for x in y { z }`.
+    /// A for loop: `for x in y { z }`, `for <outer> x in y { z }`.
     ForLoop
 }
 
 impl<'a> ForLoop<'a> {
+    /// The label that `break` and `continue` can target the loop with.
+    pub fn label(self) -> Option<Label<'a>> {
+        self.0.cast_first_match()
+    }
+
     /// The pattern to assign to.
     pub fn pattern(self) -> Pattern<'a> {
-        self.0.cast_first_match().unwrap_or_default()
+        self.0
+            .children()
+            .filter(|node| node.kind() != SyntaxKind::Label)
+            .find_map(SyntaxNode::cast)
+            .unwrap_or_default()
     }
 
     /// The expression to iterate over.
@@ -2141,15 +2203,31 @@ impl<'a> ModuleInclude<'a> {
 }
 
 node! {
-    /// A break from a loop: `break`.
+    /// A break from a loop: `break`, `break <outer>`.
     LoopBreak
 }
 
+impl<'a> LoopBreak<'a> {
+    /// The label of the loop to break out of. Without one, the innermost loop
+    /// is targeted.
+    pub fn label(self) -> Option<Label<'a>> {
+        self.0.cast_first_match()
+    }
+}
+
 node! {
-    /// A continue in a loop: `continue`.
+    /// A continue in a loop: `continue`, `continue <outer>`.
     LoopContinue
 }
 
+impl<'a> LoopContinue<'a> {
+    /// The label of the loop to continue. Without one, the innermost loop is
+    /// targeted.
+    pub fn label(self) -> Option<Label<'a>> {
+        self.0.cast_first_match()
+    }
+}
+
 node! {
     /// A return from a function: `return`, `return x + 1`.
     FuncReturn
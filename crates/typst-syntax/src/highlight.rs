@@ -330,6 +330,13 @@ fn highlight_ident(node: &LinkedNode) -> Option<Tag> {
         return Some(Tag::Function);
     }
 
+    // Are we one of multiple set rule targets?
+    if ancestor.parent_kind() == Some(SyntaxKind::Array)
+        && ancestor.parent()?.parent_kind() == Some(SyntaxKind::SetRule)
+    {
+        return Some(Tag::Function);
+    }
+
     // Are we (or an ancestor field access) directly after a hash.
     if ancestor.prev_leaf().map(|leaf| leaf.kind()) == Some(SyntaxKind::Hash) {
         return Some(Tag::Interpolated);
@@ -460,5 +467,22 @@ mod tests {
                 (10..11, Operator),
             ],
         );
+
+        test(
+            "#set (a, b)(x: 1)",
+            &[
+                (0..1, Keyword),
+                (1..4, Keyword),
+                (5..6, Punctuation),
+                (6..7, Function),
+                (7..8, Punctuation),
+                (9..10, Function),
+                (10..11, Punctuation),
+                (11..12, Punctuation),
+                (13..14, Punctuation),
+                (15..16, Number),
+                (16..17, Punctuation),
+            ],
+        );
     }
 }
@@ -683,6 +683,19 @@ fn embedded_code_expr(p: &mut Parser) {
 
 /// Parses a code expression with at least the given precedence.
 fn code_expr_prec(p: &mut Parser, atomic: bool, min_prec: usize) {
+    if p.depth >= Parser::MAX_DEPTH {
+        too_deep(p);
+        return;
+    }
+
+    p.depth += 1;
+    code_expr_prec_nested(p, atomic, min_prec);
+    p.depth -= 1;
+}
+
+/// Parses a code expression with at least the given precedence, after the
+/// nesting depth was checked.
+fn code_expr_prec_nested(p: &mut Parser, atomic: bool, min_prec: usize) {
     let m = p.marker();
     if !atomic && p.at_set(set::UNARY_OP) {
         let op = ast::UnOp::from_kind(p.current()).unwrap();
@@ -749,6 +762,46 @@ fn code_expr_prec(p: &mut Parser, atomic: bool, min_prec: usize) {
     }
 }
 
+/// Consumes the rest of the enclosing group as a single error without
+/// recursing into it. This keeps both the parser and the resulting syntax tree
+/// from overflowing the stack for pathologically nested input.
+fn too_deep(p: &mut Parser) {
+    let m = p.marker();
+    let start = p.current_start();
+    p.enter_newline_mode(NewlineMode::Continue);
+
+    let mut open = 0usize;
+    loop {
+        match p.current() {
+            SyntaxKind::LeftBrace | SyntaxKind::LeftBracket | SyntaxKind::LeftParen => {
+                open += 1
+            }
+            SyntaxKind::RightBrace
+            | SyntaxKind::RightBracket
+            | SyntaxKind::RightParen
+                if open > 0 =>
+            {
+                open -= 1
+            }
+            SyntaxKind::RightBrace
+            | SyntaxKind::RightBracket
+            | SyntaxKind::RightParen
+            | SyntaxKind::End => break,
+            _ => {}
+        }
+        p.eat();
+    }
+
+    p.exit_newline_mode();
+    p.balanced = false;
+
+    let end = p.before_trivia().0.max(m.0);
+    let text = &p.text[start..p.prev_end().max(start)];
+    p.nodes.drain(m.0..end);
+    let error = SyntaxError::new("maximum nesting depth exceeded");
+    p.nodes.insert(m.0, SyntaxNode::error(error, text));
+}
+
 /// Parses an primary in a code expression. These are the atoms that unary and
 /// binary operations, functions calls, and field accesses start with / are
 /// composed of.
@@ -893,20 +946,76 @@ fn set_rule(p: &mut Parser) {
     let m = p.marker();
     p.assert(SyntaxKind::Set);
 
-    let m2 = p.marker();
-    p.expect(SyntaxKind::Ident);
-    while p.eat_if(SyntaxKind::Dot) {
-        p.expect(SyntaxKind::Ident);
-        p.wrap(m2, SyntaxKind::FieldAccess);
+    // In a group of set rules, each target has its own arguments.
+    let grouped = if p.at(SyntaxKind::LeftParen) {
+        set_targets(p)
+    } else {
+        set_target(p);
+        false
+    };
+
+    if !grouped {
+        args(p);
     }
 
-    args(p);
     if p.eat_if(SyntaxKind::If) {
         code_expr(p);
     }
     p.wrap(m, SyntaxKind::SetRule);
 }
 
+/// Parses the target of a set rule: `heading` or `math.frac`.
+fn set_target(p: &mut Parser) {
+    let m = p.marker();
+    p.expect(SyntaxKind::Ident);
+    while p.eat_if(SyntaxKind::Dot) {
+        p.expect(SyntaxKind::Ident);
+        p.wrap(m, SyntaxKind::FieldAccess);
+    }
+}
+
+/// Parses multiple targets of a set rule: `(heading, figure)`, or a group of
+/// set rules with their own arguments: `(heading(numbering: "1."), list())`.
+///
+/// Returns whether any of the targets has its own arguments.
+fn set_targets(p: &mut Parser) -> bool {
+    let m = p.marker();
+    p.enter_newline_mode(NewlineMode::Continue);
+    p.assert(SyntaxKind::LeftParen);
+
+    let mut empty = true;
+    let mut grouped = false;
+    while !p.current().is_terminator() {
+        if !p.at(SyntaxKind::Ident) {
+            p.unexpected();
+            continue;
+        }
+
+        let m2 = p.marker();
+        set_target(p);
+        if p.directly_at(SyntaxKind::LeftParen) || p.directly_at(SyntaxKind::LeftBracket)
+        {
+            args(p);
+            p.wrap(m2, SyntaxKind::FuncCall);
+            grouped = true;
+        }
+        empty = false;
+
+        if !p.current().is_terminator() {
+            p.expect(SyntaxKind::Comma);
+        }
+    }
+
+    if empty {
+        p.expected("identifier");
+    }
+
+    p.expect_closing_delimiter(m, SyntaxKind::RightParen);
+    p.exit_newline_mode();
+    p.wrap(m, SyntaxKind::Array);
+    grouped
+}
+
 /// Parses a show rule: `show heading: it => emph(it.body)`.
 fn show_rule(p: &mut Parser) {
     let m = p.marker();
@@ -950,19 +1059,21 @@ fn conditional(p: &mut Parser) {
     p.wrap(m, SyntaxKind::Conditional);
 }
 
-/// Parses a while loop: `while x { y }`.
+/// Parses a while loop: `while x { y }`, `while <outer> x { y }`.
 fn while_loop(p: &mut Parser) {
     let m = p.marker();
     p.assert(SyntaxKind::While);
+    p.eat_if(SyntaxKind::Label);
     code_expr(p);
     block(p);
     p.wrap(m, SyntaxKind::WhileLoop);
 }
 
-/// Parses a for loop: `for x in y { z }`.
+/// Parses a for loop: `for x in y { z }`, `for <outer> x in y { z }`.
 fn for_loop(p: &mut Parser) {
     let m = p.marker();
     p.assert(SyntaxKind::For);
+    p.eat_if(SyntaxKind::Label);
 
     let mut seen = HashSet::new();
     pattern(p, false, &mut seen, None);
@@ -1037,17 +1148,19 @@ fn module_include(p: &mut Parser) {
     p.wrap(m, SyntaxKind::ModuleInclude);
 }
 
-/// Parses a break from a loop: `break`.
+/// Parses a break from a loop: `break`, `break <outer>`.
 fn break_stmt(p: &mut Parser) {
     let m = p.marker();
     p.assert(SyntaxKind::Break);
+    p.eat_if(SyntaxKind::Label);
     p.wrap(m, SyntaxKind::LoopBreak);
 }
 
-/// Parses a continue in a loop: `continue`.
+/// Parses a continue in a loop: `continue`, `continue <outer>`.
 fn continue_stmt(p: &mut Parser) {
     let m = p.marker();
     p.assert(SyntaxKind::Continue);
+    p.eat_if(SyntaxKind::Label);
     p.wrap(m, SyntaxKind::LoopContinue);
 }
 
@@ -1504,6 +1617,7 @@ struct Parser<'s> {
     current_start: usize,
     current: SyntaxKind,
     balanced: bool,
+    depth: usize,
     nodes: Vec<SyntaxNode>,
     modes: Vec<LexMode>,
     newline_modes: Vec<NewlineMode>,
@@ -1535,6 +1649,11 @@ struct Checkpoint<'s> {
 }
 
 impl<'s> Parser<'s> {
+    /// The maximum nesting depth of code expressions. Anything nested more
+    /// deeply is turned into an error. This also bounds how deeply evaluation
+    /// recurses, so the evaluator doesn't need a limit of its own.
+    const MAX_DEPTH: usize = 256;
+
     fn new(text: &'s str, offset: usize, mode: LexMode) -> Self {
         let mut lexer = Lexer::new(text, mode);
         lexer.jump(offset);
@@ -1546,6 +1665,7 @@ impl<'s> Parser<'s> {
             current_start: offset,
             current,
             balanced: true,
+            depth: 0,
             nodes: vec![],
             modes: vec![],
             newline_modes: vec![],
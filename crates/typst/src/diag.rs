@@ -164,6 +164,10 @@ pub struct SourceDiagnostic {
     /// Additional hints to the user, indicating how this problem could be avoided
     /// or worked around.
     pub hints: EcoVec<EcoString>,
+    /// Whether the error must stop compilation even when it occurs within a
+    /// call to `catch`. This is the case for panics, exceeded resource limits
+    /// and misplaced control flow.
+    pub(crate) uncatchable: bool,
 }
 
 /// The severity of a [`SourceDiagnostic`].
@@ -184,6 +188,7 @@ impl SourceDiagnostic {
             trace: eco_vec![],
             message: message.into(),
             hints: eco_vec![],
+            uncatchable: false,
         }
     }
 
@@ -195,6 +200,7 @@ impl SourceDiagnostic {
             trace: eco_vec![],
             message: message.into(),
             hints: eco_vec![],
+            uncatchable: false,
         }
     }
 
@@ -214,6 +220,12 @@ impl SourceDiagnostic {
         self.hints.extend(hints);
         self
     }
+
+    /// Marks the diagnostic as [uncatchable](Self::uncatchable).
+    pub fn uncatchable(mut self) -> Self {
+        self.uncatchable = true;
+        self
+    }
 }
 
 impl From<SyntaxError> for SourceDiagnostic {
@@ -224,6 +236,7 @@ impl From<SyntaxError> for SourceDiagnostic {
             message: error.message,
             trace: eco_vec![],
             hints: error.hints,
+            uncatchable: false,
         }
     }
 }
@@ -237,6 +250,8 @@ pub enum Tracepoint {
     Show(EcoString),
     /// A module import.
     Import,
+    /// The inclusion of an item in an array of included files.
+    Include(usize, EcoString),
 }
 
 impl Display for Tracepoint {
@@ -254,6 +269,9 @@ impl Display for Tracepoint {
             Tracepoint::Import => {
                 write!(f, "error occurred while importing this module")
             }
+            Tracepoint::Include(index, item) => {
+                write!(f, "error occurred while including item {index} ({item})")
+            }
         }
     }
 }
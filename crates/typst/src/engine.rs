@@ -1,16 +1,19 @@
 //! Definition of the central compilation context.
 
-use std::collections::HashSet;
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
 
 use comemo::{Track, Tracked, TrackedMut, Validate};
-use ecow::EcoVec;
+use ecow::{eco_vec, EcoString, EcoVec};
 use rayon::iter::{IndexedParallelIterator, IntoParallelIterator, ParallelIterator};
+use serde::Serialize;
 
-use crate::diag::{SourceDiagnostic, SourceResult};
-use crate::foundations::{Styles, Value};
+use crate::diag::{FileResult, Severity, SourceDiagnostic, SourceResult};
+use crate::foundations::{Array, Bytes, Dict, Repr, Styles, Value};
 use crate::introspection::Introspector;
-use crate::syntax::{FileId, Span};
+use crate::syntax::package::PackageSpec;
+use crate::syntax::{FileId, Source, Span};
 use crate::World;
 
 /// Holds all data needed during compilation.
@@ -46,6 +49,39 @@ impl Engine<'_> {
         }
     }
 
+    /// Reads a file from the world and records it as a dependency.
+    pub fn file(&mut self, id: FileId) -> FileResult<Bytes> {
+        self.sink.depend(id);
+        self.world.file(id)
+    }
+
+    /// Reads a source file from the world and records it as a dependency.
+    pub fn source(&mut self, id: FileId) -> FileResult<Source> {
+        self.sink.depend(id);
+        self.world.source(id)
+    }
+
+    /// Emits a warning, or fails with it as an error in strict mode.
+    pub fn lint(&mut self, mut warning: SourceDiagnostic) -> SourceResult<()> {
+        if self.world.library().strict {
+            warning.severity = Severity::Error;
+            return Err(eco_vec![warning]);
+        }
+        self.sink.warn(warning);
+        Ok(())
+    }
+
+    /// Emits a warning, or a delayed error in strict mode. Unlike
+    /// [`lint`](Self::lint), this never aborts the current evaluation.
+    pub fn lint_delayed(&mut self, mut warning: SourceDiagnostic) {
+        if self.world.library().strict {
+            warning.severity = Severity::Error;
+            self.sink.delay(eco_vec![warning]);
+        } else {
+            self.sink.warn(warning);
+        }
+    }
+
     /// Runs tasks on the engine in parallel.
     pub fn parallelize<P, I, T, U, F>(&mut self, iter: P, f: F) -> impl Iterator<Item = U>
     where
@@ -56,6 +92,12 @@ impl Engine<'_> {
         F: Fn(&mut Engine, T) -> U + Send + Sync,
     {
         let Engine { world, introspector, traced, ref route, .. } = *self;
+        let budget = self.sink.remaining();
+        // Subsinks only need to know whether imports are profiled, whether
+        // dependencies are tracked, and whether this is a dry run.
+        let profiling = self.sink.profiled().then_some(0);
+        let dependencies = self.sink.tracks_dependencies().then(Dependencies::default);
+        let dry_run = self.sink.dry_run();
 
         // We collect into a vector and then call `into_par_iter` instead of
         // using `par_bridge` because it does not retain the ordering.
@@ -65,7 +107,12 @@ impl Engine<'_> {
         let mut pairs: Vec<(U, Sink)> = Vec::with_capacity(work.len());
         work.into_par_iter()
             .map(|value| {
-                let mut sink = Sink::new();
+                let mut sink = Sink {
+                    profiling,
+                    dependencies: dependencies.clone(),
+                    dry_run,
+                    ..Sink::with_budget(budget)
+                };
                 let mut engine = Engine {
                     world,
                     introspector,
@@ -79,24 +126,94 @@ impl Engine<'_> {
 
         // Apply the subsinks to the outer sink.
         for (_, sink) in &mut pairs {
-            let sink = std::mem::take(sink);
-            self.sink.extend(sink.delayed, sink.warnings, sink.values);
+            self.absorb(std::mem::take(sink));
         }
 
         pairs.into_iter().map(|(output, _)| output)
     }
+
+    /// Runs a fallible task on a separate sink. If the task fails, the delayed
+    /// errors it produced are discarded so that they cannot resurface at the
+    /// end of compilation. Its warnings and spent budget are kept either way.
+    pub fn attempt<F, T>(&mut self, f: F) -> SourceResult<T>
+    where
+        F: FnOnce(&mut Engine) -> SourceResult<T>,
+    {
+        let Engine { world, introspector, traced, ref route, .. } = *self;
+        let budget = self.sink.remaining();
+        let profiling = self.sink.profiled().then_some(0);
+        let dependencies = self.sink.tracks_dependencies().then(Dependencies::default);
+        let dry_run = self.sink.dry_run();
+        let mut sink = Sink {
+            profiling,
+            dependencies,
+            dry_run,
+            ..Sink::with_budget(budget)
+        };
+        let result = f(&mut Engine {
+            world,
+            introspector,
+            traced,
+            sink: sink.track_mut(),
+            route: route.clone(),
+        });
+
+        if result.is_err() {
+            sink.delayed.clear();
+        }
+
+        self.absorb(sink);
+        result
+    }
+
+    /// Applies a subsink to the outer sink.
+    fn absorb(&mut self, sink: Sink) {
+        let analysis = Analysis { definitions: sink.definitions, usages: sink.usages };
+        self.sink.extend(
+            sink.delayed,
+            sink.warnings,
+            sink.values,
+            sink.calls,
+            analysis,
+            sink.packages,
+            sink.dependencies,
+            sink.imports,
+            sink.steps,
+        );
+    }
 }
 
-/// May hold a span that is currently under inspection.
+/// May hold a span that is currently under inspection and a file whose
+/// variable definitions and uses are recorded. Can also request that produced
+/// content is anchored with stable identities.
 #[derive(Default)]
-pub struct Traced(Option<Span>);
+pub struct Traced {
+    /// The traced span.
+    span: Option<Span>,
+    /// The analyzed file.
+    analyzed: Option<FileId>,
+    /// Whether content is anchored.
+    anchored: bool,
+}
 
 impl Traced {
     /// Wraps a to-be-traced `Span`.
     ///
     /// Call `Traced::default()` to trace nothing.
     pub fn new(traced: Span) -> Self {
-        Self(Some(traced))
+        Self { span: Some(traced), ..Self::default() }
+    }
+
+    /// Records the definitions and uses of variables in the given file.
+    pub fn analyzing(id: FileId) -> Self {
+        Self { analyzed: Some(id), ..Self::default() }
+    }
+
+    /// Attaches a [`StableId`](crate::foundations::StableId) to the content
+    /// produced by markup, so that elements can be matched up across
+    /// re-evaluations.
+    pub fn anchoring() -> Self {
+        Self { anchored: true, ..Self::default() }
     }
 }
 
@@ -108,19 +225,31 @@ impl Traced {
     /// We hide the span if it isn't in the given file so that only results for
     /// the file with the traced span are invalidated.
     pub fn get(&self, id: FileId) -> Option<Span> {
-        if self.0.and_then(Span::id) == Some(id) {
-            self.0
+        if self.span.and_then(Span::id) == Some(id) {
+            self.span
         } else {
             None
         }
     }
+
+    /// Whether definitions and uses of variables should be recorded for the
+    /// given source file.
+    pub fn analyzed(&self, id: FileId) -> bool {
+        self.analyzed == Some(id)
+    }
+
+    /// Whether content produced by markup should be anchored.
+    pub fn anchored(&self) -> bool {
+        self.anchored
+    }
 }
 
 /// A push-only sink for delayed errors, warnings, and traced values.
 ///
-/// All tracked methods of this type are of the form `(&mut self, ..) -> ()`, so
-/// in principle they do not need validation (though that optimization is not
-/// yet implemented in comemo).
+/// Apart from the queries of the evaluation [budget](Budget), all tracked
+/// methods of this type are of the form `(&mut self, ..) -> ()`, so in
+/// principle they do not need validation (though that optimization is not yet
+/// implemented in comemo).
 #[derive(Default, Clone)]
 pub struct Sink {
     /// Delayed errors: Those are errors that we can ignore until the last
@@ -135,6 +264,34 @@ pub struct Sink {
     warnings_set: HashSet<u128>,
     /// A sequence of traced values for a span.
     values: EcoVec<(Value, Option<Styles>)>,
+    /// The function calls at the traced span, with their arguments.
+    calls: EcoVec<TracedCall>,
+    /// Variable definitions in the analyzed file.
+    definitions: EcoVec<Definition>,
+    /// Variable uses in the analyzed file.
+    usages: EcoVec<Usage>,
+    /// Hashes of all recorded definitions and uses for deduplication.
+    analysis_set: HashSet<u128>,
+    /// Packages that were successfully imported, without duplicates.
+    packages: EcoVec<PackageSpec>,
+    /// The first import nonce of this compilation, if imports are profiled.
+    profiling: Option<u64>,
+    /// The files read so far, if dependencies are tracked.
+    dependencies: Option<Dependencies>,
+    /// Profiled imports, in the order in which they finished, along with
+    /// whether the imported file was evaluated rather than taken from the
+    /// cache.
+    imports: EcoVec<(ImportRecord, bool)>,
+    /// The nonce of the latest evaluation of each file, if imports are
+    /// profiled.
+    evaluations: HashMap<FileId, u64>,
+    /// Whether this is a dry run, in which costly work that only serves the
+    /// output is skipped.
+    dry_run: bool,
+    /// Limits for the amount of work that evaluation may perform.
+    budget: Budget,
+    /// The number of expressions evaluated so far.
+    steps: u64,
 }
 
 impl Sink {
@@ -146,6 +303,36 @@ impl Sink {
         Self::default()
     }
 
+    /// Create a new empty sink that makes evaluation fail once the given
+    /// budget is used up.
+    pub fn with_budget(budget: Budget) -> Self {
+        Self { budget, ..Self::default() }
+    }
+
+    /// Profile imports during compilation, so that they can be retrieved
+    /// with [`imports`](Self::imports) afterwards.
+    ///
+    /// Not available on WebAssembly, where the current time can't be queried.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn with_profiling(self) -> Self {
+        Self { profiling: Some(ImportRecord::nonce()), ..self }
+    }
+
+    /// Track the files that are read during compilation, so that they can be
+    /// retrieved with [`dependencies`](Self::dependencies) afterwards.
+    pub fn with_dependencies(self) -> Self {
+        Self {
+            dependencies: Some(Dependencies::default()),
+            ..self
+        }
+    }
+
+    /// Perform a dry run, in which the output is discarded. Work that only
+    /// serves the output is skipped, while inputs are still validated.
+    pub fn with_dry_run(self) -> Self {
+        Self { dry_run: true, ..self }
+    }
+
     /// Get the stored delayed errors.
     pub fn delayed(&mut self) -> EcoVec<SourceDiagnostic> {
         std::mem::take(&mut self.delayed)
@@ -160,6 +347,71 @@ impl Sink {
     pub fn values(self) -> EcoVec<(Value, Option<Styles>)> {
         self.values
     }
+
+    /// Get the function calls at the traced span, with the arguments they
+    /// received.
+    pub fn calls(self) -> EcoVec<TracedCall> {
+        self.calls
+    }
+
+    /// Get the values for the traced span in a serializable form.
+    pub fn values_structured(self) -> Vec<StructuredValue> {
+        self.values
+            .iter()
+            .map(|(value, _)| StructuredValue::new(value))
+            .collect()
+    }
+
+    /// Get the number of expressions evaluated so far.
+    ///
+    /// This is only counted if evaluation is limited by a budget.
+    pub fn steps(&self) -> u64 {
+        self.steps
+    }
+
+    /// Get the definitions and uses of variables in the analyzed file.
+    pub fn analysis(self) -> Analysis {
+        Analysis { definitions: self.definitions, usages: self.usages }
+    }
+
+    /// Get the packages that were imported, directly or transitively, in the
+    /// order in which their imports finished.
+    pub fn packages(&self) -> EcoVec<PackageSpec> {
+        self.packages.clone()
+    }
+
+    /// Get the files that were read, directly or by imports, if dependencies
+    /// are tracked.
+    pub fn dependencies(&self) -> Option<Dependencies> {
+        self.dependencies.clone()
+    }
+
+    /// Get the profiled imports as a tree following the import hierarchy.
+    ///
+    /// The returned imports are those of the main file, in import order.
+    /// Imports whose evaluation was taken from the cache are marked as cached
+    /// and have a duration of zero.
+    pub fn imports(&self) -> Vec<ImportProfile> {
+        let Some(start) = self.profiling else { return vec![] };
+
+        // Imports finish after the imports they perform themselves, so the
+        // direct imports of an import are the unclaimed ones right before it.
+        let mut finished: Vec<(Option<FileId>, ImportProfile)> = vec![];
+        for (record, evaluated) in &self.imports {
+            let split = finished
+                .iter()
+                .rposition(|(importer, _)| *importer != Some(record.id))
+                .map_or(0, |i| i + 1);
+            let children = finished.drain(split..).map(|(_, profile)| profile).collect();
+
+            let cached = !evaluated || record.nonce < start;
+            let duration = if cached { Duration::ZERO } else { record.duration };
+            let profile = ImportProfile { id: record.id, duration, cached, children };
+            finished.push((record.importer, profile));
+        }
+
+        finished.into_iter().map(|(_, profile)| profile).collect()
+    }
 }
 
 #[comemo::track]
@@ -185,23 +437,361 @@ impl Sink {
         }
     }
 
+    /// Trace a function call at the traced span.
+    pub fn call(&mut self, call: TracedCall) {
+        if self.calls.len() < Self::MAX_VALUES {
+            self.calls.push(call);
+        }
+    }
+
+    /// Record a variable definition in the analyzed file.
+    pub fn definition(&mut self, definition: Definition) {
+        if self.analysis_set.insert(crate::utils::hash128(&definition)) {
+            self.definitions.push(definition);
+        }
+    }
+
+    /// Record a variable use in the analyzed file.
+    pub fn usage(&mut self, usage: Usage) {
+        if self.analysis_set.insert(crate::utils::hash128(&usage)) {
+            self.usages.push(usage);
+        }
+    }
+
+    /// Record a successfully imported package.
+    pub fn package(&mut self, spec: PackageSpec) {
+        if !self.packages.contains(&spec) {
+            self.packages.push(spec);
+        }
+    }
+
+    /// Record that a file was read, if dependencies are tracked.
+    pub fn depend(&mut self, id: FileId) {
+        if let Some(dependencies) = &mut self.dependencies {
+            dependencies.insert(id);
+        }
+    }
+
+    /// Record that a file was evaluated, if imports are profiled.
+    ///
+    /// The nonce is passed in instead of being produced here, so that an
+    /// evaluation that is taken from the cache replays the nonce of the
+    /// original one.
+    pub fn evaluate(&mut self, id: FileId, nonce: u64) {
+        self.evaluations.insert(id, nonce);
+    }
+
+    /// Record a profiled import.
+    ///
+    /// The imported file counts as evaluated if it was evaluated after the
+    /// import started. An evaluation that was taken from the cache is replayed
+    /// with an older nonce.
+    pub fn import(&mut self, record: ImportRecord) {
+        let evaluated = self
+            .evaluations
+            .get(&record.id)
+            .is_some_and(|&nonce| nonce > record.nonce);
+        self.imports.push((record, evaluated));
+    }
+
+    /// Record that a number of expressions were evaluated.
+    pub fn spend(&mut self, steps: u64) {
+        self.steps = self.steps.saturating_add(steps);
+    }
+
+    /// Whether imports are profiled.
+    pub fn profiled(&self) -> bool {
+        self.profiling.is_some()
+    }
+
+    /// Whether dependencies are tracked.
+    pub fn tracks_dependencies(&self) -> bool {
+        self.dependencies.is_some()
+    }
+
+    /// Whether this is a dry run.
+    pub fn dry_run(&self) -> bool {
+        self.dry_run
+    }
+
+    /// Whether evaluation is limited by a budget.
+    pub fn limited(&self) -> bool {
+        self.budget.is_limited()
+    }
+
+    /// Whether the steps of the evaluation budget are used up.
+    pub fn exhausted(&self) -> bool {
+        self.budget.steps.is_some_and(|max| self.steps > max)
+    }
+
+    /// The point in time after which evaluation fails, if any.
+    ///
+    /// Tracked methods must be pure, so the deadline is compared with the
+    /// current time by the caller.
+    pub fn deadline(&self) -> Option<Instant> {
+        self.budget.deadline
+    }
+
+    /// How many elements the content of a single file may have.
+    pub fn content_limit(&self) -> u64 {
+        self.budget.content_limit()
+    }
+
+    /// The part of the budget that is not yet used up.
+    pub fn remaining(&self) -> Budget {
+        Budget {
+            steps: self.budget.steps.map(|max| max.saturating_sub(self.steps)),
+            ..self.budget
+        }
+    }
+
     /// Extend from another sink.
     fn extend(
         &mut self,
         delayed: EcoVec<SourceDiagnostic>,
         warnings: EcoVec<SourceDiagnostic>,
         values: EcoVec<(Value, Option<Styles>)>,
+        calls: EcoVec<TracedCall>,
+        analysis: Analysis,
+        packages: EcoVec<PackageSpec>,
+        dependencies: Option<Dependencies>,
+        imports: EcoVec<(ImportRecord, bool)>,
+        steps: u64,
     ) {
         self.delayed.extend(delayed);
+        self.spend(steps);
         for warning in warnings {
             self.warn(warning);
         }
         if let Some(remaining) = Self::MAX_VALUES.checked_sub(self.values.len()) {
             self.values.extend(values.into_iter().take(remaining));
         }
+        for call in calls {
+            self.call(call);
+        }
+        for definition in analysis.definitions {
+            self.definition(definition);
+        }
+        for usage in analysis.usages {
+            self.usage(usage);
+        }
+        for spec in packages {
+            self.package(spec);
+        }
+        for id in dependencies.iter().flat_map(Dependencies::files) {
+            self.depend(*id);
+        }
+        self.imports.extend(imports);
+    }
+}
+
+/// Limits for the amount of work that evaluation may perform.
+///
+/// This is useful when compiling untrusted documents, which could otherwise
+/// keep the compiler busy for a long time within the limits on iterations and
+/// nesting. Once the budget is used up, evaluation fails with an error. By
+/// default, there are no limits, except for the size of the content a file
+/// produces.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Budget {
+    /// The maximum number of evaluated expressions.
+    steps: Option<u64>,
+    /// The point in time after which evaluation fails.
+    deadline: Option<Instant>,
+    /// The maximum number of elements in the content of a single file.
+    content: Option<u64>,
+}
+
+impl Budget {
+    /// How many elements the content of a single file may have by default.
+    ///
+    /// Content that includes itself repeatedly grows exponentially and would
+    /// otherwise keep layout busy long after evaluation finished.
+    pub const DEFAULT_CONTENT_LIMIT: u64 = 5_000_000;
+
+    /// Create a budget without any limits on the work evaluation performs.
+    pub fn unlimited() -> Self {
+        Self::default()
+    }
+
+    /// Limit the number of expressions that may be evaluated.
+    ///
+    /// Evaluated expressions are charged to the budget in batches, so
+    /// evaluation may slightly overshoot this limit before it fails. Function
+    /// calls that only evaluate a handful of expressions may go uncounted.
+    pub fn with_steps(self, steps: u64) -> Self {
+        Self { steps: Some(steps), ..self }
+    }
+
+    /// Make evaluation fail once the given point in time has passed.
+    ///
+    /// Not available on WebAssembly, where the current time can't be queried.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn with_deadline(self, deadline: Instant) -> Self {
+        Self { deadline: Some(deadline), ..self }
+    }
+
+    /// Limit the number of elements in the content of a single file, as
+    /// approximated by [`Content::nodes`](crate::foundations::Content::nodes).
+    /// Defaults to [`DEFAULT_CONTENT_LIMIT`](Self::DEFAULT_CONTENT_LIMIT).
+    pub fn with_content_limit(self, elements: u64) -> Self {
+        Self { content: Some(elements), ..self }
+    }
+
+    /// How many expressions may be evaluated, if that is limited.
+    pub fn steps(&self) -> Option<u64> {
+        self.steps
+    }
+
+    /// How many elements the content of a single file may have.
+    pub fn content_limit(&self) -> u64 {
+        self.content.unwrap_or(Self::DEFAULT_CONTENT_LIMIT)
+    }
+
+    /// Whether the budget limits the work evaluation performs.
+    pub fn is_limited(&self) -> bool {
+        self.steps.is_some() || self.deadline.is_some()
+    }
+}
+
+/// A function call at the traced span.
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub struct TracedCall {
+    /// The name of the called function, if it has one.
+    pub name: Option<EcoString>,
+    /// The positional arguments the function received.
+    pub positional: Array,
+    /// The named arguments the function received.
+    pub named: Dict,
+    /// The value the function returned, or `None` if it failed.
+    pub output: Option<Value>,
+}
+
+/// A traced value in a serializable form, e.g. for editor tooling.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StructuredValue {
+    /// The `repr` of the value.
+    pub repr: EcoString,
+    /// The value as JSON, as produced by [`Value::to_json`].
+    pub json: serde_json::Value,
+}
+
+impl StructuredValue {
+    /// How deeply nested collections are converted to JSON before falling
+    /// back to their `repr`.
+    pub const MAX_DEPTH: usize = 32;
+
+    /// Convert a traced value.
+    pub fn new(value: &Value) -> Self {
+        Self {
+            repr: value.repr(),
+            json: value.to_json(Self::MAX_DEPTH),
+        }
     }
 }
 
+/// The files that were read during compilation, as recorded in the [`Sink`].
+///
+/// Files that were attempted to be read, but couldn't be, are included too,
+/// since their creation affects the result.
+#[derive(Debug, Default, Clone, Eq, PartialEq, Hash)]
+pub struct Dependencies(EcoVec<FileId>);
+
+impl Dependencies {
+    /// The files, without duplicates and in the order in which they were
+    /// first read.
+    pub fn files(&self) -> &[FileId] {
+        &self.0
+    }
+
+    /// Record that a file was read.
+    fn insert(&mut self, id: FileId) {
+        if !self.0.contains(&id) {
+            self.0.push(id);
+        }
+    }
+}
+
+/// A profiled import, as recorded in the [`Sink`].
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct ImportRecord {
+    /// The file that performed the import.
+    pub importer: Option<FileId>,
+    /// The imported file. For packages, this is the entrypoint.
+    pub id: FileId,
+    /// How long the import took.
+    pub duration: Duration,
+    /// Taken when the import started. Distinguishes imports recorded in this
+    /// compilation from those replayed from the cache of an earlier one.
+    pub nonce: u64,
+}
+
+impl ImportRecord {
+    /// Produce a new nonce, which is larger than all previous ones.
+    pub fn nonce() -> u64 {
+        static NONCE: AtomicU64 = AtomicU64::new(0);
+        NONCE.fetch_add(1, Ordering::Relaxed)
+    }
+}
+
+/// An import performed during compilation, together with the imports it
+/// performed in turn.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct ImportProfile {
+    /// The imported file. For packages, this is the entrypoint.
+    pub id: FileId,
+    /// How long evaluating the imported file took, including its own imports.
+    pub duration: Duration,
+    /// Whether the imported file was taken from the cache instead of being
+    /// evaluated during this compilation.
+    pub cached: bool,
+    /// The imports performed by the imported file, in import order.
+    pub children: Vec<ImportProfile>,
+}
+
+/// The definitions and uses of variables in an analyzed file.
+#[derive(Debug, Default, Clone, Eq, PartialEq, Hash)]
+pub struct Analysis {
+    /// The variables defined in the file, in evaluation order.
+    pub definitions: EcoVec<Definition>,
+    /// The uses of variables in the file, in evaluation order.
+    pub usages: EcoVec<Usage>,
+}
+
+/// A variable definition.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Definition {
+    /// The name of the variable.
+    pub name: EcoString,
+    /// The span of the defining identifier.
+    pub span: Span,
+    /// How the variable was defined.
+    pub kind: DefinitionKind,
+}
+
+/// How a variable was defined.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum DefinitionKind {
+    /// A `let` binding, including destructuring and loop variables.
+    Variable,
+    /// A parameter of a closure.
+    Param,
+    /// An import.
+    Import,
+}
+
+/// A use of a variable.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Usage {
+    /// The name of the variable.
+    pub name: EcoString,
+    /// The span of the identifier that uses the variable.
+    pub span: Span,
+    /// The span of the definition the use resolved to. This is `None` for
+    /// variables from the standard library.
+    pub definition: Option<Span>,
+}
+
 /// The route the engine took during compilation. This is used to detect
 /// cyclic imports and excessive nesting.
 pub struct Route<'a> {
@@ -215,6 +805,13 @@ pub struct Route<'a> {
     /// This is set if this route segment was inserted through the start of a
     /// module evaluation.
     id: Option<FileId>,
+    /// This is set if this route segment was inserted through a call of a
+    /// closure. Holds the closure's name and the span of the call site.
+    call: Option<(Option<EcoString>, Span)>,
+    /// This is set if this route segment was inserted through the eager
+    /// application of a show rule without a selector. Holds the show rule's
+    /// span.
+    show: Option<Span>,
     /// This is set whenever we enter a function, nested layout, or are applying
     /// a show rule. The length of this segment plus the lengths of all `outer`
     /// route segments make up the length of the route. If the length of the
@@ -249,6 +846,8 @@ impl<'a> Route<'a> {
     pub fn root() -> Self {
         Self {
             id: None,
+            call: None,
+            show: None,
             outer: None,
             len: 0,
             upper: AtomicUsize::new(0),
@@ -260,6 +859,8 @@ impl<'a> Route<'a> {
         Route {
             outer: Some(outer),
             id: None,
+            call: None,
+            show: None,
             len: 1,
             upper: AtomicUsize::new(usize::MAX),
         }
@@ -270,6 +871,17 @@ impl<'a> Route<'a> {
         Self { id: Some(id), ..self }
     }
 
+    /// Attach a call of the closure with the given name to the route segment.
+    pub fn with_call(self, name: Option<EcoString>, span: Span) -> Self {
+        Self { call: Some((name, span)), ..self }
+    }
+
+    /// Attach the application of the show rule with the given span to the
+    /// route segment.
+    pub fn with_show(self, span: Span) -> Self {
+        Self { show: Some(span), ..self }
+    }
+
     /// Set the length of the route segment to zero.
     pub fn unnested(self) -> Self {
         Self { len: 0, ..self }
@@ -281,7 +893,14 @@ impl<'a> Route<'a> {
     /// if it does not contribute anything.
     pub fn track(&self) -> Tracked<'_, Self> {
         match self.outer {
-            Some(outer) if self.id.is_none() && self.len == 0 => outer,
+            Some(outer)
+                if self.id.is_none()
+                    && self.call.is_none()
+                    && self.show.is_none()
+                    && self.len == 0 =>
+            {
+                outer
+            }
             _ => Track::track(self),
         }
     }
@@ -304,6 +923,32 @@ impl<'a> Route<'a> {
         self.id == Some(id) || self.outer.is_some_and(|outer| outer.contains(id))
     }
 
+    /// The closure calls on the route, innermost first.
+    pub fn calls(&self) -> EcoVec<(Option<EcoString>, Span)> {
+        let mut calls = self.outer.map(|outer| outer.calls()).unwrap_or_default();
+        if let Some(call) = &self.call {
+            calls.insert(0, call.clone());
+        }
+        calls
+    }
+
+    /// The span of the innermost show rule that was applied after the file
+    /// with the given id was entered, if any.
+    ///
+    /// When the file is imported again, this is the show rule that the import
+    /// cycle passes through.
+    pub fn show_since(&self, id: FileId) -> Option<Span> {
+        if self.id == Some(id) {
+            return None;
+        }
+
+        let outer = self.outer?;
+        match self.show {
+            Some(span) if outer.contains(id) => Some(span),
+            _ => outer.show_since(id),
+        }
+    }
+
     /// Whether the route's depth is less than or equal to the given depth.
     pub fn within(&self, depth: usize) -> bool {
         // We only need atomicity and no synchronization of other operations, so
@@ -342,8 +987,55 @@ impl Clone for Route<'_> {
         Self {
             outer: self.outer,
             id: self.id,
+            call: self.call.clone(),
+            show: self.show,
             len: self.len,
             upper: AtomicUsize::new(self.upper.load(Ordering::Relaxed)),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::syntax::VirtualPath;
+
+    /// Stands in for the memoized evaluation of a file.
+    #[comemo::memoize]
+    fn evaluate(mut sink: TrackedMut<Sink>, id: FileId) {
+        if sink.profiled() {
+            sink.evaluate(id, ImportRecord::nonce());
+        }
+    }
+
+    /// Imports a file like `eval_import` does.
+    fn import(sink: &mut Sink, id: FileId) {
+        let nonce = ImportRecord::nonce();
+        evaluate(sink.track_mut(), id);
+        sink.import(ImportRecord {
+            importer: None,
+            id,
+            duration: Duration::ZERO,
+            nonce,
+        });
+    }
+
+    #[test]
+    fn test_import_profile_cached() {
+        let id = FileId::new(None, VirtualPath::new("profiled.typ"));
+        let mut sink = Sink::new().with_profiling();
+
+        // The first import evaluates the file, the second one replays the
+        // cached evaluation.
+        import(&mut sink, id);
+        import(&mut sink, id);
+        let cached: Vec<_> = sink.imports().iter().map(|import| import.cached).collect();
+        assert_eq!(cached, [false, true]);
+
+        // In a later compilation, the file is cached from the start.
+        let mut sink = Sink::new().with_profiling();
+        import(&mut sink, id);
+        let cached: Vec<_> = sink.imports().iter().map(|import| import.cached).collect();
+        assert_eq!(cached, [true]);
+    }
+}
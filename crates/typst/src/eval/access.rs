@@ -26,17 +26,45 @@ impl Access for ast::Expr<'_> {
     }
 }
 
+/// Access an expression mutably to assign a new value to it.
+///
+/// In contrast to [`Access::access`], this fails for loop variables.
+pub(crate) fn access_for_assign<'a>(
+    expr: ast::Expr,
+    vm: &'a mut Vm,
+) -> SourceResult<&'a mut Value> {
+    match expr {
+        ast::Expr::Ident(v) => access_ident(v, vm, true),
+        ast::Expr::Parenthesized(v) => access_for_assign(v.expr(), vm),
+        _ => expr.access(vm),
+    }
+}
+
 impl Access for ast::Ident<'_> {
     fn access<'a>(self, vm: &'a mut Vm) -> SourceResult<&'a mut Value> {
-        let span = self.span();
-        if vm.inspected == Some(span) {
-            if let Ok(value) = vm.scopes.get(&self).cloned() {
-                vm.trace(value);
-            }
+        access_ident(self, vm, false)
+    }
+}
+
+/// Access a variable mutably, either to assign to it or to mutate it.
+fn access_ident<'a>(
+    ident: ast::Ident,
+    vm: &'a mut Vm,
+    assign: bool,
+) -> SourceResult<&'a mut Value> {
+    let span = ident.span();
+    if vm.inspected == Some(span) {
+        if let Ok(value) = vm.scopes.get(&ident).cloned() {
+            vm.trace(value);
         }
-        let value = vm.scopes.get_mut(&self).at(span)?;
-        Ok(value)
     }
+    vm.record_usage(&ident, span);
+    let value = if assign {
+        vm.scopes.get_mut_for_assign(&ident).at(span)?
+    } else {
+        vm.scopes.get_mut(&ident).at(span)?
+    };
+    Ok(value)
 }
 
 impl Access for ast::Parenthesized<'_> {
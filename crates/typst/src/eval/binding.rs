@@ -1,9 +1,10 @@
-use std::collections::HashSet;
+use ecow::{eco_format, eco_vec};
 
-use crate::diag::{bail, At, SourceResult};
-use crate::eval::{Access, Eval, Vm};
-use crate::foundations::{Array, Dict, Value};
+use crate::diag::{bail, SourceDiagnostic, SourceResult};
+use crate::eval::{access_for_assign, Eval, Vm};
+use crate::foundations::{repr, Args, Array, Dict, Func, Value};
 use crate::syntax::ast::{self, AstNode};
+use crate::syntax::Span;
 
 impl Eval for ast::LetBinding<'_> {
     type Output = Value;
@@ -18,7 +19,11 @@ impl Eval for ast::LetBinding<'_> {
         }
 
         match self.kind() {
-            ast::LetBindingKind::Normal(pattern) => destructure(vm, pattern, value)?,
+            ast::LetBindingKind::Normal(pattern) => {
+                destructure_with(vm, pattern, value, self.init(), |vm, ident, value| {
+                    vm.define(ident, value)
+                })?
+            }
             ast::LetBindingKind::Closure(ident) => vm.define(ident, value),
         }
 
@@ -30,12 +35,20 @@ impl Eval for ast::DestructAssignment<'_> {
     type Output = Value;
 
     fn eval(self, vm: &mut Vm) -> SourceResult<Self::Output> {
-        let value = self.value().eval(vm)?;
-        destructure_impl(vm, self.pattern(), value, &mut |vm, expr, value| {
-            let location = expr.access(vm)?;
-            *location = value;
-            Ok(())
-        })?;
+        let init = self.value();
+        let value = init.eval(vm)?;
+        let returned = returned_by(vm, Some(init));
+        destructure_returned(
+            vm,
+            self.pattern(),
+            value,
+            returned,
+            &mut |vm, expr, value| {
+                let location = access_for_assign(expr, vm)?;
+                *location = value;
+                Ok(())
+            },
+        )?;
         Ok(Value::None)
     }
 }
@@ -46,15 +59,76 @@ pub(crate) fn destructure(
     pattern: ast::Pattern,
     value: Value,
 ) -> SourceResult<()> {
-    destructure_impl(vm, pattern, value, &mut |vm, expr, value| match expr {
-        ast::Expr::Ident(ident) => {
-            vm.define(ident, value);
-            Ok(())
-        }
-        _ => bail!(expr.span(), "cannot assign to this expression"),
+    destructure_with(vm, pattern, value, None, |vm, ident, value| vm.define(ident, value))
+}
+
+/// Destructures a value into the pattern of a for loop, defining loop
+/// variables.
+pub(crate) fn destructure_loop(
+    vm: &mut Vm,
+    pattern: ast::Pattern,
+    value: Value,
+) -> SourceResult<()> {
+    destructure_with(vm, pattern, value, None, |vm, ident, value| {
+        vm.define_loop_variable(ident, value)
     })
 }
 
+/// Destructures a value into a pattern, defining variables with `define`.
+///
+/// The `init` is the expression the value was evaluated from, if any.
+fn destructure_with(
+    vm: &mut Vm,
+    pattern: ast::Pattern,
+    value: Value,
+    init: Option<ast::Expr>,
+    define: fn(&mut Vm, ast::Ident, Value),
+) -> SourceResult<()> {
+    let returned = returned_by(vm, init);
+    destructure_returned(
+        vm,
+        pattern,
+        value,
+        returned,
+        &mut |vm, expr, value| match expr {
+            ast::Expr::Ident(ident) => {
+                define(vm, ident, value);
+                Ok(())
+            }
+            _ => bail!(expr.span(), "cannot assign to this expression"),
+        },
+    )
+}
+
+/// The function that returned the value of the given expression, if the
+/// expression is a call to a function with a fixed set of returned keys.
+fn returned_by(vm: &mut Vm, init: Option<ast::Expr>) -> Option<Func> {
+    let Some(ast::Expr::FuncCall(call)) = init else { return None };
+    let (span, func) = vm.returned.take()?;
+    (span == call.span()).then_some(func)
+}
+
+/// Destructures a value returned by the given function into the pattern. If a
+/// dictionary pattern asks for a key the function doesn't return, the error
+/// lists the keys it does return.
+fn destructure_returned<F>(
+    vm: &mut Vm,
+    pattern: ast::Pattern,
+    value: Value,
+    returned: Option<Func>,
+    f: &mut F,
+) -> SourceResult<()>
+where
+    F: Fn(&mut Vm, ast::Expr, Value) -> SourceResult<()>,
+{
+    match (pattern, value, returned) {
+        (ast::Pattern::Destructuring(destruct), Value::Dict(dict), Some(func)) => {
+            destructure_dict(vm, destruct, dict, Some(&func), f)
+        }
+        (pattern, value, _) => destructure_impl(vm, pattern, value, f),
+    }
+}
+
 /// Destruct the given value into the pattern and apply the function to each binding.
 fn destructure_impl<F>(
     vm: &mut Vm,
@@ -73,7 +147,8 @@ where
         }
         ast::Pattern::Destructuring(destruct) => match value {
             Value::Array(value) => destructure_array(vm, destruct, value, f)?,
-            Value::Dict(value) => destructure_dict(vm, destruct, value, f)?,
+            Value::Dict(value) => destructure_dict(vm, destruct, value, None, f)?,
+            Value::Args(value) => destructure_args(vm, destruct, value, f)?,
             _ => bail!(pattern.span(), "cannot destructure {}", value.ty()),
         },
     }
@@ -150,13 +225,33 @@ fn destructure_dict<F>(
     vm: &mut Vm,
     destruct: ast::Destructuring,
     dict: Dict,
+    returned: Option<&Func>,
     f: &mut F,
 ) -> SourceResult<()>
 where
     F: Fn(&mut Vm, ast::Expr, Value) -> SourceResult<()>,
 {
+    let get = |key: &str, span: Span| match dict.get_full(key) {
+        Ok((index, v)) => Ok((index, v.clone())),
+        Err(message) => {
+            let mut diag = SourceDiagnostic::error(span, message);
+            if let Some(func) = returned {
+                let keys: Vec<_> =
+                    func.keys().iter().map(|key| eco_format!("`{key}`")).collect();
+                diag.hint(eco_format!(
+                    "`{}` returns a dictionary with the keys {}",
+                    func.name().unwrap_or_default(),
+                    repr::separated_list(&keys, "and"),
+                ));
+            }
+            Err(eco_vec![diag])
+        }
+    };
+
+    // Track the used entries by index, so that the sink can be computed
+    // without looking up every key again.
     let mut sink = None;
-    let mut used = HashSet::new();
+    let mut used = vec![false; dict.len()];
 
     for p in destruct.items() {
         match p {
@@ -164,15 +259,15 @@ where
             ast::DestructuringItem::Pattern(ast::Pattern::Normal(ast::Expr::Ident(
                 ident,
             ))) => {
-                let v = dict.get(&ident).at(ident.span())?;
-                f(vm, ast::Expr::Ident(ident), v.clone())?;
-                used.insert(ident.get().clone());
+                let (index, v) = get(ident.get(), ident.span())?;
+                f(vm, ast::Expr::Ident(ident), v)?;
+                used[index] = true;
             }
             ast::DestructuringItem::Named(named) => {
                 let name = named.name();
-                let v = dict.get(&name).at(name.span())?;
-                destructure_impl(vm, named.pattern(), v.clone(), f)?;
-                used.insert(name.get().clone());
+                let (index, v) = get(name.get(), name.span())?;
+                destructure_impl(vm, named.pattern(), v, f)?;
+                used[index] = true;
             }
             ast::DestructuringItem::Spread(spread) => sink = spread.sink_expr(),
             ast::DestructuringItem::Pattern(expr) => {
@@ -182,13 +277,76 @@ where
     }
 
     if let Some(expr) = sink {
-        let mut sink = Dict::new();
-        for (key, value) in dict {
-            if !used.contains(key.as_str()) {
-                sink.insert(key, value);
+        let mut sink = dict;
+        sink.retain_indices(|index| !used[index]);
+        f(vm, expr, Value::Dict(sink))?;
+    }
+
+    Ok(())
+}
+
+fn destructure_args<F>(
+    vm: &mut Vm,
+    destruct: ast::Destructuring,
+    mut args: Args,
+    f: &mut F,
+) -> SourceResult<()>
+where
+    F: Fn(&mut Vm, ast::Expr, Value) -> SourceResult<()>,
+{
+    let positional = destruct
+        .items()
+        .filter(|p| matches!(p, ast::DestructuringItem::Pattern(_)))
+        .count();
+    let len = args.remaining();
+    let provided = match len {
+        1 => "1 positional argument".into(),
+        n => eco_format!("{n} positional arguments"),
+    };
+    let mut sink = None;
+
+    for p in destruct.items() {
+        match p {
+            ast::DestructuringItem::Pattern(pattern) => {
+                let Some(v) = args.eat::<Value>()? else {
+                    bail!(
+                        pattern.span(), "not enough positional arguments to destructure";
+                        hint: "the provided arguments have {provided}",
+                    );
+                };
+                destructure_impl(vm, pattern, v, f)?;
+            }
+            ast::DestructuringItem::Named(named) => {
+                let name = named.name();
+                let Some(v) = args.named::<Value>(&name)? else {
+                    bail!(name.span(), "missing argument: {}", name.as_str());
+                };
+                destructure_impl(vm, named.pattern(), v, f)?;
+            }
+            ast::DestructuringItem::Spread(spread) => {
+                let Some(sink_size) = len.checked_sub(positional) else {
+                    bail!(
+                        spread.span(), "not enough positional arguments to destructure";
+                        hint: "the provided arguments have {provided}",
+                    );
+                };
+                let items = args.consume(sink_size)?;
+                sink = Some((spread.sink_expr(), items));
             }
         }
-        f(vm, expr, Value::Dict(sink))?;
+    }
+
+    let Some((expr, mut items)) = sink else {
+        return args.finish();
+    };
+
+    if let Some(expr) = expr {
+        items.extend(args.items.into_iter().filter(|arg| arg.name.is_some()));
+        let rest = Args {
+            span: args.span,
+            items: items.into_iter().collect(),
+        };
+        f(vm, expr, Value::Args(rest))?;
     }
 
     Ok(())
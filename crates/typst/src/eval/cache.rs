@@ -0,0 +1,136 @@
+use comemo::Tracked;
+use ecow::{eco_format, EcoString};
+use serde::{Deserialize, Serialize};
+
+use crate::foundations::Bytes;
+use crate::syntax::package::{PackageManifest, PackageSpec, PackageVersion};
+use crate::utils::hash128;
+use crate::World;
+
+/// The version of the persisted format. Must be bumped whenever the layout or
+/// meaning of an [`Entry`] changes, so that entries written by older versions
+/// are ignored instead of misread.
+const FORMAT: u32 = 1;
+
+/// A parsed and validated package manifest, as persisted by the world.
+#[derive(Serialize, Deserialize)]
+struct Entry {
+    /// Identifies what the manifest was created from.
+    key: Key,
+    /// The manifest itself.
+    manifest: PackageManifest,
+}
+
+/// Everything a cached manifest depends on.
+#[derive(PartialEq, Serialize, Deserialize)]
+struct Key {
+    /// The format version the entry was written with.
+    format: u32,
+    /// The compiler version that validated the manifest.
+    compiler: EcoString,
+    /// The package the manifest belongs to.
+    spec: EcoString,
+    /// The hash of the manifest file.
+    hash: EcoString,
+}
+
+impl Key {
+    /// The key for the given package and manifest file.
+    fn new(spec: &PackageSpec, bytes: &[u8]) -> Self {
+        Self {
+            format: FORMAT,
+            compiler: eco_format!("{}", PackageVersion::compiler()),
+            spec: eco_format!("{spec}"),
+            hash: eco_format!("{:032x}", hash128(bytes)),
+        }
+    }
+}
+
+/// Load the validated manifest of a package persisted by the world.
+///
+/// Returns `None` if there is no entry for the package or if it is stale or
+/// corrupted. The caller should then parse the manifest itself.
+pub fn load(
+    world: Tracked<dyn World + '_>,
+    spec: &PackageSpec,
+    bytes: &[u8],
+) -> Option<PackageManifest> {
+    decode(&world.cache_read(&name(spec))?, spec, bytes)
+}
+
+/// Persist the validated manifest of a package through the world.
+pub fn store(
+    world: Tracked<dyn World + '_>,
+    spec: &PackageSpec,
+    bytes: &[u8],
+    manifest: &PackageManifest,
+) {
+    if let Some(data) = encode(spec, bytes, manifest) {
+        world.cache_write(&name(spec), data);
+    }
+}
+
+/// The key under which the entry for a package is persisted.
+fn name(spec: &PackageSpec) -> String {
+    format!("{}.{}.{}.json", spec.namespace, spec.name, spec.version)
+}
+
+/// Serialize the entry for a manifest.
+fn encode(spec: &PackageSpec, bytes: &[u8], manifest: &PackageManifest) -> Option<Bytes> {
+    let entry = Entry {
+        key: Key::new(spec, bytes),
+        manifest: manifest.clone(),
+    };
+    serde_json::to_vec(&entry).ok().map(Bytes::from)
+}
+
+/// Deserialize an entry, returning its manifest if it is still valid for the
+/// given manifest file.
+fn decode(data: &[u8], spec: &PackageSpec, bytes: &[u8]) -> Option<PackageManifest> {
+    let entry: Entry = serde_json::from_slice(data).ok()?;
+    (entry.key == Key::new(spec, bytes)).then_some(entry.manifest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The spec, manifest file, and parsed manifest of a test package.
+    fn package(entrypoint: &str) -> (PackageSpec, String, PackageManifest) {
+        let spec: PackageSpec = "@preview/pkg:0.1.0".parse().unwrap();
+        let toml = format!(
+            "[package]\nname = \"pkg\"\nversion = \"0.1.0\"\n\
+             entrypoint = \"{entrypoint}\"\n"
+        );
+        let manifest = toml::from_str(&toml).unwrap();
+        (spec, toml, manifest)
+    }
+
+    #[test]
+    fn test_cache_roundtrip() {
+        let (spec, toml, manifest) = package("lib.typ");
+        let data = encode(&spec, toml.as_bytes(), &manifest).unwrap();
+        let decoded = decode(&data, &spec, toml.as_bytes()).unwrap();
+        assert_eq!(decoded.package.entrypoint, "lib.typ");
+    }
+
+    #[test]
+    fn test_cache_stale() {
+        let (spec, toml, _) = package("lib.typ");
+        let (_, stale, manifest) = package("alt.typ");
+        let data = encode(&spec, stale.as_bytes(), &manifest).unwrap();
+        assert!(decode(&data, &spec, toml.as_bytes()).is_none());
+
+        let other: PackageSpec = "@preview/other:0.1.0".parse().unwrap();
+        let data = encode(&other, toml.as_bytes(), &manifest).unwrap();
+        assert!(decode(&data, &spec, toml.as_bytes()).is_none());
+    }
+
+    #[test]
+    fn test_cache_corrupted() {
+        let (spec, toml, manifest) = package("lib.typ");
+        let data = encode(&spec, toml.as_bytes(), &manifest).unwrap();
+        assert!(decode(&data[..data.len() / 2], &spec, toml.as_bytes()).is_none());
+        assert!(decode(b"", &spec, toml.as_bytes()).is_none());
+    }
+}
@@ -1,18 +1,25 @@
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+
 use comemo::{Tracked, TrackedMut};
-use ecow::{eco_format, EcoVec};
+use ecow::{eco_format, EcoString, EcoVec};
+use indexmap::IndexSet;
 
-use crate::diag::{bail, error, At, HintedStrResult, SourceResult, Trace, Tracepoint};
-use crate::engine::{Engine, Sink, Traced};
-use crate::eval::{Access, Eval, FlowEvent, Route, Vm};
+use crate::diag::{
+    bail, error, warning, At, SourceDiagnostic, SourceResult, Trace, Tracepoint,
+};
+use crate::engine::{Engine, Sink, Traced, TracedCall};
+use crate::eval::{maybe_grow, Access, Eval, FlowEvent, Route, Vm};
 use crate::foundations::{
-    call_method_mut, is_mutating_method, Arg, Args, Bytes, Capturer, Closure, Content,
-    Context, Func, IntoValue, NativeElement, Scope, Scopes, Value,
+    call_method_mut, call_method_mut_with_engine, is_builder_method, is_calling_method,
+    is_mutating_method, Arg, Args, Builder, Bytes, Capturer, Closure, Content, Context,
+    Func, IntoValue, NativeElement, Scope, Scopes, SequenceBuilder, Value,
 };
 use crate::introspection::Introspector;
-use crate::math::LrElem;
+use crate::math::{LrElem, OpElem, UnknownCallElem};
 use crate::syntax::ast::{self, AstNode};
-use crate::syntax::{Span, Spanned, SyntaxNode};
-use crate::text::TextElem;
+use crate::syntax::{Span, Spanned, SyntaxKind, SyntaxNode};
+use crate::text::{TextElem, UniqueSlugs};
 use crate::utils::LazyHash;
 use crate::World;
 
@@ -28,7 +35,7 @@ impl Eval for ast::FuncCall<'_> {
         let trailing_comma = args.trailing_comma();
 
         if !vm.engine.route.within(Route::MAX_CALL_DEPTH) {
-            bail!(span, "maximum function call depth exceeded");
+            bail!(error!(span, "maximum function call depth exceeded").uncatchable());
         }
 
         // Try to evaluate as a call to an associated function or field.
@@ -38,12 +45,53 @@ impl Eval for ast::FuncCall<'_> {
             let field = access.field();
             let field_span = field.span();
 
-            let target = if is_mutating_method(&field) {
+            // Counters and states also have an `update` method, which only
+            // mutates when called on a dictionary. Likewise, the methods that
+            // only builders have only mutate when called on a builder.
+            let mut evaluated = None;
+            if field.as_str() == "update" {
+                evaluated = Some(target.eval(vm)?)
+                    .filter(|value| !matches!(value, Value::Dict(_)));
+            } else if is_builder_method(&field) {
+                evaluated = Some(target.eval(vm)?).filter(
+                    |value| !matches!(value, Value::Dyn(dynamic) if dynamic.is::<Builder>()),
+                );
+            }
+
+            let target = if let Some(value) = evaluated {
+                value
+            } else if is_mutating_method(&field) || is_builder_method(&field) {
                 let mut args = args.eval(vm)?.spanned(span);
+                if is_calling_method(&field) {
+                    // The target can't stay borrowed while functions are
+                    // called, so the method operates on a copy that is only
+                    // written back once it succeeded.
+                    let mut copy = target.access(vm)?.clone();
+                    if matches!(copy, Value::Array(_) | Value::Dict(_)) {
+                        args.span = span;
+                        let point = || Tracepoint::Call(Some(field.get().clone()));
+                        let output = call_method_mut_with_engine(
+                            &mut copy,
+                            &mut vm.engine,
+                            vm.context,
+                            &field,
+                            args,
+                            span,
+                        )
+                        .trace(vm.world(), point, span)?;
+                        *target.access(vm)? = copy;
+                        return Ok(output);
+                    }
+                }
+
                 let target = target.access(vm)?;
 
-                // Only arrays and dictionaries have mutable methods.
-                if matches!(target, Value::Array(_) | Value::Dict(_)) {
+                // Only arrays, dictionaries, builders, and slug trackers have
+                // mutable methods.
+                if matches!(target, Value::Array(_) | Value::Dict(_))
+                    || matches!(target, Value::Dyn(dynamic)
+                        if dynamic.is::<Builder>() || dynamic.is::<UniqueSlugs>())
+                {
                     args.span = span;
                     let point = || Tracepoint::Call(Some(field.get().clone()));
                     return call_method_mut(target, &field, args, span).trace(
@@ -64,7 +112,10 @@ impl Eval for ast::FuncCall<'_> {
             if let Value::Plugin(plugin) = &target {
                 let bytes = args.all::<Bytes>()?;
                 args.finish()?;
-                return Ok(plugin.call(&field, bytes).at(span)?.into_value());
+                return Ok(plugin
+                    .call_budgeted(&mut vm.engine, &field, bytes)
+                    .at(span)?
+                    .into_value());
             }
 
             // Prioritize associated functions on the value's type (i.e.,
@@ -130,21 +181,62 @@ impl Eval for ast::FuncCall<'_> {
 
         let func_result = callee.clone().cast::<Func>();
         if in_math && func_result.is_err() {
+            // Named arguments can't be displayed, so we don't silently drop
+            // them.
+            if let Some(arg) = args.items.iter().find(|arg| arg.name.is_some()) {
+                bail!(
+                    arg.span,
+                    "named arguments are only allowed when calling a function; \
+                     `{}` is a {}",
+                    self.callee().to_untyped().clone().into_text(),
+                    callee.ty(),
+                );
+            }
+
             // For non-functions in math, we wrap the arguments in parentheses.
-            let mut body = Content::empty();
-            for (i, arg) in args.all::<Content>()?.into_iter().enumerate() {
+            // The delimiters and commas keep the spans of their syntax, so that
+            // they can't be confused with the arguments' content.
+            let syntax = self.args().to_untyped();
+            let punct = |kind: SyntaxKind| {
+                syntax
+                    .children()
+                    .filter(move |child| child.kind() == kind)
+                    .map(|child| {
+                        TextElem::packed(child.text().clone()).spanned(child.span())
+                    })
+            };
+
+            let spans: Vec<Span> = args.items.iter().map(|arg| arg.value.span).collect();
+            let mut commas = punct(SyntaxKind::Comma);
+            let mut body = SequenceBuilder::new();
+            body.extend(punct(SyntaxKind::LeftParen));
+            for (i, (arg, span)) in
+                args.all::<Content>()?.into_iter().zip(spans).enumerate()
+            {
                 if i > 0 {
-                    body += TextElem::packed(',');
+                    body.extend(commas.next());
                 }
-                body += arg;
+                body.push(arg.spanned(span));
             }
             if trailing_comma {
-                body += TextElem::packed(',');
+                body.extend(commas.next());
             }
+            body.extend(punct(SyntaxKind::RightParen));
+
+            let display = callee.clone().display().spanned(callee_span);
+            let args = LrElem::new(body.build()).pack();
+
+            // Operators are meant to be followed by their arguments, so only
+            // other values depend on the equation's `unknown-calls` property.
+            if matches!(&callee, Value::Content(content) if content.is::<OpElem>()) {
+                return Ok(Value::Content(display + args));
+            }
+
+            let name = self.callee().to_untyped().clone().into_text();
             return Ok(Value::Content(
-                callee.display().spanned(callee_span)
-                    + LrElem::new(TextElem::packed('(') + body + TextElem::packed(')'))
-                        .pack(),
+                UnknownCallElem::new(display, args, name, callee.ty())
+                    .pack()
+                    .spanned(span),
             ));
         }
 
@@ -163,18 +255,75 @@ impl Eval for ast::FuncCall<'_> {
             })
             .at(callee_span)?;
 
+        // Keep the arguments of a traced call around, as the call consumes
+        // them.
+        let traced =
+            (vm.inspected == Some(span)).then(|| (args.to_pos(), args.to_named()));
+
+        // A closure only knows that an argument is unexpected, so the call
+        // explains how many positional arguments it passed.
+        let num_pos_params = func.num_pos_params();
+        let num_pos_args = args.items.iter().filter(|arg| arg.name.is_none()).count();
+
         let point = || Tracepoint::Call(func.name().map(Into::into));
         let f = || {
             func.call(&mut vm.engine, vm.context, args)
                 .trace(vm.world(), point, span)
         };
 
-        // Stacker is broken on WASM.
-        #[cfg(target_arch = "wasm32")]
-        return f();
+        let mut output = maybe_grow(f);
+        if let (Err(errors), Some(num_pos_params)) = (&mut output, num_pos_params) {
+            hint_too_many_positional(errors, self.args(), num_pos_params, num_pos_args);
+        }
+        if output.is_ok() && !func.keys().is_empty() {
+            vm.returned = Some((span, func.clone()));
+        }
 
-        #[cfg(not(target_arch = "wasm32"))]
-        stacker::maybe_grow(32 * 1024, 2 * 1024 * 1024, f)
+        if let Some((positional, named)) = traced {
+            vm.engine.sink.call(TracedCall {
+                name: func.name().map(Into::into),
+                positional,
+                named,
+                output: output.as_ref().ok().cloned(),
+            });
+        }
+
+        output
+    }
+}
+
+/// Explain an unexpected positional argument of a closure call by counting the
+/// call's positional arguments, including those from trailing content blocks.
+fn hint_too_many_positional(
+    errors: &mut EcoVec<SourceDiagnostic>,
+    args: ast::Args,
+    num_pos_params: usize,
+    num_pos_args: usize,
+) {
+    let is_positional = |span: Span| {
+        args.items()
+            .any(|arg| matches!(arg, ast::Arg::Pos(expr) if expr.span() == span))
+    };
+
+    for error in errors.make_mut() {
+        if error.message != "unexpected argument" || !is_positional(error.span) {
+            continue;
+        }
+
+        let takes = match num_pos_params {
+            1 => "1 positional argument".into(),
+            n => eco_format!("{n} positional arguments"),
+        };
+        let given = match args.trailing().count() {
+            0 => eco_format!("{num_pos_args} were given"),
+            1 => eco_format!(
+                "{num_pos_args} were given, one of them as a trailing content block"
+            ),
+            n => eco_format!(
+                "{num_pos_args} were given, {n} of them as trailing content blocks"
+            ),
+        };
+        error.hint(eco_format!("the function takes {takes}, but {given}"));
     }
 }
 
@@ -182,6 +331,8 @@ impl Eval for ast::Args<'_> {
     type Output = Args;
 
     fn eval(self, vm: &mut Vm) -> SourceResult<Self::Output> {
+        check_arg_order(vm, self)?;
+
         let mut items = EcoVec::with_capacity(self.items().count());
 
         for arg in self.items() {
@@ -219,6 +370,10 @@ impl Eval for ast::Args<'_> {
                         }));
                     }
                     Value::Args(args) => items.extend(args.items),
+                    Value::Str(_) => bail!(
+                        spread.span(), "cannot spread string";
+                        hint: "use `.clusters()` to spread the string's characters"
+                    ),
                     v => bail!(spread.span(), "cannot spread {}", v.ty()),
                 },
             }
@@ -230,6 +385,32 @@ impl Eval for ast::Args<'_> {
     }
 }
 
+/// Warn about positional arguments that follow named ones in the parentheses
+/// of an argument list. This is allowed, but may hide an argument that lost
+/// its name. Only checked if pedantic lints are enabled.
+fn check_arg_order(vm: &mut Vm, args: ast::Args) -> SourceResult<()> {
+    if !vm.engine.world.library().pedantic {
+        return Ok(());
+    }
+
+    // Trailing content blocks come after the parentheses and are exempt.
+    let trailing = args.trailing().count();
+
+    let mut after_named = false;
+    for arg in args.items().take(args.items().count() - trailing) {
+        match arg {
+            ast::Arg::Named(_) => after_named = true,
+            ast::Arg::Pos(expr) if after_named => vm.engine.lint(warning!(
+                expr.span(), "positional argument after named argument";
+                hint: "move it before the named arguments or give it a name",
+            ))?,
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
 impl Eval for ast::Closure<'_> {
     type Output = Value;
 
@@ -243,11 +424,7 @@ impl Eval for ast::Closure<'_> {
         }
 
         // Collect captured variables.
-        let captured = {
-            let mut visitor = CapturesVisitor::new(Some(&vm.scopes), Capturer::Function);
-            visitor.visit(self.to_untyped());
-            visitor.finish()
-        };
+        let captured = capture(&vm.scopes, self.to_untyped(), Capturer::Function);
 
         // Define the closure.
         let closure = Closure {
@@ -295,7 +472,7 @@ pub(crate) fn call_closure(
         introspector,
         traced,
         sink,
-        route: Route::extend(route),
+        route: Route::extend(route).with_call(func.name().map(Into::into), args.span),
     };
 
     // Prepare VM.
@@ -303,7 +480,7 @@ pub(crate) fn call_closure(
 
     // Provide the closure itself for recursive calls.
     if let Some(name) = name {
-        vm.define(name, Value::Func(func.clone()));
+        vm.define_unchecked(name, Value::Func(func.clone()));
     }
 
     let num_pos_args = args.to_pos().len();
@@ -316,7 +493,7 @@ pub(crate) fn call_closure(
         match p {
             ast::Param::Pos(pattern) => match pattern {
                 ast::Pattern::Normal(ast::Expr::Ident(ident)) => {
-                    vm.define(ident, args.expect::<Value>(&ident)?)
+                    vm.define_param(ident, args.expect::<Value>(&ident)?)
                 }
                 pattern => {
                     crate::eval::destructure(
@@ -337,7 +514,7 @@ pub(crate) fn call_closure(
                 let default = defaults.next().unwrap();
                 let value =
                     args.named::<Value>(&name)?.unwrap_or_else(|| default.clone());
-                vm.define(name, value);
+                vm.define_param(name, value);
             }
         }
     }
@@ -349,7 +526,7 @@ pub(crate) fn call_closure(
             if let Some(sink_pos_values) = sink_pos_values {
                 remaining_args.items.extend(sink_pos_values);
             }
-            vm.define(sink_name, remaining_args);
+            vm.define_param(sink_name, remaining_args);
         }
     }
 
@@ -376,12 +553,103 @@ fn in_math(expr: ast::Expr) -> bool {
     }
 }
 
+/// Capture the variables that the given closure or context expression uses
+/// from the scopes.
+///
+/// Which variables are used only depends on the syntax, so the names are
+/// collected once per node and each instantiation only looks them up.
+pub(crate) fn capture(scopes: &Scopes, node: &SyntaxNode, capturer: Capturer) -> Scope {
+    let names = match has_unique_span(node).then(|| cached_captured_names(BySpan(node))) {
+        // Spans of removed nodes may be reused after an edit, so the cached
+        // names are only valid for the very same node. Comparing the nodes
+        // is cheap as they usually share their allocation.
+        Some((cached, names)) if cached == *node => names,
+        _ => captured_names(node),
+    };
+
+    let mut captures = Scope::new();
+    for (name, math) in names.iter() {
+        capture_from(&mut captures, Some(scopes), name, *math, capturer);
+    }
+    captures
+}
+
+/// Whether the node's span identifies it within the current version of its
+/// file. This is the case for inner nodes of parsed files. In code passed to
+/// `eval`, all nodes share the span of the call instead.
+fn has_unique_span(node: &SyntaxNode) -> bool {
+    !node.span().is_detached()
+        && node.children().len() > 0
+        && node.children().all(|child| child.span() != node.span())
+}
+
+/// A syntax node that is only hashed by its span.
+struct BySpan<'a>(&'a SyntaxNode);
+
+impl Hash for BySpan<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.span().hash(state);
+    }
+}
+
+/// The captured names of a node with a [unique span](has_unique_span),
+/// memoized by that span rather than by the node's whole subtree. Returns the
+/// node the names were collected for along with them.
+#[comemo::memoize]
+fn cached_captured_names(node: BySpan) -> (SyntaxNode, Arc<[(EcoString, bool)]>) {
+    (node.0.clone(), captured_names(node.0))
+}
+
+/// The names of the variables that are used but not bound within the node, in
+/// the order of their first use, along with whether they are used in math.
+fn captured_names(node: &SyntaxNode) -> Arc<[(EcoString, bool)]> {
+    free_names(std::slice::from_ref(node)).into_iter().collect()
+}
+
+/// The names of the variables that are used but not bound within a sequence
+/// of sibling nodes, along with whether they are used in math.
+///
+/// Bindings of earlier nodes are visible in later ones, like those of
+/// top-level `let` expressions in markup.
+pub(crate) fn free_names(nodes: &[SyntaxNode]) -> IndexSet<(EcoString, bool)> {
+    let mut visitor = CapturesVisitor::new(None, Capturer::Function);
+    for node in nodes {
+        visitor.visit(node);
+    }
+    visitor.names
+}
+
+/// Define a variable in the captured scope with its value from the external
+/// scopes. Variables that are not defined externally are not captured.
+/// Without external scopes, all variables are captured as `none`.
+fn capture_from(
+    captures: &mut Scope,
+    external: Option<&Scopes>,
+    name: &str,
+    math: bool,
+    capturer: Capturer,
+) {
+    let value = match external {
+        Some(external) => match external.find(name, math) {
+            Some(value) => value.clone(),
+            None => return,
+        },
+        None => Value::None,
+    };
+
+    let span = external
+        .and_then(|external| external.get_span(name))
+        .unwrap_or_else(Span::detached);
+    captures.define_captured(name, value, capturer, span);
+}
+
 /// A visitor that determines which variables to capture for a closure.
 pub struct CapturesVisitor<'a> {
     external: Option<&'a Scopes<'a>>,
     internal: Scopes<'a>,
     captures: Scope,
     capturer: Capturer,
+    names: IndexSet<(EcoString, bool)>,
 }
 
 impl<'a> CapturesVisitor<'a> {
@@ -392,6 +660,7 @@ impl<'a> CapturesVisitor<'a> {
             internal: Scopes::new(None),
             captures: Scope::new(),
             capturer,
+            names: IndexSet::new(),
         }
     }
 
@@ -407,10 +676,8 @@ impl<'a> CapturesVisitor<'a> {
             // Identifiers that shouldn't count as captures because they
             // actually bind a new name are handled below (individually through
             // the expressions that contain them).
-            Some(ast::Expr::Ident(ident)) => self.capture(&ident, Scopes::get),
-            Some(ast::Expr::MathIdent(ident)) => {
-                self.capture(&ident, Scopes::get_in_math)
-            }
+            Some(ast::Expr::Ident(ident)) => self.capture(&ident, false),
+            Some(ast::Expr::MathIdent(ident)) => self.capture(&ident, true),
 
             // Code and content blocks create a scope.
             Some(ast::Expr::Code(_) | ast::Expr::Content(_)) => {
@@ -521,21 +788,10 @@ impl<'a> CapturesVisitor<'a> {
     }
 
     /// Capture a variable if it isn't internal.
-    fn capture(
-        &mut self,
-        ident: &str,
-        getter: impl FnOnce(&'a Scopes<'a>, &str) -> HintedStrResult<&'a Value>,
-    ) {
+    fn capture(&mut self, ident: &str, math: bool) {
         if self.internal.get(ident).is_err() {
-            let Some(value) = self
-                .external
-                .map(|external| getter(external, ident).ok())
-                .unwrap_or(Some(&Value::None))
-            else {
-                return;
-            };
-
-            self.captures.define_captured(ident, value.clone(), self.capturer);
+            self.names.insert((ident.into(), math));
+            capture_from(&mut self.captures, self.external, ident, math, self.capturer);
         }
     }
 }
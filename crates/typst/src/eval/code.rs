@@ -1,9 +1,10 @@
 use ecow::{eco_vec, EcoVec};
 
 use crate::diag::{bail, error, At, SourceResult};
-use crate::eval::{ops, CapturesVisitor, Eval, Vm};
+use crate::eval::{capture, ops, Eval, Vm};
 use crate::foundations::{
-    Array, Capturer, Closure, Content, ContextElem, Dict, Func, NativeElement, Str, Value,
+    Array, Capturer, Closure, Content, ContextElem, Dict, Func, NativeElement, Str,
+    Style, Value,
 };
 use crate::syntax::ast::{self, AstNode};
 
@@ -32,7 +33,7 @@ fn eval_code<'a>(
                     break;
                 }
 
-                let tail = eval_code(vm, exprs)?.display();
+                let tail = vm.with_styles(&styles, |vm| eval_code(vm, exprs))?.display();
                 Value::Content(tail.styled_with_map(styles))
             }
             ast::Expr::Show(show) => {
@@ -70,6 +71,8 @@ impl Eval for ast::Expr<'_> {
 
     fn eval(self, vm: &mut Vm) -> SourceResult<Self::Output> {
         let span = self.span();
+        vm.step(span)?;
+
         let forbidden = |name| {
             error!(span, "{} is only allowed directly in code and content blocks", name)
         };
@@ -122,7 +125,9 @@ impl Eval for ast::Expr<'_> {
             Self::Let(v) => v.eval(vm),
             Self::DestructAssign(v) => v.eval(vm),
             Self::Set(_) => bail!(forbidden("set")),
-            Self::Show(_) => bail!(forbidden("show")),
+            Self::Show(v) => {
+                v.eval(vm).map(|recipe| Value::Styles(Style::from(recipe).into()))
+            }
             Self::Contextual(v) => v.eval(vm).map(Value::Content),
             Self::Conditional(v) => v.eval(vm),
             Self::While(v) => v.eval(vm),
@@ -147,7 +152,9 @@ impl Eval for ast::Ident<'_> {
     type Output = Value;
 
     fn eval(self, vm: &mut Vm) -> SourceResult<Self::Output> {
-        vm.scopes.get(&self).cloned().at(self.span())
+        let value = vm.scopes.get(&self).cloned().at(self.span())?;
+        vm.record_usage(&self, self.span());
+        Ok(value)
     }
 }
 
@@ -211,21 +218,29 @@ impl Eval for ast::Array<'_> {
     type Output = Array;
 
     fn eval(self, vm: &mut Vm) -> SourceResult<Self::Output> {
-        let items = self.items();
-
-        let mut vec = EcoVec::with_capacity(items.size_hint().0);
-        for item in items {
-            match item {
-                ast::ArrayItem::Pos(expr) => vec.push(expr.eval(vm)?),
-                ast::ArrayItem::Spread(spread) => match spread.expr().eval(vm)? {
-                    Value::None => {}
-                    Value::Array(array) => vec.extend(array.into_iter()),
-                    v => bail!(spread.span(), "cannot spread {} into array", v.ty()),
-                },
+        vm.nested(|vm| {
+            let items = self.items();
+
+            let mut vec = EcoVec::with_capacity(items.size_hint().0);
+            for item in items {
+                match item {
+                    ast::ArrayItem::Pos(expr) => vec.push(expr.eval(vm)?),
+                    ast::ArrayItem::Spread(spread) => match spread.expr().eval(vm)? {
+                        Value::None => {}
+                        Value::Array(array) => vec.extend(array.into_iter()),
+                        Value::Dict(dict) => vec.extend(dict.pairs()),
+                        Value::Str(string) => vec.extend(string.clusters()),
+                        Value::Args(_) => bail!(
+                            spread.span(), "cannot spread arguments into array";
+                            hint: "use `.pos()` to spread the positional arguments"
+                        ),
+                        v => bail!(spread.span(), "cannot spread {} into array", v.ty()),
+                    },
+                }
             }
-        }
 
-        Ok(vec.into())
+            Ok(vec.into())
+        })
     }
 }
 
@@ -233,37 +248,58 @@ impl Eval for ast::Dict<'_> {
     type Output = Dict;
 
     fn eval(self, vm: &mut Vm) -> SourceResult<Self::Output> {
-        let mut map = indexmap::IndexMap::new();
-        let mut invalid_keys = eco_vec![];
-
-        for item in self.items() {
-            match item {
-                ast::DictItem::Named(named) => {
-                    map.insert(named.name().get().clone().into(), named.expr().eval(vm)?);
+        vm.nested(|vm| {
+            let mut map = indexmap::IndexMap::new();
+            let mut invalid_keys = eco_vec![];
+
+            for item in self.items() {
+                match item {
+                    ast::DictItem::Named(named) => {
+                        map.insert(
+                            named.name().get().clone().into(),
+                            named.expr().eval(vm)?,
+                        );
+                    }
+                    ast::DictItem::Keyed(keyed) => {
+                        let raw_key = keyed.key();
+                        let key = raw_key.eval(vm)?;
+                        let key = key.cast::<Str>().at(raw_key.span()).unwrap_or_else(
+                            |errors| {
+                                invalid_keys.extend(errors);
+                                Str::default()
+                            },
+                        );
+                        map.insert(key, keyed.expr().eval(vm)?);
+                    }
+                    ast::DictItem::Spread(spread) => match spread.expr().eval(vm)? {
+                        Value::None => {}
+                        // Spreading into an empty dictionary takes over the
+                        // map without hashing the keys again.
+                        Value::Dict(dict) if map.is_empty() => map = dict.into_map(),
+                        Value::Dict(dict) => map.extend(dict.into_iter()),
+                        Value::Array(_) => bail!(
+                            spread.span(), "cannot spread array into dictionary";
+                            hint: "use `.to-dict()` to turn an array of pairs into a dictionary"
+                        ),
+                        Value::Args(_) => bail!(
+                            spread.span(), "cannot spread arguments into dictionary";
+                            hint: "use `.named()` to spread the named arguments"
+                        ),
+                        v => bail!(
+                            spread.span(),
+                            "cannot spread {} into dictionary",
+                            v.ty()
+                        ),
+                    },
                 }
-                ast::DictItem::Keyed(keyed) => {
-                    let raw_key = keyed.key();
-                    let key = raw_key.eval(vm)?;
-                    let key =
-                        key.cast::<Str>().at(raw_key.span()).unwrap_or_else(|errors| {
-                            invalid_keys.extend(errors);
-                            Str::default()
-                        });
-                    map.insert(key, keyed.expr().eval(vm)?);
-                }
-                ast::DictItem::Spread(spread) => match spread.expr().eval(vm)? {
-                    Value::None => {}
-                    Value::Dict(dict) => map.extend(dict.into_iter()),
-                    v => bail!(spread.span(), "cannot spread {} into dictionary", v.ty()),
-                },
             }
-        }
 
-        if !invalid_keys.is_empty() {
-            return Err(invalid_keys);
-        }
+            if !invalid_keys.is_empty() {
+                return Err(invalid_keys);
+            }
 
-        Ok(map.into())
+            Ok(map.into())
+        })
     }
 }
 
@@ -271,10 +307,13 @@ impl Eval for ast::CodeBlock<'_> {
     type Output = Value;
 
     fn eval(self, vm: &mut Vm) -> SourceResult<Self::Output> {
-        vm.scopes.enter();
-        let output = self.body().eval(vm)?;
-        vm.scopes.exit();
-        Ok(output)
+        vm.nested(|vm| {
+            vm.scopes.enter();
+            let output = self.body().eval(vm)?;
+            vm.check_unused_imports();
+            vm.scopes.exit();
+            Ok(output)
+        })
     }
 }
 
@@ -282,10 +321,13 @@ impl Eval for ast::ContentBlock<'_> {
     type Output = Content;
 
     fn eval(self, vm: &mut Vm) -> SourceResult<Self::Output> {
-        vm.scopes.enter();
-        let content = self.body().eval(vm)?;
-        vm.scopes.exit();
-        Ok(content)
+        vm.nested(|vm| {
+            vm.scopes.enter();
+            let content = self.body().eval(vm)?;
+            vm.check_unused_imports();
+            vm.scopes.exit();
+            Ok(content)
+        })
     }
 }
 
@@ -293,7 +335,7 @@ impl Eval for ast::Parenthesized<'_> {
     type Output = Value;
 
     fn eval(self, vm: &mut Vm) -> SourceResult<Self::Output> {
-        self.expr().eval(vm)
+        vm.nested(|vm| self.expr().eval(vm))
     }
 }
 
@@ -304,7 +346,13 @@ impl Eval for ast::FieldAccess<'_> {
         let value = self.target().eval(vm)?;
         let field = self.field();
 
-        let err = match value.field(&field).at(field.span()) {
+        // For symbols, hint at the modifiers that could be applied instead.
+        let result = value.field(&field).map_err(|err| match &value {
+            Value::Symbol(symbol) => symbol.unknown_modifier(),
+            _ => err.into(),
+        });
+
+        let err = match result.at(field.span()) {
             Ok(value) => return Ok(value),
             Err(err) => err,
         };
@@ -338,11 +386,7 @@ impl Eval for ast::Contextual<'_> {
         let body = self.body();
 
         // Collect captured variables.
-        let captured = {
-            let mut visitor = CapturesVisitor::new(Some(&vm.scopes), Capturer::Context);
-            visitor.visit(body.to_untyped());
-            visitor.finish()
-        };
+        let captured = capture(&vm.scopes, body.to_untyped(), Capturer::Context);
 
         // Define the closure.
         let closure = Closure {
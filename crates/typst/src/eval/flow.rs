@@ -1,8 +1,13 @@
+use std::collections::HashSet;
+
+use ecow::EcoString;
 use unicode_segmentation::UnicodeSegmentation;
 
-use crate::diag::{bail, error, At, SourceDiagnostic, SourceResult};
-use crate::eval::{destructure, ops, Eval, Vm};
-use crate::foundations::{IntoValue, Value};
+use crate::diag::{bail, error, warning, At, SourceDiagnostic, SourceResult};
+use crate::eval::{destructure_loop, ops, Eval, Vm};
+use crate::foundations::{
+    is_builder_method, is_mutating_method, IntoValue, Label, Repr, Value,
+};
 use crate::syntax::ast::{self, AstNode};
 use crate::syntax::{Span, SyntaxKind, SyntaxNode};
 
@@ -12,10 +17,12 @@ const MAX_ITERATIONS: usize = 10_000;
 /// A control flow event that occurred during evaluation.
 #[derive(Debug, Clone, PartialEq)]
 pub(crate) enum FlowEvent {
-    /// Stop iteration in a loop.
-    Break(Span),
-    /// Skip the remainder of the current iteration in a loop.
-    Continue(Span),
+    /// Stop iteration in a loop, optionally targeting an outer loop by its
+    /// label.
+    Break(Span, Option<Label>),
+    /// Skip the remainder of the current iteration in a loop, optionally
+    /// targeting an outer loop by its label.
+    Continue(Span, Option<Label>),
     /// Stop execution of a function early, optionally returning an explicit
     /// value.
     Return(Span, Option<Value>),
@@ -24,16 +31,41 @@ pub(crate) enum FlowEvent {
 impl FlowEvent {
     /// Return an error stating that this control flow is forbidden.
     pub fn forbidden(&self) -> SourceDiagnostic {
-        match *self {
-            Self::Break(span) => {
+        let error = match *self {
+            Self::Break(span, Some(label)) | Self::Continue(span, Some(label)) => {
+                error!(span, "no enclosing loop is labelled {}", label.repr())
+            }
+            Self::Break(span, None) => {
                 error!(span, "cannot break outside of loop")
             }
-            Self::Continue(span) => {
+            Self::Continue(span, None) => {
                 error!(span, "cannot continue outside of loop")
             }
             Self::Return(span, _) => {
                 error!(span, "cannot return outside of function")
             }
+        };
+        error.uncatchable()
+    }
+
+    /// Handle this event at the end of an iteration of the loop with the given
+    /// label, returning whether the loop should stop.
+    ///
+    /// Breaks and continues that target the loop are consumed. All other events
+    /// stop the loop and keep propagating.
+    fn handle(flow: &mut Option<Self>, label: Option<Label>) -> bool {
+        let targeted = |target: Option<Label>| target.is_none() || target == label;
+        match *flow {
+            Some(Self::Break(_, target)) if targeted(target) => {
+                *flow = None;
+                true
+            }
+            Some(Self::Continue(_, target)) if targeted(target) => {
+                *flow = None;
+                false
+            }
+            Some(_) => true,
+            None => false,
         }
     }
 }
@@ -62,30 +94,32 @@ impl Eval for ast::WhileLoop<'_> {
         let mut output = Value::None;
         let mut i = 0;
 
+        let label = self.label().map(|label| Label::new(label.get()));
         let condition = self.condition();
         let body = self.body();
 
         while condition.eval(vm)?.cast::<bool>().at(condition.span())? {
-            if i == 0
-                && is_invariant(condition.to_untyped())
-                && !can_diverge(body.to_untyped())
-            {
-                bail!(condition.span(), "condition is always true");
-            } else if i >= MAX_ITERATIONS {
+            if i == 0 && !can_diverge(body.to_untyped()) {
+                let mut mutated = HashSet::new();
+                find_mutated(condition.to_untyped(), &mut mutated);
+                find_mutated(body.to_untyped(), &mut mutated);
+                if is_invariant(condition.to_untyped(), &mutated) {
+                    vm.engine.lint(warning!(
+                        condition.span(), "condition is always true";
+                        hint: "the loop can only end if a variable in the condition changes"
+                    ))?;
+                }
+            }
+
+            if i >= MAX_ITERATIONS {
                 bail!(self.span(), "loop seems to be infinite");
             }
 
             let value = body.eval(vm)?;
             output = ops::join(output, value).at(body.span())?;
 
-            match vm.flow {
-                Some(FlowEvent::Break(_)) => {
-                    vm.flow = None;
-                    break;
-                }
-                Some(FlowEvent::Continue(_)) => vm.flow = None,
-                Some(FlowEvent::Return(..)) => break,
-                None => {}
+            if FlowEvent::handle(&mut vm.flow, label) {
+                break;
             }
 
             i += 1;
@@ -106,6 +140,7 @@ impl Eval for ast::ForLoop<'_> {
     fn eval(self, vm: &mut Vm) -> SourceResult<Self::Output> {
         let flow = vm.flow.take();
         let mut output = Value::None;
+        let label = self.label().map(|label| Label::new(label.get()));
 
         macro_rules! iter {
             (for $pat:ident in $iterable:expr) => {{
@@ -113,20 +148,14 @@ impl Eval for ast::ForLoop<'_> {
 
                 #[allow(unused_parens)]
                 for value in $iterable {
-                    destructure(vm, $pat, value.into_value())?;
+                    destructure_loop(vm, $pat, value.into_value())?;
 
                     let body = self.body();
                     let value = body.eval(vm)?;
                     output = ops::join(output, value).at(body.span())?;
 
-                    match vm.flow {
-                        Some(FlowEvent::Break(_)) => {
-                            vm.flow = None;
-                            break;
-                        }
-                        Some(FlowEvent::Continue(_)) => vm.flow = None,
-                        Some(FlowEvent::Return(..)) => break,
-                        None => {}
+                    if FlowEvent::handle(&mut vm.flow, label) {
+                        break;
                     }
                 }
 
@@ -177,7 +206,8 @@ impl Eval for ast::LoopBreak<'_> {
 
     fn eval(self, vm: &mut Vm) -> SourceResult<Self::Output> {
         if vm.flow.is_none() {
-            vm.flow = Some(FlowEvent::Break(self.span()));
+            let label = self.label().map(|label| Label::new(label.get()));
+            vm.flow = Some(FlowEvent::Break(self.span(), label));
         }
         Ok(Value::None)
     }
@@ -188,7 +218,8 @@ impl Eval for ast::LoopContinue<'_> {
 
     fn eval(self, vm: &mut Vm) -> SourceResult<Self::Output> {
         if vm.flow.is_none() {
-            vm.flow = Some(FlowEvent::Continue(self.span()));
+            let label = self.label().map(|label| Label::new(label.get()));
+            vm.flow = Some(FlowEvent::Continue(self.span(), label));
         }
         Ok(Value::None)
     }
@@ -206,19 +237,60 @@ impl Eval for ast::FuncReturn<'_> {
     }
 }
 
-/// Whether the expression always evaluates to the same value.
-fn is_invariant(expr: &SyntaxNode) -> bool {
+/// Whether the expression always evaluates to the same value, given the
+/// names of the variables that may change in between evaluations.
+fn is_invariant(expr: &SyntaxNode, mutated: &HashSet<EcoString>) -> bool {
     match expr.cast() {
-        Some(ast::Expr::Ident(_)) => false,
+        Some(ast::Expr::Ident(ident)) => !mutated.contains(ident.get()),
         Some(ast::Expr::MathIdent(_)) => false,
-        Some(ast::Expr::FieldAccess(access)) => {
-            is_invariant(access.target().to_untyped())
-        }
-        Some(ast::Expr::FuncCall(call)) => {
-            is_invariant(call.callee().to_untyped())
-                && is_invariant(call.args().to_untyped())
-        }
-        _ => expr.children().all(is_invariant),
+        _ => expr.children().all(|child| is_invariant(child, mutated)),
+    }
+}
+
+/// Collect the names of all variables that the expression assigns to or
+/// calls a mutating method on. For an assignment like `{x.at(i) = 1}`, all
+/// names on its left-hand side are collected to stay on the safe side.
+fn find_mutated(expr: &SyntaxNode, mutated: &mut HashSet<EcoString>) {
+    let target = match expr.cast() {
+        Some(ast::Expr::Binary(binary)) => matches!(
+            binary.op(),
+            ast::BinOp::Assign
+                | ast::BinOp::AddAssign
+                | ast::BinOp::SubAssign
+                | ast::BinOp::MulAssign
+                | ast::BinOp::DivAssign
+        )
+        .then(|| binary.lhs().to_untyped()),
+        Some(ast::Expr::DestructAssign(assign)) => Some(assign.pattern().to_untyped()),
+        Some(ast::Expr::FuncCall(call)) => match call.callee() {
+            ast::Expr::FieldAccess(access)
+                if is_mutating_method(access.field().get())
+                    || is_builder_method(access.field().get()) =>
+            {
+                Some(access.target().to_untyped())
+            }
+            _ => None,
+        },
+        _ => None,
+    };
+
+    if let Some(target) = target {
+        collect_idents(target, mutated);
+    }
+
+    for child in expr.children() {
+        find_mutated(child, mutated);
+    }
+}
+
+/// Collect the names of all identifiers in the expression.
+fn collect_idents(expr: &SyntaxNode, names: &mut HashSet<EcoString>) {
+    if let Some(ident) = expr.cast::<ast::Ident>() {
+        names.insert(ident.get().clone());
+    }
+
+    for child in expr.children() {
+        collect_idents(child, names);
     }
 }
 
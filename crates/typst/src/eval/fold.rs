@@ -0,0 +1,124 @@
+use crate::diag::SourceResult;
+use crate::eval::{ops, Vm};
+use crate::foundations::Value;
+use crate::syntax::ast::{self, AstNode};
+use crate::syntax::SyntaxNode;
+
+/// Evaluate a unary or binary operation, reusing the result of an earlier
+/// evaluation if the expression only consists of literals and pure operators.
+///
+/// Errors are never cached, so that they are reported at the right span every
+/// time.
+pub(crate) fn fold<F>(vm: &mut Vm, expr: ast::Expr, f: F) -> SourceResult<Value>
+where
+    F: FnOnce(&mut Vm) -> SourceResult<Value>,
+{
+    if vm.inspected.is_some() || !is_constant(expr) {
+        return f(vm);
+    }
+
+    match constant(expr.to_untyped()) {
+        Some(value) => Ok(value),
+        None => f(vm),
+    }
+}
+
+/// Compute the value of a constant expression.
+///
+/// This is memoized by the expression's syntax, so that the value is reused
+/// across edits as long as the expression itself doesn't change. Returns
+/// `None` if the expression fails, leaving the error to the regular
+/// evaluation.
+#[comemo::memoize]
+fn constant(node: &SyntaxNode) -> Option<Value> {
+    evaluate(node.cast()?)
+}
+
+/// Evaluate a constant expression without a virtual machine.
+fn evaluate(expr: ast::Expr) -> Option<Value> {
+    Some(match expr {
+        ast::Expr::Int(int) => Value::Int(int.get()),
+        ast::Expr::Float(float) => Value::Float(float.get()),
+        ast::Expr::Bool(bool) => Value::Bool(bool.get()),
+        ast::Expr::Str(str) => Value::Str(str.get().into()),
+        ast::Expr::Parenthesized(parenthesized) => evaluate(parenthesized.expr())?,
+        ast::Expr::Unary(unary) => {
+            let value = evaluate(unary.expr())?;
+            let result = match unary.op() {
+                ast::UnOp::Pos => ops::pos(value),
+                ast::UnOp::Neg => ops::neg(value),
+                ast::UnOp::Not => ops::not(value),
+            };
+            result.ok()?
+        }
+        ast::Expr::Binary(binary) => {
+            let lhs = evaluate(binary.lhs())?;
+            let op = match binary.op() {
+                ast::BinOp::Add => ops::add,
+                ast::BinOp::Sub => ops::sub,
+                ast::BinOp::Mul => ops::mul,
+                ast::BinOp::Div => ops::div,
+                ast::BinOp::Eq => ops::eq,
+                ast::BinOp::Neq => ops::neq,
+                ast::BinOp::Lt => ops::lt,
+                ast::BinOp::Leq => ops::leq,
+                ast::BinOp::Gt => ops::gt,
+                ast::BinOp::Geq => ops::geq,
+                op @ (ast::BinOp::And | ast::BinOp::Or) => {
+                    let Value::Bool(lhs) = lhs else { return None };
+                    if lhs == (op == ast::BinOp::Or) {
+                        return Some(Value::Bool(lhs));
+                    }
+                    let Value::Bool(rhs) = evaluate(binary.rhs())? else { return None };
+                    return Some(Value::Bool(rhs));
+                }
+                op @ (ast::BinOp::In | ast::BinOp::NotIn) => {
+                    let rhs = evaluate(binary.rhs())?;
+                    let negated = op == ast::BinOp::NotIn;
+                    return Some(Value::Bool(ops::contains(&lhs, &rhs)? != negated));
+                }
+                _ => return None,
+            };
+            op(lhs, evaluate(binary.rhs())?).ok()?
+        }
+        _ => return None,
+    })
+}
+
+/// Whether an expression only consists of literals combined with pure
+/// operators, so that its value never changes.
+fn is_constant(expr: ast::Expr) -> bool {
+    match expr {
+        ast::Expr::Int(_)
+        | ast::Expr::Float(_)
+        | ast::Expr::Bool(_)
+        | ast::Expr::Str(_) => true,
+        ast::Expr::Parenthesized(parenthesized) => is_constant(parenthesized.expr()),
+        ast::Expr::Unary(unary) => is_constant(unary.expr()),
+        ast::Expr::Binary(binary) => {
+            is_pure(binary.op()) && is_constant(binary.lhs()) && is_constant(binary.rhs())
+        }
+        _ => false,
+    }
+}
+
+/// Whether a binary operator computes a value without side effects.
+fn is_pure(op: ast::BinOp) -> bool {
+    matches!(
+        op,
+        ast::BinOp::Add
+            | ast::BinOp::Sub
+            | ast::BinOp::Mul
+            | ast::BinOp::Div
+            | ast::BinOp::And
+            | ast::BinOp::Or
+            | ast::BinOp::Eq
+            | ast::BinOp::Neq
+            | ast::BinOp::Lt
+            | ast::BinOp::Leq
+            | ast::BinOp::Gt
+            | ast::BinOp::Geq
+            | ast::BinOp::In
+            | ast::BinOp::NotIn
+    )
+}
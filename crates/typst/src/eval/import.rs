@@ -1,18 +1,36 @@
-use comemo::TrackedMut;
+use std::time::Instant;
+
+use comemo::{Track, Tracked, TrackedMut};
 use ecow::{eco_format, eco_vec, EcoString};
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
 
-use crate::diag::{bail, error, warning, At, FileError, SourceResult, Trace, Tracepoint};
-use crate::eval::{eval, Eval, Vm};
-use crate::foundations::{Content, Module, Value};
+use crate::diag::{
+    bail, error, warning, At, FileError, SourceResult, StrResult, Trace, Tracepoint,
+};
+use crate::engine::{DefinitionKind, ImportRecord, Route, Sink, Traced};
+use crate::eval::{cache, eval, Eval, Vm};
+use crate::foundations::{Array, Bytes, Content, Module, Repr, Value};
 use crate::syntax::ast::{self, AstNode};
 use crate::syntax::package::{PackageManifest, PackageSpec};
-use crate::syntax::{FileId, Span, VirtualPath};
+use crate::syntax::{FileId, Source, Span, VirtualPath};
 use crate::World;
 
 impl Eval for ast::ModuleImport<'_> {
     type Output = Value;
 
     fn eval(self, vm: &mut Vm) -> SourceResult<Self::Output> {
+        // Read the items from the module if it was resolved before the
+        // enclosing chunk of markup was evaluated.
+        if let (Some(resolved), Some(ast::Imports::Items(items))) =
+            (vm.imports, self.imports())
+        {
+            let span = self.span();
+            if resolved.contains(span) {
+                import_items(vm, items, |name| resolved.get(span, name))?;
+                return Ok(Value::None);
+            }
+        }
+
         let source = self.source();
         let source_span = source.span();
         let mut source = source.eval(vm)?;
@@ -43,7 +61,17 @@ impl Eval for ast::ModuleImport<'_> {
             }
 
             // Define renamed module on the scope.
-            vm.scopes.top.define(new_name.as_str(), source.clone());
+            let span = new_name.span();
+            vm.scopes
+                .top
+                .define_spanned(new_name.get().clone(), source.clone(), span);
+            if vm.analyzed {
+                vm.record_definition(
+                    new_name.get().clone(),
+                    span,
+                    DefinitionKind::Import,
+                );
+            }
         }
 
         let scope = source.scope().unwrap();
@@ -52,7 +80,14 @@ impl Eval for ast::ModuleImport<'_> {
                 // Only import here if there is no rename.
                 if new_name.is_none() {
                     let name: EcoString = source.name().unwrap().into();
-                    vm.scopes.top.define(name, source);
+                    if vm.analyzed {
+                        vm.record_definition(
+                            name.clone(),
+                            source_span,
+                            DefinitionKind::Import,
+                        );
+                    }
+                    vm.scopes.top.define_spanned(name, source, source_span);
                 }
             }
             Some(ast::Imports::Wildcard) => {
@@ -61,73 +96,140 @@ impl Eval for ast::ModuleImport<'_> {
                 }
             }
             Some(ast::Imports::Items(items)) => {
-                let mut errors = eco_vec![];
-                for item in items.iter() {
-                    let mut path = item.path().iter().peekable();
-                    let mut scope = scope;
-
-                    while let Some(component) = &path.next() {
-                        let Some(value) = scope.get(component) else {
-                            errors.push(error!(component.span(), "unresolved import"));
-                            break;
-                        };
-
-                        if path.peek().is_some() {
-                            // Nested import, as this is not the last component.
-                            // This must be a submodule.
-                            let Some(submodule) = value.scope() else {
-                                let error = if matches!(value, Value::Func(function) if function.scope().is_none())
-                                {
-                                    error!(
-                                        component.span(),
-                                        "cannot import from user-defined functions"
-                                    )
-                                } else if !matches!(
-                                    value,
-                                    Value::Func(_) | Value::Module(_) | Value::Type(_)
-                                ) {
-                                    error!(
-                                        component.span(),
-                                        "expected module, function, or type, found {}",
-                                        value.ty()
-                                    )
-                                } else {
-                                    panic!("unexpected nested import failure")
-                                };
-                                errors.push(error);
-                                break;
-                            };
-
-                            // Walk into the submodule.
-                            scope = submodule;
-                        } else {
-                            // Now that we have the scope of the innermost submodule
-                            // in the import path, we may extract the desired item from
-                            // it.
-
-                            // Warn on `import ...: x as x`
-                            if let ast::ImportItem::Renamed(renamed_item) = &item {
-                                if renamed_item.original_name().as_str()
-                                    == renamed_item.new_name().as_str()
-                                {
-                                    vm.engine.sink.warn(warning!(
-                                        renamed_item.new_name().span(),
-                                        "unnecessary import rename to same name",
-                                    ));
-                                }
-                            }
-
-                            vm.define(item.bound_name(), value.clone());
-                        }
-                    }
+                import_items(vm, items, |name| scope.get(name).cloned())?;
+            }
+        }
+
+        Ok(Value::None)
+    }
+}
+
+/// Define the items of an import, looking up the first component of each
+/// item's path with `lookup`.
+fn import_items(
+    vm: &mut Vm,
+    items: ast::ImportItems,
+    lookup: impl Fn(&str) -> Option<Value>,
+) -> SourceResult<()> {
+    let mut errors = eco_vec![];
+    for item in items.iter() {
+        let mut path = item.path().iter().peekable();
+        let mut parent: Option<Value> = None;
+
+        while let Some(component) = &path.next() {
+            let found = match &parent {
+                None => lookup(component),
+                Some(parent) => {
+                    parent.scope().and_then(|scope| scope.get(component)).cloned()
                 }
-                if !errors.is_empty() {
-                    return Err(errors);
+            };
+            let Some(value) = found else {
+                errors.push(error!(component.span(), "unresolved import"));
+                break;
+            };
+
+            if path.peek().is_some() {
+                // Nested import, as this is not the last component.
+                // This must be a submodule.
+                if value.scope().is_none() {
+                    let error = if matches!(&value, Value::Func(function) if function.scope().is_none())
+                    {
+                        error!(
+                            component.span(),
+                            "cannot import from user-defined functions"
+                        )
+                    } else if !matches!(
+                        value,
+                        Value::Func(_) | Value::Module(_) | Value::Type(_)
+                    ) {
+                        error!(
+                            component.span(),
+                            "expected module, function, or type, found {}",
+                            value.ty()
+                        )
+                    } else {
+                        panic!("unexpected nested import failure")
+                    };
+                    errors.push(error);
+                    break;
                 }
+
+                // Walk into the submodule.
+                parent = Some(value);
+            } else {
+                // Now that we have the scope of the innermost submodule
+                // in the import path, we may extract the desired item from
+                // it.
+
+                // Warn on `import ...: x as x`
+                if let ast::ImportItem::Renamed(renamed_item) = &item {
+                    if renamed_item.original_name().as_str()
+                        == renamed_item.new_name().as_str()
+                    {
+                        vm.engine.sink.warn(warning!(
+                            renamed_item.new_name().span(),
+                            "unnecessary import rename to same name",
+                        ));
+                    }
+                }
+
+                vm.define_imported(item.bound_name(), value);
             }
         }
+    }
 
-        Ok(Value::None)
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    Ok(())
+}
+
+/// The modules imported by the top-level import statements of a chunk of
+/// markup.
+///
+/// They are resolved before the chunk is evaluated. The chunk then reads the
+/// imported bindings through tracked accesses, so that it only depends on the
+/// values of those bindings instead of on the whole imported file.
+#[derive(Default)]
+pub(crate) struct ResolvedImports(Vec<(Span, Module)>);
+
+impl ResolvedImports {
+    /// Resolve the modules of the imports of specific items from a literal
+    /// path among the expressions.
+    pub(crate) fn resolve(vm: &mut Vm, exprs: &[ast::Expr]) -> Self {
+        let mut resolved = vec![];
+        for expr in exprs {
+            let ast::Expr::Import(stmt) = expr else { continue };
+            let ast::Expr::Str(path) = stmt.source() else { continue };
+            if stmt.new_name().is_some()
+                || !matches!(stmt.imports(), Some(ast::Imports::Items(_)))
+            {
+                continue;
+            }
+
+            // If this fails, the error is reported when the chunk evaluates
+            // the import itself.
+            let source = Value::Str(path.get().into());
+            if let Ok(module) = import(vm, source, path.span(), true) {
+                resolved.push((stmt.span(), module));
+            }
+        }
+        Self(resolved)
+    }
+}
+
+#[comemo::track]
+impl ResolvedImports {
+    /// Whether the module of the import statement at the span was resolved.
+    fn contains(&self, span: Span) -> bool {
+        self.0.iter().any(|(s, _)| *s == span)
+    }
+
+    /// Get a binding of the module imported by the statement at the span.
+    fn get(&self, span: Span, name: &str) -> Option<Value> {
+        let (_, module) = self.0.iter().find(|(s, _)| *s == span)?;
+        module.scope().get(name).cloned()
     }
 }
 
@@ -136,10 +238,37 @@ impl Eval for ast::ModuleInclude<'_> {
 
     fn eval(self, vm: &mut Vm) -> SourceResult<Self::Output> {
         let span = self.source().span();
-        let source = self.source().eval(vm)?;
-        let module = import(vm, source, span, false)?;
-        Ok(module.content())
+        match self.source().eval(vm)? {
+            Value::Array(array) => include_all(vm, array, span),
+            source @ (Value::Str(_) | Value::Module(_)) => {
+                Ok(import(vm, source, span, false)?.content())
+            }
+            v => bail!(span, "expected path, module, or array, found {}", v.ty()),
+        }
+    }
+}
+
+/// Include each path or module in an array in order and join the contents.
+fn include_all(vm: &mut Vm, array: Array, span: Span) -> SourceResult<Content> {
+    let mut seq = Vec::with_capacity(array.len());
+    for (index, item) in array.into_iter().enumerate() {
+        let repr = item.repr();
+        let point = || Tracepoint::Include(index, repr.clone());
+        let module = import(vm, item, span, false)
+            .map_err(|mut errors| {
+                // Errors directly at the include are not traced, so they
+                // name the offending item in a hint instead.
+                for error in errors.make_mut() {
+                    if error.span == span {
+                        error.hint(point().to_string());
+                    }
+                }
+                errors
+            })
+            .trace(vm.world(), point, span)?;
+        seq.push(module.content());
     }
+    Ok(Content::sequence(seq))
 }
 
 /// Process an import of a module relative to the current location.
@@ -172,48 +301,204 @@ pub fn import(
 fn import_package(vm: &mut Vm, spec: PackageSpec, span: Span) -> SourceResult<Module> {
     // Evaluate the manifest.
     let manifest_id = FileId::new(Some(spec.clone()), VirtualPath::new("typst.toml"));
-    let bytes = vm.world().file(manifest_id).at(span)?;
-    let string = std::str::from_utf8(&bytes).map_err(FileError::from).at(span)?;
-    let manifest: PackageManifest = toml::from_str(string)
-        .map_err(|err| eco_format!("package manifest is malformed ({})", err.message()))
-        .at(span)?;
-    manifest.validate(&spec).at(span)?;
+    vm.engine.sink.depend(manifest_id);
+    let (manifest, parsed) = manifest(vm.world(), &spec).at(span)?;
+
+    // Persist a freshly parsed manifest. This happens out here because the
+    // memoized `manifest` must not have side effects.
+    if let Some(bytes) = parsed {
+        cache::store(vm.world(), &spec, &bytes, &manifest);
+    }
 
     // Evaluate the entry point.
     let entrypoint_id = manifest_id.join(&manifest.package.entrypoint);
-    let source = vm.world().source(entrypoint_id).at(span)?;
-    let point = || Tracepoint::Import;
-    Ok(eval(
-        vm.world(),
-        vm.engine.traced,
-        TrackedMut::reborrow_mut(&mut vm.engine.sink),
-        vm.engine.route.track(),
-        &source,
-    )
-    .trace(vm.world(), point, span)?
-    .with_name(manifest.package.name))
+    let source = match vm.engine.source(entrypoint_id) {
+        Ok(source) => source,
+        Err(err) => bail!(
+            span, "{err}";
+            hint: "the file is named as the `entrypoint` in the manifest of {spec}",
+        ),
+    };
+    let module = eval_import(vm, &source, span)?;
+
+    // Record the package with the version declared by its manifest.
+    vm.engine.sink.package(PackageSpec {
+        version: manifest.package.version,
+        name: manifest.package.name.clone(),
+        ..spec
+    });
+
+    Ok(module.with_name(manifest.package.name))
+}
+
+/// Load the manifest of a package, unless the world persisted a validated copy.
+///
+/// If the manifest had to be parsed, the bytes of the manifest file are
+/// returned alongside it, so that the caller can persist it.
+///
+/// This is memoized without regard to the importing file, so that a package's
+/// manifest is read and validated only once, however many files import it.
+#[comemo::memoize]
+fn manifest(
+    world: Tracked<dyn World + '_>,
+    spec: &PackageSpec,
+) -> StrResult<(PackageManifest, Option<Bytes>)> {
+    let id = FileId::new(Some(spec.clone()), VirtualPath::new("typst.toml"));
+    let bytes = world.file(id)?;
+    if let Some(manifest) = cache::load(world, spec, &bytes) {
+        return Ok((manifest, None));
+    }
+
+    let manifest = parse_manifest(spec, &bytes)?;
+    Ok((manifest, Some(bytes)))
+}
+
+/// Parse a package manifest and ensure that it belongs to the package.
+fn parse_manifest(spec: &PackageSpec, bytes: &[u8]) -> StrResult<PackageManifest> {
+    let string = std::str::from_utf8(bytes).map_err(FileError::from)?;
+    let manifest: PackageManifest = toml::from_str(string).map_err(|err| {
+        eco_format!("package manifest is malformed ({})", err.message())
+    })?;
+    manifest.validate(spec)?;
+    Ok(manifest)
 }
 
 /// Import a file from a path.
 fn import_file(vm: &mut Vm, path: &str, span: Span) -> SourceResult<Module> {
     // Load the source file.
-    let world = vm.world();
-    let id = span.resolve_path(path).at(span)?;
-    let source = world.source(id).at(span)?;
+    let id = resolve_import_path(vm, path, span)?;
+    let source = vm.engine.source(id).at(span)?;
 
     // Prevent cyclic importing.
     if vm.engine.route.contains(source.id()) {
-        bail!(span, "cyclic import");
+        let mut diag = error!(span, "cyclic import");
+        if let Some(show) = vm.engine.route.show_since(source.id()) {
+            diag.hint(match locate(vm.world(), show) {
+                Some(location) => eco_format!(
+                    "the cycle passes through a show rule defined at {location}"
+                ),
+                None => "the cycle passes through a show rule".into(),
+            });
+        }
+        return Err(eco_vec![diag]);
     }
 
     // Evaluate the file.
+    eval_import(vm, &source, span)
+}
+
+/// Resolve the path of an import or include.
+fn resolve_import_path(vm: &Vm, path: &str, span: Span) -> SourceResult<FileId> {
+    if let Some(base) = vm.base {
+        return Ok(base.join(path));
+    }
+
+    if span.is_detached() {
+        bail!(
+            span, "cannot import relative to an anonymous string";
+            hint: "provide a base file to resolve the path against"
+        );
+    }
+
+    span.resolve_path(path).at(span)
+}
+
+/// Format the position of a span as `path:line:column`.
+pub(crate) fn locate(world: Tracked<dyn World + '_>, span: Span) -> Option<EcoString> {
+    let id = span.id()?;
+    let source = world.source(id).ok()?;
+    let start = source.range(span)?.start;
+    let line = source.byte_to_line(start)? + 1;
+    let column = source.byte_to_column(start)? + 1;
+    let path = id.vpath().as_rootless_path().display();
+    Some(eco_format!("{path}:{line}:{column}"))
+}
+
+/// Evaluate the files imported by a source file's top-level imports and
+/// includes in parallel.
+///
+/// This is an optional pre-pass: Since evaluation is memoized, the imports
+/// made while subsequently evaluating `source` with the same `world` and
+/// `traced` will hit the cache instead of evaluating each file in turn. Only
+/// imports of literal file paths are considered, dynamic paths and packages
+/// are left to the main pass. Each file is evaluated into its own sink, which
+/// is then discarded. Diagnostics are not lost because the cache replays them
+/// into the main pass's sink. Likewise, cyclic imports are still reported
+/// by the main pass.
+///
+/// Compilation runs this pass if [`Library::prefetch`](crate::Library::prefetch)
+/// is enabled.
+pub fn eval_prefetch(
+    world: Tracked<dyn World + '_>,
+    traced: Tracked<Traced>,
+    source: &Source,
+) {
+    let Some(markup) = source.root().cast::<ast::Markup>() else { return };
+
+    let mut sources: Vec<Source> = vec![];
+    for expr in markup.exprs() {
+        let (path, span) = match expr {
+            ast::Expr::Import(import) => (import.source(), import.span()),
+            ast::Expr::Include(include) => (include.source(), include.span()),
+            _ => continue,
+        };
+
+        let ast::Expr::Str(path) = path else { continue };
+        let path = path.get();
+        if path.starts_with('@') {
+            continue;
+        }
+
+        // Files that fail to load are reported by the main pass. Importing
+        // the file itself is a cycle, which the main pass reports, too.
+        let Ok(id) = span.resolve_path(&path) else { continue };
+        if id == source.id() || sources.iter().any(|source| source.id() == id) {
+            continue;
+        }
+
+        if let Ok(source) = world.source(id) {
+            sources.push(source);
+        }
+    }
+
+    sources.into_par_iter().for_each(|imported| {
+        // Mirror the route that the main pass uses for the import.
+        let root = Route::default();
+        let route = Route::extend(root.track()).with_id(source.id());
+        let mut sink = Sink::new();
+        eval(world, traced, sink.track_mut(), route.track(), &imported).ok();
+    });
+}
+
+/// Evaluate an imported source file and record the import if imports are
+/// profiled.
+fn eval_import(vm: &mut Vm, source: &Source, span: Span) -> SourceResult<Module> {
+    let started = vm
+        .engine
+        .sink
+        .profiled()
+        .then(|| (Instant::now(), ImportRecord::nonce()));
+
     let point = || Tracepoint::Import;
-    eval(
-        world,
+    let module = eval(
+        vm.world(),
         vm.engine.traced,
         TrackedMut::reborrow_mut(&mut vm.engine.sink),
         vm.engine.route.track(),
-        &source,
+        source,
     )
-    .trace(world, point, span)
+    .trace(vm.world(), point, span)?;
+
+    // The sink tells from the nonce of the file's evaluation whether the
+    // module came from the cache.
+    if let Some((start, nonce)) = started {
+        vm.engine.sink.import(ImportRecord {
+            importer: span.id(),
+            id: source.id(),
+            duration: start.elapsed(),
+            nonce,
+        });
+    }
+
+    Ok(module)
 }
@@ -1,6 +1,14 @@
+use comemo::{Track, Tracked, TrackedMut};
+use ecow::eco_format;
+
 use crate::diag::{warning, SourceResult};
-use crate::eval::{Eval, Vm};
-use crate::foundations::{Content, Label, NativeElement, Smart, Unlabellable, Value};
+use crate::engine::{Engine, Route, Sink, Traced};
+use crate::eval::{free_names, Eval, FlowEvent, ResolvedImports, Vm};
+use crate::foundations::{
+    Content, Context, Func, Label, NativeElement, Recipe, Repr, Scope, Scopes, Selector,
+    SequenceBuilder, Smart, Unlabellable, Value,
+};
+use crate::introspection::Introspector;
 use crate::math::EquationElem;
 use crate::model::{
     EmphElem, EnumItem, HeadingElem, LinkElem, ListItem, ParbreakElem, RefElem,
@@ -8,55 +16,189 @@ use crate::model::{
 };
 use crate::symbols::Symbol;
 use crate::syntax::ast::{self, AstNode};
+use crate::syntax::{Span, SyntaxKind, SyntaxNode};
 use crate::text::{
     LinebreakElem, RawContent, RawElem, SmartQuoteElem, SpaceElem, TextElem,
 };
+use crate::{Feature, World};
 
 impl Eval for ast::Markup<'_> {
     type Output = Content;
 
     fn eval(self, vm: &mut Vm) -> SourceResult<Self::Output> {
-        eval_markup(vm, &mut self.exprs())
+        eval_markup(vm, &mut self.exprs(), false, true)
     }
 }
 
-/// Evaluate a stream of markup.
+/// Evaluate the top-level markup of a module.
+///
+/// In contrast to other markup, the expressions between set and show rules
+/// are evaluated in chunks whose results are cached. This way, an edit only
+/// needs to re-evaluate the chunk it happened in and those after it that
+/// depend on changed definitions.
+///
+/// Without `content`, the expressions are only evaluated for their effects on
+/// the module's scope and the produced content is dropped.
+pub(crate) fn eval_module_markup(
+    vm: &mut Vm,
+    markup: ast::Markup,
+    content: bool,
+) -> SourceResult<Content> {
+    eval_markup(vm, &mut markup.exprs(), true, content)
+}
+
+/// Evaluate a stream of markup, building its content only if requested.
 fn eval_markup<'a>(
     vm: &mut Vm,
     exprs: &mut impl Iterator<Item = ast::Expr<'a>>,
+    chunked: bool,
+    content: bool,
 ) -> SourceResult<Content> {
     let flow = vm.flow.take();
-    let mut seq = Vec::with_capacity(exprs.size_hint().1.unwrap_or_default());
+    let mut seq = SequenceBuilder::with_capacity(exprs.size_hint().1.unwrap_or_default());
+
+    loop {
+        // Collect the expressions up to the next set or show rule.
+        let mut run = vec![];
+        let mut rule = None;
+        for expr in exprs.by_ref() {
+            if matches!(expr, ast::Expr::Set(_) | ast::Expr::Show(_)) {
+                rule = Some(expr);
+                break;
+            }
+            run.push(expr);
+        }
 
-    while let Some(expr) = exprs.next() {
-        match expr {
-            ast::Expr::Set(set) => {
+        if chunked && is_chunkable(vm, &seq) {
+            eval_chunked(vm, &run, &mut seq)?;
+        } else {
+            eval_run(vm, run.into_iter(), &mut seq)?;
+        }
+
+        if !content {
+            seq = SequenceBuilder::new();
+        }
+
+        if vm.flow.is_some() {
+            break;
+        }
+
+        match rule {
+            Some(ast::Expr::Set(set)) => {
                 let styles = set.eval(vm)?;
                 if vm.flow.is_some() {
                     break;
                 }
 
-                seq.push(eval_markup(vm, exprs)?.styled_with_map(styles))
+                let tail = vm.with_styles(&styles, |vm| {
+                    eval_markup(vm, exprs, chunked, content)
+                })?;
+                if content {
+                    seq.push(tail.styled_with_map(styles));
+                }
             }
-            ast::Expr::Show(show) => {
+            Some(ast::Expr::Show(show)) => {
                 let recipe = show.eval(vm)?;
                 if vm.flow.is_some() {
                     break;
                 }
 
-                let tail = eval_markup(vm, exprs)?;
-                seq.push(tail.styled_with_recipe(&mut vm.engine, vm.context, recipe)?)
+                if recipe.is_show_everywhere() {
+                    let prev = std::mem::replace(&mut vm.show_everywhere, true);
+                    let tail = eval_markup(vm, exprs, chunked, content);
+                    vm.show_everywhere = prev;
+                    seq.push(tail?);
+                    break;
+                }
+
+                check_retroactive(vm, show, &recipe, seq.children())?;
+                if recipe.everywhere {
+                    if let Some(hoisted) = &mut vm.hoisted {
+                        hoisted.push(recipe);
+                        continue;
+                    }
+                }
+
+                let tail = eval_markup(vm, exprs, chunked, content)?;
+                if content {
+                    let styled =
+                        tail.styled_with_recipe(&mut vm.engine, vm.context, recipe)?;
+                    seq.push(styled);
+                }
             }
-            expr => match expr.eval(vm)? {
-                Value::Label(label) => {
-                    if let Some(elem) =
-                        seq.iter_mut().rev().find(|node| !node.can::<dyn Unlabellable>())
-                    {
-                        *elem = std::mem::take(elem).labelled(label);
+            _ => {}
+        }
+
+        break;
+    }
+
+    if flow.is_some() {
+        vm.flow = flow;
+    }
+
+    if chunked && content {
+        remember_largest(vm, seq.children());
+    }
+
+    Ok(seq.build())
+}
+
+/// Remember the largest piece of the module's content before it is flattened
+/// into the module's sequence, so that an error about content that grew too
+/// large can point at the expression that produced it.
+fn remember_largest(vm: &mut Vm, pieces: &[Content]) {
+    // Pieces without a span wrap the content of nested set and show rules,
+    // whose own pieces were already considered.
+    let Some(piece) = pieces
+        .iter()
+        .filter(|piece| !piece.span().is_detached())
+        .max_by_key(|piece| piece.nodes())
+    else {
+        return;
+    };
+
+    if vm
+        .largest
+        .as_ref()
+        .map_or(true, |largest| piece.nodes() > largest.nodes())
+    {
+        vm.largest = Some(piece.clone());
+    }
+}
+
+/// Evaluate a run of expressions without set and show rules, adding the
+/// results to the sequence.
+fn eval_run<'a>(
+    vm: &mut Vm,
+    exprs: impl Iterator<Item = ast::Expr<'a>>,
+    seq: &mut SequenceBuilder,
+) -> SourceResult<()> {
+    for expr in exprs {
+        if let ast::Expr::Conditional(conditional) = expr {
+            check_scoped_set(vm, conditional);
+        }
+
+        match expr.eval(vm)? {
+            Value::Label(label) => {
+                if let Some(elem) = seq
+                    .children_mut()
+                    .iter_mut()
+                    .rev()
+                    .find(|node| !node.can::<dyn Unlabellable>())
+                {
+                    *elem = std::mem::take(elem).labelled(label);
+                    if let Some(labels) = &mut vm.labels {
+                        labels.push((label, expr.span()));
                     }
                 }
-                value => seq.push(value.display().spanned(expr.span())),
-            },
+            }
+            value => {
+                if let Value::Func(func) = &value {
+                    check_uncalled(vm, expr, func)?;
+                }
+                let content = value.display().spanned(expr.span());
+                seq.push(vm.anchor(content, expr.span()))
+            }
         }
 
         if vm.flow.is_some() {
@@ -64,11 +206,231 @@ fn eval_markup<'a>(
         }
     }
 
-    if flow.is_some() {
-        vm.flow = flow;
+    Ok(())
+}
+
+/// Whether the next run of expressions can be evaluated as a cached chunk.
+///
+/// This is only the case for the module's own scope, if the library allows it,
+/// and if nothing is inspected or analyzed. Moreover, the sequence must be
+/// empty, as a label at the start of the run could otherwise attach to content
+/// from an earlier run.
+fn is_chunkable(vm: &Vm, seq: &SequenceBuilder) -> bool {
+    vm.world().library().chunked
+        && vm.hoisted.is_some()
+        && vm.scopes.scopes.is_empty()
+        && vm.inspected.is_none()
+        && !vm.analyzed
+        && seq.is_empty()
+}
+
+/// Evaluate a run of expressions as a cached chunk, adding the results to the
+/// sequence and applying the chunk's effects to the virtual machine.
+///
+/// The chunk only sees the module's bindings that it uses, so that its cache
+/// key doesn't grow with the module. If it uses a variable that isn't defined
+/// anywhere, the run is evaluated directly instead, so that the error can
+/// suggest similar names from the whole module.
+fn eval_chunked(
+    vm: &mut Vm,
+    run: &[ast::Expr],
+    seq: &mut SequenceBuilder,
+) -> SourceResult<()> {
+    if run.is_empty() {
+        return Ok(());
     }
 
-    Ok(Content::sequence(seq))
+    let nodes: Vec<SyntaxNode> =
+        run.iter().map(|expr| expr.to_untyped().clone()).collect();
+
+    let names = free_names(&nodes);
+    if names.iter().any(|(name, math)| vm.scopes.find(name, *math).is_none()) {
+        return eval_run(vm, run.iter().copied(), seq);
+    }
+    let scope = vm.scopes.top.subset(names.iter().map(|(name, _)| name.as_str()));
+
+    // Resolve imports up front, so that the chunk only depends on the imported
+    // bindings instead of the imported files.
+    let imports = ResolvedImports::resolve(vm, run);
+
+    let chunk = eval_chunk(
+        vm.engine.world,
+        vm.engine.introspector,
+        vm.engine.traced,
+        TrackedMut::reborrow_mut(&mut vm.engine.sink),
+        vm.engine.route.track(),
+        vm.context,
+        &scope,
+        vm.show_everywhere,
+        imports.track(),
+        &nodes,
+    )?;
+
+    // The chunk's scope may be shared with the cache, so its bindings are
+    // copied into the module's scope.
+    vm.scopes.top.absorb(&chunk.scope);
+
+    if let Some(hoisted) = &mut vm.hoisted {
+        hoisted.extend(chunk.hoisted);
+    }
+
+    if let Some(labels) = &mut vm.labels {
+        labels.extend(chunk.labels);
+    }
+
+    seq.append(chunk.seq);
+    vm.flow = chunk.flow;
+    Ok(())
+}
+
+/// The results of evaluating a chunk of markup.
+#[derive(Clone)]
+struct Chunk {
+    /// The content produced by the chunk's expressions.
+    seq: SequenceBuilder,
+    /// The bindings the chunk used or defined, with their final values.
+    scope: Scope,
+    /// Show rules that were hoisted to the whole module.
+    hoisted: Vec<Recipe>,
+    /// The labels attached in the chunk's markup.
+    labels: Vec<(Label, Span)>,
+    /// A control flow event that stopped the chunk's evaluation.
+    flow: Option<FlowEvent>,
+}
+
+/// Evaluate a chunk of markup expressions with the given bindings of the
+/// module's scope.
+#[allow(clippy::too_many_arguments)]
+#[comemo::memoize]
+fn eval_chunk(
+    world: Tracked<dyn World + '_>,
+    introspector: Tracked<Introspector>,
+    traced: Tracked<Traced>,
+    sink: TrackedMut<Sink>,
+    route: Tracked<Route>,
+    context: Tracked<Context>,
+    scope: &Scope,
+    show_everywhere: bool,
+    imports: Tracked<ResolvedImports>,
+    nodes: &[SyntaxNode],
+) -> SourceResult<Chunk> {
+    let engine = Engine {
+        world,
+        introspector,
+        traced,
+        sink,
+        route: Route::extend(route).unnested(),
+    };
+
+    let mut scopes = Scopes::new(Some(world.library()));
+    scopes.top = scope.clone();
+
+    let span = nodes[0].span();
+    let mut vm = Vm::new(engine, context, scopes, span);
+    vm.show_everywhere = show_everywhere;
+    vm.hoisted = Some(vec![]);
+    vm.labels = Some(vec![]);
+    vm.imports = Some(imports);
+
+    let exprs = nodes.iter().filter_map(|node| match node.kind() {
+        SyntaxKind::Space => node.cast().map(ast::Expr::Space),
+        _ => node.cast(),
+    });
+
+    let mut seq = SequenceBuilder::new();
+    eval_run(&mut vm, exprs, &mut seq)?;
+    vm.settle(span)?;
+
+    Ok(Chunk {
+        seq,
+        scope: vm.scopes.top,
+        hoisted: vm.hoisted.unwrap_or_default(),
+        labels: vm.labels.unwrap_or_default(),
+        flow: vm.flow,
+    })
+}
+
+/// Warn about a show rule for a label that was already attached to earlier
+/// content, which the rule won't affect.
+fn check_retroactive(
+    vm: &mut Vm,
+    show: ast::ShowRule,
+    recipe: &Recipe,
+    seq: &[Content],
+) -> SourceResult<()> {
+    let Some(Selector::Label(label)) = recipe.selector else { return Ok(()) };
+    if recipe.everywhere || !seq.iter().any(|content| content.label() == Some(label)) {
+        return Ok(());
+    }
+
+    vm.engine.lint(warning!(
+        show.span(),
+        "show rule does not apply to earlier content labelled {}",
+        label.repr();
+        hint: "move the show rule before the labelled content";
+        hint: "or use `show: show-everywhere` to apply it to the whole file",
+    ))
+}
+
+/// Warn about a function that is embedded into markup by name, which most
+/// likely was meant to be called.
+fn check_uncalled(vm: &mut Vm, expr: ast::Expr, func: &Func) -> SourceResult<()> {
+    if !matches!(expr, ast::Expr::Ident(_) | ast::Expr::FieldAccess(_)) {
+        return Ok(());
+    }
+
+    let text = expr.to_untyped().clone().into_text();
+    let hint = if func.element().is_some() {
+        eco_format!("to apply it to content, write `#{text}[..]`")
+    } else {
+        eco_format!("to call it, write `#{text}(..)`")
+    };
+
+    vm.engine.lint(warning!(
+        expr.span(), "this is a function; did you mean to call it?";
+        hint: "{hint}",
+    ))
+}
+
+/// Warns about a set rule that is the only thing in a branch of a conditional,
+/// like `{if two-column { set page(columns: 2) }}`. It ends with the branch
+/// and thus has no effect.
+fn check_scoped_set(vm: &mut Vm, conditional: ast::Conditional) {
+    let Some(set) = lone_set(ast::Expr::Conditional(conditional)) else { return };
+    let rule = if set.grouped() {
+        "set (..)".into()
+    } else {
+        eco_format!("set {}(..)", set.target().to_untyped().clone().into_text())
+    };
+
+    vm.engine.lint_delayed(warning!(
+        set.span(), "set rule has no effect";
+        hint: "set rules only apply until the end of the block they are in";
+        hint: "to apply it to everything after the conditional, write `{rule} if condition` instead",
+    ));
+}
+
+/// Finds a set rule that is the only expression of a branch, looking into
+/// nested conditionals.
+fn lone_set(expr: ast::Expr) -> Option<ast::SetRule> {
+    match expr {
+        ast::Expr::Set(set) => Some(set),
+        ast::Expr::Code(block) => lone_set(only(block.body().exprs())?),
+        ast::Expr::Content(block) => {
+            lone_set(only(block.body().exprs().filter(|expr| {
+                !matches!(expr, ast::Expr::Space(_) | ast::Expr::Parbreak(_))
+            }))?)
+        }
+        ast::Expr::Conditional(conditional) => lone_set(conditional.if_body())
+            .or_else(|| conditional.else_body().and_then(lone_set)),
+        _ => None,
+    }
+}
+
+/// The only item of an iterator.
+fn only<T>(mut iter: impl Iterator<Item = T>) -> Option<T> {
+    let first = iter.next()?;
+    iter.next().is_none().then_some(first)
 }
 
 impl Eval for ast::Text<'_> {
@@ -131,6 +493,7 @@ impl Eval for ast::Strong<'_> {
     type Output = Content;
 
     fn eval(self, vm: &mut Vm) -> SourceResult<Self::Output> {
+        vm.require(Feature::Content, self.span())?;
         let body = self.body();
         if body.exprs().next().is_none() {
             vm.engine
@@ -149,6 +512,7 @@ impl Eval for ast::Emph<'_> {
     type Output = Content;
 
     fn eval(self, vm: &mut Vm) -> SourceResult<Self::Output> {
+        vm.require(Feature::Content, self.span())?;
         let body = self.body();
         if body.exprs().next().is_none() {
             vm.engine
@@ -166,7 +530,8 @@ impl Eval for ast::Emph<'_> {
 impl Eval for ast::Raw<'_> {
     type Output = Content;
 
-    fn eval(self, _: &mut Vm) -> SourceResult<Self::Output> {
+    fn eval(self, vm: &mut Vm) -> SourceResult<Self::Output> {
+        vm.require(Feature::Content, self.span())?;
         let lines = self.lines().map(|line| (line.get().clone(), line.span())).collect();
         let mut elem = RawElem::new(RawContent::Lines(lines)).with_block(self.block());
         if let Some(lang) = self.lang() {
@@ -179,7 +544,8 @@ impl Eval for ast::Raw<'_> {
 impl Eval for ast::Link<'_> {
     type Output = Content;
 
-    fn eval(self, _: &mut Vm) -> SourceResult<Self::Output> {
+    fn eval(self, vm: &mut Vm) -> SourceResult<Self::Output> {
+        vm.require(Feature::Content, self.span())?;
         Ok(LinkElem::from_url(self.get().clone()).pack())
     }
 }
@@ -196,6 +562,7 @@ impl Eval for ast::Ref<'_> {
     type Output = Content;
 
     fn eval(self, vm: &mut Vm) -> SourceResult<Self::Output> {
+        vm.require(Feature::Content, self.span())?;
         let target = Label::new(self.target());
         let mut elem = RefElem::new(target);
         if let Some(supplement) = self.supplement() {
@@ -211,6 +578,7 @@ impl Eval for ast::Heading<'_> {
     type Output = Content;
 
     fn eval(self, vm: &mut Vm) -> SourceResult<Self::Output> {
+        vm.require(Feature::Content, self.span())?;
         let depth = self.depth();
         let body = self.body().eval(vm)?;
         Ok(HeadingElem::new(body).with_depth(depth).pack())
@@ -221,6 +589,7 @@ impl Eval for ast::ListItem<'_> {
     type Output = Content;
 
     fn eval(self, vm: &mut Vm) -> SourceResult<Self::Output> {
+        vm.require(Feature::Content, self.span())?;
         Ok(ListItem::new(self.body().eval(vm)?).pack())
     }
 }
@@ -229,6 +598,7 @@ impl Eval for ast::EnumItem<'_> {
     type Output = Content;
 
     fn eval(self, vm: &mut Vm) -> SourceResult<Self::Output> {
+        vm.require(Feature::Content, self.span())?;
         let body = self.body().eval(vm)?;
         let mut elem = EnumItem::new(body);
         if let Some(number) = self.number() {
@@ -242,6 +612,7 @@ impl Eval for ast::TermItem<'_> {
     type Output = Content;
 
     fn eval(self, vm: &mut Vm) -> SourceResult<Self::Output> {
+        vm.require(Feature::Content, self.span())?;
         let term = self.term().eval(vm)?;
         let description = self.description().eval(vm)?;
         Ok(TermItem::new(term, description).pack())
@@ -252,6 +623,7 @@ impl Eval for ast::Equation<'_> {
     type Output = Content;
 
     fn eval(self, vm: &mut Vm) -> SourceResult<Self::Output> {
+        vm.require(Feature::Math, self.span())?;
         let body = self.body().eval(vm)?;
         let block = self.block();
         Ok(EquationElem::new(body).with_block(block).pack())
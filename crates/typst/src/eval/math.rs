@@ -2,7 +2,7 @@ use ecow::eco_format;
 
 use crate::diag::{At, SourceResult};
 use crate::eval::{Eval, Vm};
-use crate::foundations::{Content, NativeElement, Value};
+use crate::foundations::{Content, NativeElement, SequenceBuilder, Value};
 use crate::math::{AlignPointElem, AttachElem, FracElem, LrElem, PrimesElem, RootElem};
 use crate::syntax::ast::{self, AstNode};
 use crate::text::TextElem;
@@ -10,11 +10,11 @@ use crate::text::TextElem;
 impl Eval for ast::Math<'_> {
     type Output = Content;
     fn eval(self, vm: &mut Vm) -> SourceResult<Self::Output> {
-        Ok(Content::sequence(
-            self.exprs()
-                .map(|expr| expr.eval_display(vm))
-                .collect::<SourceResult<Vec<_>>>()?,
-        ))
+        let mut seq = SequenceBuilder::new();
+        for expr in self.exprs() {
+            seq.push(expr.eval_display(vm)?);
+        }
+        Ok(seq.build())
     }
 }
 
@@ -22,7 +22,9 @@ impl Eval for ast::MathIdent<'_> {
     type Output = Value;
 
     fn eval(self, vm: &mut Vm) -> SourceResult<Self::Output> {
-        vm.scopes.get_in_math(&self).cloned().at(self.span())
+        let value = vm.scopes.get_in_math(&self).cloned().at(self.span())?;
+        vm.record_usage(&self, self.span());
+        Ok(value)
     }
 }
 
@@ -4,9 +4,11 @@ pub(crate) mod ops;
 
 mod access;
 mod binding;
+mod cache;
 mod call;
 mod code;
 mod flow;
+mod fold;
 mod import;
 mod markup;
 mod math;
@@ -20,26 +22,63 @@ pub use self::vm::*;
 pub(crate) use self::access::*;
 pub(crate) use self::binding::*;
 pub(crate) use self::flow::*;
+pub(crate) use self::fold::*;
+
+use std::collections::{HashMap, HashSet};
 
 use comemo::{Track, Tracked, TrackedMut};
+use ecow::eco_format;
 
-use crate::diag::{bail, SourceResult};
-use crate::engine::{Engine, Route, Sink, Traced};
-use crate::foundations::{Cast, Context, Module, NativeElement, Scope, Scopes, Value};
+use crate::diag::{bail, warning, SourceResult};
+use crate::engine::{Engine, ImportRecord, Route, Sink, Traced};
+use crate::foundations::{
+    Cast, Content, Context, Module, NativeElement, Repr, Scope, Scopes, Value,
+};
 use crate::introspection::Introspector;
 use crate::math::EquationElem;
-use crate::syntax::{ast, parse, parse_code, parse_math, Source, Span};
+use crate::syntax::{ast, parse, parse_code, parse_math, FileId, Source, Span};
 use crate::World;
 
 /// Evaluate a source file and return the resulting module.
-#[comemo::memoize]
-#[typst_macros::time(name = "eval", span = source.root().span())]
 pub fn eval(
     world: Tracked<dyn World + '_>,
     traced: Tracked<Traced>,
     sink: TrackedMut<Sink>,
     route: Tracked<Route>,
     source: &Source,
+) -> SourceResult<Module> {
+    eval_module(world, traced, sink, route, source, true)
+}
+
+/// Evaluate a source file only for its definitions and return the resulting
+/// module, whose content is empty.
+///
+/// All expressions are still evaluated, so the module's scope is the same as
+/// with [`eval`]. Only the content of the file's top-level markup is dropped
+/// instead of being assembled and styled, which saves time for large files
+/// that are only used for their definitions. As a consequence, errors that
+/// only arise from the assembled content are not reported, like those of show
+/// rules applied to it or of content that grows too large.
+pub fn eval_scope(
+    world: Tracked<dyn World + '_>,
+    traced: Tracked<Traced>,
+    sink: TrackedMut<Sink>,
+    route: Tracked<Route>,
+    source: &Source,
+) -> SourceResult<Module> {
+    eval_module(world, traced, sink, route, source, false)
+}
+
+/// Evaluate a source file, building its content only if requested.
+#[comemo::memoize]
+#[typst_macros::time(name = "eval", span = source.root().span())]
+fn eval_module(
+    world: Tracked<dyn World + '_>,
+    traced: Tracked<Traced>,
+    mut sink: TrackedMut<Sink>,
+    route: Tracked<Route>,
+    source: &Source,
+    content: bool,
 ) -> SourceResult<Module> {
     // Prevent cyclic evaluation.
     let id = source.id();
@@ -47,6 +86,12 @@ pub fn eval(
         panic!("Tried to cyclicly evaluate {:?}", id.vpath());
     }
 
+    // Record the evaluation for import profiling. If this evaluation is taken
+    // from the cache, the call is replayed with the same nonce.
+    if sink.profiled() {
+        sink.evaluate(id, ImportRecord::nonce());
+    }
+
     // Prepare the engine.
     let introspector = Introspector::default();
     let engine = Engine {
@@ -71,13 +116,33 @@ pub fn eval(
 
     // Evaluate the module.
     let markup = root.cast::<ast::Markup>().unwrap();
-    let output = markup.eval(&mut vm)?;
+    vm.hoisted = Some(vec![]);
+    vm.labels = Some(vec![]);
+    let mut output = markup::eval_module_markup(&mut vm, markup, content)?;
+    vm.settle(root.span())?;
 
     // Handle control flow.
     if let Some(flow) = vm.flow {
         bail!(flow.forbidden());
     }
 
+    check_duplicate_labels(&mut vm);
+
+    vm.check_unused_imports();
+
+    // Apply hoisted show rules to the whole module, with later rules taking
+    // precedence like they would if they were nested.
+    let hoisted = vm.hoisted.take().unwrap_or_default();
+    if content {
+        for recipe in hoisted.into_iter().rev() {
+            output = output.styled_with_recipe(&mut vm.engine, vm.context, recipe)?;
+        }
+    }
+
+    // Fail here rather than leaving layout to grind through content that grew
+    // out of proportion.
+    check_content_size(&vm, &output, root.span())?;
+
     // Assemble the module.
     let name = id
         .vpath()
@@ -86,7 +151,55 @@ pub fn eval(
         .unwrap_or_default()
         .to_string_lossy();
 
-    Ok(Module::new(name, vm.scopes.top).with_content(output))
+    Ok(Module::new(name, vm.scopes.top).with_content(output).with_file_id(id))
+}
+
+/// Warn about labels that are attached in more than one place of a file's
+/// markup, at the second place. Attaching a label in the same place more than
+/// once, for example in a loop, is fine. This is a pedantic lint because
+/// selecting several elements by a shared label is legitimate.
+fn check_duplicate_labels(vm: &mut Vm) {
+    let labels = vm.labels.take().unwrap_or_default();
+    if !vm.engine.world.library().pedantic {
+        return;
+    }
+
+    let mut first = HashMap::new();
+    let mut warned = HashSet::new();
+    for (label, span) in labels {
+        let earlier = *first.entry(label).or_insert(span);
+        if earlier == span || !warned.insert(label) {
+            continue;
+        }
+
+        let mut diag = warning!(
+            span,
+            "label {} is attached to more than one element",
+            label.repr(),
+        );
+        if let Some(location) = locate(vm.world(), earlier) {
+            diag.hint(eco_format!("it was first attached at {location}"));
+        }
+        diag.hint("references to it are ambiguous");
+        vm.engine.lint_delayed(diag);
+    }
+}
+
+/// Fail if the content of a file has more elements than the budget allows,
+/// pointing to the expression that produced most of it.
+fn check_content_size(vm: &Vm, content: &Content, root: Span) -> SourceResult<()> {
+    let limit = vm.engine.sink.content_limit();
+    if content.nodes() <= limit {
+        return Ok(());
+    }
+
+    bail!(
+        vm.largest.as_ref().map_or(root, Content::span),
+        "content is too large to lay out";
+        hint: "it has more than {limit} elements";
+        hint: "content that contains itself repeatedly, like `body = [#body #body]` \
+               in a loop, grows exponentially",
+    )
 }
 
 /// Evaluate a string as code and return the resulting value.
@@ -99,6 +212,100 @@ pub fn eval_string(
     span: Span,
     mode: EvalMode,
     scope: Scope,
+) -> SourceResult<Value> {
+    let introspector = Introspector::default();
+    let context = Context::none();
+    eval_string_impl(
+        world,
+        introspector.track(),
+        context.track(),
+        &mut Sink::new(),
+        string,
+        span,
+        mode,
+        scope,
+        None,
+    )
+}
+
+/// Evaluate a string like [`eval_string`], but resolve relative imports and
+/// includes as if the code was part of the `base` file.
+///
+/// This is useful for tools like REPLs, where code isn't part of a file, but
+/// should still be able to import files next to some file. Importing the base
+/// file itself is a cyclic import.
+#[comemo::memoize]
+pub fn eval_string_at(
+    world: Tracked<dyn World + '_>,
+    string: &str,
+    span: Span,
+    mode: EvalMode,
+    scope: Scope,
+    base: FileId,
+) -> SourceResult<Value> {
+    let introspector = Introspector::default();
+    let context = Context::none();
+    eval_string_impl(
+        world,
+        introspector.track(),
+        context.track(),
+        &mut Sink::new(),
+        string,
+        span,
+        mode,
+        scope,
+        Some(base),
+    )
+}
+
+/// Evaluate a string like [`eval_string`], charging the evaluated expressions
+/// to the budget of the given sink.
+///
+/// Unlike there, the string sees the document through the given introspector
+/// and context, so that it can query the document when called within a
+/// context expression. Apart from that, the string is evaluated in isolation:
+/// Warnings are not passed on to the sink.
+#[comemo::memoize]
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn eval_string_budgeted(
+    world: Tracked<dyn World + '_>,
+    introspector: Tracked<Introspector>,
+    context: Tracked<Context>,
+    mut sink: TrackedMut<Sink>,
+    string: &str,
+    span: Span,
+    mode: EvalMode,
+    scope: Scope,
+) -> SourceResult<Value> {
+    let mut inner = Sink::with_budget(sink.remaining());
+    let output = eval_string_impl(
+        world,
+        introspector,
+        context,
+        &mut inner,
+        string,
+        span,
+        mode,
+        scope,
+        None,
+    );
+    sink.spend(inner.steps());
+    output
+}
+
+/// The shared implementation of [`eval_string`], [`eval_string_at`], and
+/// [`eval_string_budgeted`].
+#[allow(clippy::too_many_arguments)]
+fn eval_string_impl(
+    world: Tracked<dyn World + '_>,
+    introspector: Tracked<Introspector>,
+    context: Tracked<Context>,
+    sink: &mut Sink,
+    string: &str,
+    span: Span,
+    mode: EvalMode,
+    scope: Scope,
+    base: Option<FileId>,
 ) -> SourceResult<Value> {
     let mut root = match mode {
         EvalMode::Code => parse_code(string),
@@ -115,22 +322,24 @@ pub fn eval_string(
     }
 
     // Prepare the engine.
-    let mut sink = Sink::new();
-    let introspector = Introspector::default();
     let traced = Traced::default();
+    let mut route = Route::default();
+    if let Some(base) = base {
+        route = route.with_id(base);
+    }
     let engine = Engine {
         world,
-        introspector: introspector.track(),
+        introspector,
         traced: traced.track(),
         sink: sink.track_mut(),
-        route: Route::default(),
+        route,
     };
 
     // Prepare VM.
-    let context = Context::none();
     let scopes = Scopes::new(Some(world.library()));
-    let mut vm = Vm::new(engine, context.track(), scopes, root.span());
+    let mut vm = Vm::new(engine, context, scopes, root.span());
     vm.scopes.scopes.push(scope);
+    vm.base = base;
 
     // Evaluate the code.
     let output = match mode {
@@ -144,6 +353,7 @@ pub fn eval_string(
                 .pack(),
         ),
     };
+    vm.settle(span)?;
 
     // Handle control flow.
     if let Some(flow) = vm.flow {
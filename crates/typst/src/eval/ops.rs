@@ -4,11 +4,12 @@ use std::cmp::Ordering;
 
 use ecow::eco_format;
 
-use crate::diag::{bail, At, HintedStrResult, SourceResult, StrResult};
-use crate::eval::{access_dict, Access, Eval, Vm};
+use crate::diag::{bail, warning, At, HintedStrResult, SourceResult, StrResult};
+use crate::eval::{access_dict, access_for_assign, fold, maybe_grow, Access, Eval, Vm};
 use crate::foundations::{format_str, Datetime, IntoValue, Regex, Repr, Value};
 use crate::layout::{Alignment, Length, Rel};
 use crate::syntax::ast::{self, AstNode};
+use crate::syntax::{Span, Spanned, SyntaxKind, SyntaxNode};
 use crate::text::TextElem;
 use crate::utils::Numeric;
 use crate::visualize::Stroke;
@@ -17,13 +18,15 @@ impl Eval for ast::Unary<'_> {
     type Output = Value;
 
     fn eval(self, vm: &mut Vm) -> SourceResult<Self::Output> {
-        let value = self.expr().eval(vm)?;
-        let result = match self.op() {
-            ast::UnOp::Pos => pos(value),
-            ast::UnOp::Neg => neg(value),
-            ast::UnOp::Not => not(value),
-        };
-        result.at(self.span())
+        fold(vm, ast::Expr::Unary(self), |vm| {
+            let value = self.expr().eval(vm)?;
+            let result = match self.op() {
+                ast::UnOp::Pos => pos(value),
+                ast::UnOp::Neg => neg(value),
+                ast::UnOp::Not => not(value),
+            };
+            result.at(self.span())
+        })
     }
 }
 
@@ -31,27 +34,32 @@ impl Eval for ast::Binary<'_> {
     type Output = Value;
 
     fn eval(self, vm: &mut Vm) -> SourceResult<Self::Output> {
-        match self.op() {
-            ast::BinOp::Add => apply_binary(self, vm, add),
-            ast::BinOp::Sub => apply_binary(self, vm, sub),
-            ast::BinOp::Mul => apply_binary(self, vm, mul),
-            ast::BinOp::Div => apply_binary(self, vm, div),
-            ast::BinOp::And => apply_binary(self, vm, and),
-            ast::BinOp::Or => apply_binary(self, vm, or),
-            ast::BinOp::Eq => apply_binary(self, vm, eq),
-            ast::BinOp::Neq => apply_binary(self, vm, neq),
-            ast::BinOp::Lt => apply_binary(self, vm, lt),
-            ast::BinOp::Leq => apply_binary(self, vm, leq),
-            ast::BinOp::Gt => apply_binary(self, vm, gt),
-            ast::BinOp::Geq => apply_binary(self, vm, geq),
-            ast::BinOp::In => apply_binary(self, vm, in_),
-            ast::BinOp::NotIn => apply_binary(self, vm, not_in),
-            ast::BinOp::Assign => apply_assignment(self, vm, |_, b| Ok(b)),
-            ast::BinOp::AddAssign => apply_assignment(self, vm, add),
-            ast::BinOp::SubAssign => apply_assignment(self, vm, sub),
-            ast::BinOp::MulAssign => apply_assignment(self, vm, mul),
-            ast::BinOp::DivAssign => apply_assignment(self, vm, div),
-        }
+        fold(vm, ast::Expr::Binary(self), |vm| eval_binary(self, vm))
+    }
+}
+
+/// Evaluate a binary operation.
+fn eval_binary(binary: ast::Binary, vm: &mut Vm) -> SourceResult<Value> {
+    match binary.op() {
+        ast::BinOp::Add => apply_binary(binary, vm, add),
+        ast::BinOp::Sub => apply_binary(binary, vm, sub),
+        ast::BinOp::Mul => apply_binary(binary, vm, mul),
+        ast::BinOp::Div => apply_binary(binary, vm, div),
+        ast::BinOp::And => apply_logical(binary, vm),
+        ast::BinOp::Or => apply_logical(binary, vm),
+        ast::BinOp::Eq => apply_equality(binary, vm, eq),
+        ast::BinOp::Neq => apply_equality(binary, vm, neq),
+        ast::BinOp::Lt => apply_binary(binary, vm, lt),
+        ast::BinOp::Leq => apply_binary(binary, vm, leq),
+        ast::BinOp::Gt => apply_binary(binary, vm, gt),
+        ast::BinOp::Geq => apply_binary(binary, vm, geq),
+        ast::BinOp::In => apply_membership(binary, vm),
+        ast::BinOp::NotIn => apply_membership(binary, vm),
+        ast::BinOp::Assign => apply_assignment(binary, vm, |_, b| Ok(b)),
+        ast::BinOp::AddAssign => apply_assignment(binary, vm, add),
+        ast::BinOp::SubAssign => apply_assignment(binary, vm, sub),
+        ast::BinOp::MulAssign => apply_assignment(binary, vm, mul),
+        ast::BinOp::DivAssign => apply_assignment(binary, vm, div),
     }
 }
 
@@ -62,16 +70,85 @@ fn apply_binary(
     op: fn(Value, Value) -> HintedStrResult<Value>,
 ) -> SourceResult<Value> {
     let lhs = binary.lhs().eval(vm)?;
+    let rhs = binary.rhs().eval(vm)?;
+    match op(lhs, rhs) {
+        Ok(value) => Ok(value),
+        Err(err) => Err(err).at(binary_span(binary)),
+    }
+}
 
-    // Short-circuit boolean operations.
-    if (binary.op() == ast::BinOp::And && lhs == false.into_value())
-        || (binary.op() == ast::BinOp::Or && lhs == true.into_value())
+/// Apply an `==` or `!=` operation.
+///
+/// Comparing a type with a string still works for compatibility with the time
+/// when `type` returned strings, but is deprecated.
+fn apply_equality(
+    binary: ast::Binary,
+    vm: &mut Vm,
+    op: fn(Value, Value) -> HintedStrResult<Value>,
+) -> SourceResult<Value> {
+    let lhs = binary.lhs().eval(vm)?;
+    let rhs = binary.rhs().eval(vm)?;
+    if let (Value::Type(ty), Value::Str(_)) | (Value::Str(_), Value::Type(ty)) =
+        (&lhs, &rhs)
     {
-        return Ok(lhs);
+        vm.engine.lint(warning!(
+            binary_span(binary),
+            "comparing a type with a string is deprecated";
+            hint: "compare with the type itself instead, like `{}`", ty.short_name()
+        ))?;
     }
+    op(lhs, rhs).at(binary_span(binary))
+}
 
-    let rhs = binary.rhs().eval(vm)?;
-    op(lhs, rhs).at(binary.span())
+/// The span at which a failed binary operation is reported.
+///
+/// This is usually the whole expression. If the expression spans multiple
+/// lines, it is only the operator, as pointing at long operands in their
+/// entirety wouldn't help to find the problem.
+fn binary_span(binary: ast::Binary) -> Span {
+    let node = binary.to_untyped();
+    if !node.clone().into_text().contains('\n') {
+        return binary.span();
+    }
+
+    node.children()
+        .find(|child| {
+            child.kind() == SyntaxKind::Not
+                || ast::BinOp::from_kind(child.kind()).is_some()
+        })
+        .map_or(binary.span(), SyntaxNode::span)
+}
+
+/// Apply an `in` or `not in` operation.
+///
+/// Unlike for other binary operations, a type mismatch is reported at the
+/// operand that has the offending type rather than at the whole expression.
+fn apply_membership(binary: ast::Binary, vm: &mut Vm) -> SourceResult<Value> {
+    let lhs = binary.lhs();
+    let lhs = Spanned::new(lhs.eval(vm)?, lhs.span());
+    let rhs = binary.rhs();
+    let rhs = Spanned::new(rhs.eval(vm)?, rhs.span());
+    let negated = binary.op() == ast::BinOp::NotIn;
+    Ok(Value::Bool(contains_spanned(&lhs, &rhs, negated)? != negated))
+}
+
+/// Apply a short-circuiting boolean operation.
+///
+/// Both operands must be booleans. The right-hand side is only evaluated if
+/// the left-hand side doesn't already determine the result.
+fn apply_logical(binary: ast::Binary, vm: &mut Vm) -> SourceResult<Value> {
+    let lhs = binary.lhs();
+    let lhs = lhs.eval(vm)?.cast::<bool>().at(lhs.span())?;
+
+    // Short-circuit.
+    match (binary.op(), lhs) {
+        (ast::BinOp::And, false) => return Ok(Value::Bool(false)),
+        (ast::BinOp::Or, true) => return Ok(Value::Bool(true)),
+        _ => {}
+    }
+
+    let rhs = binary.rhs();
+    Ok(Value::Bool(rhs.eval(vm)?.cast::<bool>().at(rhs.span())?))
 }
 
 /// Apply an assignment operation.
@@ -93,9 +170,21 @@ fn apply_assignment(
         }
     }
 
-    let location = binary.lhs().access(vm)?;
+    let location = match lhs {
+        _ if binary.op() == ast::BinOp::Assign => access_for_assign(lhs, vm)?,
+        ast::Expr::FieldAccess(access) => {
+            let field = access.field();
+            access_dict(vm, access)?
+                .at_mut_for_update(field.get(), binary.op().as_str())
+                .at(access.span())?
+        }
+        _ => lhs.access(vm)?,
+    };
     let lhs = std::mem::take(&mut *location);
-    *location = op(lhs, rhs).at(binary.span())?;
+    *location = match op(lhs, rhs) {
+        Ok(value) => value,
+        Err(err) => return Err(err).at(binary_span(binary)),
+    };
     Ok(Value::None)
 }
 
@@ -107,7 +196,7 @@ macro_rules! mismatch {
 }
 
 /// Join a value with another value.
-pub fn join(lhs: Value, rhs: Value) -> StrResult<Value> {
+pub fn join(lhs: Value, rhs: Value) -> HintedStrResult<Value> {
     use Value::*;
     Ok(match (lhs, rhs) {
         (a, None) => a,
@@ -129,7 +218,12 @@ pub fn join(lhs: Value, rhs: Value) -> StrResult<Value> {
         (Type(a), Str(b)) => Str(format_str!("{a}{b}")),
         (Str(a), Type(b)) => Str(format_str!("{a}{b}")),
 
-        (a, b) => mismatch!("cannot join {} with {}", a, b),
+        (a, b) => bail!(
+            "cannot join {} with {}", a.ty(), b.ty();
+            hint: "use `str` or `repr` to convert values, or `joined` to join \
+                   them with a separator";
+            hint: "unlike joining, the `+` operator can also add numbers",
+        ),
     })
 }
 
@@ -169,7 +263,7 @@ pub fn neg(value: Value) -> HintedStrResult<Value> {
         Ratio(v) => Ratio(-v),
         Relative(v) => Relative(-v),
         Fraction(v) => Fraction(-v),
-        Duration(v) => Duration(-v),
+        Duration(v) => Duration((-v)?),
         Datetime(_) => mismatch!("cannot apply unary '-' to {}", value),
         v => mismatch!("cannot apply '-' to {}", v),
     })
@@ -228,9 +322,9 @@ pub fn add(lhs: Value, rhs: Value) -> HintedStrResult<Value> {
             Stroke::from_pair(pattern, thickness).into_value()
         }
 
-        (Duration(a), Duration(b)) => Duration(a + b),
-        (Datetime(a), Duration(b)) => Datetime(a + b),
-        (Duration(a), Datetime(b)) => Datetime(b + a),
+        (Duration(a), Duration(b)) => Duration((a + b)?),
+        (Datetime(a), Duration(b)) => Datetime((a + b)?),
+        (Duration(a), Datetime(b)) => Datetime((b + a)?),
 
         // Type compatibility.
         (Type(a), Str(b)) => Str(format_str!("{a}{b}")),
@@ -276,8 +370,8 @@ pub fn sub(lhs: Value, rhs: Value) -> HintedStrResult<Value> {
 
         (Fraction(a), Fraction(b)) => Fraction(a - b),
 
-        (Duration(a), Duration(b)) => Duration(a - b),
-        (Datetime(a), Duration(b)) => Datetime(a - b),
+        (Duration(a), Duration(b)) => Duration((a - b)?),
+        (Datetime(a), Duration(b)) => Datetime((a - b)?),
         (Datetime(a), Datetime(b)) => Duration((a - b)?),
 
         (a, b) => mismatch!("cannot subtract {1} from {0}", a, b),
@@ -334,10 +428,10 @@ pub fn mul(lhs: Value, rhs: Value) -> HintedStrResult<Value> {
         (Content(a), b @ Int(_)) => Content(a.repeat(b.cast()?)),
         (a @ Int(_), Content(b)) => Content(b.repeat(a.cast()?)),
 
-        (Int(a), Duration(b)) => Duration(b * (a as f64)),
-        (Float(a), Duration(b)) => Duration(b * a),
-        (Duration(a), Int(b)) => Duration(a * (b as f64)),
-        (Duration(a), Float(b)) => Duration(a * b),
+        (Int(a), Duration(b)) => Duration((b * (a as f64))?),
+        (Float(a), Duration(b)) => Duration((b * a)?),
+        (Duration(a), Int(b)) => Duration((a * (b as f64))?),
+        (Duration(a), Float(b)) => Duration((a * b)?),
 
         (a, b) => mismatch!("cannot multiply {} with {}", a, b),
     })
@@ -380,8 +474,8 @@ pub fn div(lhs: Value, rhs: Value) -> HintedStrResult<Value> {
         (Fraction(a), Float(b)) => Fraction(a / b),
         (Fraction(a), Fraction(b)) => Float(a / b),
 
-        (Duration(a), Int(b)) => Duration(a / (b as f64)),
-        (Duration(a), Float(b)) => Duration(a / b),
+        (Duration(a), Int(b)) => Duration((a / (b as f64))?),
+        (Duration(a), Float(b)) => Duration((a / b)?),
         (Duration(a), Duration(b)) => Float(a / b),
 
         (a, b) => mismatch!("cannot divide {} by {}", a, b),
@@ -423,22 +517,6 @@ pub fn not(value: Value) -> HintedStrResult<Value> {
     }
 }
 
-/// Compute the logical "and" of two values.
-pub fn and(lhs: Value, rhs: Value) -> HintedStrResult<Value> {
-    match (lhs, rhs) {
-        (Value::Bool(a), Value::Bool(b)) => Ok(Value::Bool(a && b)),
-        (a, b) => mismatch!("cannot apply 'and' to {} and {}", a, b),
-    }
-}
-
-/// Compute the logical "or" of two values.
-pub fn or(lhs: Value, rhs: Value) -> HintedStrResult<Value> {
-    match (lhs, rhs) {
-        (Value::Bool(a), Value::Bool(b)) => Ok(Value::Bool(a || b)),
-        (a, b) => mismatch!("cannot apply 'or' to {} and {}", a, b),
-    }
-}
-
 /// Compute whether two values are equal.
 pub fn eq(lhs: Value, rhs: Value) -> HintedStrResult<Value> {
     Ok(Value::Bool(equal(&lhs, &rhs)))
@@ -466,81 +544,88 @@ comparison!(geq, ">=", Ordering::Greater | Ordering::Equal);
 
 /// Determine whether two values are equal.
 pub fn equal(lhs: &Value, rhs: &Value) -> bool {
-    use Value::*;
-    match (lhs, rhs) {
-        // Compare reflexively.
-        (None, None) => true,
-        (Auto, Auto) => true,
-        (Bool(a), Bool(b)) => a == b,
-        (Int(a), Int(b)) => a == b,
-        (Float(a), Float(b)) => a == b,
-        (Length(a), Length(b)) => a == b,
-        (Angle(a), Angle(b)) => a == b,
-        (Ratio(a), Ratio(b)) => a == b,
-        (Relative(a), Relative(b)) => a == b,
-        (Fraction(a), Fraction(b)) => a == b,
-        (Color(a), Color(b)) => a == b,
-        (Symbol(a), Symbol(b)) => a == b,
-        (Version(a), Version(b)) => a == b,
-        (Str(a), Str(b)) => a == b,
-        (Bytes(a), Bytes(b)) => a == b,
-        (Label(a), Label(b)) => a == b,
-        (Content(a), Content(b)) => a == b,
-        (Array(a), Array(b)) => a == b,
-        (Dict(a), Dict(b)) => a == b,
-        (Func(a), Func(b)) => a == b,
-        (Args(a), Args(b)) => a == b,
-        (Type(a), Type(b)) => a == b,
-        (Module(a), Module(b)) => a == b,
-        (Plugin(a), Plugin(b)) => a == b,
-        (Datetime(a), Datetime(b)) => a == b,
-        (Duration(a), Duration(b)) => a == b,
-        (Dyn(a), Dyn(b)) => a == b,
-
-        // Some technically different things should compare equal.
-        (&Int(i), &Float(f)) | (&Float(f), &Int(i)) => i as f64 == f,
-        (&Length(len), &Relative(rel)) | (&Relative(rel), &Length(len)) => {
-            len == rel.abs && rel.rel.is_zero()
-        }
-        (&Ratio(rat), &Relative(rel)) | (&Relative(rel), &Ratio(rat)) => {
-            rat == rel.rel && rel.abs.is_zero()
-        }
+    // Arrays and dictionaries can be nested arbitrarily deeply.
+    maybe_grow(|| {
+        use Value::*;
+        match (lhs, rhs) {
+            // Compare reflexively.
+            (None, None) => true,
+            (Auto, Auto) => true,
+            (Bool(a), Bool(b)) => a == b,
+            (Int(a), Int(b)) => a == b,
+            (Float(a), Float(b)) => a == b,
+            (Length(a), Length(b)) => a == b,
+            (Angle(a), Angle(b)) => a == b,
+            (Ratio(a), Ratio(b)) => a == b,
+            (Relative(a), Relative(b)) => a == b,
+            (Fraction(a), Fraction(b)) => a == b,
+            (Color(a), Color(b)) => a == b,
+            (Symbol(a), Symbol(b)) => a == b,
+            (Version(a), Version(b)) => a == b,
+            (Str(a), Str(b)) => a == b,
+            (Bytes(a), Bytes(b)) => a == b,
+            (Label(a), Label(b)) => a == b,
+            (Content(a), Content(b)) => a == b,
+            (Array(a), Array(b)) => a == b,
+            (Dict(a), Dict(b)) => a == b,
+            (Func(a), Func(b)) => a == b,
+            (Args(a), Args(b)) => a == b,
+            (Type(a), Type(b)) => a == b,
+            (Module(a), Module(b)) => a == b,
+            (Plugin(a), Plugin(b)) => a == b,
+            (Datetime(a), Datetime(b)) => a == b,
+            (Duration(a), Duration(b)) => a == b,
+            (Dyn(a), Dyn(b)) => a == b,
+
+            // Some technically different things should compare equal.
+            (&Int(i), &Float(f)) | (&Float(f), &Int(i)) => i as f64 == f,
+            (&Length(len), &Relative(rel)) | (&Relative(rel), &Length(len)) => {
+                len == rel.abs && rel.rel.is_zero()
+            }
+            (&Ratio(rat), &Relative(rel)) | (&Relative(rel), &Ratio(rat)) => {
+                rat == rel.rel && rel.abs.is_zero()
+            }
 
-        // Type compatibility.
-        (Type(ty), Str(str)) | (Str(str), Type(ty)) => ty.compat_name() == str.as_str(),
+            // Type compatibility.
+            (Type(ty), Str(str)) | (Str(str), Type(ty)) => {
+                ty.compat_name() == str.as_str()
+            }
 
-        _ => false,
-    }
+            _ => false,
+        }
+    })
 }
 
 /// Compare two values.
 pub fn compare(lhs: &Value, rhs: &Value) -> StrResult<Ordering> {
-    use Value::*;
-    Ok(match (lhs, rhs) {
-        (Bool(a), Bool(b)) => a.cmp(b),
-        (Int(a), Int(b)) => a.cmp(b),
-        (Float(a), Float(b)) => try_cmp_values(a, b)?,
-        (Length(a), Length(b)) => try_cmp_values(a, b)?,
-        (Angle(a), Angle(b)) => a.cmp(b),
-        (Ratio(a), Ratio(b)) => a.cmp(b),
-        (Relative(a), Relative(b)) => try_cmp_values(a, b)?,
-        (Fraction(a), Fraction(b)) => a.cmp(b),
-        (Version(a), Version(b)) => a.cmp(b),
-        (Str(a), Str(b)) => a.cmp(b),
-
-        // Some technically different things should be comparable.
-        (Int(a), Float(b)) => try_cmp_values(&(*a as f64), b)?,
-        (Float(a), Int(b)) => try_cmp_values(a, &(*b as f64))?,
-        (Length(a), Relative(b)) if b.rel.is_zero() => try_cmp_values(a, &b.abs)?,
-        (Ratio(a), Relative(b)) if b.abs.is_zero() => a.cmp(&b.rel),
-        (Relative(a), Length(b)) if a.rel.is_zero() => try_cmp_values(&a.abs, b)?,
-        (Relative(a), Ratio(b)) if a.abs.is_zero() => a.rel.cmp(b),
-
-        (Duration(a), Duration(b)) => a.cmp(b),
-        (Datetime(a), Datetime(b)) => try_cmp_datetimes(a, b)?,
-        (Array(a), Array(b)) => try_cmp_arrays(a.as_slice(), b.as_slice())?,
-
-        _ => mismatch!("cannot compare {} and {}", lhs, rhs),
+    maybe_grow(|| {
+        use Value::*;
+        Ok(match (lhs, rhs) {
+            (Bool(a), Bool(b)) => a.cmp(b),
+            (Int(a), Int(b)) => a.cmp(b),
+            (Float(a), Float(b)) => try_cmp_values(a, b)?,
+            (Length(a), Length(b)) => try_cmp_values(a, b)?,
+            (Angle(a), Angle(b)) => a.cmp(b),
+            (Ratio(a), Ratio(b)) => a.cmp(b),
+            (Relative(a), Relative(b)) => try_cmp_values(a, b)?,
+            (Fraction(a), Fraction(b)) => a.cmp(b),
+            (Version(a), Version(b)) => a.cmp(b),
+            (Str(a), Str(b)) => a.cmp(b),
+
+            // Some technically different things should be comparable.
+            (Int(a), Float(b)) => try_cmp_values(&(*a as f64), b)?,
+            (Float(a), Int(b)) => try_cmp_values(a, &(*b as f64))?,
+            (Length(a), Relative(b)) if b.rel.is_zero() => try_cmp_values(a, &b.abs)?,
+            (Ratio(a), Relative(b)) if b.abs.is_zero() => a.cmp(&b.rel),
+            (Relative(a), Length(b)) if a.rel.is_zero() => try_cmp_values(&a.abs, b)?,
+            (Relative(a), Ratio(b)) if a.abs.is_zero() => a.rel.cmp(b),
+
+            (Duration(a), Duration(b)) => a.cmp(b),
+            (Datetime(a), Datetime(b)) => try_cmp_datetimes(a, b)?,
+            (Array(a), Array(b)) => try_cmp_arrays(a.as_slice(), b.as_slice())?,
+
+            _ => mismatch!("cannot compare {} and {}", lhs, rhs),
+        })
     })
 }
 
@@ -576,21 +661,35 @@ fn try_cmp_arrays(a: &[Value], b: &[Value]) -> StrResult<Ordering> {
         })
 }
 
-/// Test whether one value is "in" another one.
-pub fn in_(lhs: Value, rhs: Value) -> HintedStrResult<Value> {
-    if let Some(b) = contains(&lhs, &rhs) {
-        Ok(Value::Bool(b))
-    } else {
-        mismatch!("cannot apply 'in' to {} and {}", lhs, rhs)
+/// Test for containment, failing with an error at the span of the operand
+/// that can't take part in the check.
+///
+/// The `negated` flag only determines how the operator is named in the error
+/// message, the result is always whether `lhs` is contained in `rhs`.
+fn contains_spanned(
+    lhs: &Spanned<Value>,
+    rhs: &Spanned<Value>,
+    negated: bool,
+) -> SourceResult<bool> {
+    if let Some(b) = contains(&lhs.v, &rhs.v) {
+        return Ok(b);
     }
-}
 
-/// Test whether one value is "not in" another one.
-pub fn not_in(lhs: Value, rhs: Value) -> HintedStrResult<Value> {
-    if let Some(b) = contains(&lhs, &rhs) {
-        Ok(Value::Bool(!b))
-    } else {
-        mismatch!("cannot apply 'not in' to {} and {}", lhs, rhs)
+    let op = if negated { "not in" } else { "in" };
+    let (lhs_ty, rhs_ty) = (lhs.v.ty(), rhs.v.ty());
+    match rhs.v {
+        Value::Dict(_) => bail!(
+            lhs.span, "cannot apply '{op}' to {lhs_ty} and {rhs_ty}";
+            hint: "dictionary keys are always strings"
+        ),
+        Value::Str(_) => bail!(
+            lhs.span, "cannot apply '{op}' to {lhs_ty} and {rhs_ty}";
+            hint: "only strings and regular expressions can be searched for in a string"
+        ),
+        _ => bail!(
+            rhs.span, "cannot apply '{op}' to {lhs_ty} and {rhs_ty}";
+            hint: "only strings, arrays, and dictionaries can contain values"
+        ),
     }
 }
 
@@ -1,4 +1,6 @@
-use crate::diag::{At, SourceResult};
+use ecow::eco_format;
+
+use crate::diag::{bail, At, SourceResult};
 use crate::eval::{Eval, Vm};
 use crate::foundations::{Func, Recipe, ShowableSelector, Styles, Transformation};
 use crate::syntax::ast::{self, AstNode};
@@ -13,18 +15,64 @@ impl Eval for ast::SetRule<'_> {
             }
         }
 
-        let target = self.target();
-        let target = target
-            .eval(vm)?
-            .cast::<Func>()
-            .and_then(|func| {
-                func.element().ok_or_else(|| {
-                    "only element functions can be used in set rules".into()
-                })
+        let rules = self
+            .rules()
+            .map(|(target, args)| {
+                let elem = target
+                    .eval(vm)?
+                    .cast::<Func>()
+                    .and_then(|func| {
+                        func.element().ok_or_else(|| {
+                            "only element functions can be used in set rules".into()
+                        })
+                    })
+                    .at(target.span())?;
+                Ok((target, elem, args))
             })
-            .at(target.span())?;
-        let args = self.args().eval(vm)?.spanned(self.span());
-        Ok(target.set(&mut vm.engine, args)?.spanned(self.span()))
+            .collect::<SourceResult<Vec<_>>>()?;
+
+        // In a group of set rules, each element has its own arguments.
+        let shared = if self.grouped() {
+            None
+        } else {
+            Some(self.args().eval(vm)?.spanned(self.span()))
+        };
+
+        let shared = match (rules.as_slice(), shared) {
+            ([(_, elem, None)], Some(args)) => {
+                return Ok(elem.set(&mut vm.engine, args)?.spanned(self.span()));
+            }
+            (_, shared) => shared,
+        };
+
+        // Each element validates the arguments on its own, so errors need to
+        // say which of the elements rejected them.
+        let mut styles = Styles::new();
+        for (target, elem, args) in rules {
+            let args = match (args, &shared) {
+                (Some(args), _) => args.eval(vm)?.spanned(self.span()),
+                (None, Some(shared)) => shared.clone(),
+                (None, None) => bail!(
+                    target.span(), "missing arguments for `{}`", elem.name();
+                    hint: "in a group of set rules, each element needs its own arguments";
+                    hint: "to leave it unchanged, remove it from the group",
+                ),
+            };
+
+            let mut set = elem.set(&mut vm.engine, args).map_err(|mut errors| {
+                for error in errors.make_mut() {
+                    error.hint(eco_format!(
+                        "error occurred while setting `{}`",
+                        elem.name()
+                    ));
+                }
+                errors
+            })?;
+            set.apply(styles);
+            styles = set;
+        }
+
+        Ok(styles.spanned(self.span()))
     }
 }
 
@@ -46,6 +94,7 @@ impl Eval for ast::ShowRule<'_> {
             expr => expr.eval(vm)?.cast::<Transformation>().at(span)?,
         };
 
-        Ok(Recipe { span, selector, transform })
+        let everywhere = vm.show_everywhere && selector.is_some();
+        Ok(Recipe { span, selector, transform, everywhere })
     }
 }
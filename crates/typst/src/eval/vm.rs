@@ -1,11 +1,17 @@
+use std::collections::HashMap;
+
 use comemo::Tracked;
+use ecow::EcoString;
 
-use crate::engine::Engine;
-use crate::eval::FlowEvent;
-use crate::foundations::{Context, IntoValue, Scopes, Value};
+use crate::diag::{bail, error, warning, SourceResult};
+use crate::engine::{Definition, DefinitionKind, Engine, Usage};
+use crate::eval::{FlowEvent, ResolvedImports};
+use crate::foundations::{
+    Content, Context, Func, IntoValue, Label, Recipe, Scopes, StableId, Styles, Value,
+};
 use crate::syntax::ast::{self, AstNode};
-use crate::syntax::Span;
-use crate::World;
+use crate::syntax::{FileId, Span};
+use crate::{Feature, World};
 
 /// A virtual machine.
 ///
@@ -20,11 +26,52 @@ pub struct Vm<'a> {
     pub(crate) scopes: Scopes<'a>,
     /// A span that is currently under inspection.
     pub(crate) inspected: Option<Span>,
+    /// Whether definitions and uses of variables are recorded.
+    pub(crate) analyzed: bool,
     /// Data that is contextually made accessible to code behind the scenes.
     pub(crate) context: Tracked<'a, Context<'a>>,
+    /// The styles of the set rules that enclose the currently evaluated code.
+    /// Only collected while a span is inspected.
+    pub(crate) styles: Styles,
+    /// Whether the currently evaluated code is in the scope of a
+    /// `show: show-everywhere` rule.
+    pub(crate) show_everywhere: bool,
+    /// Recipes that apply to the whole module. Only present for the
+    /// evaluation of a module, not for function calls.
+    pub(crate) hoisted: Option<Vec<Recipe>>,
+    /// The labels attached to content in markup, along with the spans of the
+    /// labels. Only present for the evaluation of a module, not for function
+    /// calls.
+    pub(crate) labels: Option<Vec<(Label, Span)>>,
+    /// Modules imported by the chunk of markup that is being evaluated,
+    /// resolved ahead of time.
+    pub(crate) imports: Option<Tracked<'a, ResolvedImports>>,
+    /// The file relative to which imports and includes are resolved instead
+    /// of the file that contains them. Only present for strings evaluated
+    /// with [`eval_string_at`](crate::eval::eval_string_at).
+    pub(crate) base: Option<FileId>,
+    /// The span of the most recent call to a function that returns a
+    /// dictionary with fixed keys, along with that function. Used to hint at
+    /// the available keys when destructuring the call's result fails.
+    pub(crate) returned: Option<(Span, Func)>,
+    /// The largest piece of content that the module's markup produced. Used
+    /// to point at it when the module's content grows too large.
+    pub(crate) largest: Option<Content>,
+    /// How many pieces of content each markup expression produced so far.
+    /// Only present if content is anchored.
+    anchors: Option<HashMap<Span, usize>>,
+    /// Whether evaluation is limited by a budget. Only queried once enough
+    /// expressions were evaluated, as tracked queries aren't free.
+    limited: Option<bool>,
+    /// The number of expressions evaluated since the budget was last charged.
+    steps: u64,
 }
 
 impl<'a> Vm<'a> {
+    /// How many expressions are evaluated between two checks of the
+    /// evaluation budget.
+    const BUDGET_INTERVAL: u64 = 1024;
+
     /// Create a new virtual machine.
     pub fn new(
         engine: Engine<'a>,
@@ -33,7 +80,27 @@ impl<'a> Vm<'a> {
         target: Span,
     ) -> Self {
         let inspected = target.id().and_then(|id| engine.traced.get(id));
-        Self { engine, context, flow: None, scopes, inspected }
+        let analyzed = target.id().is_some_and(|id| engine.traced.analyzed(id));
+        let anchors = engine.traced.anchored().then(HashMap::new);
+        Self {
+            engine,
+            context,
+            flow: None,
+            scopes,
+            inspected,
+            analyzed,
+            styles: Styles::new(),
+            show_everywhere: false,
+            hoisted: None,
+            labels: None,
+            imports: None,
+            base: None,
+            returned: None,
+            largest: None,
+            anchors,
+            limited: None,
+            steps: 0,
+        }
     }
 
     /// Access the underlying world.
@@ -41,20 +108,235 @@ impl<'a> Vm<'a> {
         self.engine.world
     }
 
+    /// Ensure that a feature group is part of the library.
+    pub(crate) fn require(&self, feature: Feature, span: Span) -> SourceResult<()> {
+        if !self.engine.world.library().features.contains(feature) {
+            bail!(span, "{}", feature.unavailable());
+        }
+        Ok(())
+    }
+
     /// Define a variable in the current scope.
+    ///
+    /// If pedantic lints are enabled, this warns when the variable shadows a
+    /// definition of the standard library.
     pub fn define(&mut self, var: ast::Ident, value: impl IntoValue) {
+        self.check_shadowing(var);
+        self.define_unchecked(var, value);
+    }
+
+    /// Define a variable in the current scope without checking whether it
+    /// shadows the standard library.
+    pub(crate) fn define_unchecked(&mut self, var: ast::Ident, value: impl IntoValue) {
+        let value = self.bind(var, value, DefinitionKind::Variable);
+        self.scopes.top.define_spanned(var.get().clone(), value, var.span());
+    }
+
+    /// Define a closure parameter in the current scope.
+    pub(crate) fn define_param(&mut self, var: ast::Ident, value: impl IntoValue) {
+        let value = self.bind(var, value, DefinitionKind::Param);
+        self.scopes.top.define_spanned(var.get().clone(), value, var.span());
+    }
+
+    /// Define a loop variable in the current scope.
+    pub(crate) fn define_loop_variable(
+        &mut self,
+        var: ast::Ident,
+        value: impl IntoValue,
+    ) {
+        self.check_shadowing(var);
+        let value = self.bind(var, value, DefinitionKind::Variable);
+        self.scopes
+            .top
+            .define_loop_variable(var.get().clone(), value, var.span());
+    }
+
+    /// Define a variable that was imported from a module in the current scope.
+    pub fn define_imported(&mut self, var: ast::Ident, value: impl IntoValue) {
+        let value = self.bind(var, value, DefinitionKind::Import);
+        self.scopes.top.define_imported(var.get().clone(), value, var.span());
+    }
+
+    /// Warn about items imported into the current scope that were never used.
+    /// Only checked if pedantic lints are enabled.
+    ///
+    /// Imports in packages are exempt because packages often import items just
+    /// to re-export them.
+    pub(crate) fn check_unused_imports(&mut self) {
+        if !self.scopes.base.is_some_and(|base| base.pedantic) {
+            return;
+        }
+
+        let unused: Vec<_> = self
+            .scopes
+            .top
+            .unused_imports()
+            .filter(|(_, span)| span.id().is_some_and(|id| id.package().is_none()))
+            .map(|(name, span)| warning!(span, "unused import: {name}"))
+            .collect();
+
+        for warning in unused {
+            self.engine.lint_delayed(warning);
+        }
+    }
+
+    /// Warn if a variable shadows a global definition of the standard
+    /// library. Only checked if pedantic lints are enabled.
+    ///
+    /// Closures don't have access to the base scopes, so this consults the
+    /// world's library directly.
+    fn check_shadowing(&mut self, var: ast::Ident) {
+        let library = self.engine.world.library();
+        if !library.pedantic {
+            return;
+        }
+
+        let Some(value) = library.global.scope().get(var.get()) else { return };
+        let warning = warning!(
+            var.span(), "variable `{}` shadows the standard library", var.get();
+            hint: "this shadows the standard library {} `{}`", value.ty(), var.get();
+            hint: "rename the variable or access the original as `std.{}`", var.get()
+        );
+        self.engine.lint_delayed(warning);
+    }
+
+    /// Trace and record a definition before it is bound.
+    fn bind(
+        &mut self,
+        var: ast::Ident,
+        value: impl IntoValue,
+        kind: DefinitionKind,
+    ) -> Value {
         let value = value.into_value();
         if self.inspected == Some(var.span()) {
             self.trace(value.clone());
         }
-        self.scopes.top.define(var.get().clone(), value);
+        if self.analyzed {
+            self.record_definition(var.get().clone(), var.span(), kind);
+        }
+        value
     }
 
-    /// Trace a value.
+    /// Record a variable definition for analysis.
+    pub(crate) fn record_definition(
+        &mut self,
+        name: EcoString,
+        span: Span,
+        kind: DefinitionKind,
+    ) {
+        self.engine.sink.definition(Definition { name, span, kind });
+    }
+
+    /// Record a use of a variable for analysis, resolving it to the innermost
+    /// definition.
+    pub(crate) fn record_usage(&mut self, name: &str, span: Span) {
+        if self.analyzed {
+            let definition = self.scopes.get_span(name);
+            self.engine.sink.usage(Usage { name: name.into(), span, definition });
+        }
+    }
+
+    /// Evaluate the tail of a set rule with the rule's styles in effect for
+    /// tracing.
+    pub(crate) fn with_styles<T>(
+        &mut self,
+        styles: &Styles,
+        f: impl FnOnce(&mut Self) -> T,
+    ) -> T {
+        if self.inspected.is_none() {
+            return f(self);
+        }
+
+        let mut inner = styles.clone();
+        inner.apply(self.styles.clone());
+        let outer = std::mem::replace(&mut self.styles, inner);
+        let output = f(self);
+        self.styles = outer;
+        output
+    }
+
+    /// Attach a stable identity to content produced by the markup expression
+    /// with the given span, if content is anchored.
+    pub(crate) fn anchor(&mut self, mut content: Content, span: Span) -> Content {
+        if let Some(anchors) = &mut self.anchors {
+            let occurrence = anchors.entry(span).or_default();
+            content.set_stable_id(StableId::new(span, *occurrence));
+            *occurrence += 1;
+        }
+        content
+    }
+
+    /// Evaluate nested syntax, growing the stack if necessary. How deeply
+    /// syntax can be nested is already limited by the parser.
+    pub(crate) fn nested<T>(&mut self, f: impl FnOnce(&mut Self) -> T) -> T {
+        maybe_grow(|| f(self))
+    }
+
+    /// Count an evaluated expression, failing once the evaluation budget is
+    /// used up.
+    pub(crate) fn step(&mut self, span: Span) -> SourceResult<()> {
+        self.steps += 1;
+        if self.steps >= Self::BUDGET_INTERVAL {
+            self.settle(span)?;
+        }
+        Ok(())
+    }
+
+    /// Charge the expressions evaluated since the last check to the budget,
+    /// failing if it is used up.
+    pub(crate) fn settle(&mut self, span: Span) -> SourceResult<()> {
+        let steps = std::mem::take(&mut self.steps);
+        if steps == 0 {
+            return Ok(());
+        }
+
+        let limited = match self.limited {
+            Some(limited) => limited,
+            None => *self.limited.insert(self.engine.sink.limited()),
+        };
+
+        if limited {
+            self.engine.sink.spend(steps);
+            if self.engine.sink.exhausted() {
+                bail!(error!(span, "evaluation budget exceeded").uncatchable());
+            }
+
+            #[cfg(not(target_arch = "wasm32"))]
+            if self
+                .engine
+                .sink
+                .deadline()
+                .is_some_and(|deadline| std::time::Instant::now() >= deadline)
+            {
+                bail!(error!(span, "evaluation budget exceeded").uncatchable());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Trace a value along with the styles in effect.
     #[cold]
     pub fn trace(&mut self, value: Value) {
-        self.engine
-            .sink
-            .value(value.clone(), self.context.styles().ok().map(|s| s.to_map()));
+        let styles = match self.context.styles() {
+            Ok(chain) => {
+                let mut styles = self.styles.clone();
+                styles.apply(chain.to_map());
+                Some(styles)
+            }
+            Err(_) => (!self.styles.is_empty()).then(|| self.styles.clone()),
+        };
+        self.engine.sink.value(value, styles);
     }
 }
+
+/// Run a function with more stack space if the remaining stack is running
+/// low.
+pub(crate) fn maybe_grow<T>(f: impl FnOnce() -> T) -> T {
+    // Stacker is broken on WASM.
+    #[cfg(target_arch = "wasm32")]
+    return f();
+
+    #[cfg(not(target_arch = "wasm32"))]
+    stacker::maybe_grow(32 * 1024, 2 * 1024 * 1024, f)
+}
@@ -38,6 +38,17 @@ use crate::syntax::{Span, Spanned};
 /// #let dict = (fill: blue)
 /// #text(..dict)[Hello]
 /// ```
+///
+/// # Destructuring
+/// Arguments can be destructured in `let` bindings. Unnamed patterns bind
+/// positional arguments, named patterns bind named arguments and a sink
+/// collects the remaining ones into new arguments.
+///
+/// ```example
+/// #let args = arguments(1, 2, fill: red)
+/// #let (first, fill: f, ..rest) = args
+/// #first, #f, #rest
+/// ```
 #[ty(scope, cast, name = "arguments")]
 #[derive(Clone, Hash)]
 #[allow(clippy::derived_hash_with_manual_eq)]
@@ -134,6 +145,14 @@ impl Args {
         }
     }
 
+    /// Consume the first positional argument without casting it, keeping its
+    /// span for diagnostics.
+    ///
+    /// This is the same as `expect::<Spanned<Value>>(what)`.
+    pub fn spanned_expect(&mut self, what: &str) -> SourceResult<Spanned<Value>> {
+        self.expect(what)
+    }
+
     /// The error message for missing arguments.
     fn missing_argument(&self, what: &str) -> SourceDiagnostic {
         for item in &self.items {
@@ -212,6 +231,14 @@ impl Args {
         Ok(found)
     }
 
+    /// Remove the value for the given named argument without casting it,
+    /// keeping its span for diagnostics.
+    ///
+    /// This is the same as `named::<Spanned<Value>>(name)`.
+    pub fn spanned_named(&mut self, name: &str) -> SourceResult<Option<Spanned<Value>>> {
+        self.named(name)
+    }
+
     /// Same as named, but with fallback to find.
     pub fn named_or_find<T>(&mut self, name: &str) -> SourceResult<Option<T>>
     where
@@ -285,6 +312,32 @@ impl Args {
             .filter_map(|item| item.name.clone().map(|name| (name, item.value.v.clone())))
             .collect()
     }
+
+    /// Whether a named argument with the given name was passed.
+    ///
+    /// This distinguishes an argument that was omitted from one that was
+    /// explicitly passed with its default value, for instance `{auto}`.
+    ///
+    /// ```example
+    /// #let f(..args) = {
+    ///   let size = args.named().at("size", default: auto)
+    ///   if args.provided("size") [#repr(size) was passed]
+    ///   else [size was omitted]
+    /// }
+    ///
+    /// #f(size: auto) \
+    /// #f()
+    /// ```
+    #[func]
+    pub fn provided(
+        &self,
+        /// The name of the argument.
+        name: Str,
+    ) -> bool {
+        self.items
+            .iter()
+            .any(|item| item.name.as_deref() == Some(name.as_str()))
+    }
 }
 
 impl Debug for Args {
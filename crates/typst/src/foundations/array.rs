@@ -1,16 +1,18 @@
 use std::cmp::Ordering;
 use std::fmt::{Debug, Formatter};
-use std::num::{NonZeroI64, NonZeroUsize};
+use std::num::NonZeroUsize;
 use std::ops::{Add, AddAssign};
 
 use comemo::Tracked;
 use ecow::{eco_format, EcoString, EcoVec};
+use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 use smallvec::SmallVec;
 
 use crate::diag::{bail, At, HintedStrResult, SourceDiagnostic, SourceResult, StrResult};
 use crate::engine::Engine;
-use crate::eval::ops;
+use crate::eval::{maybe_grow, ops};
+use crate::foundations::calc::Num;
 use crate::foundations::{
     cast, func, repr, scope, ty, Args, Bytes, CastInfo, Context, Dict, FromValue, Func,
     IntoValue, Reflect, Repr, Str, Value, Version,
@@ -49,6 +51,11 @@ pub use crate::__array as array;
 /// added together with the `+` operator, [joined together]($scripting/#blocks)
 /// and multiplied with integers.
 ///
+/// With `..`, you can spread the items of other arrays into an array literal,
+/// as in `{(0, ..values)}`. Spreading a dictionary yields its
+/// [key-value pairs]($dictionary.pairs) and spreading a string yields its
+/// [grapheme clusters]($str.clusters).
+///
 /// **Note:** An array of length one needs a trailing comma, as in `{(1,)}`.
 /// This is to disambiguate from a simple parenthesized expressions like `{(1 +
 /// 2) * 3}`. An empty array is written as `{()}`.
@@ -259,6 +266,56 @@ impl Array {
             .ok_or_else(|| out_of_bounds_no_default(index, self.len()))
     }
 
+    /// Replaces the items between the start and end index with the items of
+    /// another array, shifting all subsequent items. The replacement may have
+    /// a different length than the replaced range. Fails with an error if the
+    /// start or end index is out of bounds.
+    ///
+    /// ```example
+    /// #let values = (1, 2, 3, 4, 5)
+    /// #values.splice(1, 3, ("a", "b", "c"))
+    /// #values
+    /// ```
+    #[func]
+    pub fn splice(
+        &mut self,
+        /// The start index (inclusive). If negative, indexes from the back.
+        start: i64,
+        /// The end index (exclusive). If negative, indexes from the back.
+        end: i64,
+        /// The items to insert in place of the removed ones. If omitted, the
+        /// range is just removed.
+        #[default]
+        replacement: Array,
+    ) -> StrResult<()> {
+        let (first, last) = (self.locate(start, true)?, self.locate(end, true)?);
+        if last < first {
+            bail!("array splice end is before start (start: {start}, end: {end})");
+        }
+        self.0 = self.0[..first]
+            .iter()
+            .cloned()
+            .chain(replacement)
+            .chain(self.0[last..].iter().cloned())
+            .collect();
+        Ok(())
+    }
+
+    /// Shortens the array to the specified length by removing items from the
+    /// back. Fails with an error if the length is larger than the array's
+    /// length.
+    #[func]
+    pub fn truncate(
+        &mut self,
+        /// The new length. If negative, counts from the back, so that `{-1}`
+        /// removes the last item.
+        len: i64,
+    ) -> StrResult<()> {
+        let len = self.locate(len, true)?;
+        self.0.truncate(len);
+        Ok(())
+    }
+
     /// Extracts a subslice of the array. Fails with an error if the start or
     /// index is out of bounds.
     #[func]
@@ -352,6 +409,14 @@ impl Array {
     /// `end` of the range. If you pass two, they describe the `start` and `end`
     /// of the range.
     ///
+    /// To count down, pass a negative `step`. If the step doesn't move from
+    /// the start towards the end, this is an error.
+    ///
+    /// If the start, end, or step is a float, the range consists of floats.
+    /// Instead of a step, you can then also specify the `count` of evenly
+    /// spaced values. The values are always computed as multiples of the step
+    /// from the start, so that rounding errors don't accumulate.
+    ///
     /// This function is available both in the array function's scope and
     /// globally.
     ///
@@ -360,7 +425,10 @@ impl Array {
     /// #range(2, 5) \
     /// #range(20, step: 4) \
     /// #range(21, step: 4) \
-    /// #range(5, 2, step: -1)
+    /// #range(5, 2, step: -1) \
+    /// #range(1, 3, inclusive: true) \
+    /// #range(0, 1, step: 0.25) \
+    /// #range(0, 1, count: 5, inclusive: true)
     /// ```
     #[func]
     pub fn range(
@@ -369,33 +437,54 @@ impl Array {
         args: &mut Args,
         /// The start of the range (inclusive).
         #[external]
-        #[default]
-        start: i64,
-        /// The end of the range (exclusive).
+        #[default(Num::Int(0))]
+        start: Num,
+        /// The end of the range (exclusive, unless `inclusive` is `{true}`).
+        #[external]
+        end: Num,
+        /// The distance between the generated numbers. Must not be zero and
+        /// must be negative if the end is smaller than the start.
         #[external]
-        end: i64,
-        /// The distance between the generated numbers.
         #[named]
-        #[default(NonZeroI64::new(1).unwrap())]
-        step: NonZeroI64,
+        #[default(Num::Int(1))]
+        step: Num,
+        /// The number of evenly spaced floats to generate, as an alternative
+        /// to the `step`.
+        #[external]
+        #[named]
+        count: Option<usize>,
+        /// Whether to include the end of the range if it is reached.
+        #[external]
+        #[named]
+        #[default(false)]
+        inclusive: bool,
     ) -> SourceResult<Array> {
-        let first = args.expect::<i64>("end")?;
-        let (start, end) = match args.eat::<i64>()? {
+        let first = args.expect::<Num>("end")?;
+        let (start, end) = match args.eat::<Num>()? {
             Some(second) => (first, second),
-            None => (0, first),
+            None => (Num::Int(0), first),
         };
+        let step = args.named::<Spanned<Num>>("step")?;
+        let count = args.named::<Spanned<usize>>("count")?;
+        let inclusive = args.named::<bool>("inclusive")?.unwrap_or(false);
 
-        let step = step.get();
-
-        let mut x = start;
-        let mut array = Self::new();
-
-        while x.cmp(&end) == 0.cmp(&step) {
-            array.push(x.into_value());
-            x += step;
+        if let Some(count) = count {
+            if let Some(step) = step {
+                bail!(step.span, "cannot specify both step and count");
+            }
+            return range_count(start.float(), end.float(), count, inclusive);
         }
 
-        Ok(array)
+        let span = step.as_ref().map_or(args.span, |step| step.span);
+        let step = step.map_or(Num::Int(1), |step| step.v);
+        match (start, end, step) {
+            (Num::Int(start), Num::Int(end), Num::Int(step)) => {
+                range_int(start, end, step, inclusive).at(span)
+            }
+            _ => {
+                range_float(start.float(), end.float(), step.float(), inclusive).at(span)
+            }
+        }
     }
 
     /// Produces a new array with only the items from the original one for which
@@ -423,6 +512,29 @@ impl Array {
         Ok(kept.into())
     }
 
+    /// Removes all items for which the given function returns false, keeping
+    /// the order of the remaining ones. If the function fails, the array is
+    /// left unchanged.
+    ///
+    /// ```example
+    /// #let values = (1, 2, 3, 4, 5)
+    /// #values.retain(calc.odd)
+    /// #values
+    /// ```
+    #[func]
+    pub fn retain(
+        &mut self,
+        /// The engine.
+        engine: &mut Engine,
+        /// The callsite context.
+        context: Tracked<Context>,
+        /// The function to apply to each item. Must return a boolean.
+        test: Func,
+    ) -> SourceResult<()> {
+        *self = self.filter(engine, context, test)?;
+        Ok(())
+    }
+
     /// Produces a new array in which all items from the original one were
     /// transformed with the given function.
     #[func]
@@ -440,6 +552,53 @@ impl Array {
             .collect()
     }
 
+    /// Produces a new array with the value at the given key in each of the
+    /// original array's dictionaries.
+    ///
+    /// Fails with an error if one of the items is not a dictionary or if a
+    /// dictionary does not contain the key and no default value was specified.
+    ///
+    /// ```example
+    /// #let people = (
+    ///   (name: "Alice", age: 31),
+    ///   (name: "Bob"),
+    /// )
+    ///
+    /// #people.field("name") \
+    /// #people.field("age", default: none)
+    /// ```
+    #[func]
+    pub fn field(
+        self,
+        /// The key at which to retrieve the values.
+        key: Str,
+        /// A default value for dictionaries that don't contain the key.
+        #[named]
+        default: Option<Value>,
+    ) -> StrResult<Array> {
+        // Check the types of all items before looking up any key.
+        let dicts = self
+            .into_iter()
+            .enumerate()
+            .map(|(i, item)| match item {
+                Value::Dict(dict) => Ok(dict),
+                item => bail!("expected dictionary at index {i}, found {}", item.ty()),
+            })
+            .collect::<StrResult<Vec<_>>>()?;
+
+        dicts
+            .into_iter()
+            .enumerate()
+            .map(|(i, dict)| {
+                dict.get(&key)
+                    .ok()
+                    .cloned()
+                    .or_else(|| default.clone())
+                    .ok_or_else(|| missing_field(i, &key))
+            })
+            .collect()
+    }
+
     /// Returns a new array with the values alongside their indices.
     ///
     /// The returned array consists of `(index, value)` pairs in the form of
@@ -710,7 +869,7 @@ impl Array {
         /// An alternative separator between the last two items.
         #[named]
         last: Option<Value>,
-    ) -> StrResult<Value> {
+    ) -> HintedStrResult<Value> {
         let len = self.0.len();
         let separator = separator.unwrap_or(Value::None);
 
@@ -810,6 +969,100 @@ impl Array {
             .collect()
     }
 
+    /// Groups the items of the array by the result of a key function.
+    ///
+    /// Returns a dictionary that maps each key to an array of the items for
+    /// which the key function returned it. The keys appear in the order in
+    /// which they were first returned and the items keep their order within
+    /// each group.
+    ///
+    /// The key function must return a string, an integer, or a boolean.
+    /// Integers and booleans are converted to strings, so the keys `{1}` and
+    /// `{"1"}` end up in the same group.
+    ///
+    /// ```example
+    /// #let fruits = (
+    ///   (name: "Apple", color: "red"),
+    ///   (name: "Banana", color: "yellow"),
+    ///   (name: "Cherry", color: "red"),
+    /// )
+    /// #fruits.group-by(fruit => fruit.color)
+    /// ```
+    #[func]
+    pub fn group_by(
+        self,
+        /// The engine.
+        engine: &mut Engine,
+        /// The callsite context.
+        context: Tracked<Context>,
+        /// The function to apply to each item to determine its group.
+        key: Func,
+    ) -> SourceResult<Dict> {
+        let mut groups = IndexMap::<Str, EcoVec<Value>>::new();
+        for (i, item) in self.into_iter().enumerate() {
+            let group = match key.call(engine, context, [item.clone()])? {
+                Value::Str(string) => string,
+                value @ (Value::Int(_) | Value::Bool(_)) => value.repr().into(),
+                value => bail!(
+                    key.span(),
+                    "expected string, integer, or boolean as group key, found {}",
+                    value.ty();
+                    hint: "the key function returned this for the item at index {i}",
+                ),
+            };
+            groups.entry(group).or_default().push(item);
+        }
+        Ok(groups
+            .into_iter()
+            .map(|(group, items)| (group, Array(items).into_value()))
+            .collect())
+    }
+
+    /// Splits the array into runs of consecutive items for which the key
+    /// function returns equal values.
+    ///
+    /// Unlike [`group-by`]($array.group-by), items with the same key only end
+    /// up in the same chunk if they are adjacent. This is useful for arrays
+    /// that are already sorted by the key.
+    ///
+    /// ```example
+    /// #let events = (
+    ///   (day: 1, what: "Arrival"),
+    ///   (day: 1, what: "Dinner"),
+    ///   (day: 2, what: "Hike"),
+    ///   (day: 3, what: "Departure"),
+    /// )
+    /// #for chunk in events.chunk-by(event => event.day) [
+    ///   / Day #chunk.first().day: #chunk.map(e => e.what).join(", ")
+    /// ]
+    /// ```
+    #[func]
+    pub fn chunk_by(
+        self,
+        /// The engine.
+        engine: &mut Engine,
+        /// The callsite context.
+        context: Tracked<Context>,
+        /// The function to apply to each item to determine its key.
+        key: Func,
+    ) -> SourceResult<Array> {
+        let mut chunks = EcoVec::new();
+        let mut chunk = EcoVec::new();
+        let mut last = None;
+        for item in self {
+            let current = key.call(engine, context, [item.clone()])?;
+            if last.as_ref().is_some_and(|last| !ops::equal(last, &current)) {
+                chunks.push(Array(std::mem::take(&mut chunk)).into_value());
+            }
+            chunk.push(item);
+            last = Some(current);
+        }
+        if !chunk.is_empty() {
+            chunks.push(Array(chunk).into_value());
+        }
+        Ok(Self(chunks))
+    }
+
     /// Return a sorted version of this array, optionally by a given key
     /// function. The sorting algorithm used is stable.
     ///
@@ -817,7 +1070,7 @@ impl Array {
     /// function (if given) yields an error.
     #[func]
     pub fn sorted(
-        self,
+        mut self,
         /// The engine.
         engine: &mut Engine,
         /// The callsite context.
@@ -830,7 +1083,7 @@ impl Array {
         key: Option<Func>,
     ) -> SourceResult<Array> {
         let mut result = Ok(());
-        let mut vec = self.0;
+        let mut vec = std::mem::take(&mut self.0);
         let mut key_of = |x: Value| match &key {
             // NOTE: We are relying on `comemo`'s memoization of function
             // evaluation to not excessively reevaluate the `key`.
@@ -1008,7 +1261,7 @@ impl Add for Array {
 
 impl AddAssign for Array {
     fn add_assign(&mut self, rhs: Self) {
-        self.0.extend(rhs.0);
+        self.0.extend(rhs);
     }
 }
 
@@ -1024,12 +1277,23 @@ impl FromIterator<Value> for Array {
     }
 }
 
+impl Drop for Array {
+    fn drop(&mut self) {
+        // Dropping the items could recurse arbitrarily deeply for nested
+        // arrays.
+        if !self.0.is_empty() && self.0.is_unique() {
+            let items = std::mem::take(&mut self.0);
+            maybe_grow(move || drop(items));
+        }
+    }
+}
+
 impl IntoIterator for Array {
     type Item = Value;
     type IntoIter = ecow::vec::IntoIter<Value>;
 
-    fn into_iter(self) -> Self::IntoIter {
-        self.0.into_iter()
+    fn into_iter(mut self) -> Self::IntoIter {
+        std::mem::take(&mut self.0).into_iter()
     }
 }
 
@@ -1106,6 +1370,116 @@ impl<T: FromValue, const N: usize> FromValue for SmallVec<[T; N]> {
     }
 }
 
+/// The maximum number of values in a float range.
+const MAX_FLOAT_RANGE: usize = 1_000_000;
+
+/// Create an integer range.
+fn range_int(start: i64, end: i64, step: i64, inclusive: bool) -> HintedStrResult<Array> {
+    if step == 0 {
+        bail!("step must not be zero");
+    }
+
+    check_direction(start.cmp(&end), step.cmp(&0))?;
+
+    let mut x = start;
+    let mut array = Array::new();
+    while x.cmp(&end) == 0.cmp(&step) || (inclusive && x == end) {
+        array.push(x.into_value());
+        let Some(next) = x.checked_add(step) else { break };
+        x = next;
+    }
+
+    Ok(array)
+}
+
+/// Create a float range with a step.
+fn range_float(
+    start: f64,
+    end: f64,
+    step: f64,
+    inclusive: bool,
+) -> HintedStrResult<Array> {
+    if !start.is_finite() || !end.is_finite() {
+        bail!("range bounds must be finite");
+    }
+
+    if step == 0.0 {
+        bail!("step must not be zero");
+    } else if !step.is_finite() {
+        bail!("step must be finite");
+    }
+
+    check_direction(start.total_cmp(&end), step.total_cmp(&0.0))?;
+
+    // The number of steps that fit into the range. The end is reached when
+    // this is (very close to) a whole number.
+    let steps = (end - start) / step;
+    let whole = steps.round();
+    let reaches_end = (steps - whole).abs() <= 1e-9 * whole.max(1.0);
+    let count = if reaches_end { whole + f64::from(inclusive) } else { steps.ceil() };
+    if count > MAX_FLOAT_RANGE as f64 {
+        bail!(
+            "range would contain more than {MAX_FLOAT_RANGE} values";
+            hint: "use a larger step"
+        );
+    }
+
+    let count = count as usize;
+    let mut array = Array::with_capacity(count);
+    for i in 0..count {
+        let x = if reaches_end && i == whole as usize {
+            end
+        } else {
+            start + i as f64 * step
+        };
+        array.push(x.into_value());
+    }
+
+    Ok(array)
+}
+
+/// Create a float range with a given number of evenly spaced values.
+fn range_count(
+    start: f64,
+    end: f64,
+    count: Spanned<usize>,
+    inclusive: bool,
+) -> SourceResult<Array> {
+    if !start.is_finite() || !end.is_finite() {
+        bail!(count.span, "range bounds must be finite");
+    } else if count.v > MAX_FLOAT_RANGE {
+        bail!(count.span, "range would contain more than {MAX_FLOAT_RANGE} values");
+    }
+
+    let count = count.v;
+    let intervals = if inclusive { count.saturating_sub(1) } else { count };
+    let step = if intervals == 0 { 0.0 } else { (end - start) / intervals as f64 };
+
+    let mut array = Array::with_capacity(count);
+    for i in 0..count {
+        // Hit the end exactly, unless a single value is requested.
+        let x = if i > 0 && i == intervals { end } else { start + i as f64 * step };
+        array.push(x.into_value());
+    }
+
+    Ok(array)
+}
+
+/// Ensure that a range's step moves from its start towards its end.
+fn check_direction(bounds: Ordering, step: Ordering) -> HintedStrResult<()> {
+    match (bounds, step) {
+        (Ordering::Greater, Ordering::Greater) => bail!(
+            "step does not move from the start towards the end";
+            hint: "use a negative step to count down"
+        ),
+        (Ordering::Less, Ordering::Less) => bail!(
+            "step does not move from the start towards the end";
+            hint: "use a positive step to count up"
+        ),
+        _ => Ok(()),
+    }
+}
+
 /// The error message when the array is empty.
 #[cold]
 fn array_is_empty() -> EcoString {
@@ -1126,3 +1500,14 @@ fn out_of_bounds_no_default(index: i64, len: usize) -> EcoString {
          and no default value was specified",
     )
 }
+
+/// The error message when a dictionary in the array lacks a key and no default
+/// value was given.
+#[cold]
+fn missing_field(index: usize, key: &str) -> EcoString {
+    eco_format!(
+        "dictionary at index {index} does not contain key {} \
+         and no default value was specified",
+        key.repr()
+    )
+}
@@ -0,0 +1,172 @@
+use ecow::{eco_format, EcoString};
+
+use crate::diag::{bail, HintedStrResult, StrResult};
+use crate::foundations::{
+    func, scope, ty, Array, DynamicRepr, IntoValue, Repr, Str, Type, Value,
+};
+
+/// Incrementally builds an array or a string.
+///
+/// Repeatedly adding to an array or string with `+=` or `push` is usually
+/// fast, but can degrade if the value is shared elsewhere. A builder is a
+/// dedicated accumulator that is never part of another value, so appending to
+/// it always happens in place. Once you are done, call `finish` to retrieve the
+/// built value. A finished builder can't be used anymore.
+///
+/// Just like arrays, builders can only be modified through variables that the
+/// current scope owns. A builder captured by a function is read-only.
+///
+/// ```example
+/// #let b = builder()
+/// #for i in range(5) {
+///   b.push(i * i)
+/// }
+/// #b.finish()
+///
+/// #let s = builder(str)
+/// #for c in ("a", "b", "c") {
+///   s.push-str(upper(c))
+/// }
+/// #s.finish()
+/// ```
+#[ty(scope)]
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub struct Builder(Option<Buffer>);
+
+/// The contents of a builder that was not yet finished.
+#[derive(Debug, Clone, PartialEq, Hash)]
+enum Buffer {
+    Array(Array),
+    Str(EcoString),
+}
+
+impl Builder {
+    /// Whether nothing was added to the builder yet (or it was finished).
+    pub fn is_empty(&self) -> bool {
+        match &self.0 {
+            Some(Buffer::Array(array)) => array.is_empty(),
+            Some(Buffer::Str(string)) => string.is_empty(),
+            None => true,
+        }
+    }
+
+    /// Access the buffer of a builder that was not yet finished.
+    fn buffer(&mut self) -> StrResult<&mut Buffer> {
+        self.0.as_mut().ok_or_else(finished)
+    }
+}
+
+#[scope]
+impl Builder {
+    /// Creates a new, empty builder.
+    #[func(constructor)]
+    pub fn construct(
+        /// The type of value to build. Either `array` or `str`.
+        #[default(Type::of::<Array>())]
+        kind: Type,
+    ) -> StrResult<Builder> {
+        let buffer = if kind == Type::of::<Array>() {
+            Buffer::Array(Array::new())
+        } else if kind == Type::of::<Str>() {
+            Buffer::Str(EcoString::new())
+        } else {
+            bail!("cannot build a value of type {kind}");
+        };
+        Ok(Self(Some(buffer)))
+    }
+
+    /// The number of values (for array builders) or bytes (for string
+    /// builders) added so far.
+    #[func(title = "Length")]
+    pub fn len(&self) -> StrResult<usize> {
+        match &self.0 {
+            Some(Buffer::Array(array)) => Ok(array.len()),
+            Some(Buffer::Str(string)) => Ok(string.len()),
+            None => Err(finished()),
+        }
+    }
+
+    /// Adds a value to the end of an array builder.
+    #[func]
+    pub fn push(
+        &mut self,
+        /// The value to add.
+        value: Value,
+    ) -> HintedStrResult<()> {
+        match self.buffer()? {
+            Buffer::Array(array) => array.push(value),
+            Buffer::Str(_) => bail!(
+                "cannot push to a string builder";
+                hint: "use `push-str` instead"
+            ),
+        }
+        Ok(())
+    }
+
+    /// Adds all values of an array to the end of an array builder.
+    #[func]
+    pub fn extend(
+        &mut self,
+        /// The values to add.
+        values: Array,
+    ) -> HintedStrResult<()> {
+        match self.buffer()? {
+            Buffer::Array(array) => array.extend(values),
+            Buffer::Str(_) => bail!(
+                "cannot extend a string builder";
+                hint: "use `push-str` instead"
+            ),
+        }
+        Ok(())
+    }
+
+    /// Adds a string to the end of a string builder.
+    #[func]
+    pub fn push_str(
+        &mut self,
+        /// The string to add.
+        string: Str,
+    ) -> HintedStrResult<()> {
+        match self.buffer()? {
+            Buffer::Str(buf) => buf.push_str(&string),
+            Buffer::Array(_) => bail!(
+                "cannot push a string to an array builder";
+                hint: "use `push` instead"
+            ),
+        }
+        Ok(())
+    }
+
+    /// Returns the built array or string. The builder can't be used
+    /// afterwards.
+    #[func]
+    pub fn finish(&mut self) -> StrResult<Value> {
+        match self.0.take() {
+            Some(Buffer::Array(array)) => Ok(array.into_value()),
+            Some(Buffer::Str(string)) => Ok(Str::from(string).into_value()),
+            None => Err(finished()),
+        }
+    }
+}
+
+impl Repr for Builder {
+    fn repr(&self) -> EcoString {
+        match &self.0 {
+            Some(Buffer::Array(array)) => {
+                eco_format!("builder(array, len: {})", array.len())
+            }
+            Some(Buffer::Str(string)) => {
+                eco_format!("builder(str, len: {})", string.len())
+            }
+            None => "builder(..)".into(),
+        }
+    }
+}
+
+impl DynamicRepr for Builder {}
+
+/// The error message when a builder is used after it was finished.
+#[cold]
+fn finished() -> EcoString {
+    "builder was already finished".into()
+}
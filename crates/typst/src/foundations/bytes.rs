@@ -7,7 +7,7 @@ use ecow::{eco_format, EcoString};
 use serde::{Serialize, Serializer};
 
 use crate::diag::{bail, StrResult};
-use crate::foundations::{cast, func, scope, ty, Array, Reflect, Repr, Str, Value};
+use crate::foundations::{cast, func, scope, ty, Array, Cast, Reflect, Repr, Str, Value};
 use crate::utils::LazyHash;
 
 /// A sequence of bytes.
@@ -246,6 +246,16 @@ cast! {
     v: Bytes => Self(v),
 }
 
+/// The order of the bytes of a number that is converted to or from bytes.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum Endianness {
+    /// The most significant byte comes first.
+    Big,
+    /// The least significant byte comes first.
+    #[default]
+    Little,
+}
+
 /// The out of bounds access error message.
 #[cold]
 fn out_of_bounds(index: i64, len: usize) -> EcoString {
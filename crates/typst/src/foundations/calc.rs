@@ -4,11 +4,12 @@ use std::cmp;
 use std::cmp::Ordering;
 use std::ops::{Div, Rem};
 
-use crate::diag::{bail, At, SourceResult, StrResult};
+use crate::diag::{bail, At, HintedStrResult, SourceResult, StrResult};
 use crate::eval::ops;
-use crate::foundations::{cast, func, IntoValue, Module, Scope, Value};
-use crate::layout::{Angle, Fr, Length, Ratio};
+use crate::foundations::{cast, func, int, IntoValue, Module, Repr, Scope, Value};
+use crate::layout::{Angle, Em, Fr, Length, Ratio};
 use crate::syntax::{Span, Spanned};
+use crate::visualize::{Color, ColorSpace, WeightedColor};
 
 /// A module with calculation definitions.
 pub fn module() -> Module {
@@ -41,6 +42,9 @@ pub fn module() -> Module {
     scope.define_func::<fract>();
     scope.define_func::<round>();
     scope.define_func::<clamp>();
+    scope.define_func::<lerp>();
+    scope.define_func::<map>();
+    scope.define_func::<smoothstep>();
     scope.define_func::<min>();
     scope.define_func::<max>();
     scope.define_func::<even>();
@@ -49,6 +53,12 @@ pub fn module() -> Module {
     scope.define_func::<div_euclid>();
     scope.define_func::<rem_euclid>();
     scope.define_func::<quo>();
+    scope.define_func::<bit_not>();
+    scope.define_func::<bit_and>();
+    scope.define_func::<bit_or>();
+    scope.define_func::<bit_xor>();
+    scope.define_func::<bit_lshift>();
+    scope.define_func::<bit_rshift>();
     scope.define("inf", f64::INFINITY);
     scope.define("nan", f64::NAN);
     scope.define("pi", std::f64::consts::PI);
@@ -702,26 +712,196 @@ pub fn round(
     }
 }
 
-/// Clamps a number between a minimum and maximum value.
+/// Clamps a value between a minimum and maximum value.
+///
+/// Works with numbers as well as with other comparable values like lengths or
+/// ratios. If any of the numbers is a float, the result is a float, too.
 ///
 /// ```example
 /// #assert(calc.clamp(5, 0, 10) == 5)
 /// #assert(calc.clamp(5, 6, 10) == 6)
+/// #assert(calc.clamp(5pt, 1cm, 2cm) == 1cm)
 /// #calc.clamp(5, 0, 4)
 /// ```
 #[func]
 pub fn clamp(
-    /// The number to clamp.
-    value: Num,
+    /// The value to clamp.
+    value: Spanned<Value>,
     /// The inclusive minimum value.
-    min: Num,
+    min: Spanned<Value>,
     /// The inclusive maximum value.
-    max: Spanned<Num>,
-) -> SourceResult<Num> {
-    if max.v.float() < min.float() {
+    max: Spanned<Value>,
+) -> SourceResult<Value> {
+    if ops::compare(&max.v, &min.v).at(max.span)? == Ordering::Less {
         bail!(max.span, "max must be greater than or equal to min")
     }
-    Ok(value.apply3(min, max.v, i64::clamp, f64::clamp))
+
+    if let (Some(v), Some(min), Some(max)) =
+        (Num::of(&value.v), Num::of(&min.v), Num::of(&max.v))
+    {
+        return Ok(v.apply3(min, max, i64::clamp, f64::clamp).into_value());
+    }
+
+    if ops::compare(&value.v, &min.v).at(value.span)? == Ordering::Less {
+        Ok(min.v)
+    } else if ops::compare(&value.v, &max.v).at(value.span)? == Ordering::Greater {
+        Ok(max.v)
+    } else {
+        Ok(value.v)
+    }
+}
+
+/// Linearly interpolates between two values.
+///
+/// Returns `a` for a factor of `{0}` and `b` for a factor of `{1}`. Works with
+/// numbers as well as with lengths, ratios, angles, and other values that
+/// support addition and multiplication with a float. Colors are
+/// [mixed]($color.mix) in the Oklab color space.
+///
+/// ```example
+/// #calc.lerp(0, 10, 0.25) \
+/// #calc.lerp(1cm, 3cm, 50%) \
+/// #calc.lerp(0deg, 90deg, 2) \
+/// #square(fill: calc.lerp(red, blue, 30%))
+/// ```
+#[func(title = "Linear Interpolation")]
+pub fn lerp(
+    /// The callsite span.
+    span: Span,
+    /// The value to start from.
+    a: Value,
+    /// The value to interpolate towards.
+    b: Value,
+    /// The interpolation factor. Factors outside of the range from `{0}` to
+    /// `{1}` extrapolate beyond `a` and `b`, except for colors, which cannot
+    /// be extrapolated.
+    t: Spanned<Factor>,
+) -> SourceResult<Value> {
+    // Between colors, only the factor can be at fault.
+    let span = match (&a, &b) {
+        (Value::Color(_), Value::Color(_)) => t.span,
+        _ => span,
+    };
+    interpolate(a, b, t.v.0).at(span)
+}
+
+/// Maps a value from one range to another.
+///
+/// The value's relative position in the input range determines its position
+/// in the output range. The output range can hold any values that can be
+/// [interpolated]($calc.lerp) while the input range must be numeric.
+///
+/// ```example
+/// #calc.map(5, 0, 10, 100, 200) \
+/// #calc.map(15, 0, 10, 0pt, 1pt) \
+/// #calc.map(15, 0, 10, 0pt, 1pt, clamp: true) \
+/// #square(fill: calc.map(2cm, 1cm, 5cm, red, blue))
+/// ```
+#[func]
+pub fn map(
+    /// The callsite span.
+    span: Span,
+    /// The value to map.
+    value: Value,
+    /// The start of the input range.
+    in_min: Value,
+    /// The end of the input range. Must be different from the start.
+    in_max: Value,
+    /// The start of the output range.
+    out_min: Value,
+    /// The end of the output range.
+    out_max: Value,
+    /// Whether to clamp the result to the output range when the value lies
+    /// outside of the input range.
+    #[named]
+    #[default(false)]
+    clamp: bool,
+) -> SourceResult<Value> {
+    let mut t = locate(value, in_min, in_max, ["in-min", "in-max"]).at(span)?;
+    if clamp {
+        t = t.clamp(0.0, 1.0);
+    }
+    interpolate(out_min, out_max, t).at(span)
+}
+
+/// Smoothly interpolates between zero and one as a value moves between two
+/// edges.
+///
+/// Returns `{0.0}` for values up to the first edge and `{1.0}` for values from
+/// the second edge and follows a smooth Hermite curve in between.
+///
+/// ```example
+/// #range(5).map(x => calc.smoothstep(0, 4, x))
+/// ```
+#[func(title = "Smooth Step")]
+pub fn smoothstep(
+    /// The callsite span.
+    span: Span,
+    /// The edge below which the result is zero.
+    edge0: Value,
+    /// The edge above which the result is one. Must be different from the
+    /// first edge.
+    edge1: Value,
+    /// The value whose position between the edges to determine.
+    x: Value,
+) -> SourceResult<f64> {
+    let t = locate(x, edge0, edge1, ["edge0", "edge1"]).at(span)?.clamp(0.0, 1.0);
+    Ok(t * t * (3.0 - 2.0 * t))
+}
+
+/// Interpolate between two values.
+fn interpolate(a: Value, b: Value, t: f64) -> HintedStrResult<Value> {
+    match (a, b) {
+        (Value::Color(a), Value::Color(b)) => {
+            if !(0.0..=1.0).contains(&t) {
+                bail!(
+                    "cannot extrapolate between colors";
+                    hint: "the interpolation factor must lie between 0% and 100%"
+                );
+            }
+            let colors = [WeightedColor::new(a, 1.0 - t), WeightedColor::new(b, t)];
+            Ok(Color::mix_iter(colors, ColorSpace::Oklab)?.into_value())
+        }
+        (a, b) => {
+            let delta = ops::sub(b, a.clone())?;
+            ops::add(a, ops::mul(delta, Value::Float(t))?)
+        }
+    }
+}
+
+/// Determine the relative position of a value in a range, which is zero at
+/// the start of the range and one at its end. The names of the range's bounds
+/// are used in error messages.
+fn locate(
+    value: Value,
+    start: Value,
+    end: Value,
+    [start_name, end_name]: [&str; 2],
+) -> HintedStrResult<f64> {
+    for v in [&value, &start, &end] {
+        if let Value::Length(length) = v {
+            if length.em != Em::zero() {
+                bail!(
+                    "cannot map a length with non-zero em units (`{}`)", length.repr();
+                    hint: "use `length.abs` instead to ignore its em component"
+                );
+            }
+        }
+    }
+
+    if ops::equal(&start, &end) {
+        bail!(
+            "{start_name} and {end_name} must not be equal (both are {})",
+            start.repr()
+        );
+    }
+
+    let offset = ops::sub(value, start.clone())?;
+    let size = ops::sub(end, start)?;
+    match ops::div(offset, size)? {
+        Value::Float(t) => Ok(t),
+        v => bail!("expected a numeric range, found {}", v.ty()),
+    }
 }
 
 /// Determines the minimum of a sequence of values.
@@ -908,6 +1088,122 @@ pub fn quo(
     Ok(floor(dividend.apply2(divisor.v, Div::div, Div::div)))
 }
 
+/// Calculates the bitwise NOT of an integer.
+///
+/// The operand is treated as a signed integer of 64 bits. This is the same as
+/// the [`bit-not`]($int.bit-not) method of integers.
+///
+/// ```example
+/// #calc.bit-not(4) \
+/// #calc.bit-not(-1)
+/// ```
+#[func(title = "Bitwise NOT")]
+pub fn bit_not(
+    /// The operand.
+    value: i64,
+) -> i64 {
+    !value
+}
+
+/// Calculates the bitwise AND between two integers.
+///
+/// The operands are treated as signed integers of 64 bits. This is the same as
+/// the [`bit-and`]($int.bit-and) method of integers.
+///
+/// ```example
+/// #calc.bit-and(128, 192)
+/// ```
+#[func(title = "Bitwise AND")]
+pub fn bit_and(
+    /// The left-hand operand.
+    lhs: i64,
+    /// The right-hand operand.
+    rhs: i64,
+) -> i64 {
+    lhs & rhs
+}
+
+/// Calculates the bitwise OR between two integers.
+///
+/// The operands are treated as signed integers of 64 bits. This is the same as
+/// the [`bit-or`]($int.bit-or) method of integers.
+///
+/// ```example
+/// #calc.bit-or(64, 32)
+/// ```
+#[func(title = "Bitwise OR")]
+pub fn bit_or(
+    /// The left-hand operand.
+    lhs: i64,
+    /// The right-hand operand.
+    rhs: i64,
+) -> i64 {
+    lhs | rhs
+}
+
+/// Calculates the bitwise XOR between two integers.
+///
+/// The operands are treated as signed integers of 64 bits. This is the same as
+/// the [`bit-xor`]($int.bit-xor) method of integers.
+///
+/// ```example
+/// #calc.bit-xor(64, 96)
+/// ```
+#[func(title = "Bitwise XOR")]
+pub fn bit_xor(
+    /// The left-hand operand.
+    lhs: i64,
+    /// The right-hand operand.
+    rhs: i64,
+) -> i64 {
+    lhs ^ rhs
+}
+
+/// Shifts an integer's bits to the left by the specified amount.
+///
+/// This is the same as the [`bit-lshift`]($int.bit-lshift) method of integers:
+/// An error occurs if the result does not fit in a 64-bit integer.
+///
+/// ```example
+/// #calc.bit-lshift(33, 2) \
+/// #calc.bit-lshift(-1, 3)
+/// ```
+#[func(title = "Bitwise Left Shift")]
+pub fn bit_lshift(
+    /// The operand to shift.
+    value: i64,
+    /// The amount of bits to shift. Must not be negative.
+    shift: u32,
+) -> StrResult<i64> {
+    int::lshift(value, shift)
+}
+
+/// Shifts an integer's bits to the right by the specified amount.
+///
+/// This is the same as the [`bit-rshift`]($int.bit-rshift) method of integers:
+/// The shift is arithmetic unless `logical` is set and saturates for shifts
+/// larger than 63.
+///
+/// ```example
+/// #calc.bit-rshift(64, 2) \
+/// #calc.bit-rshift(-8, 2) \
+/// #calc.bit-rshift(-8, 2, logical: true)
+/// ```
+#[func(title = "Bitwise Right Shift")]
+pub fn bit_rshift(
+    /// The operand to shift.
+    value: i64,
+    /// The amount of bits to shift. Must not be negative.
+    shift: u32,
+    /// Whether to perform a logical (unsigned) instead of an arithmetic right
+    /// shift.
+    #[named]
+    #[default(false)]
+    logical: bool,
+) -> i64 {
+    int::rshift(value, shift, logical)
+}
+
 /// A value which can be passed to functions that work with integers and floats.
 #[derive(Debug, Copy, Clone)]
 pub enum Num {
@@ -916,6 +1212,14 @@ pub enum Num {
 }
 
 impl Num {
+    fn of(value: &Value) -> Option<Self> {
+        match *value {
+            Value::Int(v) => Some(Self::Int(v)),
+            Value::Float(v) => Some(Self::Float(v)),
+            _ => None,
+        }
+    }
+
     fn apply2(
         self,
         other: Self,
@@ -941,7 +1245,8 @@ impl Num {
         }
     }
 
-    fn float(self) -> f64 {
+    /// Convert the number to a float.
+    pub fn float(self) -> f64 {
         match self {
             Self::Int(v) => v as f64,
             Self::Float(v) => v,
@@ -959,6 +1264,15 @@ cast! {
     v: f64 => Self::Float(v),
 }
 
+/// An interpolation factor, given as a number or ratio.
+pub struct Factor(f64);
+
+cast! {
+    Factor,
+    v: f64 => Self(v),
+    v: Ratio => Self(v.get()),
+}
+
 /// A value that can be passed to a trigonometric function.
 pub enum AngleLike {
     Int(i64),
@@ -4,27 +4,36 @@ use std::hash::{Hash, Hasher};
 use std::iter::{self, Sum};
 use std::marker::PhantomData;
 use std::ops::{Add, AddAssign, Deref, DerefMut};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 
-use comemo::Tracked;
-use ecow::{eco_format, EcoString};
+use comemo::{Tracked, TrackedMut};
+use ecow::{eco_format, eco_vec, EcoString};
 use serde::{Serialize, Serializer};
 use smallvec::smallvec;
 
-use crate::diag::{SourceResult, StrResult};
-use crate::engine::Engine;
+use crate::diag::{
+    bail, error, At, HintedStrResult, SourceResult, StrResult, Trace, Tracepoint,
+};
+use crate::engine::{Engine, Route};
 use crate::foundations::{
-    elem, func, scope, ty, Context, Dict, Element, Fields, IntoValue, Label,
-    NativeElement, Recipe, RecipeIndex, Repr, Selector, Str, Style, StyleChain, Styles,
-    Value,
+    elem, func, scope, ty, Array, Cast, Context, Dict, Element, Fields, Func, IntoValue,
+    Label, NativeElement, Recipe, RecipeIndex, Reflect, Repr, Selector, Str, Style,
+    StyleChain, Styles, Value,
 };
 use crate::introspection::Location;
-use crate::layout::{AlignElem, Alignment, Axes, Length, MoveElem, PadElem, Rel, Sides};
-use crate::model::{Destination, EmphElem, LinkElem, StrongElem};
+use crate::layout::{
+    AlignElem, Alignment, Axes, GroupElem, Length, MoveElem, PadElem, Rel, Sides,
+};
+use crate::model::{Destination, EmphElem, LinkElem, ParbreakElem, StrongElem};
 use crate::realize::{Behave, Behaviour};
-use crate::syntax::Span;
-use crate::text::UnderlineElem;
-use crate::utils::{fat, LazyHash, SmallBitSet};
+use crate::syntax::{FileId, Span, Spanned};
+use crate::text::{
+    HighlightElem, LinebreakElem, OverlineElem, SmallcapsElem, SpaceElem, StrikeElem,
+    SubElem, SuperElem, TextElem, UnderlineElem,
+};
+use crate::utils::{fat, similar, LazyHash, SmallBitSet};
+use crate::World;
 
 /// A piece of document content.
 ///
@@ -86,15 +95,30 @@ struct Inner<T: ?Sized + 'static> {
     label: Option<Label>,
     /// The element's location which identifies it in the layouted output.
     location: Option<Location>,
+    /// The element's identity across re-evaluations, if it was anchored.
+    stable_id: Option<StableId>,
     /// Manages the element during realization.
     /// - If bit 0 is set, the element is prepared.
     /// - If bit n is set, the element is guarded against the n-th show rule
     ///   recipe from the top of the style chain (counting from 1).
     lifecycle: SmallBitSet,
+    /// The cached result of [`Content::nodes`].
+    nodes: NodeCount,
     /// The element's raw data.
     elem: LazyHash<T>,
 }
 
+/// A lazily computed number of elements in a content tree. Zero if it was not
+/// computed yet.
+///
+/// Derived from the element, so it doesn't take part in hashing.
+#[derive(Default)]
+struct NodeCount(AtomicU64);
+
+impl Hash for NodeCount {
+    fn hash<H: Hasher>(&self, _: &mut H) {}
+}
+
 impl Content {
     /// Creates a new content from an element.
     pub fn new<T: NativeElement>(elem: T) -> Self {
@@ -102,7 +126,9 @@ impl Content {
             inner: Arc::new(Inner {
                 label: None,
                 location: None,
+                stable_id: None,
                 lifecycle: SmallBitSet::new(),
+                nodes: NodeCount::default(),
                 elem: elem.into(),
             }),
             span: Span::detached(),
@@ -164,6 +190,19 @@ impl Content {
         self.make_mut().location = Some(location);
     }
 
+    /// Get the identity of the content across re-evaluations.
+    ///
+    /// This is only present if the content was produced by an evaluation with
+    /// [anchoring](crate::engine::Traced::anchoring) enabled.
+    pub fn stable_id(&self) -> Option<StableId> {
+        self.inner.stable_id
+    }
+
+    /// Set the identity of the content across re-evaluations.
+    pub(crate) fn set_stable_id(&mut self, id: StableId) {
+        self.make_mut().stable_id = Some(id);
+    }
+
     /// Check whether a show rule recipe is disabled.
     pub fn is_guarded(&self, index: RecipeIndex) -> bool {
         self.inner.lifecycle.contains(index.0)
@@ -250,6 +289,11 @@ impl Content {
         self.make_mut().elem.materialize(styles);
     }
 
+    /// Set a field by ID to a value.
+    pub fn set_field(&mut self, id: u8, value: Value) -> HintedStrResult<()> {
+        self.make_mut().elem.set_field(id, value)
+    }
+
     /// Create a new sequence element from multiples elements.
     pub fn sequence(iter: impl IntoIterator<Item = Self>) -> Self {
         let mut iter = iter.into_iter();
@@ -296,7 +340,17 @@ impl Content {
         if Arc::strong_count(arc) > 1 || Arc::weak_count(arc) > 0 {
             *self = arc.elem.dyn_clone(arc, self.span);
         }
-        Arc::get_mut(&mut self.inner).unwrap()
+        let inner = Arc::get_mut(&mut self.inner).unwrap();
+        *inner.nodes.0.get_mut() = 0;
+        inner
+    }
+
+    /// Whether the content carries no identity of its own, so that it can be
+    /// dissolved into its parent.
+    fn is_dissolvable(&self) -> bool {
+        self.inner.label.is_none()
+            && self.inner.location.is_none()
+            && self.inner.stable_id.is_none()
     }
 
     /// Whether the contained element has the given capability.
@@ -351,17 +405,47 @@ impl Content {
         sequence.children.is_empty()
     }
 
-    /// Also auto expands sequence of sequences into flat sequence
+    /// Also auto expands sequence of sequences into flat sequence. Groups are
+    /// seen through in the same way.
     pub fn sequence_recursive_for_each<'a>(&'a self, f: &mut impl FnMut(&'a Self)) {
         if let Some(sequence) = self.to_packed::<SequenceElem>() {
             for child in &sequence.children {
                 child.sequence_recursive_for_each(f);
             }
+        } else if let Some(group) = self.to_packed::<GroupElem>() {
+            for child in group.children() {
+                child.sequence_recursive_for_each(f);
+            }
         } else {
             f(self);
         }
     }
 
+    /// The approximate number of elements in this content.
+    ///
+    /// Counts the children of sequences and styled elements, but not content
+    /// nested in the fields of other elements. Content that occurs several
+    /// times is counted each time, like layout would process it, but the count
+    /// of shared content is only computed once.
+    pub fn nodes(&self) -> u64 {
+        let cached = self.inner.nodes.0.load(Ordering::Relaxed);
+        if cached != 0 {
+            return cached;
+        }
+
+        let mut nodes = 1u64;
+        if let Some(sequence) = self.to_packed::<SequenceElem>() {
+            for child in &sequence.children {
+                nodes = nodes.saturating_add(child.nodes());
+            }
+        } else if let Some(styled) = self.to_packed::<StyledElem>() {
+            nodes = nodes.saturating_add(styled.child.nodes());
+        }
+
+        self.inner.nodes.0.store(nodes, Ordering::Relaxed);
+        nodes
+    }
+
     /// Style this content with a recipe, eagerly applying it if possible.
     pub fn styled_with_recipe(
         self,
@@ -370,7 +454,18 @@ impl Content {
         recipe: Recipe,
     ) -> SourceResult<Self> {
         if recipe.selector.is_none() {
-            recipe.apply(engine, context, self)
+            // Record the show rule on the route, so that a cyclic import in
+            // the transformation can point to it.
+            let mut engine = Engine {
+                world: engine.world,
+                introspector: engine.introspector,
+                traced: engine.traced,
+                sink: TrackedMut::reborrow_mut(&mut engine.sink),
+                route: Route::extend(engine.route.track())
+                    .with_show(recipe.span)
+                    .unnested(),
+            };
+            recipe.apply(&mut engine, context, self, 0)
         } else {
             Ok(self.styled(recipe))
         }
@@ -601,6 +696,647 @@ impl Content {
     pub fn location(&self) -> Option<Location> {
         self.inner.location
     }
+
+    /// Transforms elements in this content according to a set of rules.
+    ///
+    /// Each rule maps an element name to what the element should be replaced
+    /// with: A function is called with the element and its result replaces
+    /// the element, `{none}` removes the element and content replaces it
+    /// literally. The content is traversed bottom-up in a single pass, so a
+    /// function sees an element whose children were already rewritten.
+    ///
+    /// This works similarly to [show rules]($styling/#show-rules), but
+    /// operates directly on a content value instead of going through the
+    /// style system.
+    ///
+    /// ```example
+    /// #let doc = [
+    ///   = Introduction
+    ///   This is *important*.
+    ///   #image("tiger.jpg")
+    /// ]
+    ///
+    /// #doc.rewrite((
+    ///   heading: it => [Section: #it.body],
+    ///   strong: it => it.body,
+    ///   image: none,
+    /// ))
+    /// ```
+    #[func]
+    pub fn rewrite(
+        self,
+        /// The engine.
+        engine: &mut Engine,
+        /// The callsite context.
+        context: Tracked<Context>,
+        /// A dictionary from element names to functions, content or `{none}`.
+        rules: Spanned<Dict>,
+    ) -> SourceResult<Content> {
+        let rules = Rewrite::resolve(engine, rules)?;
+        self.rewrite_with(engine, context, &rules)
+    }
+
+    /// Groups this content with the content that follows it, so that the two
+    /// are kept together. This is a shorthand for `{group(a, b, keep: true)}`.
+    ///
+    /// ```example
+    /// #show heading: it => it.keep-with[
+    ///   This paragraph sticks
+    ///   to the heading.
+    /// ]
+    ///
+    /// = Introduction
+    /// ```
+    #[func]
+    pub fn keep_with(
+        self,
+        /// The content to keep this content with.
+        next: Content,
+    ) -> Content {
+        GroupElem::new(vec![self, next]).with_keep(true).pack()
+    }
+
+    /// Shortens this content to its first words or characters, for example to
+    /// produce a preview or an abstract.
+    ///
+    /// The content is traversed in reading order. Text is cut at a word
+    /// boundary and the styling of the kept part is preserved. Other elements
+    /// like equations and raw text are kept or dropped as a whole, depending
+    /// on the length of their text. Everything after the cut is dropped and
+    /// the ellipsis is appended, unless nothing was dropped.
+    ///
+    /// ```example
+    /// #let body = [
+    ///   This is a *rather long* text
+    ///   with some _styling._
+    /// ]
+    ///
+    /// #body.excerpt(words: 4) \
+    /// #body.excerpt(chars: 12, ellipsis: [ (more)])
+    /// ```
+    #[func]
+    pub fn excerpt(
+        self,
+        /// The callsite span.
+        span: Span,
+        /// The number of words to keep.
+        #[named]
+        words: Option<usize>,
+        /// The number of characters to keep. When the limit falls into a word,
+        /// the cut is moved to the start of that word if possible.
+        #[named]
+        chars: Option<usize>,
+        /// The content to append if anything was dropped.
+        #[named]
+        #[default(TextElem::packed('…'))]
+        ellipsis: Content,
+    ) -> SourceResult<Content> {
+        let (unit, limit) = match (words, chars) {
+            (Some(words), None) => (ExcerptUnit::Words, words),
+            (None, Some(chars)) => (ExcerptUnit::Chars, chars),
+            _ => bail!(span, "expected either `words` or `chars`"),
+        };
+
+        let mut excerpt = Excerpt {
+            unit,
+            left: limit,
+            words: 0,
+            in_word: false,
+            cut: false,
+        };
+        let content = self.excerpt_with(&mut excerpt);
+        Ok(if excerpt.cut { content + ellipsis } else { content })
+    }
+
+    /// Whether this content starts with whitespace, that is, a space, a line
+    /// break, a paragraph break or text beginning with whitespace.
+    ///
+    /// This is a shallow check that only looks through sequences, styling and
+    /// groups. It is useful to join pieces of content without doubling or
+    /// dropping spaces, see [`smart-join`]($smart-join).
+    ///
+    /// ```example
+    /// #[ a].starts-with-space() \
+    /// #[a ].starts-with-space()
+    /// ```
+    #[func]
+    pub fn starts_with_space(&self) -> bool {
+        self.edge(false).is_some_and(|leaf| leaf.is_whitespace(false))
+    }
+
+    /// Whether this content ends with whitespace, that is, a space, a line
+    /// break, a paragraph break or text ending with whitespace.
+    ///
+    /// Like [`starts-with-space`]($content.starts-with-space), this is a
+    /// shallow check.
+    ///
+    /// ```example
+    /// #[a ].ends-with-space() \
+    /// #[a #parbreak()].ends-with-space()
+    /// ```
+    #[func]
+    pub fn ends_with_space(&self) -> bool {
+        self.edge(true).is_some_and(|leaf| leaf.is_whitespace(true))
+    }
+
+    /// Splits this content into an array of chunks at marker elements, for
+    /// example to turn a document into slides.
+    ///
+    /// A sequence with `n` markers results in `n + 1` chunks, some of which may
+    /// be empty. Spaces around the markers stay part of the chunks. Content
+    /// without any markers results in a single chunk, the content itself.
+    ///
+    /// By default, only the top level of the content is searched for markers,
+    /// so a marker inside of styled content, like in `[#text(red)[..]]`, is
+    /// not found. With `recursive`, the split sees through styling: the styled
+    /// content is split, too, and each of its parts keeps the styling.
+    ///
+    /// ```example
+    /// #let body = [
+    ///   First #pagebreak()
+    ///   Second #pagebreak()
+    ///   Third
+    /// ]
+    ///
+    /// #for chunk in body.split-at(pagebreak) {
+    ///   box(stroke: 0.5pt, inset: 4pt, chunk)
+    /// }
+    /// ```
+    #[func]
+    pub fn split_at(
+        self,
+        /// What to split at, typically an element function like
+        /// [`pagebreak`] or a label.
+        marker: Selector,
+        /// What to do with the markers: With `{"none"}`, they are dropped.
+        /// With `{"start"}`, each marker starts the chunk after it. With
+        /// `{"end"}`, each marker ends the chunk before it.
+        #[named]
+        #[default]
+        keep: SplitKeep,
+        /// Whether to also split styled content.
+        #[named]
+        #[default(false)]
+        recursive: bool,
+    ) -> Array {
+        let mut pieces = vec![];
+        self.clone().split_pieces(&marker, recursive, &mut pieces);
+        if !pieces.iter().any(|(_, is_marker)| *is_marker) {
+            return iter::once(self.into_value()).collect();
+        }
+
+        let mut chunks = Array::new();
+        let mut chunk = vec![];
+        for (piece, is_marker) in pieces {
+            if !is_marker {
+                chunk.push(piece);
+                continue;
+            }
+
+            if keep == SplitKeep::End {
+                chunk.push(piece.clone());
+            }
+            chunks.push(Content::sequence(std::mem::take(&mut chunk)).into_value());
+            if keep == SplitKeep::Start {
+                chunk.push(piece);
+            }
+        }
+        chunks.push(Content::sequence(chunk).into_value());
+        chunks
+    }
+}
+
+impl Content {
+    /// The first or last leaf of this content, looking through sequences,
+    /// styling and groups.
+    fn edge(&self, last: bool) -> Option<&Self> {
+        let children = if let Some(sequence) = self.to_packed::<SequenceElem>() {
+            sequence.children.as_slice()
+        } else if let Some(group) = self.to_packed::<GroupElem>() {
+            group.children().as_slice()
+        } else if let Some(styled) = self.to_packed::<StyledElem>() {
+            return styled.child.edge(last);
+        } else {
+            let empty = self.to_packed::<TextElem>().is_some_and(|t| t.text().is_empty());
+            return (!empty).then_some(self);
+        };
+
+        let mut iter = children.iter().filter_map(|child| child.edge(last));
+        if last {
+            iter.next_back()
+        } else {
+            iter.next()
+        }
+    }
+
+    /// Break this content into pieces for [`split-at`](Self::split_at),
+    /// flagging the markers.
+    fn split_pieces(
+        self,
+        marker: &Selector,
+        recursive: bool,
+        pieces: &mut Vec<(Content, bool)>,
+    ) {
+        if self.label().is_none() {
+            if let Some(sequence) = self.to_packed::<SequenceElem>() {
+                for child in &sequence.children {
+                    child.clone().split_pieces(marker, recursive, pieces);
+                }
+                return;
+            }
+        }
+
+        if let Some(styled) = self.to_packed::<StyledElem>().filter(|_| recursive) {
+            let mut inner = vec![];
+            styled.child.clone().split_pieces(marker, recursive, &mut inner);
+            if !inner.iter().any(|(_, is_marker)| *is_marker) {
+                pieces.push((self, false));
+                return;
+            }
+
+            // Consecutive pieces share one styled wrapper.
+            let mut run = vec![];
+            for (piece, is_marker) in inner {
+                if !is_marker {
+                    run.push(piece);
+                    continue;
+                }
+                if !run.is_empty() {
+                    let content = Content::sequence(std::mem::take(&mut run));
+                    pieces.push((content.styled_with_map(styled.styles.clone()), false));
+                }
+                pieces.push((piece.styled_with_map(styled.styles.clone()), true));
+            }
+            if !run.is_empty() {
+                let content = Content::sequence(run);
+                pieces.push((content.styled_with_map(styled.styles.clone()), false));
+            }
+            return;
+        }
+
+        let is_marker = marker.matches(&self, None);
+        pieces.push((self, is_marker));
+    }
+
+    /// Whether this leaf is whitespace or text that starts or ends with
+    /// whitespace.
+    fn is_whitespace(&self, last: bool) -> bool {
+        if let Some(text) = self.to_packed::<TextElem>() {
+            let mut chars = text.text().chars();
+            let c = if last { chars.next_back() } else { chars.next() };
+            return c.is_some_and(char::is_whitespace);
+        }
+
+        self.is::<SpaceElem>() || self.is::<LinebreakElem>() || self.is::<ParbreakElem>()
+    }
+
+    /// Shorten this content for an excerpt, updating its state.
+    fn excerpt_with(mut self, excerpt: &mut Excerpt) -> Self {
+        if excerpt.cut {
+            return Content::empty();
+        }
+
+        // Pieces that were cut away entirely are removed, so that the result
+        // looks like it was written that way.
+        if let Some(sequence) = self.to_packed_mut::<SequenceElem>() {
+            let mut kept = vec![];
+            for child in std::mem::take(&mut sequence.children) {
+                let child = child.excerpt_with(excerpt);
+                if !child.is_empty() {
+                    kept.push(child);
+                }
+                if excerpt.cut {
+                    break;
+                }
+            }
+            if excerpt.cut {
+                while kept.last().is_some_and(Content::is_blank) {
+                    kept.pop();
+                }
+            }
+            let mut content = Content::sequence(kept);
+            if let Some(label) = self.label() {
+                content.set_label(label);
+            }
+            return content;
+        }
+
+        if let Some(styled) = self.to_packed_mut::<StyledElem>() {
+            styled.child = std::mem::take(&mut styled.child).excerpt_with(excerpt);
+            return if styled.child.is_empty() { Content::empty() } else { self };
+        }
+
+        if let Some(body) = self.inline_body_mut() {
+            *body = std::mem::take(body).excerpt_with(excerpt);
+            return if body.is_empty() { Content::empty() } else { self };
+        }
+
+        if let Some(elem) = self.to_packed_mut::<TextElem>() {
+            if let Some(end) = excerpt.cut_text(&elem.text) {
+                let kept = elem.text[..end].trim_end();
+                if kept.is_empty() {
+                    return Content::empty();
+                }
+                elem.text = kept.into();
+            }
+            return self;
+        }
+
+        if excerpt.left == 0 && self.is_blank() {
+            excerpt.in_word = false;
+            return Content::empty();
+        }
+
+        if excerpt.take(&self.plain_text()) {
+            self
+        } else {
+            excerpt.cut = true;
+            Content::empty()
+        }
+    }
+
+    /// Whether this is a space, a line break or a paragraph break.
+    fn is_blank(&self) -> bool {
+        self.is::<SpaceElem>() || self.is::<LinebreakElem>() || self.is::<ParbreakElem>()
+    }
+
+    /// The body of an inline element that an excerpt can cut into.
+    fn inline_body_mut(&mut self) -> Option<&mut Content> {
+        macro_rules! bodies {
+            ($($elem:ty),*) => {
+                $(if self.is::<$elem>() {
+                    return self.to_packed_mut::<$elem>().map(|elem| &mut elem.body);
+                })*
+            };
+        }
+
+        bodies!(
+            StrongElem,
+            EmphElem,
+            LinkElem,
+            UnderlineElem,
+            OverlineElem,
+            StrikeElem,
+            HighlightElem,
+            SmallcapsElem,
+            SubElem,
+            SuperElem
+        );
+        None
+    }
+
+    /// Rewrite this content bottom-up with resolved rules.
+    fn rewrite_with(
+        self,
+        engine: &mut Engine,
+        context: Tracked<Context>,
+        rules: &[(Element, Rewrite)],
+    ) -> SourceResult<Self> {
+        let mut content = self;
+        if let Some(sequence) = content.to_packed_mut::<SequenceElem>() {
+            let children = std::mem::take(&mut sequence.children);
+            sequence.children = children
+                .into_iter()
+                .map(|child| child.rewrite_with(engine, context, rules))
+                .collect::<SourceResult<_>>()?;
+            return Ok(content);
+        }
+
+        if let Some(styled) = content.to_packed_mut::<StyledElem>() {
+            let child = std::mem::take(&mut styled.child);
+            styled.child = child.rewrite_with(engine, context, rules)?;
+            return Ok(content);
+        }
+
+        let content = content.rewrite_fields(engine, context, rules)?;
+        let elem = content.elem();
+        let Some((_, rule)) = rules.iter().find(|(e, _)| *e == elem) else {
+            return Ok(content);
+        };
+
+        match rule {
+            Rewrite::Remove => Ok(Content::empty()),
+            Rewrite::Replace(replacement) => Ok(replacement.clone()),
+            Rewrite::Func(func) => {
+                let span = content.span();
+                let point = || Tracepoint::Call(func.name().map(Into::into));
+                func.call(engine, context, [content])
+                    .trace(engine.world, point, span)?
+                    .cast::<Content>()
+                    .at(func.span())
+            }
+        }
+    }
+
+    /// Rewrite the content in the fields of this element in place, so that
+    /// its identity and synthesized fields are kept.
+    fn rewrite_fields(
+        mut self,
+        engine: &mut Engine,
+        context: Tracked<Context>,
+        rules: &[(Element, Rewrite)],
+    ) -> SourceResult<Self> {
+        let elem = self.elem();
+        for param in elem.params() {
+            let Some(id) = elem.field_id(param.name) else { continue };
+            let Ok(value) = self.inner.elem.field(id) else { continue };
+            let rewritten = rewrite_value(engine, context, rules, value.clone())?;
+            if rewritten != value {
+                let span = self.span();
+                self.set_field(id, rewritten).at(span)?;
+            }
+        }
+        Ok(self)
+    }
+}
+
+/// What [`Content::split_at`] does with the markers it splits at.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum SplitKeep {
+    /// The markers are dropped.
+    #[default]
+    None,
+    /// Each marker starts the chunk after it.
+    Start,
+    /// Each marker ends the chunk before it.
+    End,
+}
+
+/// How the length of an excerpt is measured.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum ExcerptUnit {
+    Words,
+    Chars,
+}
+
+/// The state of an excerpt while the content is traversed.
+struct Excerpt {
+    /// How the length is measured.
+    unit: ExcerptUnit,
+    /// How many words or characters may still be kept.
+    left: usize,
+    /// How many words were started so far.
+    words: usize,
+    /// Whether the text so far ends within a word.
+    in_word: bool,
+    /// Whether the content was cut, so that everything after is dropped.
+    cut: bool,
+}
+
+impl Excerpt {
+    /// Consume the text of an element that is kept or dropped as a whole.
+    /// Returns whether it fits.
+    fn take(&mut self, text: &str) -> bool {
+        let mut in_word = self.in_word;
+        let mut words = 0;
+        for c in text.chars() {
+            if !c.is_whitespace() && !in_word {
+                words += 1;
+            }
+            in_word = !c.is_whitespace();
+        }
+
+        let len = match self.unit {
+            ExcerptUnit::Words => words,
+            ExcerptUnit::Chars => text.chars().count(),
+        };
+
+        // Elements without text, like images, still take up space once
+        // nothing is left.
+        if len.max(usize::from(self.left == 0)) > self.left {
+            return false;
+        }
+
+        self.left -= len;
+        self.words += words;
+        if let Some(c) = text.chars().last() {
+            self.in_word = !c.is_whitespace();
+        }
+        true
+    }
+
+    /// Consume a piece of text. Returns the byte index at which it must be
+    /// cut, if any.
+    fn cut_text(&mut self, text: &str) -> Option<usize> {
+        let mut word_start = None;
+        for (i, c) in text.char_indices() {
+            let space = c.is_whitespace();
+            let starts_word = !space && !self.in_word;
+            if starts_word {
+                word_start = Some(i);
+                self.words += 1;
+            }
+            self.in_word = !space;
+
+            match self.unit {
+                ExcerptUnit::Words if starts_word => {
+                    if self.left == 0 {
+                        self.cut = true;
+                        return Some(i);
+                    }
+                    self.left -= 1;
+                }
+                // Whitespace after the limit doesn't count as dropped
+                // content, so that cutting exactly at the end adds no
+                // ellipsis.
+                ExcerptUnit::Chars if !space || self.left > 0 => {
+                    if self.left == 0 {
+                        self.cut = true;
+                        // Prefer to cut before the word, unless it is the
+                        // first one or started in a previous element.
+                        return Some(match word_start {
+                            Some(start) if self.words > 1 => start,
+                            _ => i,
+                        });
+                    }
+                    self.left -= 1;
+                }
+                _ => {}
+            }
+        }
+        None
+    }
+}
+
+/// Rewrite the content within a field value.
+fn rewrite_value(
+    engine: &mut Engine,
+    context: Tracked<Context>,
+    rules: &[(Element, Rewrite)],
+    value: Value,
+) -> SourceResult<Value> {
+    Ok(match value {
+        Value::Content(content) => {
+            content.rewrite_with(engine, context, rules)?.into_value()
+        }
+        Value::Array(array) => array
+            .into_iter()
+            .map(|value| rewrite_value(engine, context, rules, value))
+            .collect::<SourceResult<Array>>()?
+            .into_value(),
+        value => value,
+    })
+}
+
+/// What to replace an element with in [`Content::rewrite`].
+enum Rewrite {
+    /// Call a function with the element.
+    Func(Func),
+    /// Remove the element.
+    Remove,
+    /// Replace the element with fixed content.
+    Replace(Content),
+}
+
+impl Rewrite {
+    /// Resolve the element names and replacements of a rule dictionary.
+    fn resolve(
+        engine: &Engine,
+        rules: Spanned<Dict>,
+    ) -> SourceResult<Vec<(Element, Rewrite)>> {
+        let Spanned { v: dict, span } = rules;
+        let library = engine.world.library();
+        let elements: Vec<Element> = library
+            .global
+            .scope()
+            .iter()
+            .chain(library.math.scope().iter())
+            .filter_map(|(_, value)| match value {
+                Value::Func(func) => func.element(),
+                _ => None,
+            })
+            .collect();
+
+        let mut resolved = vec![];
+        for (name, value) in dict {
+            let Some(elem) = elements.iter().copied().find(|e| e.name() == name.as_str())
+            else {
+                let mut diag = error!(span, "unknown element: {name}");
+                let names = elements.iter().map(|elem| elem.name());
+                if let Some(similar) = similar(&name, names) {
+                    diag.hint(eco_format!("did you mean `{similar}`?"));
+                }
+                return Err(eco_vec![diag]);
+            };
+
+            let rule = match value {
+                Value::Func(func) => Rewrite::Func(func),
+                Value::None => Rewrite::Remove,
+                value if Content::castable(&value) => {
+                    Rewrite::Replace(value.cast().at(span)?)
+                }
+                value => bail!(
+                    span,
+                    "expected function, content, or none for {name}, found {}",
+                    value.ty()
+                ),
+            };
+
+            resolved.push((elem, rule));
+        }
+
+        Ok(resolved)
+    }
 }
 
 impl Default for Content {
@@ -641,22 +1377,89 @@ impl Add for Content {
         let mut lhs = self;
         match (lhs.to_packed_mut::<SequenceElem>(), rhs.to_packed_mut::<SequenceElem>()) {
             (Some(seq_lhs), Some(rhs)) => {
-                seq_lhs.children.extend(rhs.children.iter().cloned());
+                let mut children = rhs.children.iter().cloned();
+                if let Some(first) = children.next() {
+                    push_merged(&mut seq_lhs.children, first);
+                }
+                seq_lhs.children.extend(children);
                 lhs
             }
             (Some(seq_lhs), None) => {
-                seq_lhs.children.push(rhs);
+                push_merged(&mut seq_lhs.children, rhs);
                 lhs
             }
             (None, Some(rhs_seq)) => {
-                rhs_seq.children.insert(0, lhs);
+                match rhs_seq.children.first_mut() {
+                    Some(first) => match lhs.merge_styled(std::mem::take(first)) {
+                        Ok(()) => *first = lhs,
+                        Err(taken) => {
+                            *first = taken;
+                            rhs_seq.children.insert(0, lhs);
+                        }
+                    },
+                    None => rhs_seq.children.push(lhs),
+                }
                 rhs
             }
-            (None, None) => Self::sequence([lhs, rhs]),
+            (None, None) => match lhs.merge_styled(rhs) {
+                Ok(()) => lhs,
+                Err(rhs) => Self::sequence([lhs, rhs]),
+            },
         }
     }
 }
 
+/// Push content to a sequence's children, merging it with the last child if
+/// possible.
+fn push_merged(children: &mut Vec<Content>, content: Content) {
+    let content = match children.last_mut() {
+        Some(last) => match last.merge_styled(content) {
+            Ok(()) => return,
+            Err(content) => content,
+        },
+        None => content,
+    };
+    children.push(content);
+}
+
+impl Content {
+    /// Merge the following content into this one if both are styled with the
+    /// same set rule properties.
+    ///
+    /// Joining the results of a loop whose body contains a set rule would
+    /// otherwise produce one identical style wrapper per iteration. Wrappers
+    /// with show rules are never merged since a show rule may transform the
+    /// whole styled content at once.
+    fn merge_styled(&mut self, next: Self) -> Result<(), Self> {
+        let mergeable = |content: &Self| {
+            content.inner.label.is_none()
+                && content.inner.location.is_none()
+                && content.to_packed::<StyledElem>().is_some_and(|styled| {
+                    styled.styles.iter().all(|style| style.property().is_some())
+                })
+        };
+
+        let styles = |content: &Self| {
+            content.to_packed::<StyledElem>().map(|styled| styled.styles.clone())
+        };
+
+        if !mergeable(self) || !mergeable(&next) || styles(self) != styles(&next) {
+            return Err(next);
+        }
+
+        let span = next.span();
+        let next = next.unpack::<StyledElem>()?;
+        if let Some(styled) = self.to_packed_mut::<StyledElem>() {
+            styled.child = std::mem::take(&mut styled.child) + next.child;
+        }
+
+        // The merged wrapper covers both pieces, so it can use the span of
+        // the second one if the first doesn't have one.
+        *self = std::mem::take(self).spanned(span);
+        Ok(())
+    }
+}
+
 impl<'a> Add<&'a Self> for Content {
     type Output = Self;
 
@@ -711,6 +1514,46 @@ impl Serialize for Content {
     }
 }
 
+/// Identifies an element across re-evaluations of its source file.
+///
+/// The identity is derived from the span of the markup expression that
+/// produced the element and how many elements that expression produced before
+/// it, which distinguishes the elements that a loop generates from a single
+/// expression. Within a function body, elements are counted separately for
+/// each call. As long as the source code around the expression is not edited,
+/// re-evaluating yields the same identity. Embedders can use it to
+/// match up the elements of two evaluations, e.g. to preserve state in a live
+/// preview.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct StableId {
+    /// The span of the producing expression.
+    span: Span,
+    /// How many elements the expression produced before this one.
+    occurrence: usize,
+}
+
+impl StableId {
+    /// Create a new stable identity.
+    pub fn new(span: Span, occurrence: usize) -> Self {
+        Self { span, occurrence }
+    }
+
+    /// The file the producing expression is in.
+    pub fn id(self) -> Option<FileId> {
+        self.span.id()
+    }
+
+    /// The span of the producing expression.
+    pub fn span(self) -> Span {
+        self.span
+    }
+
+    /// How many elements the producing expression produced before this one.
+    pub fn occurrence(self) -> usize {
+        self.occurrence
+    }
+}
+
 /// The trait that combines all the other traits into a trait object.
 trait Bounds: Debug + Repr + Fields + Send + Sync + 'static {
     fn dyn_type_id(&self) -> TypeId;
@@ -734,7 +1577,9 @@ impl<T: NativeElement> Bounds for T {
             inner: Arc::new(Inner {
                 label: inner.label,
                 location: inner.location,
+                stable_id: inner.stable_id,
                 lifecycle: inner.lifecycle.clone(),
+                nodes: NodeCount::default(),
                 elem: LazyHash::reuse(self.clone(), &inner.elem),
             }),
             span,
@@ -936,6 +1781,107 @@ impl Repr for SequenceElem {
     }
 }
 
+/// Accumulates content into a flat sequence.
+///
+/// Nested sequences without a label or location are dissolved into the
+/// resulting sequence and adjacent plain texts with the same span are
+/// merged. This happens when the sequence is built rather than when content
+/// is pushed, so that a label can still be attached to the last pushed
+/// content as a whole.
+#[derive(Debug, Default, Clone)]
+pub struct SequenceBuilder {
+    children: Vec<Content>,
+}
+
+impl SequenceBuilder {
+    /// Create a new, empty builder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a new, empty builder with space for the given number of pieces.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self { children: Vec::with_capacity(capacity) }
+    }
+
+    /// Whether nothing was pushed yet.
+    pub fn is_empty(&self) -> bool {
+        self.children.is_empty()
+    }
+
+    /// The content pushed so far, as it was pushed.
+    pub fn children(&self) -> &[Content] {
+        &self.children
+    }
+
+    /// Mutable access to the content pushed so far.
+    pub fn children_mut(&mut self) -> &mut [Content] {
+        &mut self.children
+    }
+
+    /// Add content to the end of the sequence.
+    pub fn push(&mut self, content: Content) {
+        self.children.push(content);
+    }
+
+    /// Add all content of another builder to the end of the sequence.
+    pub fn append(&mut self, other: Self) {
+        self.children.extend(other.children);
+    }
+
+    /// Build the sequence.
+    ///
+    /// Like [`Content::sequence`], this returns empty content if nothing was
+    /// pushed and the content itself if only one piece was pushed.
+    pub fn build(self) -> Content {
+        let mut children = Vec::with_capacity(self.children.len());
+        for child in self.children {
+            flatten_into(&mut children, child);
+        }
+        Content::sequence(children)
+    }
+}
+
+impl Extend<Content> for SequenceBuilder {
+    fn extend<T: IntoIterator<Item = Content>>(&mut self, iter: T) {
+        self.children.extend(iter);
+    }
+}
+
+/// Add content to a flat list of children, dissolving nested sequences and
+/// merging adjacent plain texts.
+fn flatten_into(children: &mut Vec<Content>, content: Content) {
+    if content.is_dissolvable() {
+        if let Some(sequence) = content.to_packed::<SequenceElem>() {
+            for child in &sequence.children {
+                flatten_into(children, child.clone());
+            }
+            return;
+        }
+    }
+
+    if let Some(prev) = children.last_mut() {
+        if prev.span() == content.span() && is_plain_text(prev) && is_plain_text(&content)
+        {
+            let text = content.to_packed::<TextElem>().unwrap().text.clone();
+            prev.to_packed_mut::<TextElem>().unwrap().text.push_str(&text);
+            return;
+        }
+    }
+
+    children.push(content);
+}
+
+/// Whether the content is text without identity and without any fields set
+/// apart from the text itself.
+fn is_plain_text(content: &Content) -> bool {
+    content.is::<TextElem>()
+        && content.label().is_none()
+        && content.location().is_none()
+        && content.stable_id().is_none()
+        && content.inner.elem.fields().len() == 1
+}
+
 /// Content alongside styles.
 #[elem(Debug, Repr, PartialEq)]
 pub struct StyledElem {
@@ -7,7 +7,7 @@ use time::error::{Format, InvalidFormatDescription};
 use time::macros::format_description;
 use time::{format_description, Month, PrimitiveDateTime};
 
-use crate::diag::{bail, StrResult};
+use crate::diag::{bail, HintedStrResult, StrResult};
 use crate::engine::Engine;
 use crate::foundations::{
     cast, func, repr, scope, ty, Dict, Duration, Repr, Smart, Str, Value,
@@ -184,6 +184,13 @@ impl Datetime {
             return None;
         };
 
+        Self::from_iso(string)
+    }
+
+    /// Try to parse an ISO 8601 date, time, or combination of both, like
+    /// `2024-03-01`, `12:30:00`, or `2024-03-01T12:30:00`. A combined date and
+    /// time may end with `Z`.
+    pub fn from_iso(string: &str) -> Option<Self> {
         if let Ok(d) = time::PrimitiveDateTime::parse(
             string,
             &format_description!("[year]-[month]-[day]T[hour]:[minute]:[second]Z"),
@@ -229,6 +236,25 @@ impl Datetime {
             Datetime::Time(_) => "time",
         }
     }
+
+    /// Move the datetime by the given duration.
+    fn shift(self, by: time::Duration) -> HintedStrResult<Self> {
+        Ok(match self {
+            Self::Datetime(datetime) => {
+                Self::Datetime(datetime.checked_add(by).ok_or_else(out_of_range)?)
+            }
+            Self::Date(date) => {
+                if by.whole_seconds() % 86_400 != 0 || by.subsec_nanoseconds() != 0 {
+                    bail!(
+                        "a date can only be shifted by whole days";
+                        hint: "use a datetime with a time of day to shift by less than a day"
+                    );
+                }
+                Self::Date(date.checked_add(by).ok_or_else(out_of_range)?)
+            }
+            Self::Time(time) => Self::Time(time + by),
+        })
+    }
 }
 
 #[scope]
@@ -477,28 +503,19 @@ impl PartialOrd for Datetime {
 }
 
 impl Add<Duration> for Datetime {
-    type Output = Self;
+    type Output = HintedStrResult<Self>;
 
     fn add(self, rhs: Duration) -> Self::Output {
-        let rhs: time::Duration = rhs.into();
-        match self {
-            Self::Datetime(datetime) => Self::Datetime(datetime + rhs),
-            Self::Date(date) => Self::Date(date + rhs),
-            Self::Time(time) => Self::Time(time + rhs),
-        }
+        self.shift(rhs.into())
     }
 }
 
 impl Sub<Duration> for Datetime {
-    type Output = Self;
+    type Output = HintedStrResult<Self>;
 
     fn sub(self, rhs: Duration) -> Self::Output {
         let rhs: time::Duration = rhs.into();
-        match self {
-            Self::Datetime(datetime) => Self::Datetime(datetime - rhs),
-            Self::Date(date) => Self::Date(date - rhs),
-            Self::Time(time) => Self::Time(time - rhs),
-        }
+        self.shift(rhs.checked_neg().ok_or_else(out_of_range)?)
     }
 }
 
@@ -533,6 +550,12 @@ cast! {
     v: u8 => Self::try_from(v).map_err(|_| "month is invalid")?
 }
 
+/// The error message when a datetime is moved out of the supported range.
+#[cold]
+fn out_of_range() -> EcoString {
+    "datetime is out of range".into()
+}
+
 /// Format the `Format` error of the time crate in an appropriate way.
 fn format_time_format_error(error: Format) -> EcoString {
     match error {
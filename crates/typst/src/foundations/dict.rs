@@ -1,18 +1,22 @@
+use std::cmp::Ordering;
 use std::fmt::{Debug, Formatter};
 use std::hash::{Hash, Hasher};
 use std::ops::{Add, AddAssign};
 use std::sync::Arc;
 
+use comemo::Tracked;
 use ecow::{eco_format, EcoString};
 use indexmap::IndexMap;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
-use crate::diag::{Hint, HintedStrResult, StrResult};
+use crate::diag::{bail, At, Hint, HintedStrResult, SourceResult, StrResult};
+use crate::engine::Engine;
+use crate::eval::{maybe_grow, ops};
 use crate::foundations::{
-    array, cast, func, repr, scope, ty, Array, Module, Repr, Str, Value,
+    array, cast, func, repr, scope, ty, Array, Cast, Context, Func, Module, Repr, Str,
+    Value,
 };
-use crate::syntax::is_ident;
-use crate::utils::ArcExt;
+use crate::syntax::{is_ident, Span};
 
 /// Create a new [`Dict`] from key-value pairs.
 #[macro_export]
@@ -84,6 +88,14 @@ impl Dict {
         self.0.get(key).ok_or_else(|| missing_key(key))
     }
 
+    /// Borrow the value at the given key along with the index of its entry.
+    pub fn get_full(&self, key: &str) -> StrResult<(usize, &Value)> {
+        self.0
+            .get_full(key)
+            .map(|(index, _, value)| (index, value))
+            .ok_or_else(|| missing_key(key))
+    }
+
     /// Mutably borrow the value the given `key` maps to.
     pub fn at_mut(&mut self, key: &str) -> HintedStrResult<&mut Value> {
         Arc::make_mut(&mut self.0)
@@ -92,6 +104,34 @@ impl Dict {
             .hint("use `insert` to add or update values")
     }
 
+    /// Mutably borrow the value the given `key` maps to, in order to update it
+    /// with a compound assignment operator like `+=`.
+    ///
+    /// Unlike a plain assignment, this can't create a missing key because
+    /// there is no value to apply the operator to.
+    pub fn at_mut_for_update(
+        &mut self,
+        key: &str,
+        op: &str,
+    ) -> HintedStrResult<&mut Value> {
+        if !self.contains(key) {
+            let existing = if self.is_empty() {
+                "the dictionary is empty".into()
+            } else {
+                let mut keys: Vec<_> = self.0.keys().take(3).map(Repr::repr).collect();
+                if self.len() > 3 {
+                    keys.push("..".into());
+                }
+                let noun = if self.len() == 1 { "key" } else { "keys" };
+                eco_format!("the dictionary contains the {noun} {}", keys.join(", "))
+            };
+            return Err(missing_key(key)).hint(existing).hint(eco_format!(
+                "use `insert` or assign with `=` before updating a key with `{op}`"
+            ));
+        }
+        self.at_mut(key)
+    }
+
     /// Remove the value if the dictionary contains the given key.
     pub fn take(&mut self, key: &str) -> StrResult<Value> {
         Arc::make_mut(&mut self.0)
@@ -113,6 +153,27 @@ impl Dict {
         }
     }
 
+    /// Keep only the entries whose index satisfies the predicate, in their
+    /// order.
+    ///
+    /// Unlike collecting the entries into a new dictionary, this reuses the
+    /// hashes of the keys instead of hashing them again.
+    pub fn retain_indices(&mut self, mut keep: impl FnMut(usize) -> bool) {
+        let mut index = 0;
+        Arc::make_mut(&mut self.0).retain(|_, _| {
+            index += 1;
+            keep(index - 1)
+        });
+    }
+
+    /// Take out the underlying map, cloning it if it is shared.
+    pub(crate) fn into_map(mut self) -> IndexMap<Str, Value> {
+        match Arc::get_mut(&mut self.0) {
+            Some(map) => std::mem::take(map),
+            None => (*self.0).clone(),
+        }
+    }
+
     /// Iterate over pairs of references to the contained keys and values.
     pub fn iter(&self) -> indexmap::map::Iter<Str, Value> {
         self.0.iter()
@@ -233,12 +294,113 @@ impl Dict {
             .ok_or_else(|| missing_key(&key))
     }
 
+    /// Applies a function to the value associated with a key and stores the
+    /// result under the same key. If the key is not part of the dictionary,
+    /// the function receives the default value instead. If the function
+    /// fails, the dictionary is left unchanged.
+    ///
+    /// ```example
+    /// #let counts = (apples: 1)
+    /// #counts.update("apples", n => n + 1)
+    /// #counts.update("pears", n => n + 1, default: 0)
+    /// #counts
+    /// ```
+    #[func]
+    pub fn update(
+        &mut self,
+        /// The engine.
+        engine: &mut Engine,
+        /// The callsite context.
+        context: Tracked<Context>,
+        /// The key of the pair that should be updated.
+        key: Str,
+        /// The function to apply to the current value.
+        func: Func,
+        /// A value to pass to the function if the key does not exist.
+        #[named]
+        #[default]
+        default: Value,
+    ) -> SourceResult<()> {
+        let value = self.0.get(&key).cloned().unwrap_or(default);
+        let value = func.call(engine, context, [value])?;
+        Arc::make_mut(&mut self.0).insert(key, value);
+        Ok(())
+    }
+
+    /// Returns a sorted version of this dictionary, optionally by a given key
+    /// function. Without a key function, the pairs are ordered by their keys.
+    /// The sorting algorithm used is stable.
+    ///
+    /// Returns an error if two values could not be compared or if the key
+    /// function (if given) yields an error.
+    ///
+    /// ```example
+    /// #let ages = (Peter: 34, Anna: 27, Klaus: 41)
+    /// #ages.sorted() \
+    /// #ages.sorted(key: (name, age) => age)
+    /// ```
+    #[func]
+    pub fn sorted(
+        self,
+        /// The engine.
+        engine: &mut Engine,
+        /// The callsite context.
+        context: Tracked<Context>,
+        /// The callsite span.
+        span: Span,
+        /// If given, applies this function to the key and value of each pair in
+        /// the dictionary to determine the keys to sort by.
+        #[named]
+        key: Option<Func>,
+    ) -> SourceResult<Dict> {
+        let Some(key) = key else {
+            let mut map = self.into_map();
+            map.sort_keys();
+            return Ok(map.into());
+        };
+
+        let mut result = Ok(());
+        let mut key_of = |k: &Str, v: &Value| {
+            // NOTE: We are relying on `comemo`'s memoization of function
+            // evaluation to not excessively reevaluate the `key`.
+            key.call(engine, context, [Value::Str(k.clone()), v.clone()])
+        };
+        let mut map = self.into_map();
+        map.sort_by(|k1, v1, k2, v2| match (key_of(k1, v1), key_of(k2, v2)) {
+            (Ok(a), Ok(b)) => ops::compare(&a, &b).unwrap_or_else(|err| {
+                if result.is_ok() {
+                    result = Err(err).at(span);
+                }
+                Ordering::Equal
+            }),
+            (Err(e), _) | (_, Err(e)) => {
+                if result.is_ok() {
+                    result = Err(e);
+                }
+                Ordering::Equal
+            }
+        });
+        result.map(|_| map.into())
+    }
+
     /// Returns the keys of the dictionary as an array in insertion order.
     #[func]
     pub fn keys(&self) -> Array {
         self.0.keys().cloned().map(Value::Str).collect()
     }
 
+    /// Returns the keys of the dictionary as a sorted array.
+    ///
+    /// ```example
+    /// #(b: 1, c: 2, a: 3).keys-sorted()
+    /// ```
+    #[func]
+    pub fn keys_sorted(&self) -> Array {
+        let mut keys: Vec<_> = self.0.keys().cloned().collect();
+        keys.sort();
+        keys.into_iter().map(Value::Str).collect()
+    }
+
     /// Returns the values of the dictionary as an array in insertion order.
     #[func]
     pub fn values(&self) -> Array {
@@ -254,6 +416,150 @@ impl Dict {
             .map(|(k, v)| Value::Array(array![k.clone(), v.clone()]))
             .collect()
     }
+
+    /// Merges another dictionary into this one, recursing into nested
+    /// dictionaries.
+    ///
+    /// Unlike adding dictionaries with `+`, which replaces nested dictionaries
+    /// as a whole, this only replaces the values that the other dictionary
+    /// specifies. This is useful to apply user overrides to default settings.
+    ///
+    /// ```example
+    /// #let defaults = (
+    ///   font: "Libertinus Serif",
+    ///   colors: (text: black, link: blue),
+    /// )
+    /// #defaults.deep-merge((colors: (link: red)))
+    /// ```
+    ///
+    /// Dictionaries can be merged up to a nesting depth of 256 levels.
+    #[func]
+    pub fn deep_merge(
+        self,
+        /// The dictionary whose values take precedence.
+        other: Dict,
+        /// Whether a `{none}` value in the other dictionary removes the key
+        /// instead of being stored.
+        #[named]
+        #[default(false)]
+        none_removes: bool,
+        /// Whether an `{auto}` value in the other dictionary keeps the value
+        /// of this dictionary instead of being stored.
+        #[named]
+        #[default(true)]
+        auto_keeps_base: bool,
+        /// How to merge two arrays at the same key.
+        #[named]
+        #[default]
+        arrays: ArrayMerge,
+        /// Whether to fail if a dictionary and another kind of value are at
+        /// the same key. By default, the value of the other dictionary is
+        /// taken.
+        #[named]
+        #[default(false)]
+        strict: bool,
+    ) -> StrResult<Dict> {
+        let options = MergeOptions { none_removes, auto_keeps_base, arrays, strict };
+        let mut map = self.into_map();
+        deep_merge(&mut map, other, &options, &mut vec![])?;
+        Ok(map.into())
+    }
+}
+
+/// How [`Dict::deep_merge`] merges two arrays at the same key.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum ArrayMerge {
+    /// The array of the other dictionary replaces the existing one.
+    #[default]
+    Replace,
+    /// The array of the other dictionary is appended to the existing one.
+    Concat,
+}
+
+/// How deeply nested dictionaries [`Dict::deep_merge`] recurses into before
+/// giving up. Values are trees, so the recursion always ends, but dictionaries
+/// built in a loop can be nested deeply enough to exhaust the stack.
+const MAX_MERGE_DEPTH: usize = 256;
+
+/// The settings of a [`Dict::deep_merge`].
+struct MergeOptions {
+    none_removes: bool,
+    auto_keeps_base: bool,
+    arrays: ArrayMerge,
+    strict: bool,
+}
+
+/// Merge the pairs of `other` into `map`. The `path` holds the keys of the
+/// enclosing dictionaries for error messages.
+fn deep_merge(
+    map: &mut IndexMap<Str, Value>,
+    other: Dict,
+    options: &MergeOptions,
+    path: &mut Vec<Str>,
+) -> StrResult<()> {
+    for (key, value) in other {
+        match value {
+            Value::Auto if options.auto_keeps_base && map.contains_key(&key) => continue,
+            Value::None if options.none_removes => {
+                map.shift_remove(&key);
+                continue;
+            }
+            _ => {}
+        }
+
+        match (map.get_mut(&key), value) {
+            (Some(Value::Dict(base)), Value::Dict(nested)) => {
+                if path.len() >= MAX_MERGE_DEPTH {
+                    bail!(
+                        "cannot merge dictionaries nested more than \
+                         {MAX_MERGE_DEPTH} levels deep"
+                    );
+                }
+                path.push(key);
+                deep_merge(Arc::make_mut(&mut base.0), nested, options, path)?;
+                path.pop();
+            }
+            (Some(Value::Array(base)), Value::Array(array))
+                if options.arrays == ArrayMerge::Concat =>
+            {
+                base.extend(array);
+            }
+            (Some(base), value)
+                if options.strict
+                    && !matches!(value, Value::None | Value::Auto)
+                    && matches!(base, Value::Dict(_))
+                        != matches!(value, Value::Dict(_)) =>
+            {
+                path.push(key);
+                bail!(
+                    "cannot merge {} with {} at `{}`",
+                    base.ty(),
+                    value.ty(),
+                    format_path(path),
+                );
+            }
+            (_, value) => {
+                map.insert(key, value);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Format the keys leading to a nested value like a field access.
+fn format_path(path: &[Str]) -> EcoString {
+    let mut out = EcoString::new();
+    for (i, key) in path.iter().enumerate() {
+        if i > 0 {
+            out.push('.');
+        }
+        if is_ident(key) {
+            out.push_str(key);
+        } else {
+            out.push_str(&key.repr());
+        }
+    }
+    out
 }
 
 /// A value that can be cast to dictionary.
@@ -308,10 +614,7 @@ impl Add for Dict {
 
 impl AddAssign for Dict {
     fn add_assign(&mut self, rhs: Dict) {
-        match Arc::try_unwrap(rhs.0) {
-            Ok(map) => self.extend(map),
-            Err(rc) => self.extend(rc.iter().map(|(k, v)| (k.clone(), v.clone()))),
-        }
+        self.extend(rhs.into_map());
     }
 }
 
@@ -354,12 +657,23 @@ impl FromIterator<(Str, Value)> for Dict {
     }
 }
 
+impl Drop for Dict {
+    fn drop(&mut self) {
+        // Dropping the values could recurse arbitrarily deeply for nested
+        // dictionaries.
+        if let Some(map) = Arc::get_mut(&mut self.0).filter(|map| !map.is_empty()) {
+            let map = std::mem::take(map);
+            maybe_grow(move || drop(map));
+        }
+    }
+}
+
 impl IntoIterator for Dict {
     type Item = (Str, Value);
     type IntoIter = indexmap::map::IntoIter<Str, Value>;
 
     fn into_iter(self) -> Self::IntoIter {
-        Arc::take(self.0).into_iter()
+        self.into_map().into_iter()
     }
 }
 
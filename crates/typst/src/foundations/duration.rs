@@ -4,9 +4,13 @@ use std::ops::{Add, Div, Mul, Neg, Sub};
 use ecow::{eco_format, EcoString};
 use time::ext::NumericalDuration;
 
+use crate::diag::StrResult;
 use crate::foundations::{func, repr, scope, ty, Repr};
 
 /// Represents a positive or negative span of time.
+///
+/// Durations can be added to and subtracted from [datetimes]($datetime). A
+/// date without a time of day can only be shifted by a whole number of days.
 #[ty(scope, cast)]
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub struct Duration(time::Duration);
@@ -54,14 +58,21 @@ impl Duration {
         #[named]
         #[default(0)]
         weeks: i64,
-    ) -> Duration {
-        Duration::from(
-            time::Duration::seconds(seconds)
-                + time::Duration::minutes(minutes)
-                + time::Duration::hours(hours)
-                + time::Duration::days(days)
-                + time::Duration::weeks(weeks),
-        )
+    ) -> StrResult<Duration> {
+        let units = [
+            (seconds, 1),
+            (minutes, 60),
+            (hours, 3_600),
+            (days, 86_400),
+            (weeks, 604_800),
+        ];
+        let total = units
+            .into_iter()
+            .try_fold(0_i64, |total, (count, factor)| {
+                count.checked_mul(factor)?.checked_add(total)
+            })
+            .ok_or_else(too_large)?;
+        Ok(Duration(time::Duration::seconds(total)))
     }
 
     /// The duration expressed in seconds.
@@ -167,42 +178,46 @@ impl From<Duration> for time::Duration {
 }
 
 impl Add for Duration {
-    type Output = Duration;
+    type Output = StrResult<Duration>;
 
     fn add(self, rhs: Self) -> Self::Output {
-        Duration(self.0 + rhs.0)
+        self.0.checked_add(rhs.0).map(Self).ok_or_else(too_large)
     }
 }
 
 impl Sub for Duration {
-    type Output = Duration;
+    type Output = StrResult<Duration>;
 
     fn sub(self, rhs: Self) -> Self::Output {
-        Duration(self.0 - rhs.0)
+        self.0.checked_sub(rhs.0).map(Self).ok_or_else(too_large)
     }
 }
 
 impl Neg for Duration {
-    type Output = Duration;
+    type Output = StrResult<Duration>;
 
     fn neg(self) -> Self::Output {
-        Duration(-self.0)
+        self.0.checked_neg().map(Self).ok_or_else(too_large)
     }
 }
 
 impl Mul<f64> for Duration {
-    type Output = Duration;
+    type Output = StrResult<Duration>;
 
     fn mul(self, rhs: f64) -> Self::Output {
-        Duration(self.0 * rhs)
+        time::Duration::checked_seconds_f64(self.0.as_seconds_f64() * rhs)
+            .map(Self)
+            .ok_or_else(too_large)
     }
 }
 
 impl Div<f64> for Duration {
-    type Output = Duration;
+    type Output = StrResult<Duration>;
 
     fn div(self, rhs: f64) -> Self::Output {
-        Duration(self.0 / rhs)
+        time::Duration::checked_seconds_f64(self.0.as_seconds_f64() / rhs)
+            .map(Self)
+            .ok_or_else(too_large)
     }
 }
 
@@ -213,3 +228,9 @@ impl Div for Duration {
         self.0 / rhs.0
     }
 }
+
+/// The error message when a duration doesn't fit into the supported range.
+#[cold]
+fn too_large() -> EcoString {
+    "duration is too large".into()
+}
@@ -4,16 +4,17 @@ use std::fmt::{self, Debug};
 use std::hash::Hash;
 use std::ptr::NonNull;
 
-use ecow::EcoString;
+use ecow::{eco_format, eco_vec, EcoString};
 use once_cell::sync::Lazy;
 use smallvec::SmallVec;
 
-use crate::diag::SourceResult;
+use crate::diag::{error, HintedStrResult, SourceResult};
 use crate::engine::Engine;
 use crate::foundations::{
-    cast, Args, Content, Dict, FieldAccessError, Func, ParamInfo, Repr, Scope, Selector,
-    StyleChain, Styles, Value,
+    cast, func, repr, Args, Content, Dict, FieldAccessError, Func, ParamInfo, Repr,
+    Scope, Selector, Str, StyleChain, Styles, Value,
 };
+use crate::syntax::Spanned;
 use crate::text::{Lang, Region};
 use crate::utils::Static;
 
@@ -168,6 +169,75 @@ cast! {
     v: Func => v.element().ok_or("expected element")?,
 }
 
+/// Returns the default value of an element's settable field.
+///
+/// This is the value that the field has if no set rule applies to it. Set
+/// rules don't change what this function returns, so it can be called
+/// anywhere, also outside of [context].
+///
+/// ```example
+/// #default-of(text, "size") \
+/// #set text(size: 14pt)
+/// #default-of(text, "size")
+/// ```
+#[func]
+pub fn default_of(
+    /// The element function whose field to look up.
+    element: Element,
+    /// The name of the settable field.
+    field: Spanned<Str>,
+) -> SourceResult<Value> {
+    let Some(param) = element
+        .params()
+        .iter()
+        .find(|param| param.settable && param.name == field.v.as_str())
+    else {
+        let fields: Vec<_> = element
+            .params()
+            .iter()
+            .filter(|param| param.settable)
+            .map(|param| eco_format!("`{}`", param.name))
+            .collect();
+        let mut diag =
+            error!(field.span, "{} has no settable field `{}`", element.name(), field.v);
+        if fields.is_empty() {
+            diag.hint(eco_format!("{} has no settable fields", element.name()));
+        } else {
+            diag.hint(eco_format!(
+                "settable fields are {}",
+                repr::separated_list(&fields, "and"),
+            ));
+        }
+        return Err(eco_vec![diag]);
+    };
+
+    Ok(param.default.map(|default| default()).unwrap_or_default())
+}
+
+/// Returns the default values of all of an element's settable fields.
+///
+/// Like with [`default-of`]($default-of), set rules don't change the returned
+/// values.
+///
+/// ```example
+/// #defaults-of(strong)
+/// ```
+#[func]
+pub fn defaults_of(
+    /// The element function whose fields to look up.
+    element: Element,
+) -> Dict {
+    element
+        .params()
+        .iter()
+        .filter(|param| param.settable)
+        .map(|param| {
+            let value = param.default.map(|default| default()).unwrap_or_default();
+            (param.name.into(), value)
+        })
+        .collect()
+}
+
 /// A Typst element that is defined by a native Rust type.
 pub trait NativeElement:
     Debug
@@ -244,6 +314,9 @@ pub trait Fields {
     /// Resolve all fields with the styles and save them in-place.
     fn materialize(&mut self, styles: StyleChain);
 
+    /// Set the field with the given ID to a value.
+    fn set_field(&mut self, id: u8, value: Value) -> HintedStrResult<()>;
+
     /// Get the fields of the element.
     fn fields(&self) -> Dict;
 }
@@ -2,7 +2,8 @@ use std::num::ParseFloatError;
 
 use ecow::{eco_format, EcoString};
 
-use crate::foundations::{cast, func, repr, scope, ty, Repr, Str};
+use crate::diag::{bail, StrResult};
+use crate::foundations::{cast, func, repr, scope, ty, Bytes, Endianness, Repr, Str};
 use crate::layout::Ratio;
 
 /// A floating-point number.
@@ -48,6 +49,74 @@ impl f64 {
         value.0
     }
 
+    /// Converts bytes to a float.
+    ///
+    /// Four bytes are interpreted as a 32-bit float and eight bytes as a
+    /// 64-bit float.
+    ///
+    /// ```example
+    /// #float.from-bytes(bytes((0, 0, 0, 0, 0, 0, 240, 63))) \
+    /// #float.from-bytes(bytes((63, 128, 0, 0)), endian: "big")
+    /// ```
+    #[func]
+    pub fn from_bytes(
+        /// The bytes to convert. Must be 4 or 8 bytes long.
+        bytes: Bytes,
+        /// The order of the bytes.
+        #[named]
+        #[default]
+        endian: Endianness,
+    ) -> StrResult<f64> {
+        Ok(match bytes.len() {
+            4 => {
+                let array = bytes[..].try_into().unwrap();
+                match endian {
+                    Endianness::Big => f32::from_be_bytes(array),
+                    Endianness::Little => f32::from_le_bytes(array),
+                }
+                .into()
+            }
+            8 => {
+                let array = bytes[..].try_into().unwrap();
+                match endian {
+                    Endianness::Big => f64::from_be_bytes(array),
+                    Endianness::Little => f64::from_le_bytes(array),
+                }
+            }
+            len => bail!("expected 4 or 8 bytes, found {len}"),
+        })
+    }
+
+    /// Converts a float to bytes.
+    ///
+    /// With a size of four bytes, the float is first converted to the closest
+    /// 32-bit float.
+    ///
+    /// ```example
+    /// #array(1.0.to-bytes(endian: "big")) \
+    /// #array(1.0.to-bytes(size: 4))
+    /// ```
+    #[func]
+    pub fn to_bytes(
+        self,
+        /// The order of the bytes.
+        #[named]
+        #[default]
+        endian: Endianness,
+        /// The number of bytes. Must be 4 or 8.
+        #[named]
+        #[default(8)]
+        size: usize,
+    ) -> StrResult<Bytes> {
+        Ok(match (size, endian) {
+            (4, Endianness::Big) => (self as f32).to_be_bytes()[..].into(),
+            (4, Endianness::Little) => (self as f32).to_le_bytes()[..].into(),
+            (8, Endianness::Big) => self.to_be_bytes()[..].into(),
+            (8, Endianness::Little) => self.to_le_bytes()[..].into(),
+            _ => bail!("size must be 4 or 8, found {size}"),
+        })
+    }
+
     /// Checks if a float is not a number.
     ///
     /// In IEEE 754, more than one bit pattern represents a NaN. This function
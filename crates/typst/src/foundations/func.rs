@@ -2,10 +2,10 @@ use std::fmt::{self, Debug, Formatter};
 use std::sync::Arc;
 
 use comemo::{Tracked, TrackedMut};
-use ecow::{eco_format, EcoString};
+use ecow::{eco_format, EcoString, EcoVec};
 use once_cell::sync::Lazy;
 
-use crate::diag::{bail, SourceResult, StrResult};
+use crate::diag::{bail, SourceDiagnostic, SourceResult, StrResult};
 use crate::engine::Engine;
 use crate::foundations::{
     cast, repr, scope, ty, Args, CastInfo, Content, Context, Element, IntoArgs, Scope,
@@ -13,6 +13,7 @@ use crate::foundations::{
 };
 use crate::syntax::{ast, Span, SyntaxNode};
 use crate::utils::{LazyHash, Static};
+use crate::{World, WorldExt};
 
 #[doc(inline)]
 pub use typst_macros::func;
@@ -137,6 +138,8 @@ pub struct Func {
     repr: Repr,
     /// The span with which errors are reported when this function is called.
     span: Span,
+    /// Whether the tracepoints within this function are hidden from errors.
+    transparent: bool,
 }
 
 /// The different kinds of function representations.
@@ -205,6 +208,25 @@ impl Func {
         }
     }
 
+    /// The number of positional parameters of a closure, not counting an
+    /// argument sink. Not available for other kinds of functions.
+    pub fn num_pos_params(&self) -> Option<usize> {
+        match &self.repr {
+            Repr::Closure(closure) => Some(closure.num_pos_params),
+            _ => None,
+        }
+    }
+
+    /// The closure behind this function, looking through pre-applied
+    /// arguments. Not available for other kinds of functions.
+    pub fn closure(&self) -> Option<&Closure> {
+        match &self.repr {
+            Repr::Closure(closure) => Some(closure),
+            Repr::With(with) => with.0.closure(),
+            _ => None,
+        }
+    }
+
     /// Get the parameter info for a parameter with the given name if it exist.
     pub fn param(&self, name: &str) -> Option<&'static ParamInfo> {
         self.params()?.iter().find(|param| param.name == name)
@@ -232,6 +254,16 @@ impl Func {
         }
     }
 
+    /// The keys of the dictionary this function returns, if it always returns
+    /// a dictionary with the same keys.
+    pub fn keys(&self) -> &'static [&'static str] {
+        match &self.repr {
+            Repr::Native(native) => native.keys,
+            Repr::Element(_) | Repr::Closure(_) => &[],
+            Repr::With(with) => with.0.keys(),
+        }
+    }
+
     /// The function's associated scope of sub-definition.
     pub fn scope(&self) -> Option<&'static Scope> {
         match &self.repr {
@@ -270,7 +302,42 @@ impl Func {
         context: Tracked<Context>,
         args: A,
     ) -> SourceResult<Value> {
-        self.call_impl(engine, context, args.into_args(self.span))
+        let result = self.call_impl(engine, context, args.into_args(self.span));
+        if self.transparent {
+            result.map_err(|errors| self.hide_trace(engine.world, errors))
+        } else {
+            result
+        }
+    }
+
+    /// Remove the tracepoints that lie within this function's definition, so
+    /// that the errors read as if the functions called by it were called
+    /// directly.
+    fn hide_trace(
+        &self,
+        world: Tracked<dyn World + '_>,
+        mut errors: EcoVec<SourceDiagnostic>,
+    ) -> EcoVec<SourceDiagnostic> {
+        let Some(span) = self.closure_span() else { return errors };
+        let Some(range) = world.range(span) else { return errors };
+        for error in errors.make_mut() {
+            error.trace.retain(|point| {
+                point.span.id() != span.id()
+                    || !world.range(point.span).is_some_and(|point_range| {
+                        range.start <= point_range.start && point_range.end <= range.end
+                    })
+            });
+        }
+        errors
+    }
+
+    /// The span of the closure that defines this function, if any.
+    fn closure_span(&self) -> Option<Span> {
+        match &self.repr {
+            Repr::Closure(closure) => Some(closure.node.span()),
+            Repr::With(with) => with.0.closure_span(),
+            _ => None,
+        }
     }
 
     /// Non-generic implementation of `call`.
@@ -342,9 +409,33 @@ impl Func {
         Self {
             repr: Repr::With(Arc::new((self, args.take()))),
             span,
+            transparent: false,
         }
     }
 
+    /// Returns a version of this function that hides its own internals from
+    /// errors.
+    ///
+    /// Errors that occur while calling a function show a trace of the calls
+    /// that led to them. For a function that wraps another one, the trace
+    /// points into the wrapper, although the problem is usually in the
+    /// arguments passed to it. A transparent function leaves out the calls
+    /// within its definition, so that errors read as if the wrapped function
+    /// was called directly.
+    ///
+    /// ```example
+    /// #let fig = ((..args) => figure(
+    ///   ..args,
+    ///   kind: image,
+    /// )).transparent()
+    ///
+    /// #fig([A], caption: [Image])
+    /// ```
+    #[func]
+    pub fn transparent(self) -> Func {
+        Self { transparent: true, ..self }
+    }
+
     /// Returns a selector that filters for elements belonging to this function
     /// whose fields have the values of the given arguments.
     ///
@@ -421,7 +512,7 @@ impl PartialEq<&NativeFuncData> for Func {
 
 impl From<Repr> for Func {
     fn from(repr: Repr) -> Self {
-        Self { repr, span: Span::detached() }
+        Self { repr, span: Span::detached(), transparent: false }
     }
 }
 
@@ -456,6 +547,9 @@ pub struct NativeFuncData {
     pub docs: &'static str,
     /// A list of alternate search terms for this function.
     pub keywords: &'static [&'static str],
+    /// The keys of the dictionary this function returns, if it always
+    /// returns a dictionary with the same keys.
+    pub keys: &'static [&'static str],
     /// Whether this function makes use of context.
     pub contextual: bool,
     pub scope: Lazy<Scope>,
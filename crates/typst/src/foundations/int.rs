@@ -2,8 +2,10 @@ use std::num::{NonZeroI64, NonZeroIsize, NonZeroU64, NonZeroUsize, ParseIntError
 
 use ecow::{eco_format, EcoString};
 
-use crate::diag::StrResult;
-use crate::foundations::{cast, func, repr, scope, ty, Repr, Str, Value};
+use crate::diag::{bail, StrResult};
+use crate::foundations::{
+    cast, func, repr, scope, ty, Bytes, Endianness, Repr, Str, Value,
+};
 
 /// A whole number.
 ///
@@ -51,6 +53,92 @@ impl i64 {
         value.0
     }
 
+    /// Converts bytes to an integer.
+    ///
+    /// ```example
+    /// #int.from-bytes(bytes((1, 2))) \
+    /// #int.from-bytes(bytes((1, 2)), endian: "big") \
+    /// #int.from-bytes(bytes((255,))) \
+    /// #int.from-bytes(bytes((255,)), signed: false)
+    /// ```
+    #[func]
+    pub fn from_bytes(
+        /// The bytes to convert. Must be 1, 2, 4, or 8 bytes long.
+        bytes: Bytes,
+        /// The order of the bytes.
+        #[named]
+        #[default]
+        endian: Endianness,
+        /// Whether the bytes are interpreted as a signed integer in two's
+        /// complement. An unsigned integer of 8 bytes must not exceed the
+        /// largest integer.
+        #[named]
+        #[default(true)]
+        signed: bool,
+    ) -> StrResult<i64> {
+        let len = bytes.len();
+        if !matches!(len, 1 | 2 | 4 | 8) {
+            bail!("expected 1, 2, 4, or 8 bytes, found {len}");
+        }
+
+        let mut buf = [0; 8];
+        let unsigned = match endian {
+            Endianness::Big => {
+                buf[8 - len..].copy_from_slice(&bytes);
+                u64::from_be_bytes(buf)
+            }
+            Endianness::Little => {
+                buf[..len].copy_from_slice(&bytes);
+                u64::from_le_bytes(buf)
+            }
+        };
+
+        if signed {
+            // Move the sign bit to the top and back to extend it.
+            let shift = 64 - 8 * len as u32;
+            Ok(((unsigned << shift) as i64) >> shift)
+        } else {
+            i64::try_from(unsigned).map_err(|_| "number too large".into())
+        }
+    }
+
+    /// Converts an integer to bytes.
+    ///
+    /// The integer must fit into the given number of bytes, either as a signed
+    /// or as an unsigned integer.
+    ///
+    /// ```example
+    /// #array(258.to-bytes(size: 2)) \
+    /// #array(258.to-bytes(size: 2, endian: "big")) \
+    /// #array((-1).to-bytes(size: 4))
+    /// ```
+    #[func]
+    pub fn to_bytes(
+        self,
+        /// The order of the bytes.
+        #[named]
+        #[default]
+        endian: Endianness,
+        /// The number of bytes. Must be 1, 2, 4, or 8.
+        #[named]
+        #[default(8)]
+        size: usize,
+    ) -> StrResult<Bytes> {
+        if !matches!(size, 1 | 2 | 4 | 8) {
+            bail!("size must be 1, 2, 4, or 8, found {size}");
+        }
+
+        let bits = 8 * size as u32;
+        if bits < 64 && !(-(1 << (bits - 1))..1 << bits).contains(&self) {
+            bail!("{self} is out of range for {bits} bits");
+        }
+
+        Ok(match endian {
+            Endianness::Big => self.to_be_bytes()[8 - size..].into(),
+            Endianness::Little => self.to_le_bytes()[..size].into(),
+        })
+    }
+
     /// Calculates the sign of an integer.
     ///
     /// - If the number is positive, returns `{1}`.
@@ -136,7 +224,8 @@ impl i64 {
     ///
     /// For the purposes of this function, the operand is treated as a signed
     /// integer of 64 bits. An error will occur if the result is too large to
-    /// fit in a 64-bit integer.
+    /// fit in a 64-bit integer, that is, if the shift would change the
+    /// operand's sign or drop any bits other than copies of the sign bit.
     ///
     /// ```example
     /// #33.bit-lshift(2)
@@ -149,7 +238,7 @@ impl i64 {
         /// The amount of bits to shift. Must not be negative.
         shift: u32,
     ) -> StrResult<i64> {
-        Ok(self.checked_shl(shift).ok_or("the result is too large")?)
+        lshift(self, shift)
     }
 
     /// Shifts the operand's bits to the right by the specified amount.
@@ -188,31 +277,47 @@ impl i64 {
         #[default(false)]
         logical: bool,
     ) -> i64 {
-        if logical {
-            if shift >= u64::BITS {
-                // Excessive logical right shift would be equivalent to setting
-                // all bits to zero. Using `.min(63)` is not enough for logical
-                // right shift, since `-1 >> 63` returns 1, whereas
-                // `calc.bit-rshift(-1, 64)` should return the same as
-                // `(-1 >> 63) >> 1`, which is zero.
-                0
-            } else {
-                // Here we reinterpret the signed integer's bits as unsigned to
-                // perform logical right shift, and then reinterpret back as signed.
-                // This is valid as, according to the Rust reference, casting between
-                // two integers of same size (i64 <-> u64) is a no-op (two's complement
-                // is used).
-                // Reference:
-                // https://doc.rust-lang.org/stable/reference/expressions/operator-expr.html#numeric-cast
-                ((self as u64) >> shift) as i64
-            }
+        rshift(self, shift, logical)
+    }
+}
+
+/// Shifts an integer's bits to the left, failing if bits other than copies of
+/// the sign bit would be lost.
+pub(crate) fn lshift(value: i64, shift: u32) -> StrResult<i64> {
+    // A shift is lossless if shifting back restores the operand.
+    match value.checked_shl(shift) {
+        Some(result) if result >> shift == value => Ok(result),
+        _ => bail!("the result is too large"),
+    }
+}
+
+/// Shifts an integer's bits to the right, saturating for shifts larger than
+/// 63.
+pub(crate) fn rshift(value: i64, shift: u32, logical: bool) -> i64 {
+    if logical {
+        if shift >= u64::BITS {
+            // Excessive logical right shift would be equivalent to setting
+            // all bits to zero. Using `.min(63)` is not enough for logical
+            // right shift, since `-1 >> 63` returns 1, whereas
+            // `calc.bit-rshift(-1, 64)` should return the same as
+            // `(-1 >> 63) >> 1`, which is zero.
+            0
         } else {
-            // Saturate at -1 (negative) or 0 (otherwise) on excessive arithmetic
-            // right shift. Shifting those numbers any further does not change
-            // them, so it is consistent.
-            let shift = shift.min(i64::BITS - 1);
-            self >> shift
+            // Here we reinterpret the signed integer's bits as unsigned to
+            // perform logical right shift, and then reinterpret back as signed.
+            // This is valid as, according to the Rust reference, casting between
+            // two integers of same size (i64 <-> u64) is a no-op (two's complement
+            // is used).
+            // Reference:
+            // https://doc.rust-lang.org/stable/reference/expressions/operator-expr.html#numeric-cast
+            ((value as u64) >> shift) as i64
         }
+    } else {
+        // Saturate at -1 (negative) or 0 (otherwise) on excessive arithmetic
+        // right shift. Shifting those numbers any further does not change
+        // them, so it is consistent.
+        let shift = shift.min(i64::BITS - 1);
+        value >> shift
     }
 }
 
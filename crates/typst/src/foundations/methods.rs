@@ -1,8 +1,14 @@
 //! Handles special built-in methods on values.
 
+use comemo::Tracked;
+
 use crate::diag::{At, SourceResult};
-use crate::foundations::{Args, Array, Dict, Str, Type, Value};
+use crate::engine::Engine;
+use crate::foundations::{
+    Args, Array, Builder, Context, Dict, IntoValue, Str, Type, Value,
+};
 use crate::syntax::Span;
+use crate::text::UniqueSlugs;
 
 /// List the available methods for a type and whether they take arguments.
 pub fn mutable_methods_on(ty: Type) -> &'static [(&'static str, bool)] {
@@ -15,17 +21,52 @@ pub fn mutable_methods_on(ty: Type) -> &'static [(&'static str, bool)] {
             ("push", true),
             ("insert", true),
             ("remove", true),
+            ("splice", true),
+            ("truncate", true),
+            ("retain", true),
         ]
     } else if ty == Type::of::<Dict>() {
-        &[("at", true), ("insert", true), ("remove", true)]
+        &[("at", true), ("insert", true), ("remove", true), ("update", true)]
+    } else if ty == Type::of::<Builder>() {
+        &[("push", true), ("extend", true), ("push-str", true), ("finish", false)]
+    } else if ty == Type::of::<UniqueSlugs>() {
+        &[("issue", true)]
     } else {
         &[]
     }
 }
 
 /// Whether a specific method is mutating.
+///
+/// The methods that only builders have are not included here, see
+/// [`is_builder_method`].
 pub(crate) fn is_mutating_method(method: &str) -> bool {
-    matches!(method, "push" | "pop" | "insert" | "remove")
+    matches!(
+        method,
+        "push"
+            | "pop"
+            | "insert"
+            | "remove"
+            | "splice"
+            | "truncate"
+            | "retain"
+            | "update"
+            | "issue"
+    )
+}
+
+/// Whether a specific method is a mutating method that only builders have.
+///
+/// These only mutate when called on a builder, so that values of other types
+/// can have fields or methods with the same names.
+pub(crate) fn is_builder_method(method: &str) -> bool {
+    matches!(method, "extend" | "push-str" | "finish")
+}
+
+/// Whether a specific mutating method calls functions and thus needs the
+/// engine.
+pub(crate) fn is_calling_method(method: &str) -> bool {
+    matches!(method, "retain" | "update")
 }
 
 /// Whether a specific method is an accessor.
@@ -56,6 +97,13 @@ pub(crate) fn call_method_mut(
                     .remove(args.expect("index")?, args.named("default")?)
                     .at(span)?
             }
+            "splice" => {
+                let start = args.expect("start")?;
+                let end = args.expect("end")?;
+                let replacement = args.eat()?.unwrap_or_default();
+                array.splice(start, end, replacement).at(span)?
+            }
+            "truncate" => array.truncate(args.expect("len")?).at(span)?,
             _ => return missing(),
         },
 
@@ -68,6 +116,25 @@ pub(crate) fn call_method_mut(
             _ => return missing(),
         },
 
+        Value::Dyn(dynamic) => {
+            if let Some(builder) = dynamic.downcast_mut::<Builder>() {
+                match method {
+                    "push" => builder.push(args.expect("value")?).at(span)?,
+                    "extend" => builder.extend(args.expect("values")?).at(span)?,
+                    "push-str" => builder.push_str(args.expect("string")?).at(span)?,
+                    "finish" => output = builder.finish().at(span)?,
+                    _ => return missing(),
+                }
+            } else if let Some(slugs) = dynamic.downcast_mut::<UniqueSlugs>() {
+                match method {
+                    "issue" => output = slugs.issue(args.expect("slug")?).into_value(),
+                    _ => return missing(),
+                }
+            } else {
+                return missing();
+            }
+        }
+
         _ => return missing(),
     }
 
@@ -75,6 +142,39 @@ pub(crate) fn call_method_mut(
     Ok(output)
 }
 
+/// Call a mutating method that calls functions on a value.
+pub(crate) fn call_method_mut_with_engine(
+    value: &mut Value,
+    engine: &mut Engine,
+    context: Tracked<Context>,
+    method: &str,
+    mut args: Args,
+    span: Span,
+) -> SourceResult<Value> {
+    let ty = value.ty();
+    let missing = || Err(missing_method(ty, method)).at(span);
+
+    match value {
+        Value::Array(array) => match method {
+            "retain" => array.retain(engine, context, args.expect("test")?)?,
+            _ => return missing(),
+        },
+        Value::Dict(dict) => match method {
+            "update" => {
+                let key = args.expect("key")?;
+                let func = args.expect("func")?;
+                let default = args.named("default")?.unwrap_or_default();
+                dict.update(engine, context, key, func, default)?
+            }
+            _ => return missing(),
+        },
+        _ => return missing(),
+    }
+
+    args.finish()?;
+    Ok(Value::None)
+}
+
 /// Call an accessor method on a value.
 pub(crate) fn call_method_access<'a>(
     value: &'a mut Value,
@@ -108,3 +208,28 @@ pub(crate) fn call_method_access<'a>(
 fn missing_method(ty: Type, method: &str) -> String {
     format!("type {ty} has no method `{method}`")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mutable_methods_have_params() {
+        let types = [
+            Type::of::<Array>(),
+            Type::of::<Dict>(),
+            Type::of::<Builder>(),
+            Type::of::<UniqueSlugs>(),
+        ];
+        for ty in types {
+            for &(name, takes_args) in mutable_methods_on(ty) {
+                let Some(Value::Func(func)) = ty.scope().get(name) else {
+                    panic!("{ty} has no method `{name}` in its scope");
+                };
+                let params = func.params().unwrap();
+                assert_eq!(params.first().map(|param| param.name), Some("self"));
+                assert_eq!(params.len() > 1, takes_args, "{ty}.{name}");
+            }
+        }
+    }
+}
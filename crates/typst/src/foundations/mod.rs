@@ -8,6 +8,7 @@ mod args;
 mod array;
 mod auto;
 mod bool;
+mod builder;
 mod bytes;
 mod cast;
 mod content;
@@ -25,6 +26,7 @@ mod methods;
 mod module;
 mod none;
 mod plugin;
+mod reflect;
 mod scope;
 mod selector;
 mod str;
@@ -36,6 +38,7 @@ mod version;
 pub use self::args::*;
 pub use self::array::*;
 pub use self::auto::*;
+pub use self::builder::*;
 pub use self::bytes::*;
 pub use self::cast::*;
 pub use self::content::*;
@@ -53,6 +56,7 @@ pub use self::methods::*;
 pub use self::module::*;
 pub use self::none::*;
 pub use self::plugin::*;
+pub use self::reflect::*;
 pub use self::repr::Repr;
 pub use self::scope::*;
 pub use self::selector::*;
@@ -70,12 +74,18 @@ pub use {
     once_cell::sync::Lazy,
 };
 
+use comemo::{Tracked, TrackedMut};
 use ecow::EcoString;
+use unicode_segmentation::UnicodeSegmentation;
 
-use crate::diag::{bail, SourceResult, StrResult};
+use crate::diag::{
+    bail, At, HintedStrResult, HintedString, SourceDiagnostic, SourceResult, StrResult,
+};
 use crate::engine::Engine;
-use crate::eval::EvalMode;
-use crate::syntax::Spanned;
+use crate::eval::{ops, EvalMode};
+use crate::syntax::{is_ident, Span, Spanned};
+use crate::text::SpaceElem;
+use crate::World;
 
 /// Foundational types and functions.
 ///
@@ -95,6 +105,7 @@ pub(super) fn define(global: &mut Scope, inputs: Dict) {
     global.define_type::<Bytes>();
     global.define_type::<Content>();
     global.define_type::<Array>();
+    global.define_type::<Builder>();
     global.define_type::<Dict>();
     global.define_type::<Func>();
     global.define_type::<Args>();
@@ -106,11 +117,28 @@ pub(super) fn define(global: &mut Scope, inputs: Dict) {
     global.define_type::<Duration>();
     global.define_type::<Version>();
     global.define_type::<Plugin>();
+    global.define_func::<isinstance>();
     global.define_func::<repr::repr>();
+    global.define_func::<repr::canonical_repr>();
     global.define_func::<panic>();
+    global.define_func::<warn>();
+    global.define_func::<catch>();
+    global.define_func::<call_stack>();
     global.define_func::<assert>();
+    global.define_func::<joined>();
+    global.define_func::<smart_join>();
+    global.define_func::<format>();
+    global.define_func::<methods_of>();
+    global.define_func::<fields_of>();
+    global.define_func::<params_of>();
+    global.define_func::<path_of>();
     global.define_func::<eval>();
     global.define_func::<style>();
+    global.define_func::<show_everywhere>();
+    global.define_func::<scoped>();
+    global.define_func::<isolate>();
+    global.define_func::<default_of>();
+    global.define_func::<defaults_of>();
     global.define_module(calc::module());
     global.define_module(sys::module(inputs));
 }
@@ -127,10 +155,12 @@ pub(super) fn define(global: &mut Scope, inputs: Dict) {
 /// ```
 #[func(keywords = ["error"])]
 pub fn panic(
+    /// The callsite span.
+    span: Span,
     /// The values to panic with and display to the user.
     #[variadic]
     values: Vec<Value>,
-) -> StrResult<Never> {
+) -> SourceResult<Never> {
     let mut msg = EcoString::from("panicked");
     if !values.is_empty() {
         msg.push_str(" with: ");
@@ -141,7 +171,214 @@ pub fn panic(
             msg.push_str(&value.repr());
         }
     }
-    Err(msg)
+    // Panics are meant to stop compilation, so `catch` doesn't catch them.
+    Err(eco_vec![SourceDiagnostic::error(span, msg).uncatchable()])
+}
+
+/// Emits a warning without stopping compilation.
+///
+/// The message is displayed to the user (not rendered in the document),
+/// followed by the representations of any further values. Emitting the same
+/// message at the same place multiple times only produces a single warning. In
+/// strict mode, the warning becomes an error.
+///
+/// # Example
+/// The code below produces the warning `logo is missing: "logo.svg"`.
+/// ```typ
+/// #warn("logo is missing", "logo.svg")
+/// ```
+#[func]
+pub fn warn(
+    /// The engine.
+    engine: &mut Engine,
+    /// The callsite span.
+    span: Span,
+    /// The warning message.
+    message: EcoString,
+    /// Further values to display to the user.
+    #[variadic]
+    values: Vec<Value>,
+) -> SourceResult<NoneValue> {
+    let mut msg = message;
+    for (i, value) in values.iter().enumerate() {
+        msg.push_str(if i > 0 { ", " } else { ": " });
+        msg.push_str(&value.repr());
+    }
+    engine.lint(SourceDiagnostic::warning(span, msg))?;
+    Ok(NoneValue)
+}
+
+/// Calls a function and turns an error it fails with into a value.
+///
+/// The function is called without arguments. If it succeeds, the result is a
+/// dictionary `{(ok: true, value: ..)}` holding its return value. If it fails,
+/// the result is a dictionary `{(ok: false, message: .., trace: ..)}` with the
+/// message of the first error and an array of strings describing where it
+/// occurred, starting with the innermost call.
+///
+/// Not every failure can be caught: Panics, misplaced `{break}`, `{continue}`
+/// and `{return}` statements as well as exceeded resource limits still stop
+/// compilation.
+///
+/// # Example
+/// ```example
+/// #let result = catch(() => int("twelve"))
+/// #if not result.ok [
+///   Failed: #result.message
+/// ]
+/// ```
+#[func]
+pub fn catch(
+    /// The engine.
+    engine: &mut Engine,
+    /// The callsite context.
+    context: Tracked<Context>,
+    /// The callsite span.
+    span: Span,
+    /// The function to call.
+    func: Func,
+) -> SourceResult<Dict> {
+    let args = Args::new(span, Vec::<Value>::new());
+    let mut dict = Dict::new();
+    match engine.attempt(|engine| func.call(engine, context, args)) {
+        Ok(value) => {
+            dict.insert("ok".into(), true.into_value());
+            dict.insert("value".into(), value);
+        }
+        Err(errors) => {
+            if errors.iter().any(|error| error.uncatchable) {
+                return Err(errors);
+            }
+            let error = &errors[0];
+            let trace: Array = error
+                .trace
+                .iter()
+                .map(|point| eco_format!("{}", point.v).into_value())
+                .collect();
+            dict.insert("ok".into(), false.into_value());
+            dict.insert("message".into(), error.message.clone().into_value());
+            dict.insert("trace".into(), trace.into_value());
+        }
+    }
+    Ok(dict)
+}
+
+/// Returns the currently active function calls.
+///
+/// The result is an array with one dictionary per call of a custom function,
+/// starting with the innermost one. Each dictionary holds the `name` of the
+/// called function (`{none}` for unnamed functions) as well as the `file` and
+/// `line` of the call site (`{none}` if unknown). This is useful for debugging
+/// deeply nested templates.
+///
+/// # Example
+/// ```example
+/// #let inner() = call-stack()
+/// #let outer() = inner()
+/// #outer().map(frame => frame.name)
+/// ```
+#[func]
+pub fn call_stack(
+    /// The engine.
+    engine: &mut Engine,
+) -> Array {
+    engine
+        .route
+        .calls()
+        .into_iter()
+        .map(|(name, span)| {
+            let (file, line) = locate(engine, span).unzip();
+            let mut frame = Dict::new();
+            frame.insert("name".into(), name.into_value());
+            frame.insert("file".into(), file.into_value());
+            frame.insert("line".into(), line.into_value());
+            frame.into_value()
+        })
+        .collect()
+}
+
+/// Resolve a span to a file path and a one-based line number.
+fn locate(engine: &Engine, span: Span) -> Option<(EcoString, usize)> {
+    let id = span.id()?;
+    let source = engine.world.source(id).ok()?;
+    let line = source.byte_to_line(source.range(span)?.start)?;
+    let path = id.vpath().as_rooted_path().to_string_lossy();
+    let file = match id.package() {
+        Some(spec) => eco_format!("{spec}{path}"),
+        None => path.into(),
+    };
+    Some((file, line + 1))
+}
+
+/// Joins values with a separator.
+///
+/// This follows the same rules as the joining of values in code blocks and
+/// loops, so that strings and content can be mixed freely. Unlike there, the
+/// values are separated by a space by default. Joining no values at all
+/// results in `{none}`. To join content without doubling spaces that are
+/// already part of it, use [`smart-join`]($smart-join).
+///
+/// ```example
+/// #joined("a", "b") \
+/// #joined([*a*], "b", separator: [ -- ]) \
+/// #joined("a", "b", "c", separator: none)
+/// ```
+#[func]
+pub fn joined(
+    /// The values to join.
+    #[variadic]
+    values: Vec<Value>,
+    /// The value to insert between each two values.
+    #[named]
+    #[default(Value::Str(" ".into()))]
+    separator: Value,
+) -> HintedStrResult<Value> {
+    let mut result = Value::None;
+    for (i, value) in values.into_iter().enumerate() {
+        if i > 0 {
+            result = ops::join(result, separator.clone())?;
+        }
+        result = ops::join(result, value)?;
+    }
+    Ok(result)
+}
+
+/// Joins pieces of content with single spaces.
+///
+/// A space is only inserted between two pieces if neither of them already
+/// has whitespace at the boundary, as determined by
+/// [`ends-with-space`]($content.ends-with-space) and
+/// [`starts-with-space`]($content.starts-with-space). Paragraph breaks count
+/// as whitespace, too. Empty pieces are skipped.
+///
+/// Note that adding content with `+` never inserts a space, while
+/// [`joined`]($joined) always inserts its separator.
+///
+/// ```example
+/// #smart-join([a], [b ], [c]) \
+/// #smart-join("a", [], " b")
+/// ```
+#[func]
+pub fn smart_join(
+    /// The pieces to join.
+    #[variadic]
+    pieces: Vec<Content>,
+) -> Content {
+    let mut seq: Vec<Content> = vec![];
+    for piece in pieces {
+        if piece.is_empty() {
+            continue;
+        }
+
+        if let Some(prev) = seq.last() {
+            if !prev.ends_with_space() && !piece.starts_with_space() {
+                seq.push(SpaceElem::new().pack());
+            }
+        }
+
+        seq.push(piece);
+    }
+    Content::sequence(seq)
 }
 
 /// Ensures that a condition is fulfilled.
@@ -179,32 +416,47 @@ impl assert {
     /// Ensures that two values are equal.
     ///
     /// Fails with an error if the first value is not equal to the second. Does not
-    /// produce any output in the document.
+    /// produce any output in the document. The error points out where the values
+    /// differ: The differing keys of dictionaries, the first differing index of
+    /// arrays, and the first differing character of strings.
     ///
     /// ```typ
     /// #assert.eq(10, 10)
     /// ```
     #[func(title = "Assert Equal")]
     pub fn eq(
+        /// The engine.
+        engine: &mut Engine,
+        /// The callsite context.
+        context: Tracked<Context>,
+        /// The callsite span.
+        span: Span,
         /// The first value to compare.
         left: Value,
         /// The second value to compare.
         right: Value,
         /// An optional message to display on error instead of the representations
         /// of the compared values.
+        ///
+        /// Can also be a function without parameters returning the message. It
+        /// is only called if the assertion fails.
         #[named]
-        message: Option<EcoString>,
-    ) -> StrResult<NoneValue> {
+        message: Option<AssertMessage>,
+    ) -> SourceResult<NoneValue> {
         if left != right {
-            if let Some(message) = message {
-                bail!("equality assertion failed: {message}");
-            } else {
-                bail!(
+            let mut error = match message {
+                Some(message) => HintedString::new(eco_format!(
+                    "equality assertion failed: {}",
+                    message.resolve(engine, context, span)?
+                )),
+                None => HintedString::new(eco_format!(
                     "equality assertion failed: value {} was not equal to {}",
                     left.repr(),
                     right.repr()
-                );
-            }
+                )),
+            };
+            diff(&left, &right, "", &mut error);
+            return Err(error).at(span);
         }
         Ok(NoneValue)
     }
@@ -219,20 +471,31 @@ impl assert {
     /// ```
     #[func(title = "Assert Not Equal")]
     pub fn ne(
+        /// The engine.
+        engine: &mut Engine,
+        /// The callsite context.
+        context: Tracked<Context>,
+        /// The callsite span.
+        span: Span,
         /// The first value to compare.
         left: Value,
         /// The second value to compare.
         right: Value,
         /// An optional message to display on error instead of the representations
         /// of the compared values.
+        ///
+        /// Can also be a function without parameters returning the message. It
+        /// is only called if the assertion fails.
         #[named]
-        message: Option<EcoString>,
-    ) -> StrResult<NoneValue> {
+        message: Option<AssertMessage>,
+    ) -> SourceResult<NoneValue> {
         if left == right {
             if let Some(message) = message {
-                bail!("inequality assertion failed: {message}");
+                let message = message.resolve(engine, context, span)?;
+                bail!(span, "inequality assertion failed: {message}");
             } else {
                 bail!(
+                    span,
                     "inequality assertion failed: value {} was equal to {}",
                     left.repr(),
                     right.repr()
@@ -243,6 +506,129 @@ impl assert {
     }
 }
 
+/// The message of a failed equality assertion.
+pub enum AssertMessage {
+    /// The message itself.
+    Str(EcoString),
+    /// A function producing the message.
+    Func(Func),
+}
+
+impl AssertMessage {
+    /// Produce the message, calling the function if necessary.
+    fn resolve(
+        self,
+        engine: &mut Engine,
+        context: Tracked<Context>,
+        span: Span,
+    ) -> SourceResult<EcoString> {
+        match self {
+            Self::Str(message) => Ok(message),
+            Self::Func(func) => func
+                .call(engine, context, Args::new(span, Vec::<Value>::new()))?
+                .cast::<EcoString>()
+                .at(func.span()),
+        }
+    }
+}
+
+cast! {
+    AssertMessage,
+    self => match self {
+        Self::Str(v) => v.into_value(),
+        Self::Func(v) => v.into_value(),
+    },
+    v: EcoString => Self::Str(v),
+    v: Func => Self::Func(v),
+}
+
+/// The maximum number of differences that a failed equality assertion
+/// reports.
+const MAX_DIFFS: usize = 8;
+
+/// Add hints describing where two unequal values differ to the error.
+///
+/// The `path` is the sequence of field and `at` accesses leading from the
+/// compared values to `left` and `right`.
+fn diff(left: &Value, right: &Value, path: &str, error: &mut HintedString) {
+    if error.hints().len() >= MAX_DIFFS {
+        return;
+    }
+
+    let at = if path.is_empty() { String::new() } else { format!("at `{path}`: ") };
+    match (left, right) {
+        (Value::Dict(l), Value::Dict(r)) => {
+            for (key, value) in l.iter() {
+                if error.hints().len() >= MAX_DIFFS {
+                    return;
+                }
+                match r.get(key) {
+                    Ok(other) if other == value => {}
+                    Ok(other) if is_ident(key) => {
+                        diff(value, other, &format!("{path}.{key}"), error)
+                    }
+                    Ok(other) => {
+                        diff(value, other, &format!("{path}.at({})", key.repr()), error)
+                    }
+                    Err(_) => error.hint(eco_format!(
+                        "{at}key `{key}` only exists in the left value"
+                    )),
+                }
+            }
+            for (key, _) in r.iter() {
+                if error.hints().len() >= MAX_DIFFS {
+                    return;
+                }
+                if !l.contains(key) {
+                    error.hint(eco_format!(
+                        "{at}key `{key}` only exists in the right value"
+                    ));
+                }
+            }
+        }
+        (Value::Array(l), Value::Array(r)) => {
+            if l.len() != r.len() {
+                error.hint(eco_format!(
+                    "{at}the arrays have lengths {} and {}",
+                    l.len(),
+                    r.len()
+                ));
+            }
+            if let Some(i) = l.iter().zip(r.iter()).position(|(a, b)| a != b) {
+                diff(
+                    &l.as_slice()[i],
+                    &r.as_slice()[i],
+                    &format!("{path}.at({i})"),
+                    error,
+                );
+            }
+        }
+        (Value::Str(l), Value::Str(r)) => {
+            let (mut index, mut prefix) = (0, 0);
+            for (a, b) in l.graphemes(true).zip(r.graphemes(true)) {
+                if a != b {
+                    break;
+                }
+                index += 1;
+                prefix += a.len();
+            }
+            error.hint(eco_format!(
+                "{at}the strings differ from grapheme {index} on: {} and {}",
+                Str::from(&l[prefix..]).repr(),
+                Str::from(&r[prefix..]).repr(),
+            ));
+        }
+        _ if !path.is_empty() => {
+            error.hint(eco_format!(
+                "{at}{} is not equal to {}",
+                left.repr(),
+                right.repr()
+            ));
+        }
+        _ => {}
+    }
+}
+
 /// Evaluates a string as Typst code.
 ///
 /// This function should only be used as a last resort.
@@ -257,6 +643,8 @@ impl assert {
 pub fn eval(
     /// The engine.
     engine: &mut Engine,
+    /// The callsite context.
+    context: Tracked<Context>,
     /// A string of Typst code to evaluate.
     source: Spanned<String>,
     /// The [syntactical mode]($reference/syntax/#modes) in which the string is
@@ -292,5 +680,14 @@ pub fn eval(
     for (key, value) in dict {
         scope.define(key, value);
     }
-    crate::eval::eval_string(engine.world, &text, span, mode, scope)
+    crate::eval::eval_string_budgeted(
+        engine.world,
+        engine.introspector,
+        context,
+        TrackedMut::reborrow_mut(&mut engine.sink),
+        &text,
+        span,
+        mode,
+        scope,
+    )
 }
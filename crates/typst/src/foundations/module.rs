@@ -4,7 +4,8 @@ use std::sync::Arc;
 use ecow::{eco_format, EcoString};
 
 use crate::diag::StrResult;
-use crate::foundations::{repr, ty, Content, Scope, Value};
+use crate::foundations::{func, repr, scope, ty, Content, Scope, Value};
+use crate::syntax::FileId;
 
 /// An evaluated module, either built-in or resulting from a file.
 ///
@@ -15,6 +16,13 @@ use crate::foundations::{repr, ty, Content, Scope, Value};
 /// contents dynamically, using the
 /// [dictionary constructor]($dictionary/#constructor).
 ///
+/// The `path` field of a module holds the path of the file it was evaluated
+/// from as a string, prefixed with the package specification for files in
+/// packages. For built-in modules, it is `{none}`. A definition named `path`
+/// in the module takes precedence over this field, but
+/// [`path-of`]($path-of) always returns the path. Likewise, the `content`
+/// field holds the content of the module's file, as it would be included.
+///
 /// # Example
 /// ```example
 /// <<< #import "utils.typ"
@@ -26,7 +34,7 @@ use crate::foundations::{repr, ty, Content, Scope, Value};
 /// >>>
 /// >>> #(-3)
 /// ```
-#[ty(cast)]
+#[ty(scope, cast)]
 #[derive(Clone, Hash)]
 #[allow(clippy::derived_hash_with_manual_eq)]
 pub struct Module {
@@ -43,6 +51,8 @@ struct Repr {
     scope: Scope,
     /// The module's layoutable contents.
     content: Content,
+    /// The file the module was evaluated from, if any.
+    file_id: Option<FileId>,
 }
 
 impl Module {
@@ -50,7 +60,7 @@ impl Module {
     pub fn new(name: impl Into<EcoString>, scope: Scope) -> Self {
         Self {
             name: name.into(),
-            inner: Arc::new(Repr { scope, content: Content::empty() }),
+            inner: Arc::new(Repr { scope, content: Content::empty(), file_id: None }),
         }
     }
 
@@ -72,6 +82,12 @@ impl Module {
         self
     }
 
+    /// Update the file the module was evaluated from.
+    pub fn with_file_id(mut self, id: FileId) -> Self {
+        Arc::make_mut(&mut self.inner).file_id = Some(id);
+        self
+    }
+
     /// Get the module's name.
     pub fn name(&self) -> &EcoString {
         &self.name
@@ -87,6 +103,22 @@ impl Module {
         &mut Arc::make_mut(&mut self.inner).scope
     }
 
+    /// Get the file the module was evaluated from, if any.
+    pub fn file_id(&self) -> Option<FileId> {
+        self.inner.file_id
+    }
+
+    /// The path of the file the module was evaluated from, prefixed with the
+    /// package specification for files in packages.
+    pub fn path(&self) -> Option<EcoString> {
+        let id = self.file_id()?;
+        let path = id.vpath().as_rooted_path().to_string_lossy();
+        Some(match id.package() {
+            Some(spec) => eco_format!("{spec}{path}"),
+            None => path.into(),
+        })
+    }
+
     /// Try to access a definition in the module.
     pub fn field(&self, name: &str) -> StrResult<&Value> {
         self.scope().get(name).ok_or_else(|| {
@@ -103,12 +135,33 @@ impl Module {
     }
 }
 
+#[scope]
+impl Module {
+    /// Returns the module without its content.
+    ///
+    /// All of the module's definitions, not only its functions, are kept. This
+    /// is useful to import a file whose markup is only meant to be seen on its
+    /// own, like a preview of its functions, and whose content shouldn't end
+    /// up in a document that includes the module.
+    ///
+    /// ```typ
+    /// #import "utils.typ"
+    /// #let utils = utils.functions-only()
+    /// #include utils // Produces nothing.
+    /// ```
+    #[func]
+    pub fn functions_only(self) -> Module {
+        self.with_content(Content::empty())
+    }
+}
+
 impl Debug for Module {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         f.debug_struct("Module")
             .field("name", &self.name)
             .field("scope", &self.inner.scope)
             .field("content", &self.inner.content)
+            .field("file_id", &self.inner.file_id)
             .finish()
     }
 }
@@ -7,9 +7,8 @@ use wasmi::{AsContext, AsContextMut};
 
 use crate::diag::{bail, At, SourceResult, StrResult};
 use crate::engine::Engine;
-use crate::foundations::{func, repr, scope, ty, Bytes};
+use crate::foundations::{cast, func, repr, scope, ty, Bytes};
 use crate::syntax::Spanned;
-use crate::World;
 
 /// A WebAssembly plugin.
 ///
@@ -34,6 +33,15 @@ use crate::World;
 /// byte-based plugin interface is quite low-level, plugins are typically
 /// exposed through wrapper functions, that also live in the same package.
 ///
+/// # Limits
+/// Plugins are sandboxed: They cannot import anything besides the two protocol
+/// functions described below. Moreover, each call to a plugin function may
+/// only execute a bounded number of WebAssembly instructions and a plugin's
+/// memory may not grow beyond 1 GiB. A plugin function that exceeds the
+/// instruction limit is aborted with an error naming it. Growing the memory
+/// beyond its limit fails like any other failed growth, but if the function
+/// fails afterwards, the error points out the exceeded memory limit.
+///
 /// # Purity
 /// Plugin functions must be pure: Given the same arguments, they must always
 /// return the same value. The reason for this is that Typst functions must be
@@ -132,6 +140,18 @@ struct Repr {
 /// Owns all data associated with the WebAssembly module.
 type Store = wasmi::Store<StoreData>;
 
+/// The amount of fuel available to a single plugin function call if it isn't
+/// further limited by the evaluation budget. One unit of fuel corresponds
+/// roughly to one executed WebAssembly instruction.
+const FUEL_PER_CALL: u64 = 1 << 32;
+
+/// How much fuel a single step of the evaluation budget is worth. Executing a
+/// WebAssembly instruction is much cheaper than evaluating an expression.
+const FUEL_PER_STEP: u64 = 1 << 10;
+
+/// The maximum size of a plugin's linear memory in bytes.
+const MEMORY_LIMIT: usize = 1 << 30;
+
 /// If there was an error reading/writing memory, keep the offset + length to
 /// display an error message.
 struct MemoryError {
@@ -145,21 +165,68 @@ struct StoreData {
     args: Vec<Bytes>,
     output: Vec<u8>,
     memory_error: Option<MemoryError>,
+    limiter: Limiter,
+}
+
+/// Keeps a plugin's memory within the limit.
+///
+/// Growing the memory beyond the limit fails like any other failed growth:
+/// `memory.grow` returns -1. The limiter remembers that this happened, so that
+/// a plugin function that fails as a consequence can be reported as having
+/// exceeded its memory limit.
+#[derive(Default)]
+struct Limiter {
+    exceeded: bool,
+}
+
+impl wasmi::ResourceLimiter for Limiter {
+    fn memory_growing(
+        &mut self,
+        _: usize,
+        desired: usize,
+        maximum: Option<usize>,
+    ) -> Result<bool, wasmi::errors::MemoryError> {
+        if desired > MEMORY_LIMIT {
+            self.exceeded = true;
+            return Ok(false);
+        }
+        Ok(maximum.map_or(true, |maximum| desired <= maximum))
+    }
+
+    fn table_growing(
+        &mut self,
+        _: u32,
+        desired: u32,
+        maximum: Option<u32>,
+    ) -> Result<bool, wasmi::errors::TableError> {
+        Ok(maximum.map_or(true, |maximum| desired <= maximum))
+    }
 }
 
 #[scope]
 impl Plugin {
-    /// Creates a new plugin from a WebAssembly file.
+    /// Creates a new plugin from a WebAssembly file or raw WebAssembly bytes.
+    ///
+    /// ```example
+    /// #let data = read("hello.wasm", encoding: none)
+    /// #let myplugin = plugin(data)
+    /// #str(myplugin.hello())
+    /// ```
     #[func(constructor)]
     pub fn construct(
         /// The engine.
         engine: &mut Engine,
-        /// Path to a WebAssembly file.
-        path: Spanned<EcoString>,
+        /// Path to a WebAssembly file or the raw bytes of a WebAssembly module.
+        source: Spanned<PluginSource>,
     ) -> SourceResult<Plugin> {
-        let Spanned { v: path, span } = path;
-        let id = span.resolve_path(&path).at(span)?;
-        let data = engine.world.file(id).at(span)?;
+        let Spanned { v: source, span } = source;
+        let data = match source {
+            PluginSource::Path(path) => {
+                let id = span.resolve_path(&path).at(span)?;
+                engine.file(id).at(span)?
+            }
+            PluginSource::Bytes(bytes) => bytes,
+        };
         Plugin::new(data).at(span)
     }
 }
@@ -169,7 +236,10 @@ impl Plugin {
     #[comemo::memoize]
     #[typst_macros::time(name = "load plugin")]
     pub fn new(bytes: Bytes) -> StrResult<Plugin> {
-        let engine = wasmi::Engine::default();
+        let mut config = wasmi::Config::default();
+        config.consume_fuel(true);
+
+        let engine = wasmi::Engine::new(&config);
         let module = wasmi::Module::new(&engine, bytes.as_slice())
             .map_err(|err| format!("failed to load WebAssembly module ({err})"))?;
 
@@ -190,10 +260,16 @@ impl Plugin {
             .unwrap();
 
         let mut store = Store::new(&engine, StoreData::default());
+        store.limiter(|data| &mut data.limiter);
+
+        // The start function is subject to the same fuel limit as calls.
+        store.add_fuel(FUEL_PER_CALL).unwrap();
         let instance = linker
             .instantiate(&mut store, &module)
             .and_then(|pre_instance| pre_instance.start(&mut store))
-            .map_err(|e| eco_format!("{e}"))?;
+            .map_err(|err| {
+                eco_format!("failed to instantiate WebAssembly module ({err})")
+            })?;
 
         // Ensure that the plugin exports its memory.
         if !matches!(
@@ -216,9 +292,51 @@ impl Plugin {
     }
 
     /// Call the plugin function with the given `name`.
+    pub fn call(&self, name: &str, args: Vec<Bytes>) -> StrResult<Bytes> {
+        self.call_metered(name, args).map(|(output, _)| output)
+    }
+
+    /// Call the plugin function with the given `name` and charge the fuel it
+    /// consumed to the evaluation budget. Fails if the call consumed more fuel
+    /// than the remaining budget is worth.
+    pub fn call_budgeted(
+        &self,
+        engine: &mut Engine,
+        name: &str,
+        args: Vec<Bytes>,
+    ) -> StrResult<Bytes> {
+        let (output, consumed) = self.call_metered(name, args)?;
+        if let Some(steps) = engine.sink.remaining().steps() {
+            let spent = consumed.div_ceil(FUEL_PER_STEP);
+            if spent > steps {
+                bail!("plugin function `{name}` exceeded its execution limit");
+            }
+            engine.sink.spend(spent);
+        }
+        Ok(output)
+    }
+
+    /// Call the plugin function with the given `name` with the full amount of
+    /// fuel available to a call. Returns the output along with the consumed
+    /// fuel.
+    ///
+    /// The fuel is the same for every call, so that the cached result of a
+    /// call can be reused no matter how much of the budget is left.
     #[comemo::memoize]
     #[typst_macros::time(name = "call plugin")]
-    pub fn call(&self, name: &str, args: Vec<Bytes>) -> StrResult<Bytes> {
+    fn call_metered(&self, name: &str, args: Vec<Bytes>) -> StrResult<(Bytes, u64)> {
+        self.call_with_fuel(name, args, FUEL_PER_CALL)
+    }
+
+    /// Call the plugin function with the given `name`, aborting it once it has
+    /// consumed the given amount of `fuel`. Returns the output along with the
+    /// consumed fuel.
+    fn call_with_fuel(
+        &self,
+        name: &str,
+        args: Vec<Bytes>,
+        fuel: u64,
+    ) -> StrResult<(Bytes, u64)> {
         // Find the function with the given name.
         let func = self
             .0
@@ -263,10 +381,33 @@ impl Plugin {
         // Store the input data.
         store.data_mut().args = args;
 
+        // Refill the fuel tank so that every call gets the same budget,
+        // independently of how much previous calls consumed.
+        let remaining = store.consume_fuel(0).unwrap();
+        if let Some(missing) = fuel.checked_sub(remaining) {
+            store.add_fuel(missing).unwrap();
+        } else {
+            store.consume_fuel(remaining - fuel).unwrap();
+        }
+
         // Call the function.
         let mut code = wasmi::Value::I32(-1);
-        func.call(store.as_context_mut(), &lengths, std::slice::from_mut(&mut code))
-            .map_err(|err| eco_format!("plugin panicked: {err}"))?;
+        let result =
+            func.call(store.as_context_mut(), &lengths, std::slice::from_mut(&mut code));
+
+        // If the function tried to grow its memory beyond the limit, that is
+        // the most likely reason for a failure.
+        let exceeded = std::mem::take(&mut store.data_mut().limiter.exceeded);
+        let fail = |message: EcoString| {
+            if exceeded {
+                eco_format!("plugin function `{name}` exceeded its memory limit")
+            } else {
+                message
+            }
+        };
+
+        result.map_err(|err| fail(trap_message(name, err)))?;
+        let consumed = fuel - store.consume_fuel(0).unwrap();
         if let Some(MemoryError { offset, length, write }) =
             store.data_mut().memory_error.take()
         {
@@ -288,10 +429,10 @@ impl Plugin {
                     bail!("plugin errored, but did not return a valid error message")
                 }
             },
-            _ => bail!("plugin did not respect the protocol"),
+            _ => return Err(fail("plugin did not respect the protocol".into())),
         };
 
-        Ok(output.into())
+        Ok((output.into(), consumed))
     }
 
     /// An iterator over all the function names defined by the plugin.
@@ -324,6 +465,39 @@ impl Hash for Plugin {
     }
 }
 
+/// A source from which a plugin can be loaded.
+pub enum PluginSource {
+    /// A path to a WebAssembly file.
+    Path(EcoString),
+    /// The raw bytes of a WebAssembly module.
+    Bytes(Bytes),
+}
+
+cast! {
+    PluginSource,
+    self => match self {
+        Self::Path(v) => v.into_value(),
+        Self::Bytes(v) => v.into_value(),
+    },
+    v: EcoString => Self::Path(v),
+    v: Bytes => Self::Bytes(v),
+}
+
+/// Produce a user-facing message for a trap in the plugin function `name`.
+fn trap_message(name: &str, err: wasmi::Error) -> EcoString {
+    let code = match &err {
+        wasmi::Error::Trap(trap) => trap.trap_code(),
+        _ => None,
+    };
+
+    match code {
+        Some(wasmi::core::TrapCode::OutOfFuel) => {
+            eco_format!("plugin function `{name}` exceeded its execution limit")
+        }
+        _ => eco_format!("plugin function `{name}` panicked: {err}"),
+    }
+}
+
 /// Write the arguments to the plugin function into the plugin's memory.
 fn wasm_minimal_protocol_write_args_to_buffer(
     mut caller: wasmi::Caller<StoreData>,
@@ -361,3 +535,24 @@ fn wasm_minimal_protocol_send_result_to_host(
     }
     caller.data_mut().output = buffer;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A module exporting a function `spin` that loops forever.
+    const SPIN: &[u8] = &[
+        0, 97, 115, 109, 1, 0, 0, 0, 1, 5, 1, 96, 0, 1, 127, 3, 2, 1, 0, 5, 3, 1, 0, 1,
+        7, 17, 2, 6, 109, 101, 109, 111, 114, 121, 2, 0, 4, 115, 112, 105, 110, 0, 0, 10,
+        11, 1, 9, 0, 3, 64, 12, 0, 11, 65, 0, 11,
+    ];
+
+    #[test]
+    fn test_plugin_fuel_limit() {
+        let plugin = Plugin::new(Bytes::from_static(SPIN)).unwrap();
+        for _ in 0..2 {
+            let err = plugin.call_with_fuel("spin", vec![], 10_000).unwrap_err();
+            assert_eq!(err, "plugin function `spin` exceeded its execution limit");
+        }
+    }
+}
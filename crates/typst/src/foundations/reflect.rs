@@ -0,0 +1,209 @@
+use ecow::EcoString;
+
+use crate::diag::{bail, SourceResult};
+use crate::engine::Engine;
+use crate::foundations::{
+    cast, dict, fields_on, func, Array, CastInfo, Func, IntoValue, Module, ParamInfo,
+    Repr, Str, Type, Value,
+};
+use crate::syntax::ast::{self, AstNode};
+use crate::syntax::Spanned;
+use crate::World;
+
+/// Lists the methods that can be called on values of a type.
+///
+/// Each method is described by a dictionary with its `name`, its `params` in
+/// the format of [`params-of`]($params-of) without the value it is called on
+/// and a description of what it `returns`, like `{"array"}` or
+/// `{"str | none"}`. Associated functions that are not called on a value, like
+/// a type's constructor, are not listed.
+///
+/// ```example
+/// #for method in methods-of(array).slice(0, 3) [
+///   - #method.name: #method.returns
+/// ]
+/// ```
+#[func]
+pub fn methods_of(
+    /// The engine.
+    engine: &mut Engine,
+    /// The type or its name, like `{"array"}`.
+    ty: Spanned<TypeOrName>,
+) -> SourceResult<Array> {
+    let ty = resolve_type(engine, ty)?;
+    let mut methods = Array::new();
+    for (name, value) in ty.scope().iter() {
+        let Value::Func(func) = value else { continue };
+        let Some(params) = func.params() else { continue };
+        let Some((this, params)) = params.split_first() else { continue };
+        if this.name != "self" {
+            continue;
+        }
+
+        let returns = func.returns().map(describe).unwrap_or_else(|| "any".into());
+        methods.push(
+            dict! {
+                "name" => name.clone(),
+                "params" => params.iter().map(native_param).collect::<Array>(),
+                "returns" => returns,
+            }
+            .into_value(),
+        );
+    }
+    Ok(methods)
+}
+
+/// Lists the fields that values of a type have, like `{"em"}` and `{"abs"}`
+/// for lengths.
+///
+/// Fields of content depend on the element and are not listed. Use the
+/// [`fields`]($content.fields) method of the content instead.
+///
+/// ```example
+/// #fields-of(length) \
+/// #fields-of("stroke")
+/// ```
+#[func]
+pub fn fields_of(
+    /// The engine.
+    engine: &mut Engine,
+    /// The type or its name, like `{"length"}`.
+    ty: Spanned<TypeOrName>,
+) -> SourceResult<Array> {
+    let ty = resolve_type(engine, ty)?;
+    Ok(fields_on(ty).iter().map(|&field| field.into_value()).collect())
+}
+
+/// Lists the parameters of a function.
+///
+/// Each parameter is described by a dictionary with its `name` and whether it
+/// is `positional`, `named`, `required` and `variadic`. A parameter can be
+/// both positional and named. For a function defined with `let`, the name of a
+/// destructuring parameter is its pattern and the name of an unnamed argument
+/// sink is `{none}`. Arguments that were already applied with
+/// [`with`]($function.with) are not taken into account.
+///
+/// ```example
+/// #let greet(name, greeting: "Hi", ..rest) = none
+/// #for param in params-of(greet) [
+///   - #param.name: #param.required
+/// ]
+/// ```
+#[func]
+pub fn params_of(
+    /// The function.
+    func: Func,
+) -> Array {
+    if let Some(params) = func.params() {
+        return params.iter().map(native_param).collect();
+    }
+
+    let Some(closure) = func.closure() else { return Array::new() };
+    let Some(node) = closure.node.cast::<ast::Closure>() else {
+        return Array::new();
+    };
+
+    node.params()
+        .children()
+        .map(|param| match param {
+            ast::Param::Pos(ast::Pattern::Normal(ast::Expr::Ident(ident))) => {
+                param_dict(ident.get().clone(), true, false, true, false)
+            }
+            ast::Param::Pos(pattern) => {
+                let name = pattern.to_untyped().clone().into_text();
+                param_dict(name, true, false, true, false)
+            }
+            ast::Param::Named(named) => {
+                param_dict(named.name().get().clone(), false, true, false, false)
+            }
+            ast::Param::Spread(spread) => {
+                let name = spread.sink_ident().map(|ident| ident.get().clone());
+                param_dict(name, true, false, false, true)
+            }
+        })
+        .collect()
+}
+
+/// The path of the file a module was evaluated from.
+///
+/// This is the same as the module's `path` field, but also works for modules
+/// that define something named `path` themselves. For built-in modules, it is
+/// `{none}`.
+///
+/// ```example
+/// #path-of(calc)
+/// ```
+#[func]
+pub fn path_of(
+    /// The module.
+    module: Module,
+) -> Option<EcoString> {
+    module.path()
+}
+
+/// A type, given directly or by its name.
+pub enum TypeOrName {
+    /// The type itself.
+    Type(Type),
+    /// The name the type is defined under in the standard library.
+    Name(Str),
+}
+
+cast! {
+    TypeOrName,
+    v: Type => Self::Type(v),
+    v: Str => Self::Name(v),
+}
+
+/// Look up a type by its name in the standard library if necessary.
+fn resolve_type(engine: &Engine, ty: Spanned<TypeOrName>) -> SourceResult<Type> {
+    match ty.v {
+        TypeOrName::Type(ty) => Ok(ty),
+        TypeOrName::Name(name) => {
+            match engine.world.library().global.scope().get(&name) {
+                Some(&Value::Type(ty)) => Ok(ty),
+                _ => bail!(ty.span, "unknown type: {}", name.repr()),
+            }
+        }
+    }
+}
+
+/// Describe a native parameter.
+fn native_param(param: &ParamInfo) -> Value {
+    param_dict(param.name, param.positional, param.named, param.required, param.variadic)
+}
+
+/// Describe a parameter.
+fn param_dict(
+    name: impl IntoValue,
+    positional: bool,
+    named: bool,
+    required: bool,
+    variadic: bool,
+) -> Value {
+    dict! {
+        "name" => name,
+        "positional" => positional,
+        "named" => named,
+        "required" => required,
+        "variadic" => variadic,
+    }
+    .into_value()
+}
+
+/// Describe the values a cast accepts or produces, like `str | none`.
+fn describe(info: &CastInfo) -> EcoString {
+    let mut parts: Vec<EcoString> = vec![];
+    info.walk(|info| {
+        let part = match info {
+            CastInfo::Any => "any".into(),
+            CastInfo::Value(value, _) => value.repr(),
+            CastInfo::Type(ty) => ty.short_name().into(),
+            CastInfo::Union(_) => return,
+        };
+        if !parts.contains(&part) {
+            parts.push(part);
+        }
+    });
+    parts.join(" | ").into()
+}
@@ -1,8 +1,13 @@
 //! Debug representation of values.
 
+use std::fmt::Write;
+
 use ecow::{eco_format, EcoString};
 
-use crate::foundations::{func, Str, Value};
+use crate::diag::StrResult;
+use crate::eval::maybe_grow;
+use crate::foundations::{func, Dict, Str, Value};
+use crate::syntax::is_ident;
 
 /// The Unicode minus sign.
 pub const MINUS_SIGN: &str = "\u{2212}";
@@ -27,8 +32,124 @@ pub const MINUS_SIGN: &str = "\u{2212}";
 pub fn repr(
     /// The value whose string representation to produce.
     value: Value,
-) -> Str {
-    value.repr().into()
+) -> StrResult<Str> {
+    value.check_depth(Value::MAX_DEPTH)?;
+    Ok(value.repr().into())
+}
+
+/// Returns the canonical representation of a value.
+///
+/// Unlike [`repr`], the canonical representation is meant to be stable, for
+/// example for snapshot tests. It is the same on all platforms and it only
+/// changes in new minor versions of Typst, in which case the changelog lists
+/// the change. It is produced as follows:
+///
+/// - `{none}`, `{auto}`, booleans, integers, and labels are written as in code.
+/// - Floats are written in the shortest form that reads back as the same
+///   float, always with a decimal separator or an exponent. Special values
+///   are written as `{calc.nan}`, `{calc.inf}`, and `{-calc.inf}`.
+/// - Strings are quoted. Backslashes, quotes, line feeds, carriage returns, and
+///   tabs are escaped as `\\`, `\"`, `\n`, `\r`, and `\t` and all other
+///   control characters as `\u{..}`.
+/// - Arrays and dictionaries are written on a single line in insertion order.
+///   Dictionary keys that are not identifiers are quoted.
+/// - Content is written as a call to its element with the element's fields,
+///   e.g. `{strong(body: text(text: "Hi"))}`.
+///
+/// For plain data, the result is valid code that evaluates to an equal value.
+/// All other values are written like their [`repr`]. Their representation is
+/// _not_ covered by the stability guarantee.
+///
+/// # Example
+/// ```example
+/// #canonical-repr((a: 1.0, "b c": "\t")) \
+/// #canonical-repr([*Hi*])
+/// ```
+#[func(title = "Canonical Representation")]
+pub fn canonical_repr(
+    /// The value whose canonical representation to produce.
+    value: Value,
+) -> StrResult<Str> {
+    value.check_depth(Value::MAX_DEPTH)?;
+    Ok(value.canonical_string().into())
+}
+
+/// Write the canonical representation of a value into the buffer.
+///
+/// See [`canonical_repr`] for the format.
+pub(crate) fn write_canonical(buf: &mut EcoString, value: &Value) {
+    maybe_grow(|| match value {
+        Value::None => buf.push_str("none"),
+        Value::Auto => buf.push_str("auto"),
+        Value::Bool(v) => write!(buf, "{v}").unwrap(),
+        Value::Int(v) => write!(buf, "{v}").unwrap(),
+        Value::Label(label) => write!(buf, "<{}>", label.as_str()).unwrap(),
+        Value::Float(v) if v.is_nan() => buf.push_str("calc.nan"),
+        Value::Float(v) if v.is_infinite() => {
+            buf.push_str(if *v < 0.0 { "-calc.inf" } else { "calc.inf" })
+        }
+        Value::Float(v) => write!(buf, "{v:?}").unwrap(),
+        Value::Str(v) => write_canonical_str(buf, v),
+        Value::Array(array) => {
+            buf.push('(');
+            for (i, item) in array.iter().enumerate() {
+                if i > 0 {
+                    buf.push_str(", ");
+                }
+                write_canonical(buf, item);
+            }
+            if array.len() == 1 {
+                buf.push(',');
+            }
+            buf.push(')');
+        }
+        Value::Dict(dict) if dict.is_empty() => buf.push_str("(:)"),
+        Value::Dict(dict) => {
+            buf.push('(');
+            write_canonical_pairs(buf, dict);
+            buf.push(')');
+        }
+        Value::Content(content) => {
+            buf.push_str(content.func().name());
+            buf.push('(');
+            write_canonical_pairs(buf, &content.fields());
+            buf.push(')');
+        }
+        _ => buf.push_str(&value.repr()),
+    })
+}
+
+/// Write the canonical representation of a dictionary's pairs.
+fn write_canonical_pairs(buf: &mut EcoString, dict: &Dict) {
+    for (i, (key, value)) in dict.iter().enumerate() {
+        if i > 0 {
+            buf.push_str(", ");
+        }
+        if is_ident(key) {
+            buf.push_str(key);
+        } else {
+            write_canonical_str(buf, key);
+        }
+        buf.push_str(": ");
+        write_canonical(buf, value);
+    }
+}
+
+/// Write a quoted and escaped string.
+fn write_canonical_str(buf: &mut EcoString, string: &str) {
+    buf.push('"');
+    for c in string.chars() {
+        match c {
+            '\\' => buf.push_str(r"\\"),
+            '"' => buf.push_str(r#"\""#),
+            '\n' => buf.push_str(r"\n"),
+            '\r' => buf.push_str(r"\r"),
+            '\t' => buf.push_str(r"\t"),
+            c if c.is_control() => write!(buf, "\\u{{{:x}}}", c as u32).unwrap(),
+            c => buf.push(c),
+        }
+    }
+    buf.push('"');
 }
 
 /// A trait that defines the `repr` of a Typst value.
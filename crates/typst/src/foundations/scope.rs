@@ -1,15 +1,19 @@
 use std::fmt::{self, Debug, Formatter};
 use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 
 use ecow::{eco_format, EcoString};
 use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
 
 use crate::diag::{bail, HintedStrResult, HintedString, StrResult};
 use crate::foundations::{
-    Element, Func, IntoValue, Module, NativeElement, NativeFunc, NativeFuncData,
-    NativeType, Type, Value,
+    repr, Datetime, Element, Func, IntoValue, Label, Module, NativeElement, NativeFunc,
+    NativeFuncData, NativeType, Type, Value,
 };
-use crate::utils::Static;
+use crate::syntax::Span;
+use crate::utils::{suggestions, Static};
 use crate::Library;
 
 #[doc(inline)]
@@ -44,50 +48,102 @@ impl<'a> Scopes<'a> {
         self.top = self.scopes.pop().expect("no pushed scope");
     }
 
+    /// Take a snapshot of all scopes.
+    ///
+    /// This is cheap because scopes are copied on write: Their bindings are
+    /// only copied once they are modified after the snapshot was taken.
+    pub fn snapshot(&self) -> ScopesSnapshot {
+        ScopesSnapshot { top: self.top.clone(), scopes: self.scopes.clone() }
+    }
+
+    /// Roll back all scopes to the state of a snapshot, undoing all
+    /// definitions, assignments, and entered or exited scopes since.
+    pub fn restore(&mut self, snapshot: ScopesSnapshot) {
+        self.top = snapshot.top;
+        self.scopes = snapshot.scopes;
+    }
+
     /// Try to access a variable immutably.
     pub fn get(&self, var: &str) -> HintedStrResult<&Value> {
-        std::iter::once(&self.top)
-            .chain(self.scopes.iter().rev())
-            .find_map(|scope| scope.get(var))
-            .or_else(|| {
-                self.base.and_then(|base| match base.global.scope().get(var) {
-                    Some(value) => Some(value),
-                    None if var == "std" => Some(&base.std),
-                    None => None,
-                })
-            })
-            .ok_or_else(|| unknown_variable(var))
+        self.find(var, false)
+            .ok_or_else(|| unknown_variable(self.base, self.visible(), var, false))
     }
 
     /// Try to access a variable immutably in math.
     pub fn get_in_math(&self, var: &str) -> HintedStrResult<&Value> {
+        self.find(var, true)
+            .ok_or_else(|| unknown_variable(self.base, self.visible(), var, true))
+    }
+
+    /// Look up a variable, in math or not, without building an error if it
+    /// doesn't exist.
+    pub fn find(&self, var: &str, math: bool) -> Option<&Value> {
         std::iter::once(&self.top)
             .chain(self.scopes.iter().rev())
             .find_map(|scope| scope.get(var))
             .or_else(|| {
-                self.base.and_then(|base| match base.math.scope().get(var) {
-                    Some(value) => Some(value),
-                    None if var == "std" => Some(&base.std),
-                    None => None,
+                self.base.and_then(|base| {
+                    let library = if math { &base.math } else { &base.global };
+                    match library.scope().get(var) {
+                        Some(value) => Some(value),
+                        None if var == "std" => Some(&base.std),
+                        None => None,
+                    }
                 })
             })
-            .ok_or_else(|| unknown_variable(var))
     }
 
     /// Try to access a variable mutably.
     pub fn get_mut(&mut self, var: &str) -> HintedStrResult<&mut Value> {
+        self.find_mut(var, Scope::get_mut)
+    }
+
+    /// Try to access a variable mutably to assign a new value to it.
+    ///
+    /// Unlike [`get_mut`](Self::get_mut), this fails for loop variables
+    /// because the new value would be lost at the next iteration.
+    pub fn get_mut_for_assign(&mut self, var: &str) -> HintedStrResult<&mut Value> {
+        self.find_mut(var, Scope::get_mut_for_assign)
+    }
+
+    /// Find a variable in the scopes and access it mutably with `f`.
+    fn find_mut(
+        &mut self,
+        var: &str,
+        f: for<'s> fn(&'s mut Scope, &str) -> Option<HintedStrResult<&'s mut Value>>,
+    ) -> HintedStrResult<&mut Value> {
+        let base = self.base;
         std::iter::once(&mut self.top)
             .chain(&mut self.scopes.iter_mut().rev())
-            .find_map(|scope| scope.get_mut(var))
+            .find_map(|scope| f(scope, var))
             .ok_or_else(|| {
-                match self.base.and_then(|base| base.global.scope().get(var)) {
+                match base.and_then(|base| base.global.scope().get(var)) {
                     Some(_) => cannot_mutate_constant(var),
                     _ if var == "std" => cannot_mutate_constant(var),
-                    _ => unknown_variable(var),
+                    // The scopes are still borrowed mutably here, so only the
+                    // standard library is searched for suggestions.
+                    _ => unknown_variable(base, [], var, false),
                 }
             })?
     }
 
+    /// The scopes from the innermost to the outermost one, without the
+    /// standard library.
+    fn visible(&self) -> impl Iterator<Item = &Scope> {
+        std::iter::once(&self.top).chain(self.scopes.iter().rev())
+    }
+
+    /// The span at which a variable was defined in one of the scopes.
+    ///
+    /// Returns `None` if the variable is not defined in any scope (but maybe in
+    /// the standard library) or if its definition span is unknown.
+    pub fn get_span(&self, var: &str) -> Option<Span> {
+        std::iter::once(&self.top)
+            .chain(self.scopes.iter().rev())
+            .find_map(|scope| scope.get_span(var))
+            .filter(|span| !span.is_detached())
+    }
+
     /// Check if an std variable is shadowed.
     pub fn check_std_shadowed(&self, var: &str) -> bool {
         self.base.is_some_and(|base| base.global.scope().get(var).is_some())
@@ -97,14 +153,32 @@ impl<'a> Scopes<'a> {
     }
 }
 
+/// A snapshot of [`Scopes`], which they can be rolled back to.
+#[derive(Debug, Clone)]
+pub struct ScopesSnapshot {
+    top: Scope,
+    scopes: Vec<Scope>,
+}
+
 #[cold]
 fn cannot_mutate_constant(var: &str) -> HintedString {
     eco_format!("cannot mutate a constant: {}", var).into()
 }
 
-/// The error message when a variable is not found.
+/// The error message when a variable is not found, with suggestions for
+/// similarly named variables from the given scopes and the standard library.
+/// In math, the math module is searched instead of the global scope.
 #[cold]
-fn unknown_variable(var: &str) -> HintedString {
+fn unknown_variable<'s>(
+    base: Option<&'s Library>,
+    scopes: impl IntoIterator<Item = &'s Scope>,
+    var: &str,
+    math: bool,
+) -> HintedString {
+    if let Some(feature) = base.and_then(|base| base.unavailable.get(var)) {
+        return feature.unavailable().into();
+    }
+
     let mut res = HintedString::new(eco_format!("unknown variable: {}", var));
 
     if matches!(var, "none" | "auto" | "false" | "true") {
@@ -115,15 +189,31 @@ fn unknown_variable(var: &str) -> HintedString {
         res.hint(eco_format!(
             "if you meant to use subtraction, try adding spaces around the minus sign",
         ));
+    } else {
+        let library = base.map(|base| if math { &base.math } else { &base.global });
+        let names = scopes
+            .into_iter()
+            .chain(library.map(Module::scope))
+            .flat_map(|scope| scope.iter().map(|(name, _)| name.as_str()))
+            .chain(base.map(|_| "std"));
+        let found = suggestions(var, names, 3);
+        if !found.is_empty() {
+            let found: Vec<_> =
+                found.iter().map(|name| eco_format!("`{name}`")).collect();
+            res.hint(eco_format!("did you mean {}?", repr::separated_list(&found, "or")));
+        }
     }
 
     res
 }
 
 /// A map from binding names to values.
+///
+/// The bindings are shared between clones of a scope until one of the clones
+/// is modified.
 #[derive(Default, Clone)]
 pub struct Scope {
-    map: IndexMap<EcoString, Slot>,
+    map: Arc<IndexMap<EcoString, Slot>>,
     deduplicate: bool,
     category: Option<Category>,
 }
@@ -159,8 +249,23 @@ impl Scope {
             panic!("duplicate definition: {name}");
         }
 
-        self.map
-            .insert(name, Slot::new(value.into_value(), Kind::Normal, self.category));
+        Arc::make_mut(&mut self.map).insert(
+            name,
+            Slot::new(value.into_value(), Span::detached(), Kind::Normal, self.category),
+        );
+    }
+
+    /// Bind a value to a name defined at the given span.
+    pub fn define_spanned(
+        &mut self,
+        name: impl Into<EcoString>,
+        value: impl IntoValue,
+        span: Span,
+    ) {
+        Arc::make_mut(&mut self.map).insert(
+            name.into(),
+            Slot::new(value.into_value(), span, Kind::Normal, self.category),
+        );
     }
 
     /// Define a native function through a Rust type that shadows the function.
@@ -191,16 +296,46 @@ impl Scope {
         self.define(module.name().clone(), module);
     }
 
-    /// Define a captured, immutable binding.
+    /// Define a captured, immutable binding. The span is the one of the
+    /// original definition.
     pub fn define_captured(
         &mut self,
         var: impl Into<EcoString>,
         value: impl IntoValue,
         capturer: Capturer,
+        span: Span,
+    ) {
+        Arc::make_mut(&mut self.map).insert(
+            var.into(),
+            Slot::new(value.into_value(), span, Kind::Captured(capturer), self.category),
+        );
+    }
+
+    /// Define a loop variable, which can be mutated but not reassigned. The
+    /// span is the one of the definition.
+    pub fn define_loop_variable(
+        &mut self,
+        var: impl Into<EcoString>,
+        value: impl IntoValue,
+        span: Span,
+    ) {
+        Arc::make_mut(&mut self.map).insert(
+            var.into(),
+            Slot::new(value.into_value(), span, Kind::LoopVariable, self.category),
+        );
+    }
+
+    /// Define a binding that was imported from a module. The span is the one
+    /// of the import item.
+    pub fn define_imported(
+        &mut self,
+        var: impl Into<EcoString>,
+        value: impl IntoValue,
+        span: Span,
     ) {
-        self.map.insert(
+        Arc::make_mut(&mut self.map).insert(
             var.into(),
-            Slot::new(value.into_value(), Kind::Captured(capturer), self.category),
+            Slot::new(value.into_value(), span, Kind::Imported, self.category),
         );
     }
 
@@ -211,12 +346,73 @@ impl Scope {
 
     /// Try to access a variable mutably.
     pub fn get_mut(&mut self, var: &str) -> Option<HintedStrResult<&mut Value>> {
-        self.map
-            .get_mut(var)
+        self.slot_mut(var)
             .map(Slot::write)
             .map(|res| res.map_err(HintedString::from))
     }
 
+    /// Try to access a variable mutably to assign a new value to it.
+    pub fn get_mut_for_assign(
+        &mut self,
+        var: &str,
+    ) -> Option<HintedStrResult<&mut Value>> {
+        self.slot_mut(var).map(Slot::assign)
+    }
+
+    /// Create a scope with copies of those of the given bindings that are
+    /// defined in this scope. The copies track accesses to imported bindings
+    /// independently of this scope and start out as not accessed.
+    pub(crate) fn subset<'a>(&self, names: impl IntoIterator<Item = &'a str>) -> Self {
+        let mut map = IndexMap::new();
+        for name in names {
+            if let Some((name, slot)) = self.map.get_key_value(name) {
+                let mut slot = slot.clone();
+                *slot.accessed.get_mut() = false;
+                map.insert(name.clone(), slot);
+            }
+        }
+        Self {
+            map: Arc::new(map),
+            deduplicate: self.deduplicate,
+            category: self.category,
+        }
+    }
+
+    /// Define copies of all bindings of the other scope in this one, replacing
+    /// existing bindings of the same names in place.
+    ///
+    /// Imported bindings that were accessed in either scope remain marked as
+    /// accessed if they are the same binding.
+    pub(crate) fn absorb(&mut self, other: &Self) {
+        let map = Arc::make_mut(&mut self.map);
+        for (name, slot) in other.map.iter() {
+            let slot = slot.clone();
+            if let Some(prev) = map.get(name) {
+                if prev.kind == Kind::Imported
+                    && prev.span == slot.span
+                    && prev.accessed.load(Ordering::Relaxed)
+                {
+                    slot.mark_accessed();
+                }
+            }
+            map.insert(name.clone(), slot);
+        }
+    }
+
+    /// Access a slot mutably, copying the bindings first if they are shared.
+    fn slot_mut(&mut self, var: &str) -> Option<&mut Slot> {
+        // Don't copy the bindings if there is nothing to modify.
+        if !self.map.contains_key(var) {
+            return None;
+        }
+        Arc::make_mut(&mut self.map).get_mut(var)
+    }
+
+    /// Get the span at which a variable was defined.
+    pub fn get_span(&self, var: &str) -> Option<Span> {
+        self.map.get(var).map(|slot| slot.span)
+    }
+
     /// Get the category of a definition.
     pub fn get_category(&self, var: &str) -> Option<Category> {
         self.map.get(var)?.category
@@ -224,7 +420,16 @@ impl Scope {
 
     /// Iterate over all definitions.
     pub fn iter(&self) -> impl Iterator<Item = (&EcoString, &Value)> {
-        self.map.iter().map(|(k, v)| (k, v.read()))
+        self.map.iter().map(|(k, v)| (k, &v.value))
+    }
+
+    /// Iterate over the names and spans of imported bindings that were never
+    /// accessed.
+    pub fn unused_imports(&self) -> impl Iterator<Item = (&EcoString, Span)> {
+        self.map.iter().filter_map(|(k, v)| match v.kind {
+            Kind::Imported if !v.accessed.load(Ordering::Relaxed) => Some((k, v.span)),
+            _ => None,
+        })
     }
 }
 
@@ -232,7 +437,7 @@ impl Debug for Scope {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         f.write_str("Scope ")?;
         f.debug_map()
-            .entries(self.map.iter().map(|(k, v)| (k, v.read())))
+            .entries(self.map.iter().map(|(k, v)| (k, &v.value)))
             .finish()
     }
 }
@@ -240,7 +445,7 @@ impl Debug for Scope {
 impl Hash for Scope {
     fn hash<H: Hasher>(&self, state: &mut H) {
         state.write_usize(self.map.len());
-        for item in &self.map {
+        for item in self.map.iter() {
             item.hash(state);
         }
         self.deduplicate.hash(state);
@@ -248,6 +453,172 @@ impl Hash for Scope {
     }
 }
 
+/// The version of the format produced by [`Scope::serialize`]. Must be bumped
+/// whenever the format changes incompatibly.
+const SCOPE_FORMAT: u32 = 1;
+
+impl Scope {
+    /// Serialize the bindings of this scope, for example to persist a REPL
+    /// session across process restarts.
+    ///
+    /// Only plain data can be serialized: `none`, `auto`, booleans, integers,
+    /// floats, strings, arrays, dictionaries, datetimes, and labels. If any
+    /// binding holds something else, the error lists all such bindings.
+    pub fn serialize(&self) -> StrResult<Vec<u8>> {
+        let mut bindings = vec![];
+        let mut invalid = vec![];
+        for (name, value) in self.iter() {
+            match Data::from_value(value) {
+                Ok(data) => bindings.push((name.clone(), data)),
+                Err(ty) => invalid.push(eco_format!("`{name}` ({ty})")),
+            }
+        }
+
+        if !invalid.is_empty() {
+            let noun = if invalid.len() == 1 { "binding" } else { "bindings" };
+            bail!(
+                "cannot serialize {noun} holding non-serializable values: {}",
+                invalid.join(", "),
+            );
+        }
+
+        let session = Session { version: SCOPE_FORMAT, bindings };
+        serde_json::to_vec(&session)
+            .map_err(|err| eco_format!("failed to serialize scope ({err})"))
+    }
+
+    /// Create a scope from bindings serialized with
+    /// [`serialize`](Self::serialize).
+    pub fn deserialize(bytes: &[u8]) -> StrResult<Self> {
+        let malformed = |err| eco_format!("failed to deserialize scope ({err})");
+
+        // Check the version first since the rest of the format may differ.
+        let header: Header = serde_json::from_slice(bytes).map_err(malformed)?;
+        if header.version != SCOPE_FORMAT {
+            bail!("unsupported scope format version {}", header.version);
+        }
+
+        let session: Session = serde_json::from_slice(bytes).map_err(malformed)?;
+        let mut scope = Self::new();
+        for (name, data) in session.bindings {
+            scope.define(name, data.into_value()?);
+        }
+        Ok(scope)
+    }
+}
+
+/// The version tag of a serialized scope.
+#[derive(Deserialize)]
+struct Header {
+    version: u32,
+}
+
+/// A serialized scope.
+#[derive(Serialize, Deserialize)]
+struct Session {
+    version: u32,
+    bindings: Vec<(EcoString, Data)>,
+}
+
+/// A serialized value. Unlike values' own serialization, this retains the
+/// types of all values so that they round-trip exactly.
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type", content = "value", rename_all = "kebab-case")]
+enum Data {
+    None,
+    Auto,
+    Bool(bool),
+    Int(i64),
+    /// Stored as a string so that infinity and NaN survive.
+    Float(EcoString),
+    Str(EcoString),
+    Array(Vec<Data>),
+    Dict(Vec<(EcoString, Data)>),
+    Datetime {
+        year: Option<i32>,
+        month: Option<u8>,
+        day: Option<u8>,
+        hour: Option<u8>,
+        minute: Option<u8>,
+        second: Option<u8>,
+    },
+    Label(EcoString),
+}
+
+impl Data {
+    /// Convert a value, returning the type of the first value that is not
+    /// plain data.
+    fn from_value(value: &Value) -> Result<Self, Type> {
+        Ok(match value {
+            Value::None => Self::None,
+            Value::Auto => Self::Auto,
+            Value::Bool(v) => Self::Bool(*v),
+            Value::Int(v) => Self::Int(*v),
+            Value::Float(v) => Self::Float(eco_format!("{v}")),
+            Value::Str(v) => Self::Str(v.as_str().into()),
+            Value::Array(array) => {
+                Self::Array(array.iter().map(Self::from_value).collect::<Result<_, _>>()?)
+            }
+            Value::Dict(dict) => Self::Dict(
+                dict.iter()
+                    .map(|(k, v)| Ok((k.as_str().into(), Self::from_value(v)?)))
+                    .collect::<Result<_, Type>>()?,
+            ),
+            Value::Datetime(v) => Self::Datetime {
+                year: v.year(),
+                month: v.month(),
+                day: v.day(),
+                hour: v.hour(),
+                minute: v.minute(),
+                second: v.second(),
+            },
+            Value::Label(v) => Self::Label(v.as_str().into()),
+            other => return Err(other.ty()),
+        })
+    }
+
+    /// Convert back into a value.
+    fn into_value(self) -> StrResult<Value> {
+        Ok(match self {
+            Self::None => Value::None,
+            Self::Auto => Value::Auto,
+            Self::Bool(v) => Value::Bool(v),
+            Self::Int(v) => Value::Int(v),
+            Self::Float(v) => match v.parse() {
+                Ok(v) => Value::Float(v),
+                Err(_) => bail!("failed to deserialize scope (invalid float {v})"),
+            },
+            Self::Str(v) => Value::Str(v.into()),
+            Self::Array(items) => Value::Array(
+                items.into_iter().map(Self::into_value).collect::<StrResult<_>>()?,
+            ),
+            Self::Dict(pairs) => Value::Dict(
+                pairs
+                    .into_iter()
+                    .map(|(k, v)| Ok((k.into(), v.into_value()?)))
+                    .collect::<StrResult<_>>()?,
+            ),
+            Self::Datetime { year, month, day, hour, minute, second } => {
+                let date = year.zip(month).zip(day);
+                let time = hour.zip(minute).zip(second);
+                let datetime = match (date, time) {
+                    (Some(((y, mo), d)), Some(((h, mi), s))) => {
+                        Datetime::from_ymd_hms(y, mo, d, h, mi, s)
+                    }
+                    (Some(((y, mo), d)), None) => Datetime::from_ymd(y, mo, d),
+                    (None, Some(((h, mi), s))) => Datetime::from_hms(h, mi, s),
+                    (None, None) => None,
+                };
+                match datetime {
+                    Some(datetime) => Value::Datetime(datetime),
+                    None => bail!("failed to deserialize scope (invalid datetime)"),
+                }
+            }
+            Self::Label(v) => Value::Label(Label::new(v.as_str())),
+        })
+    }
+}
+
 /// Defines the associated scope of a Rust type.
 pub trait NativeScope {
     /// The constructor function for the type, if any.
@@ -258,14 +629,17 @@ pub trait NativeScope {
 }
 
 /// A slot where a value is stored.
-#[derive(Clone, Hash)]
 struct Slot {
     /// The stored value.
     value: Value,
+    /// The span at which the value was defined.
+    span: Span,
     /// The kind of slot, determines how the value can be accessed.
     kind: Kind,
     /// The category of the slot.
     category: Option<Category>,
+    /// Whether the value was accessed. Only tracked for imported bindings.
+    accessed: AtomicBool,
 }
 
 /// The different kinds of slots.
@@ -275,6 +649,10 @@ enum Kind {
     Normal,
     /// A captured copy of another variable.
     Captured(Capturer),
+    /// A binding imported from a module.
+    Imported,
+    /// The variable of a for loop, which is bound anew in each iteration.
+    LoopVariable,
 }
 
 /// What the variable was captured by.
@@ -288,19 +666,27 @@ pub enum Capturer {
 
 impl Slot {
     /// Create a new slot.
-    fn new(value: Value, kind: Kind, category: Option<Category>) -> Self {
-        Self { value, kind, category }
+    fn new(value: Value, span: Span, kind: Kind, category: Option<Category>) -> Self {
+        Self {
+            value,
+            span,
+            kind,
+            category,
+            accessed: AtomicBool::new(false),
+        }
     }
 
     /// Read the value.
     fn read(&self) -> &Value {
+        self.mark_accessed();
         &self.value
     }
 
     /// Try to write to the value.
     fn write(&mut self) -> StrResult<&mut Value> {
+        self.mark_accessed();
         match self.kind {
-            Kind::Normal => Ok(&mut self.value),
+            Kind::Normal | Kind::Imported | Kind::LoopVariable => Ok(&mut self.value),
             Kind::Captured(capturer) => {
                 bail!(
                     "variables from outside the {} are \
@@ -313,6 +699,49 @@ impl Slot {
             }
         }
     }
+
+    /// Try to write a new value to the slot. This is stricter than
+    /// [`write`](Self::write) since loop variables may be mutated, but not
+    /// reassigned.
+    fn assign(&mut self) -> HintedStrResult<&mut Value> {
+        if let Kind::LoopVariable = self.kind {
+            bail!(
+                "cannot reassign the loop variable";
+                hint: "bind a new variable with let if needed"
+            );
+        }
+        self.write().map_err(HintedString::from)
+    }
+
+    /// Remember that an imported value was accessed.
+    fn mark_accessed(&self) {
+        if let Kind::Imported = self.kind {
+            self.accessed.store(true, Ordering::Relaxed);
+        }
+    }
+}
+
+impl Clone for Slot {
+    fn clone(&self) -> Self {
+        Self {
+            value: self.value.clone(),
+            span: self.span,
+            kind: self.kind,
+            category: self.category,
+            accessed: AtomicBool::new(self.accessed.load(Ordering::Relaxed)),
+        }
+    }
+}
+
+impl Hash for Slot {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // Whether the slot was accessed is deliberately not hashed because it
+        // doesn't affect the slot's semantics.
+        self.value.hash(state);
+        self.span.hash(state);
+        self.kind.hash(state);
+        self.category.hash(state);
+    }
 }
 
 /// A group of related definitions.
@@ -354,3 +783,111 @@ pub struct CategoryData {
     pub title: &'static str,
     pub docs: &'static str,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::foundations::{array, dict, Content, Datetime, Label};
+
+    #[track_caller]
+    fn test(scopes: &Scopes, var: &str, exp: Option<i64>) {
+        assert_eq!(scopes.get(var).ok().cloned(), exp.map(Value::Int));
+    }
+
+    #[test]
+    fn test_scopes_restore() {
+        let mut scopes = Scopes::new(None);
+        scopes.top.define("a", 1);
+        scopes.enter();
+        scopes.top.define("b", 2);
+        let snapshot = scopes.snapshot();
+
+        // Mutate existing bindings and define new ones in a nested block.
+        *scopes.get_mut("a").unwrap() = Value::Int(10);
+        scopes.enter();
+        scopes.top.define("b", 20);
+        scopes.top.define("c", 30);
+        scopes
+            .top
+            .define_captured("d", 40, Capturer::Function, Span::detached());
+        test(&scopes, "a", Some(10));
+        test(&scopes, "b", Some(20));
+        test(&scopes, "d", Some(40));
+        assert!(scopes.get_mut("d").is_err());
+
+        scopes.restore(snapshot);
+        assert_eq!(scopes.scopes.len(), 1);
+        test(&scopes, "a", Some(1));
+        test(&scopes, "b", Some(2));
+        test(&scopes, "c", None);
+        test(&scopes, "d", None);
+
+        // The restored scopes are still usable.
+        scopes.exit();
+        test(&scopes, "b", None);
+        *scopes.get_mut("a").unwrap() = Value::Int(3);
+        test(&scopes, "a", Some(3));
+    }
+
+    #[test]
+    fn test_scopes_restore_deduplicating() {
+        let mut scopes = Scopes::new(None);
+        scopes.top = Scope::deduplicating();
+        scopes.top.define("a", 1);
+        let snapshot = scopes.snapshot();
+        scopes.top.define("b", 2);
+        scopes.restore(snapshot);
+
+        // Redefining a binding that was rolled back is not a duplicate.
+        scopes.top.define("b", 3);
+        test(&scopes, "b", Some(3));
+    }
+
+    #[test]
+    fn test_scope_serialize_round_trip() {
+        let mut scope = Scope::new();
+        scope.define("n", Value::None);
+        scope.define("a", Value::Auto);
+        scope.define("b", true);
+        scope.define("i", -3);
+        scope.define("f", array![1.5, f64::INFINITY]);
+        scope.define("s", "hi");
+        scope.define("d", dict! { "a" => dict! { "b" => array![] }, "c" => "d" });
+        scope.define(
+            "t",
+            array![
+                Datetime::from_ymd(2024, 2, 29).unwrap(),
+                Datetime::from_hms(12, 30, 5).unwrap(),
+                Datetime::from_ymd_hms(1, 1, 1, 0, 0, 0).unwrap(),
+            ],
+        );
+        scope.define("l", Label::new("intro"));
+
+        let restored = Scope::deserialize(&scope.serialize().unwrap()).unwrap();
+        let values = |scope: &Scope| {
+            scope.iter().map(|(k, v)| (k.clone(), v.clone())).collect::<Vec<_>>()
+        };
+        assert_eq!(values(&restored), values(&scope));
+    }
+
+    #[test]
+    fn test_scope_serialize_invalid() {
+        let mut scope = Scope::new();
+        scope.define("f", crate::foundations::repr::repr::func());
+        scope.define("n", 1);
+        scope.define("c", array![Content::empty()]);
+        assert_eq!(
+            scope.serialize().unwrap_err(),
+            "cannot serialize bindings holding non-serializable values: \
+             `f` (function), `c` (content)",
+        );
+    }
+
+    #[test]
+    fn test_scope_deserialize_version() {
+        let bytes = br#"{"version": 0, "bindings": [["x", 1]]}"#;
+        let error = Scope::deserialize(bytes).unwrap_err();
+        assert_eq!(error, "unsupported scope format version 0");
+        assert!(Scope::deserialize(b"{\"bindings\": []").is_err());
+    }
+}
@@ -7,8 +7,8 @@ use smallvec::SmallVec;
 
 use crate::diag::{bail, HintedStrResult, StrResult};
 use crate::foundations::{
-    cast, func, repr, scope, ty, CastInfo, Content, Context, Dict, Element, FromValue,
-    Func, Label, Reflect, Regex, Repr, Str, StyleChain, Type, Value,
+    cast, func, repr, scope, ty, CastInfo, Content, Context, Dict, DynamicRepr, Element,
+    FromValue, Func, Label, Reflect, Regex, Repr, Str, StyleChain, Type, Value,
 };
 use crate::introspection::{Introspector, Locatable, Location};
 use crate::symbols::Symbol;
@@ -275,6 +275,8 @@ impl Repr for Selector {
     }
 }
 
+impl DynamicRepr for Selector {}
+
 cast! {
     type Selector,
     text: EcoString => Self::text(&text)?,
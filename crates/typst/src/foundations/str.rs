@@ -8,14 +8,14 @@ use ecow::EcoString;
 use serde::{Deserialize, Serialize};
 use unicode_segmentation::UnicodeSegmentation;
 
-use crate::diag::{bail, At, SourceResult, StrResult};
+use crate::diag::{bail, At, HintedStrResult, SourceResult, StrResult};
 use crate::engine::Engine;
 use crate::foundations::{
-    cast, dict, func, repr, scope, ty, Array, Bytes, Context, Dict, Func, IntoValue,
-    Label, Repr, Type, Value, Version,
+    cast, dict, func, repr, scope, ty, Arg, Args, Array, Bytes, CastInfo, Context, Dict,
+    DynamicRepr, FromValue, Func, IntoValue, Label, Reflect, Repr, Type, Value, Version,
 };
 use crate::layout::Alignment;
-use crate::syntax::{Span, Spanned};
+use crate::syntax::{is_ident, Span, Spanned};
 use crate::utils::PicoStr;
 
 /// Create a new [`Str`] from a format string.
@@ -47,7 +47,8 @@ pub use ecow::eco_format;
 /// the methods quite versatile.
 ///
 /// All lengths and indices are expressed in terms of UTF-8 bytes. Indices are
-/// zero-based and negative indices wrap around to the end of the string.
+/// zero-based and negative indices wrap around to the end of the string. An
+/// index that falls into the middle of a multi-byte character is an error.
 ///
 /// You can convert a value to a string with this type's constructor.
 ///
@@ -116,8 +117,8 @@ impl Str {
             .and_then(|v| usize::try_from(v).ok())
             .filter(|&v| v <= self.0.len());
 
-        if resolved.is_some_and(|i| !self.0.is_char_boundary(i)) {
-            return Err(not_a_char_boundary(index));
+        if let Some(i) = resolved.filter(|&i| !self.0.is_char_boundary(i)) {
+            return Err(not_a_char_boundary(&self.0, index, i));
         }
 
         Ok(resolved)
@@ -133,6 +134,11 @@ impl Str {
     /// - Floats are formatted in base 10 and never in exponential notation.
     /// - From labels the name is extracted.
     /// - Bytes are decoded as UTF-8.
+    /// - Alignments and directions turn into their names, e.g. `{"top + left"}`.
+    /// - From regular expressions the pattern is extracted.
+    ///
+    /// Other values, like locations, counters, or selectors, can't be converted
+    /// to a string. Use [`repr`] to get their representation instead.
     ///
     /// If you wish to convert from and to Unicode code points, see the
     /// [`to-unicode`]($str.to-unicode) and [`from-unicode`]($str.from-unicode)
@@ -235,9 +241,9 @@ impl Str {
         #[named]
         count: Option<i64>,
     ) -> StrResult<Str> {
-        let end = end.or(count.map(|c| start + c)).unwrap_or(self.len() as i64);
         let start = self.locate(start)?;
-        let end = self.locate(end)?.max(start);
+        let end = end.or(count.map(|c| start as i64 + c));
+        let end = self.locate(end.unwrap_or(self.len() as i64))?.max(start);
         Ok(self.0[start..end].into())
     }
 
@@ -354,8 +360,11 @@ impl Str {
         }
     }
 
-    /// Searches for the specified pattern in the string and returns the index
-    /// of the first match as an integer or `{none}` if there is no match.
+    /// Searches for the specified pattern in the string and returns the byte
+    /// index of the first match as an integer or `{none}` if there is no match.
+    ///
+    /// The returned index always lies on a character boundary and can thus be
+    /// passed to [`at`]($str.at) and [`slice`]($str.slice).
     #[func]
     pub fn position(
         &self,
@@ -373,14 +382,14 @@ impl Str {
     /// match.
     ///
     /// The returned dictionary has the following keys:
-    /// - `start`: The start offset of the match
-    /// - `end`: The end offset of the match
+    /// - `start`: The start byte offset of the match
+    /// - `end`: The end byte offset of the match
     /// - `text`: The text that matched.
     /// - `captures`: An array containing a string for each matched capturing
     ///   group. The first item of the array contains the first matched
     ///   capturing, not the whole match! This is empty unless the `pattern` was
     ///   a regex with capturing groups.
-    #[func]
+    #[func(keys = ["start", "end", "text", "captures"])]
     pub fn match_(
         &self,
         /// The pattern to search for.
@@ -771,19 +780,315 @@ pub enum ToStr {
     Int(i64),
 }
 
-cast! {
-    ToStr,
-    v: i64 => Self::Int(v),
-    v: f64 => Self::Str(repr::display_float(v).into()),
-    v: Version => Self::Str(format_str!("{}", v)),
-    v: Bytes => Self::Str(
-        std::str::from_utf8(&v)
-            .map_err(|_| "bytes are not valid utf-8")?
-            .into()
-    ),
-    v: Label => Self::Str(v.as_str().into()),
-    v: Type => Self::Str(v.long_name().into()),
-    v: Str => Self::Str(v),
+impl Reflect for ToStr {
+    fn input() -> CastInfo {
+        i64::input()
+            + f64::input()
+            + Version::input()
+            + Bytes::input()
+            + Label::input()
+            + Type::input()
+            + Str::input()
+    }
+
+    fn output() -> CastInfo {
+        Self::input()
+    }
+
+    fn castable(value: &Value) -> bool {
+        matches!(
+            value,
+            Value::Int(_)
+                | Value::Float(_)
+                | Value::Version(_)
+                | Value::Bytes(_)
+                | Value::Label(_)
+                | Value::Type(_)
+                | Value::Str(_)
+                | Value::Symbol(_)
+                | Value::Dyn(_)
+        )
+    }
+}
+
+impl FromValue for ToStr {
+    fn from_value(value: Value) -> HintedStrResult<Self> {
+        Ok(match value {
+            Value::Int(v) => Self::Int(v),
+            Value::Float(v) => Self::Str(repr::display_float(v).into()),
+            Value::Version(v) => Self::Str(format_str!("{}", v)),
+            Value::Bytes(v) => Self::Str(
+                std::str::from_utf8(&v)
+                    .map_err(|_| "bytes are not valid utf-8")?
+                    .into(),
+            ),
+            Value::Label(v) => Self::Str(v.as_str().into()),
+            Value::Type(v) => Self::Str(v.long_name().into()),
+            Value::Str(v) => Self::Str(v),
+            Value::Symbol(v) => Self::Str(v.get().into()),
+            Value::Dyn(v) => Self::Str(v.to_str()?),
+            v => return Err(Self::error(&v)),
+        })
+    }
+}
+
+/// Formats values into a string.
+///
+/// The template contains placeholders in curly braces, which are replaced with
+/// the other arguments:
+/// - `{}` is replaced with the next positional argument.
+/// - `{0}`, `{1}`, and so on are replaced with the positional argument with
+///   that index.
+/// - `{name}` is replaced with the named argument `name`.
+///
+/// Numbers are displayed like with [`str`]($str) and all other values must be
+/// convertible to strings. To insert a literal curly brace, double it.
+///
+/// A placeholder can end with a colon and a format spec, which consists of the
+/// following parts, all of which are optional:
+/// - An alignment: `<` for left, `^` for centered, and `>` for right.
+///   Numbers are aligned to the right by default, everything else to the left.
+/// - A `+` to display a sign for positive numbers, too.
+/// - A `0` to pad numbers with zeros after the sign instead of with spaces.
+/// - The minimum width, measured in grapheme clusters.
+/// - A dot followed by the number of decimal places to display a float with.
+///
+/// ```example
+/// #format("{}-{id:04}", "item", id: 3) \
+/// #format("{0} is about {0:.2}", calc.pi) \
+/// #format("[{:^7}] {{braces}}", "mid") \
+/// #format("{:+}", 5)
+/// ```
+#[func]
+pub fn format(
+    /// The real arguments (the other arguments are just for the docs, this
+    /// function takes arbitrary named arguments, so we parse them manually).
+    args: &mut Args,
+    /// The template with placeholders.
+    #[external]
+    template: Str,
+    /// The values for the positional placeholders.
+    #[external]
+    #[variadic]
+    values: Vec<Value>,
+) -> SourceResult<Str> {
+    let Spanned { v: template, span } = args.expect::<Spanned<Str>>("template")?;
+    let (named, pos): (Vec<Arg>, Vec<Arg>) =
+        args.take().items.into_iter().partition(|arg| arg.name.is_some());
+    let mut used_pos = vec![false; pos.len()];
+    let mut used_named = vec![false; named.len()];
+
+    let mut output = EcoString::new();
+    let mut next = 0;
+    let mut rest = template.as_str();
+    while let Some(i) = rest.find(['{', '}']) {
+        output.push_str(&rest[..i]);
+        let brace = if rest[i..].starts_with('{') { '{' } else { '}' };
+        rest = &rest[i + 1..];
+        if let Some(after) = rest.strip_prefix(brace) {
+            output.push(brace);
+            rest = after;
+            continue;
+        }
+
+        if brace == '}' {
+            bail!(
+                span, "unmatched closing brace in format string";
+                hint: "use `}}}}` to insert a literal closing brace",
+            );
+        }
+
+        let Some(end) = rest.find('}') else {
+            bail!(
+                span, "unclosed placeholder in format string";
+                hint: "use `{{{{` to insert a literal opening brace",
+            );
+        };
+
+        let placeholder = &rest[..end];
+        rest = &rest[end + 1..];
+        let (key, spec) = placeholder.split_once(':').unwrap_or((placeholder, ""));
+        let Some(spec) = FormatSpec::parse(spec) else {
+            bail!(
+                span, "invalid format spec `{spec}`";
+                hint: "a spec consists of an alignment (`<`, `^`, or `>`), a sign \
+                       (`+`), zero padding (`0`), a width, and a precision (`.2`), \
+                       in this order",
+            );
+        };
+        if spec.width > FormatSpec::MAX_WIDTH {
+            bail!(span, "format width must be at most {}", FormatSpec::MAX_WIDTH);
+        }
+        if spec.precision.is_some_and(|p| p > FormatSpec::MAX_PRECISION) {
+            bail!(span, "format precision must be at most {}", FormatSpec::MAX_PRECISION);
+        }
+
+        let arg = if key.is_empty() || key.bytes().all(|b| b.is_ascii_digit()) {
+            let index = if key.is_empty() {
+                next += 1;
+                next - 1
+            } else {
+                key.parse().unwrap_or(usize::MAX)
+            };
+            let Some(arg) = pos.get(index) else {
+                bail!(
+                    span, "placeholder `{{{placeholder}}}` has no matching argument";
+                    hint: "{} positional argument{} given",
+                    pos.len(),
+                    if pos.len() == 1 { " was" } else { "s were" },
+                );
+            };
+            used_pos[index] = true;
+            arg
+        } else if is_ident(key) {
+            let Some(index) =
+                named.iter().position(|arg| arg.name.as_deref() == Some(key))
+            else {
+                bail!(
+                    span, "placeholder `{{{placeholder}}}` has no matching argument";
+                    hint: "pass it as a named argument like `{key}: ..`",
+                );
+            };
+            used_named[index] = true;
+            &named[index]
+        } else {
+            bail!(
+                span, "invalid placeholder `{{{placeholder}}}`";
+                hint: "placeholders contain an index, a name, or nothing, \
+                       optionally followed by a colon and a format spec",
+            );
+        };
+
+        output.push_str(&spec.apply(&arg.value)?);
+    }
+
+    output.push_str(rest);
+
+    let mut unused = pos.iter().zip(used_pos).chain(named.iter().zip(used_named));
+    if let Some((arg, _)) = unused.find(|(_, used)| !used) {
+        match &arg.name {
+            Some(name) => bail!(
+                arg.span, "unexpected argument: {name}";
+                hint: "the format string has no placeholder `{{{name}}}`",
+            ),
+            None => bail!(
+                arg.span, "unexpected argument";
+                hint: "the format string has no placeholder for it",
+            ),
+        }
+    }
+
+    Ok(output.into())
+}
+
+/// How to display a value in a [`format`] placeholder.
+#[derive(Default)]
+struct FormatSpec {
+    align: Option<char>,
+    sign: bool,
+    zero: bool,
+    width: usize,
+    precision: Option<usize>,
+}
+
+impl FormatSpec {
+    /// The largest supported width.
+    const MAX_WIDTH: usize = 1000;
+
+    /// The largest supported precision.
+    const MAX_PRECISION: usize = 100;
+
+    /// Parse a spec like `>+08.2`, returning `None` if it is malformed.
+    fn parse(mut spec: &str) -> Option<Self> {
+        let mut parsed = Self::default();
+        if let Some(c) = spec.chars().next().filter(|c| matches!(c, '<' | '^' | '>')) {
+            parsed.align = Some(c);
+            spec = &spec[1..];
+        }
+        if let Some(after) = spec.strip_prefix('+') {
+            parsed.sign = true;
+            spec = after;
+        }
+        if let Some(after) = spec.strip_prefix('0') {
+            parsed.zero = true;
+            spec = after;
+        }
+
+        let digits = |s: &str| s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+        let len = digits(spec);
+        if len > 0 {
+            // Numbers that overflow are rejected by the caller's limits.
+            parsed.width = spec[..len].parse().unwrap_or(usize::MAX);
+            spec = &spec[len..];
+        }
+        if let Some(after) = spec.strip_prefix('.') {
+            let len = digits(after);
+            if len == 0 {
+                return None;
+            }
+            parsed.precision = Some(after[..len].parse().unwrap_or(usize::MAX));
+            spec = &after[len..];
+        }
+
+        spec.is_empty().then_some(parsed)
+    }
+
+    /// Display a value according to the spec.
+    fn apply(&self, value: &Spanned<Value>) -> SourceResult<EcoString> {
+        let sign = |negative: bool| match (negative, self.sign) {
+            (true, _) => repr::MINUS_SIGN,
+            (false, true) => "+",
+            (false, false) => "",
+        };
+
+        let (sign, body, numeric) = match value.v {
+            Value::Int(n) => {
+                if self.precision.is_some() {
+                    bail!(
+                        value.span, "precision is only supported for floats";
+                        hint: "convert the integer with `float` to display decimal places",
+                    );
+                }
+                (sign(n < 0), eco_format!("{}", n.unsigned_abs()), true)
+            }
+            Value::Float(f) => {
+                let body = match self.precision {
+                    Some(precision) => eco_format!("{:.*}", precision, f.abs()),
+                    None => repr::display_float(f.abs()),
+                };
+                (sign(f < 0.0), body, true)
+            }
+            _ => {
+                if self.precision.is_some() {
+                    bail!(value.span, "precision is only supported for floats");
+                } else if self.sign {
+                    bail!(value.span, "a sign is only supported for numbers");
+                } else if self.zero {
+                    bail!(value.span, "zero padding is only supported for numbers");
+                }
+                let body = match ToStr::from_value(value.v.clone()).at(value.span)? {
+                    ToStr::Str(s) => s.into(),
+                    ToStr::Int(n) => repr::format_int_with_base(n, 10),
+                };
+                ("", body, false)
+            }
+        };
+
+        let len = sign.graphemes(true).count() + body.graphemes(true).count();
+        let fill = self.width.saturating_sub(len);
+        if self.zero {
+            return Ok(eco_format!("{sign}{}{body}", "0".repeat(fill)));
+        }
+
+        let (before, after) = match self.align {
+            Some('<') => (0, fill),
+            Some('^') => (fill / 2, fill - fill / 2),
+            Some(_) => (fill, 0),
+            None if numeric => (fill, 0),
+            None => (0, fill),
+        };
+        Ok(eco_format!("{}{sign}{body}{}", " ".repeat(before), " ".repeat(after)))
+    }
 }
 
 /// Convert an item of std's `match_indices` to a dictionary.
@@ -823,9 +1128,27 @@ fn no_default_and_out_of_bounds(index: i64, len: usize) -> EcoString {
 }
 
 /// The char boundary access error message.
+///
+/// Lists the closest valid byte offsets on both sides of the resolved index,
+/// so that the user can pick one of them.
 #[cold]
-fn not_a_char_boundary(index: i64) -> EcoString {
-    eco_format!("string index {} is not a character boundary", index)
+fn not_a_char_boundary(string: &str, index: i64, resolved: usize) -> EcoString {
+    let prev = (0..resolved).rev().find(|&i| string.is_char_boundary(i)).unwrap_or(0);
+    let next = (resolved..=string.len())
+        .find(|&i| string.is_char_boundary(i))
+        .unwrap_or(string.len());
+
+    // Only show short strings in full to keep the message readable.
+    let context = if string.len() <= 40 {
+        eco_format!(" in {}", string.repr())
+    } else {
+        EcoString::new()
+    };
+
+    eco_format!(
+        "string index {index} is not a character boundary{context} \
+         (valid boundaries near {resolved}: {prev}, {next})"
+    )
 }
 
 /// The error message when the string is empty.
@@ -898,6 +1221,12 @@ impl Repr for Regex {
     }
 }
 
+impl DynamicRepr for Regex {
+    fn to_str(&self) -> Option<Str> {
+        Some(self.0.as_str().into())
+    }
+}
+
 impl PartialEq for Regex {
     fn eq(&self, other: &Self) -> bool {
         self.0.as_str() == other.0.as_str()
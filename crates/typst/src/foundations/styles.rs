@@ -10,8 +10,8 @@ use smallvec::SmallVec;
 use crate::diag::{SourceResult, Trace, Tracepoint};
 use crate::engine::Engine;
 use crate::foundations::{
-    cast, elem, func, ty, Content, Context, Element, Func, NativeElement, Packed, Repr,
-    Selector, Show,
+    cast, elem, func, ty, Content, Context, Dict, Element, Func, IntoValue,
+    NativeElement, NativeFunc, Packed, Repr, Selector, Show, Value,
 };
 use crate::introspection::Locatable;
 use crate::syntax::Span;
@@ -46,6 +46,90 @@ pub fn style(
     StyleElem::new(func).pack().spanned(span)
 }
 
+/// Makes show rules apply to the whole module instead of just the content
+/// that follows them.
+///
+/// Normally, a show rule only affects the content after it. Once this
+/// function is applied with an everything show rule, all subsequent show rules
+/// with a selector in the same file are hoisted to the top of the file, unless
+/// they are in a function. This way, a show rule for a label also transforms
+/// elements that were labelled before it.
+///
+/// ```example
+/// #show: show-everywhere
+/// = Introduction <intro>
+/// #show <intro>: set text(red)
+/// ```
+#[func]
+pub fn show_everywhere(
+    /// The content to which subsequent show rules should be hoisted.
+    body: Content,
+) -> Content {
+    body
+}
+
+/// Applies a show rule only to the given content.
+///
+/// A show rule in markup or a code block applies to everything after it. To
+/// limit it to a specific piece of content, evaluate it in expression position,
+/// where it results in a value, and pass it to this function. Alternatively,
+/// pass a function or content, which is then applied to the whole content
+/// like a show rule without a selector.
+///
+/// ```example
+/// #let shout(body) = scoped(show "Typst": upper, body)
+/// #shout[Typst is great.] /// Typst is still great.
+/// ```
+#[func]
+pub fn scoped(
+    /// The engine.
+    engine: &mut Engine,
+    /// The callsite context.
+    context: Tracked<Context>,
+    /// The call site span.
+    span: Span,
+    /// The show rule to apply.
+    recipe: Recipe,
+    /// The content to apply the show rule to.
+    body: Content,
+) -> SourceResult<Content> {
+    let mut recipe = recipe;
+    if recipe.span.is_detached() {
+        recipe.span = span;
+    }
+    recipe.everywhere = false;
+    body.styled_with_recipe(engine, context, recipe)
+}
+
+/// Isolates content from the properties of enclosing set rules.
+///
+/// Properties set outside of the isolated content don't apply within it, so
+/// it is styled with the document's defaults instead. This is useful for fragments that
+/// should keep their own styling regardless of where they are placed. Set rules
+/// within the content as well as show rules still apply.
+///
+/// ```example
+/// #set text(red)
+/// This is red.
+///
+/// #isolate[This is not.]
+///
+/// #isolate(only: (text, par))[
+///   Neither is this.
+/// ]
+/// ```
+#[func]
+pub fn isolate(
+    /// The content to isolate.
+    body: Content,
+    /// The elements whose properties should be isolated. By default, the
+    /// properties of all elements are.
+    #[named]
+    only: Option<Vec<Element>>,
+) -> Content {
+    body.styled(Isolation::new(only.map(EcoVec::from)))
+}
+
 /// Executes a style access.
 #[elem(Locatable, Show)]
 struct StyleElem {
@@ -134,6 +218,7 @@ impl Styles {
             Style::Property(property) => property.is_of(elem).then_some(property.span),
             Style::Recipe(recipe) => recipe.is_of(elem).then_some(Some(recipe.span)),
             Style::Revocation(_) => None,
+            Style::Isolation(isolation) => isolation.covers(elem).then_some(None),
         })
     }
 
@@ -182,6 +267,8 @@ pub enum Style {
     Recipe(Recipe),
     /// Disables a specific show rule recipe.
     Revocation(RecipeIndex),
+    /// Blocks outer style properties from applying.
+    Isolation(Isolation),
 }
 
 impl Style {
@@ -208,6 +295,7 @@ impl Debug for Style {
             Self::Property(property) => property.fmt(f),
             Self::Recipe(recipe) => recipe.fmt(f),
             Self::Revocation(guard) => guard.fmt(f),
+            Self::Isolation(isolation) => isolation.fmt(f),
         }
     }
 }
@@ -224,6 +312,12 @@ impl From<Recipe> for Style {
     }
 }
 
+impl From<Isolation> for Style {
+    fn from(isolation: Isolation) -> Self {
+        Self::Isolation(isolation)
+    }
+}
+
 /// A style property originating from a set rule or constructor.
 #[derive(Clone, Hash)]
 pub struct Property {
@@ -364,9 +458,22 @@ pub struct Recipe {
     pub selector: Option<Selector>,
     /// The transformation to perform on the match.
     pub transform: Transformation,
+    /// Whether the recipe is hoisted to apply to the whole module rather than
+    /// just the content that follows it. This is the case for show rules with
+    /// a selector that are in the scope of a `show: show-everywhere` rule.
+    pub everywhere: bool,
 }
 
 impl Recipe {
+    /// Whether this recipe is a `show: show-everywhere` rule.
+    pub fn is_show_everywhere(&self) -> bool {
+        self.selector.is_none()
+            && matches!(
+                &self.transform,
+                Transformation::Func(func) if *func == show_everywhere::func()
+            )
+    }
+
     /// Whether this recipe is for the given type of element.
     pub fn is_of(&self, element: Element) -> bool {
         match self.selector {
@@ -382,17 +489,36 @@ impl Recipe {
             .is_some_and(|selector| selector.matches(target, Some(styles)))
     }
 
-    /// Apply the recipe to the given content.
+    /// Whether the transformation is a function with two positional
+    /// parameters, which receives information about the match in addition to
+    /// the match itself.
+    pub fn wants_info(&self) -> bool {
+        match &self.transform {
+            Transformation::Func(func) => func.num_pos_params() == Some(2),
+            _ => false,
+        }
+    }
+
+    /// Apply the recipe to the given content, which is the match with the
+    /// given `index` among the recipe's matches.
     pub fn apply(
         &self,
         engine: &mut Engine,
         context: Tracked<Context>,
         content: Content,
+        index: usize,
     ) -> SourceResult<Content> {
         let mut content = match &self.transform {
             Transformation::Content(content) => content.clone(),
             Transformation::Func(func) => {
-                let mut result = func.call(engine, context, [content.clone()]);
+                let mut args = eco_vec![content.clone().into_value()];
+                if self.wants_info() {
+                    let mut info = Dict::new();
+                    info.insert("index".into(), index.into_value());
+                    args.push(info.into_value());
+                }
+
+                let mut result = func.call(engine, context, args);
                 if self.selector.is_some() {
                     let point = || Tracepoint::Show(content.func().name().into());
                     result = result.trace(engine.world, point, content.span());
@@ -408,6 +534,21 @@ impl Recipe {
     }
 }
 
+cast! {
+    Recipe,
+    self => Value::Styles(Style::from(self).into()),
+    styles: Styles => match styles.0.as_slice() {
+        [style] => style.recipe().cloned().ok_or("expected show rule, found set rule")?,
+        _ => Err("expected a single show rule")?,
+    },
+    transform: Transformation => Self {
+        span: Span::detached(),
+        selector: None,
+        transform,
+        everywhere: false,
+    },
+}
+
 impl Debug for Recipe {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         f.write_str("Show(")?;
@@ -419,6 +560,27 @@ impl Debug for Recipe {
     }
 }
 
+/// A barrier that blocks the properties of outer styles from applying.
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub struct Isolation {
+    /// The elements whose properties are blocked. If `None`, the properties of
+    /// all elements are.
+    only: Option<EcoVec<Element>>,
+}
+
+impl Isolation {
+    /// Create a new isolation for the properties of the given elements or of
+    /// all elements.
+    pub fn new(only: Option<EcoVec<Element>>) -> Self {
+        Self { only }
+    }
+
+    /// Whether the properties of the given element are blocked.
+    pub fn covers(&self, elem: Element) -> bool {
+        self.only.as_ref().map_or(true, |only| only.contains(&elem))
+    }
+}
+
 /// Identifies a show rule recipe from the top of the chain.
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 pub struct RecipeIndex(pub usize);
@@ -539,8 +701,18 @@ impl<'a> StyleChain<'a> {
         id: u8,
         inherent: Option<&'a T>,
     ) -> impl Iterator<Item = &'a T> {
+        let mut entries = self.entries();
+        let visible = std::iter::from_fn(move || loop {
+            match entries.next()? {
+                Style::Isolation(isolation) if isolation.covers(func) => {
+                    entries.skip_to_root()
+                }
+                style => return Some(style),
+            }
+        });
+
         inherent.into_iter().chain(
-            self.entries()
+            visible
                 .filter_map(Style::property)
                 .filter(move |property| property.is(func, id))
                 .map(|property| &property.value)
@@ -656,6 +828,18 @@ pub struct Entries<'a> {
     links: Links<'a>,
 }
 
+impl Entries<'_> {
+    /// Skip all remaining entries that are not part of the root link of the
+    /// chain, which holds the document's default styles. If the current link
+    /// is the root link, skip all of its remaining entries.
+    fn skip_to_root(&mut self) {
+        self.inner = match self.links.by_ref().last() {
+            Some(root) => root.iter(),
+            None => [].as_slice().iter(),
+        };
+    }
+}
+
 impl<'a> Iterator for Entries<'a> {
     type Item = &'a Style;
 
@@ -49,7 +49,9 @@ pub use typst_macros::{scope, ty};
 /// type. Compatibility with the old way will remain for a while to give package
 /// authors time to upgrade, but it will be removed at some point.
 ///
-/// - Checks like `{int == "integer"}` evaluate to `{true}`
+/// - Checks like `{int == "integer"}` evaluate to `{true}`, but emit a
+///   deprecation warning. Compare with the type itself or use
+///   [`isinstance`]($isinstance) instead.
 /// - Adding/joining a type and string will yield a string
 /// - The `{in}` operator on a type and a dictionary will evaluate to `{true}`
 ///   if the dictionary has a string key matching the type's name
@@ -140,6 +142,27 @@ impl Type {
     }
 }
 
+/// Checks whether a value is of one of the given types.
+///
+/// This is the same as comparing the value's [type]($type) with each of the
+/// types in turn.
+///
+/// ```example
+/// #isinstance(12, int) \
+/// #isinstance(12, float) \
+/// #isinstance("hi", int, str)
+/// ```
+#[func]
+pub fn isinstance(
+    /// The value to check.
+    value: Value,
+    /// The types to check against.
+    #[variadic]
+    types: Vec<Type>,
+) -> bool {
+    types.contains(&value.ty())
+}
+
 impl Debug for Type {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         write!(f, "Type({})", self.long_name())
@@ -9,8 +9,8 @@ use serde::de::value::{MapAccessDeserializer, SeqAccessDeserializer};
 use serde::de::{Error, MapAccess, SeqAccess, Visitor};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
-use crate::diag::{HintedStrResult, HintedString, StrResult};
-use crate::eval::ops;
+use crate::diag::{bail, HintedStrResult, HintedString, StrResult};
+use crate::eval::{maybe_grow, ops};
 use crate::foundations::{
     fields, repr, Args, Array, AutoValue, Bytes, CastInfo, Content, Datetime, Dict,
     Duration, Fold, FromValue, Func, IntoValue, Label, Module, NativeElement, NativeType,
@@ -90,10 +90,14 @@ pub enum Value {
 }
 
 impl Value {
+    /// The maximum nesting depth of values that are turned into their
+    /// representation or encoded into a data format.
+    pub const MAX_DEPTH: usize = 512;
+
     /// Create a new dynamic value.
     pub fn dynamic<T>(any: T) -> Self
     where
-        T: Debug + Repr + NativeType + PartialEq + Hash + Sync + Send + 'static,
+        T: Debug + DynamicRepr + NativeType + PartialEq + Hash + Sync + Send + 'static,
     {
         Self::Dyn(Dynamic::new(any))
     }
@@ -164,7 +168,12 @@ impl Value {
             Self::Content(content) => content.field_by_name(field),
             Self::Type(ty) => ty.field(field).cloned(),
             Self::Func(func) => func.field(field).cloned(),
-            Self::Module(module) => module.field(field).cloned(),
+            Self::Module(module) => match module.field(field) {
+                Ok(value) => Ok(value.clone()),
+                Err(_) if field == "path" => Ok(module.path().into_value()),
+                Err(_) if field == "content" => Ok(module.clone().content().into_value()),
+                Err(err) => Err(err),
+            },
             _ => fields::field(self, field),
         }
     }
@@ -198,6 +207,68 @@ impl Value {
         }
     }
 
+    /// Ensure that arrays, dictionaries, and arguments are nested at most
+    /// `max` levels deep.
+    ///
+    /// Unlike most operations on values, this does not recurse and thus
+    /// works for arbitrarily deep values.
+    pub fn check_depth(&self, max: usize) -> StrResult<()> {
+        let mut stack = vec![(self, 0)];
+        while let Some((value, depth)) = stack.pop() {
+            let children: Box<dyn Iterator<Item = &Value>> = match value {
+                Self::Array(array) => Box::new(array.iter()),
+                Self::Dict(dict) => Box::new(dict.iter().map(|(_, v)| v)),
+                Self::Args(args) => Box::new(args.items.iter().map(|arg| &arg.value.v)),
+                _ => continue,
+            };
+            if depth >= max {
+                bail!("value is too deeply nested");
+            }
+            stack.extend(children.map(|child| (child, depth + 1)));
+        }
+        Ok(())
+    }
+
+    /// The canonical representation of the value, which is stable across
+    /// platforms and Typst versions. See [`canonical_repr`](repr::canonical_repr)
+    /// for the format and stability policy.
+    pub fn canonical_string(&self) -> EcoString {
+        let mut buf = EcoString::new();
+        repr::write_canonical(&mut buf, self);
+        buf
+    }
+
+    /// Convert the value into JSON, e.g. for inspection by editor tooling.
+    ///
+    /// None, booleans, numbers, strings, arrays, and dictionaries map to their
+    /// JSON counterparts. Other values, non-finite floats, and collections
+    /// nested deeper than `max_depth` become an object with the name of the
+    /// value's type and its `repr`.
+    pub fn to_json(&self, max_depth: usize) -> serde_json::Value {
+        use serde_json::Value as Json;
+        match self {
+            Self::None => Json::Null,
+            Self::Bool(v) => Json::Bool(*v),
+            Self::Int(v) => Json::from(*v),
+            Self::Float(v) if v.is_finite() => Json::from(*v),
+            Self::Str(v) => Json::String(v.as_str().into()),
+            Self::Array(v) if max_depth > 0 => {
+                Json::Array(v.iter().map(|item| item.to_json(max_depth - 1)).collect())
+            }
+            Self::Dict(v) if max_depth > 0 => Json::Object(
+                v.iter()
+                    .map(|(key, value)| {
+                        (key.as_str().into(), value.to_json(max_depth - 1))
+                    })
+                    .collect(),
+            ),
+            other => serde_json::json!({
+                "type": other.ty().short_name(),
+                "repr": other.repr().as_str(),
+            }),
+        }
+    }
+
     /// Return the display representation of the value.
     pub fn display(self) -> Content {
         match self {
@@ -209,6 +280,7 @@ impl Value {
             Self::Symbol(v) => TextElem::packed(v.get()),
             Self::Content(v) => v,
             Self::Module(module) => module.content(),
+            Self::Dyn(v) => v.display(),
             _ => RawElem::new(RawContent::Text(self.repr()))
                 .with_lang(Some("typc".into()))
                 .with_block(false)
@@ -228,7 +300,7 @@ impl Value {
 
 impl Debug for Value {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        match self {
+        maybe_grow(|| match self {
             Self::None => Debug::fmt(&NoneValue, f),
             Self::Auto => Debug::fmt(&AutoValue, f),
             Self::Bool(v) => Debug::fmt(v, f),
@@ -259,13 +331,13 @@ impl Debug for Value {
             Self::Module(v) => Debug::fmt(v, f),
             Self::Plugin(v) => Debug::fmt(v, f),
             Self::Dyn(v) => Debug::fmt(v, f),
-        }
+        })
     }
 }
 
 impl Repr for Value {
     fn repr(&self) -> EcoString {
-        match self {
+        maybe_grow(|| match self {
             Self::None => NoneValue.repr(),
             Self::Auto => AutoValue.repr(),
             Self::Bool(v) => v.repr(),
@@ -296,7 +368,7 @@ impl Repr for Value {
             Self::Module(v) => v.repr(),
             Self::Plugin(v) => v.repr(),
             Self::Dyn(v) => v.repr(),
-        }
+        })
     }
 }
 
@@ -314,39 +386,41 @@ impl PartialOrd for Value {
 
 impl Hash for Value {
     fn hash<H: Hasher>(&self, state: &mut H) {
-        std::mem::discriminant(self).hash(state);
-        match self {
-            Self::None => {}
-            Self::Auto => {}
-            Self::Bool(v) => v.hash(state),
-            Self::Int(v) => v.hash(state),
-            Self::Float(v) => v.to_bits().hash(state),
-            Self::Length(v) => v.hash(state),
-            Self::Angle(v) => v.hash(state),
-            Self::Ratio(v) => v.hash(state),
-            Self::Relative(v) => v.hash(state),
-            Self::Fraction(v) => v.hash(state),
-            Self::Color(v) => v.hash(state),
-            Self::Gradient(v) => v.hash(state),
-            Self::Pattern(v) => v.hash(state),
-            Self::Symbol(v) => v.hash(state),
-            Self::Version(v) => v.hash(state),
-            Self::Str(v) => v.hash(state),
-            Self::Bytes(v) => v.hash(state),
-            Self::Label(v) => v.hash(state),
-            Self::Content(v) => v.hash(state),
-            Self::Styles(v) => v.hash(state),
-            Self::Datetime(v) => v.hash(state),
-            Self::Duration(v) => v.hash(state),
-            Self::Array(v) => v.hash(state),
-            Self::Dict(v) => v.hash(state),
-            Self::Func(v) => v.hash(state),
-            Self::Args(v) => v.hash(state),
-            Self::Type(v) => v.hash(state),
-            Self::Module(v) => v.hash(state),
-            Self::Plugin(v) => v.hash(state),
-            Self::Dyn(v) => v.hash(state),
-        }
+        maybe_grow(|| {
+            std::mem::discriminant(self).hash(state);
+            match self {
+                Self::None => {}
+                Self::Auto => {}
+                Self::Bool(v) => v.hash(state),
+                Self::Int(v) => v.hash(state),
+                Self::Float(v) => v.to_bits().hash(state),
+                Self::Length(v) => v.hash(state),
+                Self::Angle(v) => v.hash(state),
+                Self::Ratio(v) => v.hash(state),
+                Self::Relative(v) => v.hash(state),
+                Self::Fraction(v) => v.hash(state),
+                Self::Color(v) => v.hash(state),
+                Self::Gradient(v) => v.hash(state),
+                Self::Pattern(v) => v.hash(state),
+                Self::Symbol(v) => v.hash(state),
+                Self::Version(v) => v.hash(state),
+                Self::Str(v) => v.hash(state),
+                Self::Bytes(v) => v.hash(state),
+                Self::Label(v) => v.hash(state),
+                Self::Content(v) => v.hash(state),
+                Self::Styles(v) => v.hash(state),
+                Self::Datetime(v) => v.hash(state),
+                Self::Duration(v) => v.hash(state),
+                Self::Array(v) => v.hash(state),
+                Self::Dict(v) => v.hash(state),
+                Self::Func(v) => v.hash(state),
+                Self::Args(v) => v.hash(state),
+                Self::Type(v) => v.hash(state),
+                Self::Module(v) => v.hash(state),
+                Self::Plugin(v) => v.hash(state),
+                Self::Dyn(v) => v.hash(state),
+            }
+        })
     }
 }
 
@@ -355,21 +429,23 @@ impl Serialize for Value {
     where
         S: Serializer,
     {
-        match self {
-            Self::None => NoneValue.serialize(serializer),
-            Self::Bool(v) => v.serialize(serializer),
-            Self::Int(v) => v.serialize(serializer),
-            Self::Float(v) => v.serialize(serializer),
-            Self::Str(v) => v.serialize(serializer),
-            Self::Bytes(v) => v.serialize(serializer),
-            Self::Symbol(v) => v.serialize(serializer),
-            Self::Content(v) => v.serialize(serializer),
-            Self::Array(v) => v.serialize(serializer),
-            Self::Dict(v) => v.serialize(serializer),
-
-            // Fall back to repr() for other things.
-            other => serializer.serialize_str(&other.repr()),
-        }
+        maybe_grow(|| {
+            match self {
+                Self::None => NoneValue.serialize(serializer),
+                Self::Bool(v) => v.serialize(serializer),
+                Self::Int(v) => v.serialize(serializer),
+                Self::Float(v) => v.serialize(serializer),
+                Self::Str(v) => v.serialize(serializer),
+                Self::Bytes(v) => v.serialize(serializer),
+                Self::Symbol(v) => v.serialize(serializer),
+                Self::Content(v) => v.serialize(serializer),
+                Self::Array(v) => v.serialize(serializer),
+                Self::Dict(v) => v.serialize(serializer),
+
+                // Fall back to repr() for other things.
+                other => serializer.serialize_str(&other.repr()),
+            }
+        })
     }
 }
 
@@ -383,7 +459,7 @@ impl<'de> Deserialize<'de> for Value {
 }
 
 /// Visitor for value deserialization.
-struct ValueVisitor;
+pub(crate) struct ValueVisitor;
 
 impl<'de> Visitor<'de> for ValueVisitor {
     type Value = Value;
@@ -501,7 +577,7 @@ impl Dynamic {
     /// Create a new instance from any value that satisfies the required bounds.
     pub fn new<T>(any: T) -> Self
     where
-        T: Debug + Repr + NativeType + PartialEq + Hash + Sync + Send + 'static,
+        T: Debug + DynamicRepr + NativeType + PartialEq + Hash + Sync + Send + 'static,
     {
         Self(Arc::new(any))
     }
@@ -516,10 +592,45 @@ impl Dynamic {
         (*self.0).as_any().downcast_ref()
     }
 
+    /// Try to downcast to a mutable reference to a specific type.
+    ///
+    /// If the value is shared, it is cloned first so that other holders are
+    /// not affected.
+    pub fn downcast_mut<T>(&mut self) -> Option<&mut T>
+    where
+        T: Debug
+            + DynamicRepr
+            + NativeType
+            + PartialEq
+            + Hash
+            + Clone
+            + Sync
+            + Send
+            + 'static,
+    {
+        if Arc::get_mut(&mut self.0).is_none() {
+            *self = Self::new(self.downcast::<T>()?.clone());
+        }
+        Arc::get_mut(&mut self.0)?.as_any_mut().downcast_mut()
+    }
+
     /// The name of the stored value's type.
     pub fn ty(&self) -> Type {
         self.0.dyn_ty()
     }
+
+    /// Convert the value to a string, as done by the `str` constructor.
+    pub fn to_str(&self) -> StrResult<Str> {
+        match self.0.to_str() {
+            Some(string) => Ok(string),
+            None => bail!("cannot convert {} to string", self.ty()),
+        }
+    }
+
+    /// Return the display representation of the value.
+    pub fn display(&self) -> Content {
+        self.0.display()
+    }
 }
 
 impl Debug for Dynamic {
@@ -540,8 +651,48 @@ impl PartialEq for Dynamic {
     }
 }
 
-trait Bounds: Debug + Repr + Sync + Send + 'static {
+/// Defines how a [dynamic](Dynamic) value is turned into a string and into
+/// content.
+///
+/// The `repr` function always uses the [`Repr`] implementation. The `str`
+/// constructor uses [`to_str`](Self::to_str) and fails with a "cannot convert
+/// .. to string" error naming the type if it returns `None`. When inserted
+/// into markup, a value is shown as its [`display`](Self::display) content,
+/// which defaults to the syntax-highlighted `repr`.
+///
+/// The built-in dynamic types convert as follows:
+///
+/// | Type           | `str`       | Display |
+/// |----------------|-------------|---------|
+/// | `alignment`    | like `repr` | `repr`  |
+/// | `direction`    | like `repr` | `repr`  |
+/// | `regex`        | the pattern | `repr`  |
+/// | `location`     | unsupported | `repr`  |
+/// | `counter`      | unsupported | `repr`  |
+/// | `state`        | unsupported | `repr`  |
+/// | `selector`     | unsupported | `repr`  |
+/// | `stroke`       | unsupported | `repr`  |
+/// | `builder`      | unsupported | `repr`  |
+/// | `unique-slugs` | unsupported | `repr`  |
+/// | `csl-style`    | unsupported | `repr`  |
+pub trait DynamicRepr: Repr {
+    /// Convert the value to a string, if that is supported.
+    fn to_str(&self) -> Option<Str> {
+        None
+    }
+
+    /// Return the content that represents the value in markup.
+    fn display(&self) -> Content {
+        RawElem::new(RawContent::Text(self.repr()))
+            .with_lang(Some("typc".into()))
+            .with_block(false)
+            .pack()
+    }
+}
+
+trait Bounds: Debug + DynamicRepr + Sync + Send + 'static {
     fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
     fn dyn_eq(&self, other: &Dynamic) -> bool;
     fn dyn_ty(&self) -> Type;
     fn dyn_hash(&self, state: &mut dyn Hasher);
@@ -549,12 +700,16 @@ trait Bounds: Debug + Repr + Sync + Send + 'static {
 
 impl<T> Bounds for T
 where
-    T: Debug + Repr + NativeType + PartialEq + Hash + Sync + Send + 'static,
+    T: Debug + DynamicRepr + NativeType + PartialEq + Hash + Sync + Send + 'static,
 {
     fn as_any(&self) -> &dyn Any {
         self
     }
 
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
     fn dyn_eq(&self, other: &Dynamic) -> bool {
         let Some(other) = other.downcast::<Self>() else { return false };
         self == other
@@ -748,4 +903,114 @@ mod tests {
         test(dict!["one" => 1], "(one: 1)");
         test(dict!["two" => false, "one" => 1], "(two: false, one: 1)");
     }
+
+    /// Advance a pseudo-random generator and return a number below `n`.
+    fn next(seed: &mut u64, n: u64) -> u64 {
+        *seed = seed
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (*seed >> 33) % n
+    }
+
+    /// Generate a pseudo-random value of plain data.
+    fn random_value(seed: &mut u64, depth: usize) -> Value {
+        const FLOATS: [f64; 8] =
+            [0.0, -0.0, 1.5, 1e21, 1.5e-7, -2.25, f64::NAN, f64::NEG_INFINITY];
+        const PIECES: [&str; 8] = ["a", "\"", "\\", "\n", "\t", "\u{1}", "ü", "🏳️‍🌈"];
+        const KEYS: [&str; 5] = ["k0", "k1", "b c", "ä", "k2"];
+
+        match next(seed, if depth > 0 { 9 } else { 7 }) {
+            0 => Value::None,
+            1 => Value::Auto,
+            2 => Value::Bool(next(seed, 2) == 0),
+            3 => Value::Int(next(seed, 2001) as i64 - 1000),
+            4 => Value::Float(FLOATS[next(seed, 8) as usize]),
+            5 => {
+                let len = next(seed, 5);
+                let pieces = (0..len).map(|_| PIECES[next(seed, 8) as usize]);
+                Value::Str(pieces.collect::<String>().into())
+            }
+            6 => Value::Label(Label::new(format!("l{}", next(seed, 3)).as_str())),
+            7 => {
+                let len = next(seed, 4);
+                Value::Array((0..len).map(|_| random_value(seed, depth - 1)).collect())
+            }
+            _ => {
+                let len = next(seed, 4);
+                let mut dict = Dict::new();
+                for _ in 0..len {
+                    let key = KEYS[next(seed, 5) as usize];
+                    dict.insert(key.into(), random_value(seed, depth - 1));
+                }
+                Value::Dict(dict)
+            }
+        }
+    }
+
+    /// Rebuild the dictionaries in a value with different internal layouts,
+    /// but the same insertion order.
+    fn rebuild(value: &Value) -> Value {
+        match value {
+            Value::Array(array) => Value::Array(array.iter().map(rebuild).collect()),
+            Value::Dict(dict) => {
+                let mut rebuilt = Dict::new();
+                for i in 0..20 {
+                    rebuilt.insert(eco_format!("junk{i}").into(), Value::None);
+                }
+                for (key, value) in dict.iter() {
+                    rebuilt.insert(key.clone(), rebuild(value));
+                }
+                for i in 0..20 {
+                    rebuilt.take(&eco_format!("junk{i}")).unwrap();
+                }
+                Value::Dict(rebuilt)
+            }
+            other => other.clone(),
+        }
+    }
+
+    #[test]
+    fn test_value_canonical_string_deterministic() {
+        let mut seed = 42;
+        for _ in 0..500 {
+            let value = random_value(&mut seed, 3);
+            assert_eq!(rebuild(&value).canonical_string(), value.canonical_string());
+        }
+    }
+
+    #[test]
+    fn test_value_to_json_round_trip() {
+        let value = array![
+            dict!["name" => "a", "tags" => array![1, 2.5, true]],
+            dict!["name" => Value::None, "nested" => dict!["deep" => array![]]],
+        ]
+        .into_value();
+        let json = value.to_json(8);
+        assert_eq!(
+            json,
+            serde_json::json!([
+                { "name": "a", "tags": [1, 2.5, true] },
+                { "name": null, "nested": { "deep": [] } },
+            ])
+        );
+        assert_eq!(serde_json::from_value::<Value>(json).unwrap(), value);
+    }
+
+    #[test]
+    fn test_value_to_json_opaque() {
+        let content = TextElem::packed("hi").into_value();
+        let json = content.to_json(8);
+        assert_eq!(
+            json,
+            serde_json::json!({ "type": "content", "repr": content.repr().as_str() })
+        );
+
+        let json = array![f64::NAN, Abs::pt(2.0)].into_value().to_json(8);
+        assert_eq!(json[0]["type"], "float");
+        assert_eq!(json[1], serde_json::json!({ "type": "length", "repr": "2pt" }));
+
+        // Collections beyond the depth cap are represented by their repr.
+        let json = array![array![array![1]]].into_value().to_json(1);
+        assert_eq!(json, serde_json::json!([{ "type": "array", "repr": "((1,),)" }]));
+    }
 }
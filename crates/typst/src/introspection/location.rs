@@ -4,7 +4,7 @@ use std::num::NonZeroUsize;
 use ecow::EcoString;
 
 use crate::engine::Engine;
-use crate::foundations::{func, scope, ty, Repr};
+use crate::foundations::{func, scope, ty, DynamicRepr, Repr};
 use crate::layout::Position;
 use crate::model::Numbering;
 
@@ -105,5 +105,7 @@ impl Repr for Location {
     }
 }
 
+impl DynamicRepr for Location {}
+
 /// Makes this element locatable through `engine.locate`.
 pub trait Locatable {}
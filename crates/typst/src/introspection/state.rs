@@ -4,9 +4,9 @@ use ecow::{eco_format, eco_vec, EcoString, EcoVec};
 use crate::diag::{bail, At, SourceResult};
 use crate::engine::{Engine, Route, Sink, Traced};
 use crate::foundations::{
-    cast, elem, func, scope, select_where, ty, Args, Construct, Content, Context, Func,
-    LocatableSelector, NativeElement, Packed, Repr, Selector, Show, Str, StyleChain,
-    Value,
+    cast, elem, func, scope, select_where, ty, Args, Construct, Content, Context,
+    DynamicRepr, Func, LocatableSelector, NativeElement, Packed, Repr, Selector, Show,
+    Str, StyleChain, Value,
 };
 use crate::introspection::{Introspector, Locatable, Location};
 use crate::syntax::Span;
@@ -383,6 +383,8 @@ impl Repr for State {
     }
 }
 
+impl DynamicRepr for State {}
+
 /// An update to perform on a state.
 #[derive(Debug, Clone, PartialEq, Hash)]
 pub enum StateUpdate {
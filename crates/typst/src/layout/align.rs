@@ -5,8 +5,8 @@ use ecow::{eco_format, EcoString};
 use crate::diag::{bail, HintedStrResult, SourceResult, StrResult};
 use crate::engine::Engine;
 use crate::foundations::{
-    cast, elem, func, scope, ty, CastInfo, Content, Fold, FromValue, IntoValue, Packed,
-    Reflect, Repr, Resolve, Show, StyleChain, Value,
+    cast, elem, func, scope, ty, CastInfo, Content, DynamicRepr, Fold, FromValue,
+    IntoValue, Packed, Reflect, Repr, Resolve, Show, Str, StyleChain, Value,
 };
 use crate::layout::{Abs, Axes, Axis, Dir, Side};
 use crate::text::TextElem;
@@ -212,6 +212,12 @@ impl Repr for Alignment {
     }
 }
 
+impl DynamicRepr for Alignment {
+    fn to_str(&self) -> Option<Str> {
+        Some(self.repr().into())
+    }
+}
+
 impl Fold for Alignment {
     fn fold(self, outer: Self) -> Self {
         match (self, outer) {
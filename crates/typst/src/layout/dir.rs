@@ -1,6 +1,6 @@
 use ecow::EcoString;
 
-use crate::foundations::{func, scope, ty, Repr};
+use crate::foundations::{func, scope, ty, DynamicRepr, Repr, Str};
 use crate::layout::{Axis, Side};
 
 /// The four directions into which content can be laid out.
@@ -130,3 +130,9 @@ impl Repr for Dir {
         }
     }
 }
+
+impl DynamicRepr for Dir {
+    fn to_str(&self) -> Option<Str> {
+        Some(self.repr().into())
+    }
+}
@@ -0,0 +1,50 @@
+use crate::diag::SourceResult;
+use crate::engine::Engine;
+use crate::foundations::{elem, Content, Packed, Show, StyleChain};
+
+/// Groups content and attaches layout hints to it.
+///
+/// A group is shown exactly like its children. It carries hints that tell the
+/// layout how the children should be kept together. This way, templates and
+/// show rules can make such decisions while constructing content, for instance
+/// to keep a heading with the paragraph that follows it. The hints are
+/// available as fields, but are not yet taken into account during layout.
+///
+/// Instead of constructing a group for two pieces of content directly, you can
+/// also use the [`keep-with`]($content.keep-with) method.
+///
+/// # Example
+/// ```example
+/// #show heading: it => it.keep-with[
+///   The paragraph that belongs to
+///   the heading.
+/// ]
+///
+/// #group(keep: true)[
+///   Two paragraphs
+/// ][
+///   that belong together.
+/// ]
+/// ```
+#[elem(Show)]
+pub struct GroupElem {
+    /// Whether the children should be kept together, for instance on the same
+    /// page.
+    #[default(false)]
+    pub keep: bool,
+
+    /// Whether the group may be broken across pages.
+    #[default(true)]
+    pub breakable: bool,
+
+    /// The grouped content.
+    #[variadic]
+    pub children: Vec<Content>,
+}
+
+impl Show for Packed<GroupElem> {
+    #[typst_macros::time(name = "group", span = self.span())]
+    fn show(&self, _: &mut Engine, _: StyleChain) -> SourceResult<Content> {
+        Ok(Content::sequence(self.children().iter().cloned()))
+    }
+}
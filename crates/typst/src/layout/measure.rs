@@ -43,7 +43,7 @@ use crate::syntax::Span;
 ///
 /// The measure function returns a dictionary with the entries `width` and
 /// `height`, both of type [`length`].
-#[func(contextual)]
+#[func(contextual, keys = ["width", "height"])]
 pub fn measure(
     /// The engine.
     engine: &mut Engine,
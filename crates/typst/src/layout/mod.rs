@@ -14,6 +14,7 @@ mod fr;
 mod fragment;
 mod frame;
 mod grid;
+mod group;
 mod hide;
 mod inline;
 #[path = "layout.rs"]
@@ -49,6 +50,7 @@ pub use self::fr::*;
 pub use self::fragment::*;
 pub use self::frame::*;
 pub use self::grid::*;
+pub use self::group::*;
 pub use self::hide::*;
 pub use self::layout_::*;
 pub use self::length::*;
@@ -71,7 +73,7 @@ pub(crate) use self::inline::*;
 
 use comemo::{Track, Tracked, TrackedMut};
 
-use crate::diag::{bail, SourceResult};
+use crate::diag::{bail, error, SourceResult};
 use crate::engine::{Engine, Route, Sink, Traced};
 use crate::foundations::{category, Category, Content, Scope, StyleChain};
 use crate::introspection::{Introspector, Locator, LocatorLink};
@@ -114,6 +116,7 @@ pub fn define(global: &mut Scope) {
     global.define_elem::<ScaleElem>();
     global.define_elem::<RotateElem>();
     global.define_elem::<HideElem>();
+    global.define_elem::<GroupElem>();
     global.define_func::<measure>();
     global.define_func::<layout>();
 }
@@ -198,10 +201,11 @@ impl Content {
             };
 
             if !engine.route.within(Route::MAX_LAYOUT_DEPTH) {
-                bail!(
+                bail!(error!(
                     content.span(), "maximum layout depth exceeded";
                     hint: "try to reduce the amount of nesting in your layout",
-                );
+                )
+                .uncatchable());
             }
 
             // If we are in a `PageElem`, this might already be a realized flow.
@@ -56,20 +56,25 @@ pub use typst_syntax as syntax;
 #[doc(inline)]
 pub use typst_utils as utils;
 
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 use std::ops::{Deref, Range};
 
 use comemo::{Track, Tracked, Validate};
-use ecow::{EcoString, EcoVec};
+use ecow::{eco_format, EcoString, EcoVec};
 use typst_timing::{timed, TimingScope};
 
 use crate::diag::{warning, FileResult, SourceDiagnostic, SourceResult, Warned};
-use crate::engine::{Engine, Route, Sink, Traced};
+use crate::engine::{
+    Analysis, Budget, Engine, ImportProfile, Route, Sink, StructuredValue, Traced,
+    TracedCall,
+};
 use crate::foundations::{
-    Array, Bytes, Datetime, Dict, Module, Scope, StyleChain, Styles, Value,
+    Array, Bytes, Datetime, Dict, Func, IntoValue, Module, Scope, StyleChain, Styles,
+    Value,
 };
 use crate::introspection::Introspector;
 use crate::layout::{Alignment, Dir};
+use crate::loading::DirEntry;
 use crate::model::Document;
 use crate::syntax::package::PackageSpec;
 use crate::syntax::{FileId, Source, Span};
@@ -83,12 +88,80 @@ use crate::visualize::Color;
 /// - Returns `Err(errors)` if there were fatal errors.
 #[typst_macros::time]
 pub fn compile(world: &dyn World) -> Warned<SourceResult<Document>> {
-    let mut sink = Sink::new();
+    compile_with_budget(world, Budget::unlimited())
+}
+
+/// Compile sources into a fully layouted document, failing once evaluation
+/// has used up the given budget.
+///
+/// This is useful for compiling untrusted documents.
+pub fn compile_with_budget(
+    world: &dyn World,
+    budget: Budget,
+) -> Warned<SourceResult<Document>> {
+    let mut sink = Sink::with_budget(budget);
     let output = compile_inner(world.track(), Traced::default().track(), &mut sink)
         .map_err(deduplicate);
     Warned { output, warnings: sink.warnings() }
 }
 
+/// Compile sources into a fully layouted document and profile the imports
+/// performed during compilation.
+///
+/// The imports are returned as a tree following the import hierarchy. Imports
+/// that were taken from the cache, be it of an earlier compilation or of an
+/// earlier import of the same file, are marked as such, so that only the files
+/// that were actually evaluated show up with a duration.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn compile_with_import_profile(
+    world: &dyn World,
+) -> (Warned<SourceResult<Document>>, Vec<ImportProfile>) {
+    let mut sink = Sink::new().with_profiling();
+    let output = compile_inner(world.track(), Traced::default().track(), &mut sink)
+        .map_err(deduplicate);
+    let imports = sink.imports();
+    (Warned { output, warnings: sink.warnings() }, imports)
+}
+
+/// Check sources for errors in a dry run, without laying them out.
+///
+/// The main source file is evaluated as usual, so all function calls are
+/// checked for valid arguments and all loaded files must exist. Data files are
+/// parsed fully, as their contents feed into evaluation. The resulting content
+/// is discarded and work that would only serve it is skipped: Images are only
+/// checked for a known format and a valid header and bibliographies for a
+/// known format. This is much faster than a full compilation, but some errors
+/// can't be caught:
+///
+/// - Errors in show rules, `context` expressions, and other code that only runs
+///   during layout.
+/// - Raster images with corrupt pixel data and SVG images that can't be parsed.
+/// - Malformed bibliography files and citations of unknown keys.
+///
+/// - Returns `Ok(())` if there were no fatal errors.
+/// - Returns `Err(errors)` if there were fatal errors.
+#[typst_macros::time]
+pub fn check(world: &dyn World) -> Warned<SourceResult<()>> {
+    let mut sink = Sink::new().with_dry_run();
+    let output = crate::eval::eval(
+        world.track(),
+        Traced::default().track(),
+        sink.track_mut(),
+        Route::default().track(),
+        &world.main(),
+    )
+    .and_then(|_| {
+        let delayed = sink.delayed();
+        if delayed.is_empty() {
+            Ok(())
+        } else {
+            Err(delayed)
+        }
+    })
+    .map_err(deduplicate);
+    Warned { output, warnings: sink.warnings() }
+}
+
 /// Compiles sources and returns all values and styles observed at the given
 /// `span` during compilation.
 #[typst_macros::time]
@@ -99,6 +172,50 @@ pub fn trace(world: &dyn World, span: Span) -> EcoVec<(Value, Option<Styles>)> {
     sink.values()
 }
 
+/// Compiles sources and returns all values observed at the given `span` during
+/// compilation in a serializable form.
+#[typst_macros::time]
+pub fn trace_structured(world: &dyn World, span: Span) -> Vec<StructuredValue> {
+    let mut sink = Sink::new();
+    let traced = Traced::new(span);
+    compile_inner(world.track(), traced.track(), &mut sink).ok();
+    sink.values_structured()
+}
+
+/// Compiles sources and returns the function calls at the given `span` during
+/// compilation, along with the arguments they received.
+///
+/// Calls are recorded even if they fail, so that the arguments that led to an
+/// error can be inspected.
+#[typst_macros::time]
+pub fn trace_calls(world: &dyn World, span: Span) -> EcoVec<TracedCall> {
+    let mut sink = Sink::new();
+    let traced = Traced::new(span);
+    compile_inner(world.track(), traced.track(), &mut sink).ok();
+    sink.calls()
+}
+
+/// Compiles sources and returns the definitions and uses of variables observed
+/// in the file with the given `id` during compilation.
+#[typst_macros::time]
+pub fn analyze(world: &dyn World, id: FileId) -> Analysis {
+    let mut sink = Sink::new();
+    let traced = Traced::analyzing(id);
+    compile_inner(world.track(), traced.track(), &mut sink).ok();
+    sink.analysis()
+}
+
+/// Compiles sources and returns the packages that were imported during
+/// compilation, including those only imported by other packages.
+///
+/// The version of each package is the one declared by its manifest.
+#[typst_macros::time]
+pub fn packages(world: &dyn World) -> EcoVec<PackageSpec> {
+    let mut sink = Sink::new();
+    compile_inner(world.track(), Traced::default().track(), &mut sink).ok();
+    sink.packages()
+}
+
 /// Relayout until introspection converges.
 fn compile_inner(
     world: Tracked<dyn World + '_>,
@@ -107,17 +224,28 @@ fn compile_inner(
 ) -> SourceResult<Document> {
     let library = world.library();
     let styles = StyleChain::new(&library.styles);
+    let main = world.main();
+
+    // If enabled, evaluate the imported files in parallel up front, so that
+    // the imports made by the main file hit the cache.
+    if library.prefetch {
+        crate::eval::eval_prefetch(world, traced, &main);
+    }
 
-    // First evaluate the main source file into a module.
+    // Then evaluate the main source file into a module.
     let content = crate::eval::eval(
         world,
         traced,
         sink.track_mut(),
         Route::default().track(),
-        &world.main(),
+        &main,
     )?
     .content();
 
+    // Keep the errors delayed during evaluation since the delayed errors of
+    // each layout iteration are cleared below.
+    let mut delayed = sink.delayed();
+
     let mut iter = 0;
     let mut document = Document::default();
 
@@ -160,7 +288,7 @@ fn compile_inner(
     }
 
     // Promote delayed errors.
-    let delayed = sink.delayed();
+    delayed.extend(sink.delayed());
     if !delayed.is_empty() {
         return Err(delayed);
     }
@@ -233,6 +361,37 @@ pub trait World: Send + Sync {
     fn packages(&self) -> &[(PackageSpec, Option<EcoString>)] {
         &[]
     }
+
+    /// List the entries of the specified directory.
+    ///
+    /// This function is optional to implement. If it returns `None`, Typst's
+    /// `files` function will return an error. The order of the entries does
+    /// not matter.
+    fn list(&self, id: FileId) -> Option<FileResult<Vec<DirEntry>>> {
+        let _ = id;
+        None
+    }
+
+    /// Read data that an earlier compilation persisted under the given key
+    /// with [`cache_write`](Self::cache_write).
+    ///
+    /// This function is optional to implement. If it returns `None`, Typst
+    /// computes the data from scratch. Currently, only parsed and validated
+    /// package manifests are persisted. Corrupted or outdated data is
+    /// detected and ignored.
+    fn cache_read(&self, key: &str) -> Option<Bytes> {
+        let _ = key;
+        None
+    }
+
+    /// Persist data under the given key for later compilations.
+    ///
+    /// This function is optional to implement. By default, nothing is
+    /// persisted. Keys are valid file names. Since persisting data is only an
+    /// optimization, failures should be ignored.
+    fn cache_write(&self, key: &str, data: Bytes) {
+        let _ = (key, data);
+    }
 }
 
 macro_rules! delegate_for_ptr {
@@ -269,6 +428,18 @@ macro_rules! delegate_for_ptr {
             fn packages(&self) -> &[(PackageSpec, Option<EcoString>)] {
                 self.deref().packages()
             }
+
+            fn list(&self, id: FileId) -> Option<FileResult<Vec<DirEntry>>> {
+                self.deref().list(id)
+            }
+
+            fn cache_read(&self, key: &str) -> Option<Bytes> {
+                self.deref().cache_read(key)
+            }
+
+            fn cache_write(&self, key: &str, data: Bytes) {
+                self.deref().cache_write(key, data)
+            }
         }
     };
 }
@@ -304,6 +475,25 @@ pub struct Library {
     /// The standard library as a value.
     /// Used to provide the `std` variable.
     pub std: Value,
+    /// Whether strict mode is enabled. In strict mode, some warnings are
+    /// promoted to errors.
+    pub strict: bool,
+    /// Whether pedantic lints are enabled. They warn about code that is valid,
+    /// but likely a mistake, and are off by default.
+    pub pedantic: bool,
+    /// Whether the top-level markup of a file is evaluated in separately
+    /// cached chunks. This only affects performance, the resulting module is
+    /// the same either way. Disabled by default.
+    pub chunked: bool,
+    /// Whether the files imported by the main file are evaluated in parallel
+    /// before compilation. This only affects performance, the output is the
+    /// same either way. Disabled by default.
+    pub prefetch: bool,
+    /// The feature groups whose definitions are part of the library.
+    pub features: Features,
+    /// The names defined by feature groups that are left out of the library,
+    /// so that using them can report the missing feature.
+    pub unavailable: BTreeMap<EcoString, Feature>,
 }
 
 impl Library {
@@ -326,6 +516,12 @@ impl Default for Library {
 #[derive(Debug, Clone, Default)]
 pub struct LibraryBuilder {
     inputs: Option<Dict>,
+    strict: bool,
+    pedantic: bool,
+    chunked: bool,
+    prefetch: bool,
+    features: Features,
+    definitions: Vec<(EcoString, Value)>,
 }
 
 impl LibraryBuilder {
@@ -335,36 +531,230 @@ impl LibraryBuilder {
         self
     }
 
+    /// Configure whether strict mode is enabled.
+    pub fn with_strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Configure whether pedantic lints are enabled.
+    pub fn with_pedantic(mut self, pedantic: bool) -> Self {
+        self.pedantic = pedantic;
+        self
+    }
+
+    /// Configure whether top-level markup is evaluated in cached chunks.
+    ///
+    /// This is disabled by default.
+    pub fn with_chunked(mut self, chunked: bool) -> Self {
+        self.chunked = chunked;
+        self
+    }
+
+    /// Configure whether the files imported by the main file are evaluated in
+    /// parallel before compilation.
+    ///
+    /// This is disabled by default.
+    pub fn with_prefetch(mut self, prefetch: bool) -> Self {
+        self.prefetch = prefetch;
+        self
+    }
+
+    /// Configure whether the definitions of a feature group are included.
+    ///
+    /// All features are included by default. Using a definition or syntax of
+    /// a left out feature fails with an error.
+    pub fn with_feature(mut self, feature: Feature, enabled: bool) -> Self {
+        self.features = self.features.with(feature, enabled);
+        self
+    }
+
+    /// Define an additional function in the global scope.
+    ///
+    /// Native functions defined with the `#[func]` attribute can be turned
+    /// into a [`Func`] with [`NativeFunc::func`](foundations::NativeFunc::func). See
+    /// [`with_module`](Self::with_module) for how name collisions are handled.
+    pub fn with_function(mut self, name: impl Into<EcoString>, func: Func) -> Self {
+        self.definitions.push((name.into(), func.into_value()));
+        self
+    }
+
+    /// Define an additional module in the global scope.
+    ///
+    /// Additional definitions are added after the standard library's own
+    /// ones. Redefining an existing global panics in debug builds. In release
+    /// builds, the new definition replaces the existing one and a warning is
+    /// logged.
+    pub fn with_module(mut self, name: impl Into<EcoString>, module: Module) -> Self {
+        self.definitions.push((name.into(), module.into_value()));
+        self
+    }
+
     /// Consumes the builder and returns a `Library`.
     pub fn build(self) -> Library {
-        let math = math::module();
+        let features = self.features;
+        let math = if features.contains(Feature::Math) {
+            math::module()
+        } else {
+            Module::new("math", Scope::new())
+        };
         let inputs = self.inputs.unwrap_or_default();
-        let global = global(math.clone(), inputs);
+        let mut unavailable = BTreeMap::new();
+        let mut global = global(math.clone(), inputs, features, &mut unavailable);
+        let scope = global.scope_mut();
+        for (name, value) in self.definitions {
+            if scope.get(&name).is_some() {
+                log::warn!("replacing existing global definition: {name}");
+            }
+            scope.define(name, value);
+        }
         let std = Value::Module(global.clone());
-        Library { global, math, styles: Styles::new(), std }
+        Library {
+            global,
+            math,
+            styles: Styles::new(),
+            std,
+            strict: self.strict,
+            pedantic: self.pedantic,
+            chunked: self.chunked,
+            prefetch: self.prefetch,
+            features,
+            unavailable,
+        }
+    }
+}
+
+/// A group of definitions in the standard library that can be left out.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Feature {
+    /// Types, `calc`, `sys`, symbols, and other foundational definitions.
+    Core,
+    /// Elements for text, document structure, layout, and introspection.
+    Content,
+    /// Equations and the definitions of math mode.
+    Math,
+    /// Functions that load data from files.
+    Data,
+    /// Shapes, images, and colors.
+    Visualize,
+}
+
+impl Feature {
+    /// All feature groups.
+    pub const ALL: [Self; 5] =
+        [Self::Core, Self::Content, Self::Math, Self::Data, Self::Visualize];
+
+    /// The name of the feature in error messages.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Core => "core",
+            Self::Content => "content",
+            Self::Math => "math",
+            Self::Data => "data loading",
+            Self::Visualize => "visualization",
+        }
+    }
+
+    /// The error message for when the feature is used, but left out of the
+    /// library.
+    pub fn unavailable(self) -> EcoString {
+        eco_format!(
+            "the {} feature is not available in this build/configuration",
+            self.name()
+        )
+    }
+
+    /// The bit of the feature in [`Features`].
+    fn bit(self) -> u8 {
+        1 << self as u8
+    }
+}
+
+/// A set of [features](Feature).
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Features(u8);
+
+impl Features {
+    /// The set of all features.
+    pub fn all() -> Self {
+        Feature::ALL
+            .into_iter()
+            .fold(Self::none(), |set, f| set.with(f, true))
+    }
+
+    /// The empty set.
+    pub fn none() -> Self {
+        Self(0)
+    }
+
+    /// Whether the feature is in the set.
+    pub fn contains(self, feature: Feature) -> bool {
+        self.0 & feature.bit() != 0
+    }
+
+    /// Add or remove a feature.
+    pub fn with(self, feature: Feature, enabled: bool) -> Self {
+        if enabled {
+            Self(self.0 | feature.bit())
+        } else {
+            Self(self.0 & !feature.bit())
+        }
+    }
+}
+
+impl Default for Features {
+    fn default() -> Self {
+        Self::all()
     }
 }
 
 /// Construct the module with global definitions.
-fn global(math: Module, inputs: Dict) -> Module {
+///
+/// The names defined by left out feature groups are recorded in
+/// `unavailable`.
+fn global(
+    math: Module,
+    mut inputs: Dict,
+    features: Features,
+    unavailable: &mut BTreeMap<EcoString, Feature>,
+) -> Module {
     let mut global = Scope::deduplicating();
-    self::foundations::define(&mut global, inputs);
-    self::model::define(&mut global);
-    self::text::define(&mut global);
-    global.reset_category();
-    global.define_module(math);
-    self::layout::define(&mut global);
-    self::visualize::define(&mut global);
-    self::introspection::define(&mut global);
-    self::loading::define(&mut global);
-    self::symbols::define(&mut global);
-    prelude(&mut global);
+    let mut group = |feature: Feature, define: &mut dyn FnMut(&mut Scope)| {
+        // Start without a category so that definitions don't inherit the one
+        // of a left out group.
+        global.reset_category();
+        if features.contains(feature) {
+            define(&mut global);
+        } else {
+            let mut scope = Scope::new();
+            define(&mut scope);
+            for (name, _) in scope.iter() {
+                unavailable.insert(name.clone(), feature);
+            }
+        }
+    };
+
+    group(Feature::Core, &mut |scope| {
+        self::foundations::define(scope, std::mem::take(&mut inputs))
+    });
+    group(Feature::Content, &mut |scope| {
+        self::model::define(scope);
+        self::text::define(scope);
+    });
+    group(Feature::Math, &mut |scope| scope.define_module(math.clone()));
+    group(Feature::Content, &mut self::layout::define);
+    group(Feature::Visualize, &mut self::visualize::define);
+    group(Feature::Content, &mut self::introspection::define);
+    group(Feature::Data, &mut self::loading::define);
+    group(Feature::Core, &mut self::symbols::define);
+    group(Feature::Visualize, &mut prelude_colors);
+    group(Feature::Core, &mut |scope| scope.define("range", Array::range_data()));
+    group(Feature::Content, &mut prelude_layout);
     Module::new("global", global)
 }
 
-/// Defines scoped values that are globally available, too.
-fn prelude(global: &mut Scope) {
-    global.reset_category();
+/// Defines scoped colors that are globally available, too.
+fn prelude_colors(global: &mut Scope) {
     global.define("black", Color::BLACK);
     global.define("gray", Color::GRAY);
     global.define("silver", Color::SILVER);
@@ -388,7 +778,10 @@ fn prelude(global: &mut Scope) {
     global.define("oklch", Color::oklch_data());
     global.define("rgb", Color::rgb_data());
     global.define("cmyk", Color::cmyk_data());
-    global.define("range", Array::range_data());
+}
+
+/// Defines scoped directions and alignments that are globally available, too.
+fn prelude_layout(global: &mut Scope) {
     global.define("ltr", Dir::LTR);
     global.define("rtl", Dir::RTL);
     global.define("ttb", Dir::TTB);
@@ -402,3 +795,49 @@ fn prelude(global: &mut Scope) {
     global.define("horizon", Alignment::HORIZON);
     global.define("bottom", Alignment::BOTTOM);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::foundations::NativeFunc;
+
+    /// The names in the global scope of a library.
+    fn global_names(library: &Library) -> Vec<EcoString> {
+        library.global.scope().iter().map(|(name, _)| name.clone()).collect()
+    }
+
+    #[test]
+    fn test_library_with_all_features() {
+        let builder = Feature::ALL
+            .into_iter()
+            .fold(Library::builder(), |builder, f| builder.with_feature(f, true));
+        let full = builder.build();
+        let default = Library::default();
+        assert_eq!(global_names(&full), global_names(&default));
+        for (name, value) in full.global.scope().iter() {
+            // Compare hashes since `calc.nan` isn't equal to itself.
+            let expected = default.global.scope().get(name).map(crate::utils::hash128);
+            assert_eq!(expected, Some(crate::utils::hash128(value)), "{name}");
+        }
+        assert_eq!(full.math.scope().iter().count(), default.math.scope().iter().count());
+        assert!(full.unavailable.is_empty());
+
+        // Without any feature, every global definition is known as unavailable.
+        let builder = Feature::ALL
+            .into_iter()
+            .fold(Library::builder(), |builder, f| builder.with_feature(f, false));
+        let empty = builder.build();
+        assert!(global_names(&empty).is_empty());
+        let mut names = global_names(&default);
+        names.sort();
+        assert!(empty.unavailable.keys().eq(names.iter()));
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "duplicate definition: text")]
+    fn test_library_with_function_collision() {
+        let func = crate::foundations::repr::repr::func();
+        Library::builder().with_function("text", func).build();
+    }
+}
@@ -1,10 +1,9 @@
 use ecow::{eco_format, EcoString};
 
-use crate::diag::{At, SourceResult};
+use crate::diag::{At, SourceResult, StrResult};
 use crate::engine::Engine;
 use crate::foundations::{func, scope, Bytes, Value};
 use crate::syntax::Spanned;
-use crate::World;
 
 /// Reads structured data from a CBOR file.
 ///
@@ -26,7 +25,7 @@ pub fn cbor(
 ) -> SourceResult<Value> {
     let Spanned { v: path, span } = path;
     let id = span.resolve_path(&path).at(span)?;
-    let data = engine.world.file(id).at(span)?;
+    let data = engine.file(id).at(span)?;
     cbor::decode(Spanned::new(data, span))
 }
 
@@ -39,9 +38,7 @@ impl cbor {
         data: Spanned<Bytes>,
     ) -> SourceResult<Value> {
         let Spanned { v: data, span } = data;
-        ciborium::from_reader(data.as_slice())
-            .map_err(|err| eco_format!("failed to parse CBOR ({err})"))
-            .at(span)
+        parse(&data).at(span)
     }
 
     /// Encode structured data into CBOR bytes.
@@ -51,6 +48,7 @@ impl cbor {
         value: Spanned<Value>,
     ) -> SourceResult<Bytes> {
         let Spanned { v: value, span } = value;
+        value.check_depth(Value::MAX_DEPTH).at(span)?;
         let mut res = Vec::new();
         ciborium::into_writer(&value, &mut res)
             .map(|_| res.into())
@@ -58,3 +56,10 @@ impl cbor {
             .at(span)
     }
 }
+
+/// Parse CBOR data into a value. Memoized on the bytes.
+#[comemo::memoize]
+fn parse(data: &Bytes) -> StrResult<Value> {
+    ciborium::from_reader(data.as_slice())
+        .map_err(|err| eco_format!("failed to parse CBOR ({err})"))
+}
@@ -0,0 +1,307 @@
+use std::fmt::{self, Display};
+
+use ecow::{eco_format, EcoString};
+use serde::de::{
+    DeserializeSeed, Deserializer, EnumAccess, Error, MapAccess, SeqAccess,
+    VariantAccess, Visitor,
+};
+
+use crate::diag::{HintedStrResult, StrResult};
+use crate::foundations::{
+    cast, Array, AutoValue, Cast, Datetime, Dict, IntoValue, NoneValue, Repr, Str, Value,
+    ValueVisitor,
+};
+use crate::syntax::is_ident;
+
+/// How the JSON and YAML loaders convert strings and numbers for which Typst
+/// has more specific types.
+#[derive(Debug, Default, Clone, PartialEq, Hash)]
+pub struct DataOptions {
+    /// Which strings to parse as dates and times.
+    pub dates: Dates,
+    /// How to represent integers that don't fit into 64 bits.
+    pub big_ints: BigInts,
+}
+
+impl DataOptions {
+    /// Deserialize a value while applying the options. The `format` names the
+    /// data format in error messages.
+    pub(crate) fn deserialize<'de, D>(
+        &self,
+        deserializer: D,
+        format: &str,
+    ) -> StrResult<Value>
+    where
+        D: Deserializer<'de>,
+        D::Error: Display,
+    {
+        let mut error = None;
+        let seed = Seed {
+            options: self,
+            path: &mut vec![],
+            error: &mut error,
+        };
+        let result = seed.deserialize(deserializer);
+
+        // A conversion that failed because of the options aborts
+        // deserialization, but has a more precise message than the
+        // deserializer's error.
+        if let Some(message) = error {
+            return Err(message);
+        }
+
+        result.map_err(|err| eco_format!("failed to parse {format} ({err})"))
+    }
+}
+
+/// Which strings a data loader parses as ISO 8601 dates and times.
+#[derive(Debug, Default, Clone, PartialEq, Hash)]
+pub enum Dates {
+    /// Strings are kept as they are.
+    #[default]
+    None,
+    /// All strings that are valid dates or times are parsed.
+    All,
+    /// Only strings stored under one of these keys, at any depth, are parsed.
+    Keys(Vec<Str>),
+}
+
+cast! {
+    Dates,
+    self => match self {
+        Self::None => Value::None,
+        Self::All => Value::Auto,
+        Self::Keys(keys) => keys.into_value(),
+    },
+    _: NoneValue => Self::None,
+    _: AutoValue => Self::All,
+    v: Array => Self::Keys(v.into_iter().map(Value::cast).collect::<HintedStrResult<_>>()?),
+}
+
+/// How a data loader represents integers that don't fit into 64 bits.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum BigInts {
+    /// As an approximate floating point number.
+    #[default]
+    Float,
+    /// As a string with the integer's digits.
+    String,
+    /// Loading fails.
+    Error,
+}
+
+/// A step on the way from the root of the data to a nested value.
+enum Segment {
+    Key(Str),
+    Index(usize),
+}
+
+/// Deserializes a value with the data options, keeping track of where in the
+/// data it is.
+struct Seed<'a> {
+    options: &'a DataOptions,
+    /// The keys and indices leading to the current value.
+    path: &'a mut Vec<Segment>,
+    /// The error of a failed conversion.
+    error: &'a mut Option<EcoString>,
+}
+
+impl Seed<'_> {
+    /// A seed for a nested value.
+    fn reborrow(&mut self) -> Seed<'_> {
+        Seed {
+            options: self.options,
+            path: &mut *self.path,
+            error: &mut *self.error,
+        }
+    }
+
+    /// Convert a string, parsing it as a date if the options ask for it.
+    fn string(self, v: &str) -> Value {
+        let parse = match &self.options.dates {
+            Dates::None => false,
+            Dates::All => true,
+            Dates::Keys(keys) => self
+                .path
+                .iter()
+                .rev()
+                .find_map(|segment| match segment {
+                    Segment::Key(key) => Some(key),
+                    Segment::Index(_) => None,
+                })
+                .is_some_and(|key| keys.contains(key)),
+        };
+
+        match Datetime::from_iso(v).filter(|_| parse) {
+            Some(datetime) => datetime.into_value(),
+            None => v.into_value(),
+        }
+    }
+
+    /// Convert an integer that doesn't fit into 64 bits.
+    fn big_int<E: Error>(self, digits: String, approx: f64) -> Result<Value, E> {
+        match self.options.big_ints {
+            BigInts::Float => Ok(Value::Float(approx)),
+            BigInts::String => Ok(Value::Str(digits.into())),
+            BigInts::Error => {
+                let mut message = eco_format!("integer {digits}");
+                if !self.path.is_empty() {
+                    message.push_str(&eco_format!(" at `{}`", format_path(self.path)));
+                }
+                message.push_str(" does not fit into 64 bits");
+                *self.error = Some(message);
+                Err(E::custom("integer does not fit into 64 bits"))
+            }
+        }
+    }
+}
+
+impl<'de> DeserializeSeed<'de> for Seed<'_> {
+    type Value = Value;
+
+    fn deserialize<D: Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> Result<Value, D::Error> {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de> Visitor<'de> for Seed<'_> {
+    type Value = Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a typst value")
+    }
+
+    fn visit_bool<E: Error>(self, v: bool) -> Result<Value, E> {
+        ValueVisitor.visit_bool(v)
+    }
+
+    fn visit_i64<E: Error>(self, v: i64) -> Result<Value, E> {
+        ValueVisitor.visit_i64(v)
+    }
+
+    fn visit_i128<E: Error>(self, v: i128) -> Result<Value, E> {
+        match i64::try_from(v) {
+            Ok(v) => ValueVisitor.visit_i64(v),
+            Err(_) => self.big_int(v.to_string(), v as f64),
+        }
+    }
+
+    fn visit_u64<E: Error>(self, v: u64) -> Result<Value, E> {
+        match i64::try_from(v) {
+            Ok(v) => ValueVisitor.visit_i64(v),
+            Err(_) => self.big_int(v.to_string(), v as f64),
+        }
+    }
+
+    fn visit_u128<E: Error>(self, v: u128) -> Result<Value, E> {
+        match i64::try_from(v) {
+            Ok(v) => ValueVisitor.visit_i64(v),
+            Err(_) => self.big_int(v.to_string(), v as f64),
+        }
+    }
+
+    fn visit_f64<E: Error>(self, v: f64) -> Result<Value, E> {
+        ValueVisitor.visit_f64(v)
+    }
+
+    fn visit_char<E: Error>(self, v: char) -> Result<Value, E> {
+        Ok(self.string(v.encode_utf8(&mut [0; 4])))
+    }
+
+    fn visit_str<E: Error>(self, v: &str) -> Result<Value, E> {
+        Ok(self.string(v))
+    }
+
+    fn visit_bytes<E: Error>(self, v: &[u8]) -> Result<Value, E> {
+        ValueVisitor.visit_bytes(v)
+    }
+
+    fn visit_none<E: Error>(self) -> Result<Value, E> {
+        ValueVisitor.visit_none()
+    }
+
+    fn visit_some<D: Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> Result<Value, D::Error> {
+        DeserializeSeed::deserialize(self, deserializer)
+    }
+
+    fn visit_unit<E: Error>(self) -> Result<Value, E> {
+        ValueVisitor.visit_unit()
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(mut self, mut seq: A) -> Result<Value, A::Error> {
+        let mut array = Array::with_capacity(seq.size_hint().unwrap_or_default());
+        loop {
+            self.path.push(Segment::Index(array.len()));
+            let next = seq.next_element_seed(self.reborrow());
+            self.path.pop();
+            match next? {
+                Some(value) => array.push(value),
+                None => break,
+            }
+        }
+        Ok(array.into_value())
+    }
+
+    fn visit_map<A: MapAccess<'de>>(mut self, mut map: A) -> Result<Value, A::Error> {
+        let mut dict = Dict::new();
+        while let Some(key) = map.next_key::<Str>()? {
+            self.path.push(Segment::Key(key.clone()));
+            let value = map.next_value_seed(self.reborrow());
+            self.path.pop();
+            dict.insert(key, value?);
+        }
+        Ok(match Datetime::from_toml_dict(&dict) {
+            None => dict.into_value(),
+            Some(datetime) => datetime.into_value(),
+        })
+    }
+
+    fn visit_enum<A: EnumAccess<'de>>(self, data: A) -> Result<Value, A::Error> {
+        // YAML presents values with custom tags as enums. Timestamps are
+        // parsed as datetimes, while other tags are ignored. The standard
+        // `!!timestamp` tag never gets here, as the YAML parser drops it and
+        // presents the value as a plain string.
+        let (tag, variant) = data.variant::<Str>()?;
+        if tag.as_str() == "timestamp" {
+            let string = variant.newtype_variant::<Str>()?;
+            return Ok(match Datetime::from_iso(&string) {
+                Some(datetime) => datetime.into_value(),
+                None => string.into_value(),
+            });
+        }
+        variant.newtype_variant_seed(self)
+    }
+}
+
+/// Format the keys and indices leading to a nested value like an access in
+/// Typst code.
+fn format_path(path: &[Segment]) -> EcoString {
+    let mut out = EcoString::new();
+    for segment in path {
+        match segment {
+            Segment::Key(key) if is_ident(key) => {
+                if !out.is_empty() {
+                    out.push('.');
+                }
+                out.push_str(key);
+            }
+            Segment::Key(key) => {
+                out.push_str(if out.is_empty() { "at(" } else { ".at(" });
+                out.push_str(&key.repr());
+                out.push(')');
+            }
+            Segment::Index(index) => {
+                out.push_str(if out.is_empty() { "at(" } else { ".at(" });
+                out.push_str(&eco_format!("{index}"));
+                out.push(')');
+            }
+        }
+    }
+    out
+}
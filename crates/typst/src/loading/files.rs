@@ -0,0 +1,169 @@
+use std::path::Component;
+
+use ecow::{eco_format, EcoString};
+
+use crate::diag::{bail, At, SourceResult};
+use crate::engine::Engine;
+use crate::foundations::{func, Array, IntoValue};
+use crate::syntax::{FileId, Spanned, VirtualPath};
+use crate::World;
+
+/// Lists the files whose paths match a pattern.
+///
+/// Like the paths passed to [`read`], the pattern is resolved relative to the
+/// current file, or relative to the project root if it starts with a `/`. Its
+/// last component may contain the wildcards `*`, which matches any number of
+/// characters, and `?`, which matches a single character. The directories
+/// leading up to it must be spelled out.
+///
+/// The resulting paths are sorted lexicographically and can be passed on to
+/// [`read`], [`csv`], or an [include]($scripting/#modules) as is. Files whose
+/// name starts with a dot are only listed if the pattern starts with a dot,
+/// too.
+///
+/// Whether files can be listed depends on the environment Typst runs in. If it
+/// can't list files, this function fails with an error.
+///
+/// # Example
+/// ```typ
+/// #for path in files("chapters/*.typ") {
+///   include path
+/// }
+/// ```
+#[func]
+pub fn files(
+    /// The engine.
+    engine: &mut Engine,
+    /// The pattern that the paths of the files must match.
+    pattern: Spanned<EcoString>,
+    /// Whether to also list matching files in subdirectories.
+    ///
+    /// In this case, the wildcards are matched against the names of the
+    /// files in any subdirectory of the pattern's directory.
+    #[named]
+    #[default(false)]
+    recursive: bool,
+) -> SourceResult<Array> {
+    let Spanned { v: pattern, span } = pattern;
+    let (dir, name) = match pattern.rfind('/') {
+        Some(i) => pattern.split_at(i + 1),
+        None => ("", pattern.as_str()),
+    };
+
+    if dir.contains(['*', '?']) {
+        bail!(
+            span, "wildcards are only supported in the last path component";
+            hint: "use `recursive: true` to also list files in subdirectories"
+        );
+    } else if name.is_empty() {
+        bail!(span, "pattern must end with a file name");
+    } else if name.contains("**") {
+        bail!(
+            span, "recursive wildcards are not supported";
+            hint: "use `recursive: true` to also list files in subdirectories"
+        );
+    }
+
+    let id = span.resolve_path(if dir.is_empty() { "." } else { dir }).at(span)?;
+    if id
+        .vpath()
+        .as_rooted_path()
+        .components()
+        .any(|c| c == Component::ParentDir)
+    {
+        match id.package() {
+            Some(_) => bail!(span, "pattern escapes the package root"),
+            None => bail!(span, "pattern escapes the project root"),
+        }
+    }
+
+    let pattern: Vec<char> = name.chars().collect();
+    let hidden = name.starts_with('.');
+    let mut paths = vec![];
+    let mut stack = vec![(id, EcoString::from(dir))];
+    while let Some((id, prefix)) = stack.pop() {
+        let Some(entries) = engine.world.list(id) else {
+            bail!(span, "file listing is not supported");
+        };
+
+        for entry in entries.at(span)? {
+            if entry.name.starts_with('.') && !hidden {
+                continue;
+            }
+
+            let path = eco_format!("{prefix}{}", entry.name);
+            if entry.is_dir {
+                if recursive {
+                    let vpath =
+                        VirtualPath::new(id.vpath().as_rooted_path().join(&*entry.name));
+                    stack.push((FileId::new(id.package().cloned(), vpath), path + "/"));
+                }
+            } else if matches(&pattern, &entry.name) {
+                paths.push(path);
+            }
+        }
+    }
+
+    paths.sort();
+    Ok(paths.into_iter().map(IntoValue::into_value).collect())
+}
+
+/// An entry of a directory, as listed by [`World::list`].
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct DirEntry {
+    /// The name of the file or directory.
+    pub name: EcoString,
+    /// Whether the entry is a directory.
+    pub is_dir: bool,
+}
+
+/// Whether a name matches a pattern with `*` and `?` wildcards.
+fn matches(pattern: &[char], name: &str) -> bool {
+    let name: Vec<char> = name.chars().collect();
+    let (mut p, mut n) = (0, 0);
+
+    // Where to resume if the part after the last star doesn't match.
+    let mut backtrack = None;
+
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, n));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match backtrack {
+                Some((star, start)) => {
+                    // Let the star swallow one more character.
+                    backtrack = Some((star, start + 1));
+                    p = star + 1;
+                    n = start + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches() {
+        let check =
+            |pattern: &str, name| matches(&pattern.chars().collect::<Vec<_>>(), name);
+        assert!(check("*", ""));
+        assert!(check("*.typ", "a.typ"));
+        assert!(check("a*b*c", "aXbYbZc"));
+        assert!(check("?ä?", "xäy"));
+        assert!(!check("*.typ", "a.typst"));
+        assert!(!check("a?", "a"));
+        assert!(!check("a*b", "acbd"));
+    }
+}
@@ -1,11 +1,10 @@
 use ecow::{eco_format, EcoString};
 
-use crate::diag::{At, SourceResult};
+use crate::diag::{At, SourceResult, StrResult};
 use crate::engine::Engine;
 use crate::foundations::{func, scope, Str, Value};
-use crate::loading::Readable;
+use crate::loading::{BigInts, DataOptions, Dates, Readable};
 use crate::syntax::Spanned;
-use crate::World;
 
 /// Reads structured data from a JSON file.
 ///
@@ -17,7 +16,10 @@ use crate::World;
 /// numbers.
 ///
 /// Be aware that integers larger than 2<sup>63</sup>-1 will be converted to
-/// floating point numbers, which may result in an approximative value.
+/// floating point numbers by default, which may result in an approximative
+/// value. Integers up to 2<sup>64</sup>-1 can instead be kept as strings with
+/// the `big-ints` parameter. Strings are never converted into
+/// [datetimes]($datetime) unless the `dates` parameter asks for it.
 ///
 /// The function returns a dictionary, an array or, depending on the JSON file,
 /// another JSON data type.
@@ -55,11 +57,30 @@ pub fn json(
     engine: &mut Engine,
     /// Path to a JSON file.
     path: Spanned<EcoString>,
+    /// Which strings to parse as [datetimes]($datetime).
+    ///
+    /// - If `{none}`, all strings are kept as they are.
+    /// - If `{auto}`, all strings that are dates or times in ISO 8601 format,
+    ///   like `{"2024-03-01"}` or `{"2024-03-01T12:30:00"}`, are parsed.
+    /// - If an array of keys, only strings stored under one of these keys, at
+    ///   any depth, are parsed.
+    #[named]
+    #[default]
+    dates: Dates,
+    /// How to represent integers that don't fit into a 64-bit [integer]($int).
+    ///
+    /// - `{"float"}`: As an approximate [float].
+    /// - `{"string"}`: As a [string]($str) with the integer's digits.
+    /// - `{"error"}`: Loading fails with an error naming the integer's
+    ///   location.
+    #[named]
+    #[default]
+    big_ints: BigInts,
 ) -> SourceResult<Value> {
     let Spanned { v: path, span } = path;
     let id = span.resolve_path(&path).at(span)?;
-    let data = engine.world.file(id).at(span)?;
-    json::decode(Spanned::new(Readable::Bytes(data), span))
+    let data = engine.file(id).at(span)?;
+    json::decode(Spanned::new(Readable::Bytes(data), span), dates, big_ints)
 }
 
 #[scope]
@@ -69,11 +90,30 @@ impl json {
     pub fn decode(
         /// JSON data.
         data: Spanned<Readable>,
+        /// Which strings to parse as [datetimes]($datetime).
+        ///
+        /// - If `{none}`, all strings are kept as they are.
+        /// - If `{auto}`, all strings that are dates or times in ISO 8601
+        ///   format, like `{"2024-03-01"}` or `{"2024-03-01T12:30:00"}`, are
+        ///   parsed.
+        /// - If an array of keys, only strings stored under one of these keys,
+        ///   at any depth, are parsed.
+        #[named]
+        #[default]
+        dates: Dates,
+        /// How to represent integers that don't fit into a 64-bit
+        /// [integer]($int).
+        ///
+        /// - `{"float"}`: As an approximate [float].
+        /// - `{"string"}`: As a [string]($str) with the integer's digits.
+        /// - `{"error"}`: Loading fails with an error naming the integer's
+        ///   location.
+        #[named]
+        #[default]
+        big_ints: BigInts,
     ) -> SourceResult<Value> {
         let Spanned { v: data, span } = data;
-        serde_json::from_slice(data.as_slice())
-            .map_err(|err| eco_format!("failed to parse JSON ({err})"))
-            .at(span)
+        parse(&data, &DataOptions { dates, big_ints }).at(span)
     }
 
     /// Encodes structured data into a JSON string.
@@ -87,6 +127,7 @@ impl json {
         pretty: bool,
     ) -> SourceResult<Str> {
         let Spanned { v: value, span } = value;
+        value.check_depth(Value::MAX_DEPTH).at(span)?;
         if pretty {
             serde_json::to_string_pretty(&value)
         } else {
@@ -97,3 +138,17 @@ impl json {
         .at(span)
     }
 }
+
+/// Parse JSON data into a value.
+///
+/// This is memoized separately from the calling module, so that an unchanged
+/// file isn't parsed again when something else in the module changes.
+#[comemo::memoize]
+fn parse(data: &Readable, options: &DataOptions) -> StrResult<Value> {
+    let mut deserializer = serde_json::Deserializer::from_slice(data.as_slice());
+    let value = options.deserialize(&mut deserializer, "JSON")?;
+    deserializer
+        .end()
+        .map_err(|err| eco_format!("failed to parse JSON ({err})"))?;
+    Ok(value)
+}
@@ -4,6 +4,9 @@
 mod cbor_;
 #[path = "csv.rs"]
 mod csv_;
+mod data;
+#[path = "files.rs"]
+mod files_;
 #[path = "json.rs"]
 mod json_;
 #[path = "read.rs"]
@@ -17,6 +20,8 @@ mod yaml_;
 
 pub use self::cbor_::*;
 pub use self::csv_::*;
+pub use self::data::*;
+pub use self::files_::*;
 pub use self::json_::*;
 pub use self::read_::*;
 pub use self::toml_::*;
@@ -36,6 +41,7 @@ pub static DATA_LOADING: Category;
 pub(super) fn define(global: &mut Scope) {
     global.category(DATA_LOADING);
     global.define_func::<read>();
+    global.define_func::<files>();
     global.define_func::<csv>();
     global.define_func::<json>();
     global.define_func::<toml>();
@@ -5,7 +5,6 @@ use crate::engine::Engine;
 use crate::foundations::{func, Cast};
 use crate::loading::Readable;
 use crate::syntax::Spanned;
-use crate::World;
 
 /// Reads plain text or data from a file.
 ///
@@ -37,7 +36,7 @@ pub fn read(
 ) -> SourceResult<Readable> {
     let Spanned { v: path, span } = path;
     let id = span.resolve_path(&path).at(span)?;
-    let data = engine.world.file(id).at(span)?;
+    let data = engine.file(id).at(span)?;
     Ok(match encoding {
         None => Readable::Bytes(data),
         Some(Encoding::Utf8) => Readable::Str(
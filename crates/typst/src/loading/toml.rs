@@ -1,11 +1,10 @@
 use ecow::{eco_format, EcoString};
 
-use crate::diag::{At, SourceResult};
+use crate::diag::{At, SourceResult, StrResult};
 use crate::engine::Engine;
 use crate::foundations::{func, scope, Str, Value};
 use crate::loading::Readable;
 use crate::syntax::{is_newline, Spanned};
-use crate::World;
 
 /// Reads structured data from a TOML file.
 ///
@@ -36,7 +35,7 @@ pub fn toml(
 ) -> SourceResult<Value> {
     let Spanned { v: path, span } = path;
     let id = span.resolve_path(&path).at(span)?;
-    let data = engine.world.file(id).at(span)?;
+    let data = engine.file(id).at(span)?;
     toml::decode(Spanned::new(Readable::Bytes(data), span))
 }
 
@@ -49,12 +48,7 @@ impl toml {
         data: Spanned<Readable>,
     ) -> SourceResult<Value> {
         let Spanned { v: data, span } = data;
-        let raw = std::str::from_utf8(data.as_slice())
-            .map_err(|_| "file is not valid utf-8")
-            .at(span)?;
-        ::toml::from_str(raw)
-            .map_err(|err| format_toml_error(err, raw))
-            .at(span)
+        parse(&data).at(span)
     }
 
     /// Encodes structured data into a TOML string.
@@ -68,6 +62,7 @@ impl toml {
         pretty: bool,
     ) -> SourceResult<Str> {
         let Spanned { v: value, span } = value;
+        value.check_depth(Value::MAX_DEPTH).at(span)?;
         if pretty { ::toml::to_string_pretty(&value) } else { ::toml::to_string(&value) }
             .map(|v| v.into())
             .map_err(|err| eco_format!("failed to encode value as TOML ({err})"))
@@ -75,6 +70,14 @@ impl toml {
     }
 }
 
+/// Parse TOML data into a value. Memoized on the data alone.
+#[comemo::memoize]
+fn parse(data: &Readable) -> StrResult<Value> {
+    let raw =
+        std::str::from_utf8(data.as_slice()).map_err(|_| "file is not valid utf-8")?;
+    ::toml::from_str(raw).map_err(|err| format_toml_error(err, raw))
+}
+
 /// Format the user-facing TOML error message.
 fn format_toml_error(error: ::toml::de::Error, raw: &str) -> EcoString {
     if let Some(head) = error.span().and_then(|range| raw.get(..range.start)) {
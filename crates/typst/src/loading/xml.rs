@@ -1,12 +1,11 @@
 use ecow::EcoString;
 use roxmltree::ParsingOptions;
 
-use crate::diag::{format_xml_like_error, At, FileError, SourceResult};
+use crate::diag::{format_xml_like_error, At, FileError, SourceResult, StrResult};
 use crate::engine::Engine;
 use crate::foundations::{dict, func, scope, Array, Dict, IntoValue, Str, Value};
 use crate::loading::Readable;
 use crate::syntax::Spanned;
-use crate::World;
 
 /// Reads structured data from an XML file.
 ///
@@ -65,7 +64,7 @@ pub fn xml(
 ) -> SourceResult<Value> {
     let Spanned { v: path, span } = path;
     let id = span.resolve_path(&path).at(span)?;
-    let data = engine.world.file(id).at(span)?;
+    let data = engine.file(id).at(span)?;
     xml::decode(Spanned::new(Readable::Bytes(data), span))
 }
 
@@ -78,19 +77,25 @@ impl xml {
         data: Spanned<Readable>,
     ) -> SourceResult<Value> {
         let Spanned { v: data, span } = data;
-        let text = std::str::from_utf8(data.as_slice())
-            .map_err(FileError::from)
-            .at(span)?;
-        let document = roxmltree::Document::parse_with_options(
-            text,
-            ParsingOptions { allow_dtd: true, ..Default::default() },
-        )
-        .map_err(format_xml_error)
-        .at(span)?;
-        Ok(convert_xml(document.root()))
+        parse(&data).at(span)
     }
 }
 
+/// Parse XML data into a value.
+///
+/// Memoized on the data, so that the conversion isn't redone when only the
+/// calling module changed.
+#[comemo::memoize]
+fn parse(data: &Readable) -> StrResult<Value> {
+    let text = std::str::from_utf8(data.as_slice()).map_err(FileError::from)?;
+    let document = roxmltree::Document::parse_with_options(
+        text,
+        ParsingOptions { allow_dtd: true, ..Default::default() },
+    )
+    .map_err(format_xml_error)?;
+    Ok(convert_xml(document.root()))
+}
+
 /// Convert an XML node to a Typst value.
 fn convert_xml(node: roxmltree::Node) -> Value {
     if node.is_text() {
@@ -1,11 +1,10 @@
 use ecow::{eco_format, EcoString};
 
-use crate::diag::{At, SourceResult};
+use crate::diag::{At, SourceResult, StrResult};
 use crate::engine::Engine;
 use crate::foundations::{func, scope, Str, Value};
-use crate::loading::Readable;
+use crate::loading::{BigInts, DataOptions, Dates, Readable};
 use crate::syntax::Spanned;
-use crate::World;
 
 /// Reads structured data from a YAML file.
 ///
@@ -14,11 +13,13 @@ use crate::World;
 /// Typst arrays. Strings and booleans will be converted into the Typst
 /// equivalents, null-values (`null`, `~` or empty ``) will be converted into
 /// `{none}`, and numbers will be converted to floats or integers depending on
-/// whether they are whole numbers. Custom YAML tags are ignored, though the
-/// loaded value will still be present.
+/// whether they are whole numbers. Values tagged as `!timestamp` will be
+/// converted into [datetimes]($datetime) if they are in ISO 8601 format. Other
+/// custom YAML tags are ignored, though the loaded value will still be present.
 ///
 /// Be aware that integers larger than 2<sup>63</sup>-1 will be converted to
-/// floating point numbers, which may give an approximative value.
+/// floating point numbers by default, which may give an approximative value.
+/// The `big-ints` parameter can keep them as strings instead.
 ///
 /// The YAML files in the example contain objects with authors as keys,
 /// each with a sequence of their own submapping with the keys
@@ -45,11 +46,30 @@ pub fn yaml(
     engine: &mut Engine,
     /// Path to a YAML file.
     path: Spanned<EcoString>,
+    /// Which strings to parse as [datetimes]($datetime).
+    ///
+    /// - If `{none}`, all strings are kept as they are.
+    /// - If `{auto}`, all strings that are dates or times in ISO 8601 format,
+    ///   like `{"2024-03-01"}` or `{"2024-03-01T12:30:00"}`, are parsed.
+    /// - If an array of keys, only strings stored under one of these keys, at
+    ///   any depth, are parsed.
+    #[named]
+    #[default]
+    dates: Dates,
+    /// How to represent integers that don't fit into a 64-bit [integer]($int).
+    ///
+    /// - `{"float"}`: As an approximate [float].
+    /// - `{"string"}`: As a [string]($str) with the integer's digits.
+    /// - `{"error"}`: Loading fails with an error naming the integer's
+    ///   location.
+    #[named]
+    #[default]
+    big_ints: BigInts,
 ) -> SourceResult<Value> {
     let Spanned { v: path, span } = path;
     let id = span.resolve_path(&path).at(span)?;
-    let data = engine.world.file(id).at(span)?;
-    yaml::decode(Spanned::new(Readable::Bytes(data), span))
+    let data = engine.file(id).at(span)?;
+    yaml::decode(Spanned::new(Readable::Bytes(data), span), dates, big_ints)
 }
 
 #[scope]
@@ -59,11 +79,30 @@ impl yaml {
     pub fn decode(
         /// YAML data.
         data: Spanned<Readable>,
+        /// Which strings to parse as [datetimes]($datetime).
+        ///
+        /// - If `{none}`, all strings are kept as they are.
+        /// - If `{auto}`, all strings that are dates or times in ISO 8601
+        ///   format, like `{"2024-03-01"}` or `{"2024-03-01T12:30:00"}`, are
+        ///   parsed.
+        /// - If an array of keys, only strings stored under one of these keys,
+        ///   at any depth, are parsed.
+        #[named]
+        #[default]
+        dates: Dates,
+        /// How to represent integers that don't fit into a 64-bit
+        /// [integer]($int).
+        ///
+        /// - `{"float"}`: As an approximate [float].
+        /// - `{"string"}`: As a [string]($str) with the integer's digits.
+        /// - `{"error"}`: Loading fails with an error naming the integer's
+        ///   location.
+        #[named]
+        #[default]
+        big_ints: BigInts,
     ) -> SourceResult<Value> {
         let Spanned { v: data, span } = data;
-        serde_yaml::from_slice(data.as_slice())
-            .map_err(|err| eco_format!("failed to parse YAML ({err})"))
-            .at(span)
+        parse(&data, &DataOptions { dates, big_ints }).at(span)
     }
 
     /// Encode structured data into a YAML string.
@@ -73,9 +112,17 @@ impl yaml {
         value: Spanned<Value>,
     ) -> SourceResult<Str> {
         let Spanned { v: value, span } = value;
+        value.check_depth(Value::MAX_DEPTH).at(span)?;
         serde_yaml::to_string(&value)
             .map(|v| v.into())
             .map_err(|err| eco_format!("failed to encode value as YAML ({err})"))
             .at(span)
     }
 }
+
+/// Parse YAML data into a value, memoized like the other data formats.
+#[comemo::memoize]
+fn parse(data: &Readable, options: &DataOptions) -> StrResult<Value> {
+    let deserializer = serde_yaml::Deserializer::from_slice(data.as_slice());
+    options.deserialize(deserializer, "YAML")
+}
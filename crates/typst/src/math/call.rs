@@ -0,0 +1,66 @@
+use ecow::EcoString;
+
+use crate::diag::{bail, SourceResult};
+use crate::engine::Engine;
+use crate::foundations::{
+    elem, Args, Cast, Construct, Content, Packed, StyleChain, Type,
+};
+use crate::math::{EquationElem, LayoutMath, MathContext};
+
+/// A call of a value that is not a function in math, like `{$pi(a, b)$}`.
+///
+/// Whether the value is displayed followed by its arguments in parentheses
+/// depends on the equation's [`unknown-calls`]($math.equation.unknown-calls)
+/// property.
+#[elem(Construct, LayoutMath)]
+pub struct UnknownCallElem {
+    /// The displayed callee.
+    #[required]
+    pub callee: Content,
+
+    /// The arguments, including the delimiters.
+    #[required]
+    pub args: Content,
+
+    /// The callee as written in the source.
+    #[required]
+    pub name: EcoString,
+
+    /// The type of the callee.
+    #[required]
+    pub ty: Type,
+}
+
+impl Construct for UnknownCallElem {
+    fn construct(_: &mut Engine, args: &mut Args) -> SourceResult<Content> {
+        bail!(args.span, "cannot be constructed manually");
+    }
+}
+
+impl LayoutMath for Packed<UnknownCallElem> {
+    #[typst_macros::time(name = "math.call", span = self.span())]
+    fn layout_math(&self, ctx: &mut MathContext, styles: StyleChain) -> SourceResult<()> {
+        if EquationElem::unknown_calls_in(styles) == UnknownCalls::Error {
+            let name = self.name();
+            bail!(
+                self.callee().span(),
+                "`{name}` is a {}, not a function", self.ty();
+                hint: "to display the parentheses, escape the opening one like `{name}\\(`";
+                hint: "or define a function named `{name}`",
+            );
+        }
+
+        self.callee().layout_math(ctx, styles)?;
+        self.args().layout_math(ctx, styles)
+    }
+}
+
+/// What to do when a value that is not a function is called in math.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum UnknownCalls {
+    /// Display the value followed by its arguments in parentheses.
+    #[default]
+    Render,
+    /// Fail with an error.
+    Error,
+}
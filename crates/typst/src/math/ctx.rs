@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::f64::consts::SQRT_2;
 
 use ecow::{eco_vec, EcoString};
@@ -51,6 +52,7 @@ pub struct MathContext<'a, 'b, 'v> {
     // External.
     pub engine: &'v mut Engine<'b>,
     pub locator: SplitLocator<'v>,
+    pub occurrences: HashMap<u128, usize>,
     pub regions: Regions<'static>,
     // Font-related.
     pub font: &'a Font,
@@ -107,6 +109,7 @@ impl<'a, 'b, 'v> MathContext<'a, 'b, 'v> {
         Self {
             engine,
             locator: locator.split(),
+            occurrences: HashMap::new(),
             regions: Regions::one(base, Axes::splat(false)),
             font,
             ttf: font.ttf(),
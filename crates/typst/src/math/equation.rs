@@ -15,7 +15,8 @@ use crate::layout::{
     VAlignment,
 };
 use crate::math::{
-    scaled_font_size, LayoutMath, MathContext, MathRunFrameBuilder, MathSize, MathVariant,
+    scaled_font_size, LayoutMath, MathContext, MathRunFrameBuilder, MathSize,
+    MathVariant, UnknownCalls,
 };
 use crate::model::{Numbering, Outlinable, ParElem, Refable, Supplement};
 use crate::syntax::Span;
@@ -106,6 +107,21 @@ pub struct EquationElem {
     /// ```
     pub supplement: Smart<Option<Supplement>>,
 
+    /// What to do when a value that is not a function is called, like `pi` in
+    /// `{$pi(a, b)$}`.
+    ///
+    /// By default, the value is displayed followed by its arguments in
+    /// parentheses. Set this to `{"error"}` to catch typos and missing imports
+    /// of functions. Operators like `sin` are always displayed with their
+    /// arguments and function symbols like `arrow` still work as accents.
+    ///
+    /// ```example
+    /// $ pi(a, b) $
+    /// #set math.equation(unknown-calls: "error")
+    /// $ sin(x) + arrow(v) $
+    /// ```
+    pub unknown_calls: UnknownCalls,
+
     /// The contents of the equation.
     #[required]
     pub body: Content,
@@ -7,6 +7,7 @@ pub mod accent;
 
 mod align;
 mod attach;
+mod call;
 mod cancel;
 #[path = "class.rs"]
 mod class_;
@@ -26,6 +27,7 @@ mod underover;
 pub use self::accent::{Accent, AccentElem};
 pub use self::align::*;
 pub use self::attach::*;
+pub use self::call::*;
 pub use self::cancel::*;
 pub use self::class_::*;
 pub use self::equation::*;
@@ -232,7 +234,9 @@ impl LayoutMath for Content {
             return elem.layout_math(ctx, styles);
         }
 
-        if let Some(realized) = process(ctx.engine, &mut ctx.locator, self, styles)? {
+        if let Some(realized) =
+            process(ctx.engine, &mut ctx.locator, &mut ctx.occurrences, self, styles)?
+        {
             return realized.layout_math(ctx, styles);
         }
 
@@ -23,9 +23,9 @@ use crate::diag::{bail, error, At, FileError, HintedStrResult, SourceResult, Str
 use crate::engine::Engine;
 use crate::eval::{eval_string, EvalMode};
 use crate::foundations::{
-    cast, elem, ty, Args, Array, Bytes, CastInfo, Content, FromValue, IntoValue, Label,
-    NativeElement, Packed, Reflect, Repr, Scope, Show, ShowSet, Smart, Str, StyleChain,
-    Styles, Synthesize, Type, Value,
+    cast, elem, ty, Args, Array, Bytes, CastInfo, Content, DynamicRepr, FromValue,
+    IntoValue, Label, NativeElement, Packed, Reflect, Repr, Scope, Show, ShowSet, Smart,
+    Str, StyleChain, Styles, Synthesize, Type, Value,
 };
 use crate::introspection::{Introspector, Locatable, Location};
 use crate::layout::{
@@ -305,12 +305,17 @@ impl Bibliography {
             .iter()
             .map(|path| {
                 let id = span.resolve_path(path).at(span)?;
-                engine.world.file(id).at(span)
+                engine.file(id).at(span)
             })
             .collect::<SourceResult<Vec<Bytes>>>()?;
 
-        // Parse.
-        let bibliography = Self::load(&paths, &data).at(span)?;
+        // Parse. In a dry run, the entries are never used, so it suffices to
+        // check that the files could be parsed.
+        let bibliography = if engine.sink.dry_run() {
+            Self::check(&paths, &data).at(span)?
+        } else {
+            Self::load(&paths, &data).at(span)?
+        };
 
         Ok((paths, bibliography))
     }
@@ -361,6 +366,28 @@ impl Bibliography {
         })
     }
 
+    /// Check that the files are UTF-8 encoded and of a known bibliography
+    /// format without parsing them, returning an empty bibliography.
+    fn check(paths: &BibliographyPaths, data: &[Bytes]) -> StrResult<Bibliography> {
+        for (path, bytes) in paths.0.iter().zip(data) {
+            std::str::from_utf8(bytes).map_err(FileError::from)?;
+
+            let ext = Path::new(path.as_str())
+                .extension()
+                .and_then(OsStr::to_str)
+                .unwrap_or_default();
+
+            if !matches!(ext.to_lowercase().as_str(), "yml" | "yaml" | "bib") {
+                bail!("unknown bibliography format (must be .yml/.yaml or .bib)");
+            }
+        }
+
+        Ok(Bibliography {
+            map: Arc::new(IndexMap::new()),
+            hash: crate::utils::hash128(data),
+        })
+    }
+
     fn has(&self, key: impl Into<PicoStr>) -> bool {
         self.map.contains_key(&key.into())
     }
@@ -445,7 +472,7 @@ impl CslStyle {
 
         if ext == "csl" {
             let id = span.resolve_path(string)?;
-            let data = engine.world.file(id)?;
+            let data = engine.file(id)?;
             CslStyle::from_data(&data)
         } else {
             CslStyle::from_name(string)
@@ -534,6 +561,8 @@ impl Repr for CslStyle {
     }
 }
 
+impl DynamicRepr for CslStyle {}
+
 /// Fully formatted citations and references, generated once (through
 /// memoization) for the whole document. This setup is necessary because
 /// citation formatting is inherently stateful and we need access to all
@@ -16,9 +16,10 @@ pub use self::arenas::Arenas;
 pub use self::behaviour::{Behave, BehavedBuilder, Behaviour, StyleVec};
 pub use self::process::process;
 
+use std::collections::HashMap;
 use std::mem;
 
-use crate::diag::{bail, SourceResult};
+use crate::diag::{bail, error, SourceResult};
 use crate::engine::{Engine, Route};
 use crate::foundations::{
     Content, NativeElement, Packed, SequenceElem, Smart, StyleChain, StyledElem, Styles,
@@ -73,6 +74,9 @@ struct Builder<'a, 'v, 't> {
     engine: &'v mut Engine<'t>,
     /// Assigns unique locations to elements.
     locator: SplitLocator<'v>,
+    /// How often show rules that want to know the index of their match were
+    /// applied so far, keyed by the recipe's hash.
+    occurrences: HashMap<u128, usize>,
     /// Scratch arenas for building.
     arenas: &'a Arenas<'a>,
     /// The current document building state.
@@ -97,6 +101,7 @@ impl<'a, 'v, 't> Builder<'a, 'v, 't> {
         Self {
             engine,
             locator: locator.split(),
+            occurrences: HashMap::new(),
             arenas,
             doc: top.then(DocBuilder::default),
             flow: FlowBuilder::default(),
@@ -121,14 +126,20 @@ impl<'a, 'v, 't> Builder<'a, 'v, 't> {
 
         // Styled elements and sequences can (at least currently) also have
         // labels, so this needs to happen before they are handled.
-        if let Some(realized) = process(self.engine, &mut self.locator, content, styles)?
-        {
+        if let Some(realized) = process(
+            self.engine,
+            &mut self.locator,
+            &mut self.occurrences,
+            content,
+            styles,
+        )? {
             self.engine.route.increase();
             if !self.engine.route.within(Route::MAX_SHOW_RULE_DEPTH) {
-                bail!(
+                bail!(error!(
                     content.span(), "maximum show rule depth exceeded";
                     hint: "check whether the show rule matches its own output"
-                );
+                )
+                .uncatchable());
             }
             let result = self.accept(self.arenas.store(realized), styles);
             self.engine.route.decrease();
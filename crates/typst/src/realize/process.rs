@@ -1,4 +1,5 @@
 use std::cell::OnceCell;
+use std::collections::HashMap;
 
 use comemo::{Track, Tracked};
 
@@ -32,9 +33,13 @@ enum ShowStep<'a> {
 }
 
 /// Processes the given `target` element when encountering it during realization.
+///
+/// The `occurrences` count how often each show rule that wants to know the
+/// index of its match was applied during this realization pass so far.
 pub fn process(
     engine: &mut Engine,
     locator: &mut SplitLocator,
+    occurrences: &mut HashMap<u128, usize>,
     target: &Content,
     styles: StyleChain,
 ) -> SourceResult<Option<Content>> {
@@ -62,7 +67,9 @@ pub fn process(
             //
             // This way, we can ignore errors that only occur in earlier
             // iterations and also show more useful errors at once.
-            engine.delay(|engine| show(engine, target, step, styles.chain(&map)))
+            engine.delay(|engine| {
+                show(engine, occurrences, target, step, styles.chain(&map))
+            })
         }
         None => target,
     };
@@ -108,7 +115,7 @@ fn verdict<'a>(
     for entry in styles.entries() {
         let recipe = match entry {
             Style::Recipe(recipe) => recipe,
-            Style::Property(_) => continue,
+            Style::Property(_) | Style::Isolation(_) => continue,
             Style::Revocation(index) => {
                 revoked.insert(index.0);
                 continue;
@@ -238,6 +245,7 @@ fn prepare(
 /// Apply a step.
 fn show(
     engine: &mut Engine,
+    occurrences: &mut HashMap<u128, usize>,
     target: Content,
     step: ShowStep,
     styles: StyleChain,
@@ -251,11 +259,22 @@ fn show(
                 // text element. This invokes special regex handling.
                 Some(Selector::Regex(regex)) => {
                     let text = target.into_packed::<TextElem>().unwrap();
-                    show_regex(engine, &text, regex, recipe, guard, context.track())
+                    show_regex(
+                        engine,
+                        occurrences,
+                        &text,
+                        regex,
+                        recipe,
+                        guard,
+                        context.track(),
+                    )
                 }
 
                 // Just apply the recipe.
-                _ => recipe.apply(engine, context.track(), target.guarded(guard)),
+                _ => {
+                    let index = occurrence(occurrences, recipe);
+                    recipe.apply(engine, context.track(), target.guarded(guard), index)
+                }
             }
         }
 
@@ -268,6 +287,7 @@ fn show(
 /// Apply a regex show rule recipe to a target.
 fn show_regex(
     engine: &mut Engine,
+    occurrences: &mut HashMap<u128, usize>,
     target: &Packed<TextElem>,
     regex: &Regex,
     recipe: &Recipe,
@@ -292,7 +312,8 @@ fn show_regex(
         }
 
         let piece = make(m.as_str());
-        let transformed = recipe.apply(engine, context, piece)?;
+        let index = occurrence(occurrences, recipe);
+        let transformed = recipe.apply(engine, context, piece, index)?;
         result.push(transformed);
         cursor = m.end();
     }
@@ -314,3 +335,18 @@ fn show_regex(
     // All these problems don't exist for text, so it's fine here.
     Ok(Content::sequence(result).styled(Style::Revocation(index)))
 }
+
+/// Count a match of a recipe and return its index among the recipe's matches
+/// in this realization pass.
+///
+/// Only recipes that want to know the index are counted, so that other
+/// recipes don't need to be hashed.
+fn occurrence(occurrences: &mut HashMap<u128, usize>, recipe: &Recipe) -> usize {
+    if !recipe.wants_info() {
+        return 0;
+    }
+
+    let count = occurrences.entry(crate::utils::hash128(recipe)).or_default();
+    *count += 1;
+    *count - 1
+}
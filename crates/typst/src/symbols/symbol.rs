@@ -6,9 +6,12 @@ use std::sync::Arc;
 use ecow::{eco_format, EcoString};
 use serde::{Serialize, Serializer};
 
-use crate::diag::{bail, SourceResult, StrResult};
-use crate::foundations::{cast, func, scope, ty, Array, Func};
+use crate::diag::{bail, HintedStrResult, HintedString, SourceResult, StrResult};
+use crate::foundations::{
+    cast, func, scope, ty, Array, Dict, Func, IntoValue, Repr as _, Str,
+};
 use crate::syntax::{Span, Spanned};
+use crate::utils::similar;
 
 #[doc(inline)]
 pub use typst_macros::symbols;
@@ -138,10 +141,7 @@ impl Symbol {
     /// Possible modifiers.
     pub fn modifiers(&self) -> impl Iterator<Item = &str> + '_ {
         let mut set = BTreeSet::new();
-        let modifiers = match &self.0 {
-            Repr::Multi(arc) => arc.1.as_str(),
-            _ => "",
-        };
+        let modifiers = self.applied();
         for modifier in self.variants().flat_map(|(name, _)| name.split('.')) {
             if !modifier.is_empty() && !contained(modifiers, modifier) {
                 set.insert(modifier);
@@ -149,6 +149,62 @@ impl Symbol {
         }
         set.into_iter()
     }
+
+    /// The variants that have all the modifiers that were already applied.
+    pub fn reachable(&self) -> impl Iterator<Item = (&str, SymChar)> {
+        let modifiers = self.applied();
+        self.variants()
+            .filter(move |(name, _)| parts(modifiers).all(|m| contained(name, m)))
+    }
+
+    /// The modifiers that were already applied, separated by dots.
+    fn applied(&self) -> &str {
+        match &self.0 {
+            Repr::Multi(arc) => arc.1.as_str(),
+            _ => "",
+        }
+    }
+
+    /// The error for a modifier that can't be applied to the symbol, with a
+    /// hint about the ones that can.
+    pub fn unknown_modifier(&self) -> HintedString {
+        let mut error = HintedString::new("unknown symbol modifier".into());
+        let applied = self.applied();
+        let mut available: Vec<&str> = vec![];
+        for (name, _) in self.reachable() {
+            for modifier in parts(name) {
+                if !contained(applied, modifier) && !available.contains(&modifier) {
+                    available.push(modifier);
+                }
+            }
+        }
+        if available.is_empty() {
+            error.hint("no further modifiers are available");
+        } else {
+            error.hint(eco_format!("available modifiers: {}", available.join(", ")));
+        }
+        error
+    }
+
+    /// The error for a variant that does not exist, with a hint about the
+    /// most similar one that does.
+    fn unknown_variant(&self, name: &str) -> HintedString {
+        let mut error =
+            HintedString::new(eco_format!("symbol has no variant {}", name.repr()));
+        let applied = self.applied();
+        let names: Vec<String> = self
+            .reachable()
+            .map(|(variant, _)| {
+                let rest: Vec<&str> =
+                    parts(variant).filter(|m| !contained(applied, m)).collect();
+                rest.join(".")
+            })
+            .collect();
+        if let Some(similar) = similar(name, names.iter().map(String::as_str)) {
+            error.hint(eco_format!("did you mean `{similar}`?"));
+        }
+        error
+    }
 }
 
 #[scope]
@@ -196,6 +252,75 @@ impl Symbol {
         }
         Ok(Symbol::runtime(list.into_boxed_slice()))
     }
+
+    /// The variants of this symbol that have all the modifiers that were
+    /// already applied to it.
+    ///
+    /// Returns an array of dictionaries, each with the variant's `name`, which
+    /// consists of its modifiers separated by dots, and its `codepoint` as a
+    /// string. The variant without any modifiers has an empty name.
+    ///
+    /// ```example
+    /// #for variant in sym.arrow.r.variants().slice(0, 4) [
+    ///   #variant.codepoint: #raw(variant.name) \
+    /// ]
+    /// ```
+    #[func(name = "variants")]
+    pub fn variant_list(&self) -> Array {
+        self.reachable()
+            .map(|(name, c)| {
+                let mut dict = Dict::new();
+                dict.insert("name".into(), name.into_value());
+                dict.insert("codepoint".into(), c.char().into_value());
+                dict.into_value()
+            })
+            .collect()
+    }
+
+    /// Applies modifiers to this symbol.
+    ///
+    /// This is the same as applying the modifiers with dot notation, but also
+    /// works with modifiers that are only known at runtime. Fails with an error
+    /// if the symbol has no variant with the modifiers.
+    ///
+    /// ```example
+    /// #for name in ("l", "r", "l.double") [
+    ///   #sym.arrow.get(name)
+    /// ]
+    /// ```
+    #[func(name = "get")]
+    pub fn lookup(
+        &self,
+        /// The modifiers to apply, separated by dots.
+        name: Str,
+    ) -> HintedStrResult<Symbol> {
+        let mut symbol = self.clone();
+        for modifier in parts(&name) {
+            symbol =
+                symbol.modified(modifier).map_err(|_| self.unknown_variant(&name))?;
+        }
+        Ok(symbol)
+    }
+
+    /// Whether this symbol has a variant with the given modifiers, in
+    /// addition to the ones that were already applied to it.
+    ///
+    /// Like for dot notation, the order of the modifiers is not relevant.
+    ///
+    /// ```example
+    /// #sym.arrow.has("r.double") \
+    /// #sym.arrow.r.has("wiggly")
+    /// ```
+    #[func]
+    pub fn has(
+        &self,
+        /// The modifiers to check for, separated by dots.
+        modifiers: Str,
+    ) -> bool {
+        parts(&modifiers)
+            .try_fold(self.clone(), |symbol, modifier| symbol.modified(modifier))
+            .is_ok()
+    }
 }
 
 impl Display for Symbol {
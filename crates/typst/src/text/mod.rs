@@ -10,6 +10,7 @@ mod linebreak;
 mod lorem_;
 mod raw;
 mod shift;
+mod slug_;
 #[path = "smallcaps.rs"]
 mod smallcaps_;
 mod smartquote;
@@ -24,6 +25,7 @@ pub use self::linebreak::*;
 pub use self::lorem_::*;
 pub use self::raw::*;
 pub use self::shift::*;
+pub use self::slug_::*;
 pub use self::smallcaps_::*;
 pub use self::smartquote::*;
 pub use self::space::*;
@@ -71,6 +73,8 @@ pub(super) fn define(global: &mut Scope) {
     global.define_func::<lower>();
     global.define_func::<upper>();
     global.define_func::<lorem>();
+    global.define_func::<slug>();
+    global.define_type::<UniqueSlugs>();
 }
 
 /// Customizes the look and layout of text in a variety of ways.
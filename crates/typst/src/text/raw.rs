@@ -18,13 +18,13 @@ use crate::foundations::{
 };
 use crate::layout::{BlockChild, BlockElem, Em, HAlignment};
 use crate::model::{Figurable, ParElem};
+use crate::syntax;
 use crate::syntax::{split_newlines, LinkedNode, Span, Spanned};
 use crate::text::{
     FontFamily, FontList, Hyphenate, LinebreakElem, LocalName, SmartQuoteElem, TextElem,
     TextSize,
 };
 use crate::visualize::Color;
-use crate::{syntax, World};
 
 // Shorthand for highlighter closures.
 type StyleFn<'a> =
@@ -772,7 +772,7 @@ fn parse_syntaxes(
         .iter()
         .map(|path| {
             let id = span.resolve_path(path).at(span)?;
-            engine.world.file(id).at(span)
+            engine.file(id).at(span)
         })
         .collect::<SourceResult<Vec<Bytes>>>()?;
 
@@ -816,7 +816,7 @@ fn parse_theme(
 
     // Load theme file.
     let id = span.resolve_path(&path).at(span)?;
-    let data = engine.world.file(id).at(span)?;
+    let data = engine.file(id).at(span)?;
 
     // Check that parsing works.
     let _ = load_theme(&path, &data).at(span)?;
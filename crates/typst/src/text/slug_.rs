@@ -0,0 +1,238 @@
+use std::collections::BTreeSet;
+
+use ecow::{eco_format, EcoString};
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::foundations::{cast, func, scope, ty, Content, DynamicRepr, Repr, Str};
+
+/// Turns a string or content into a slug that is suitable for use in URLs and
+/// as an anchor.
+///
+/// Latin letters with diacritics are transliterated to their base letters and
+/// runs of whitespace, punctuation, and symbols (including emoji) are
+/// collapsed into a single separator. Letters and digits of other scripts are
+/// kept as-is unless `ascii` is enabled. For content, only its plain text is
+/// considered.
+///
+/// To derive distinct anchors from headings that may have the same text,
+/// combine this with [`unique-slugs`]($unique-slugs).
+///
+/// # Example
+/// ```example
+/// #slug("Héllo, Wörld!") \
+/// #slug[= Über uns] \
+/// #slug("Ærøskøbing Guide", separator: "_") \
+/// #slug("日本語 and more", ascii: true)
+/// ```
+#[func]
+pub fn slug(
+    /// The text to turn into a slug.
+    text: SlugText,
+    /// The string to put between words.
+    #[named]
+    #[default(Str::from("-"))]
+    separator: Str,
+    /// Whether to convert letters to lowercase.
+    #[named]
+    #[default(true)]
+    lowercase: bool,
+    /// The maximum number of characters in the slug. The slug is only
+    /// truncated between grapheme clusters, so that no character loses its
+    /// accents. A trailing separator is removed after truncation.
+    #[named]
+    #[default(Some(64))]
+    max_length: Option<usize>,
+    /// Whether to drop letters and digits that can't be transliterated to
+    /// ASCII, such as those of non-Latin scripts. Dropped characters act as
+    /// word separators.
+    #[named]
+    #[default(false)]
+    ascii: bool,
+) -> Str {
+    let mut words = vec![];
+    let mut pending = true;
+    for grapheme in text.0.graphemes(true) {
+        let Some(base) = grapheme.chars().next() else { continue };
+        let part = if base.is_ascii_alphanumeric() {
+            EcoString::from(base)
+        } else if let Some(latin) = transliterate(base) {
+            let latin = EcoString::from(latin);
+            if base.is_uppercase() {
+                latin
+            } else {
+                latin.to_lowercase()
+            }
+        } else if base.is_alphanumeric() && !ascii {
+            grapheme.into()
+        } else {
+            pending = true;
+            continue;
+        };
+
+        if pending {
+            words.push(EcoString::new());
+            pending = false;
+        }
+
+        let word = words.last_mut().unwrap();
+        if lowercase {
+            word.push_str(&part.to_lowercase());
+        } else {
+            word.push_str(&part);
+        }
+    }
+
+    // Join the words, stopping once the maximum length is reached. A
+    // separator is only added if the following word fits at least partially.
+    let mut slug = EcoString::new();
+    let mut remaining = max_length.unwrap_or(usize::MAX);
+    let separator_len = separator.graphemes(true).count();
+    for (i, word) in words.iter().enumerate() {
+        if i > 0 {
+            if remaining <= separator_len {
+                break;
+            }
+            slug.push_str(&separator);
+            remaining -= separator_len;
+        }
+        for grapheme in word.graphemes(true).take(remaining) {
+            slug.push_str(grapheme);
+            remaining -= 1;
+        }
+        if remaining == 0 {
+            break;
+        }
+    }
+
+    slug.into()
+}
+
+/// Text that a slug can be derived from.
+pub struct SlugText(EcoString);
+
+cast! {
+    SlugText,
+    v: Str => Self(v.into()),
+    v: Content => Self(v.plain_text()),
+}
+
+/// Keeps track of issued slugs to make them unique.
+///
+/// When a slug is issued for the second time, a separator and a number are
+/// appended to it, starting at two. Like [builders]($builder), this value can
+/// only be modified through variables that the current scope owns.
+///
+/// ```example
+/// #let slugs = unique-slugs()
+/// #slugs.issue(slug("Introduction")) \
+/// #slugs.issue(slug("Introduction")) \
+/// #slugs.issue(slug("Introduction!"))
+/// ```
+#[ty(scope)]
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub struct UniqueSlugs {
+    /// The string between a duplicate slug and its number.
+    separator: Str,
+    /// All slugs that were issued so far.
+    issued: BTreeSet<Str>,
+}
+
+impl UniqueSlugs {
+    /// Whether no slug was issued yet.
+    pub fn is_empty(&self) -> bool {
+        self.issued.is_empty()
+    }
+}
+
+#[scope]
+impl UniqueSlugs {
+    /// Creates a new tracker without any issued slugs.
+    #[func(constructor)]
+    pub fn construct(
+        /// The string between a duplicate slug and its number.
+        #[named]
+        #[default(Str::from("-"))]
+        separator: Str,
+    ) -> UniqueSlugs {
+        Self { separator, issued: BTreeSet::new() }
+    }
+
+    /// The number of slugs issued so far.
+    #[func(title = "Length")]
+    pub fn len(&self) -> usize {
+        self.issued.len()
+    }
+
+    /// Whether the given slug was already issued.
+    #[func]
+    pub fn contains(
+        &self,
+        /// The slug to look for.
+        slug: Str,
+    ) -> bool {
+        self.issued.contains(&slug)
+    }
+
+    /// Issues a slug, appending a number to it if it was issued before, and
+    /// returns the result.
+    #[func]
+    pub fn issue(
+        &mut self,
+        /// The slug to issue.
+        slug: Str,
+    ) -> Str {
+        let mut unique = slug.clone();
+        let mut n = 2;
+        while self.issued.contains(&unique) {
+            unique = eco_format!("{slug}{}{n}", self.separator).into();
+            n += 1;
+        }
+        self.issued.insert(unique.clone());
+        unique
+    }
+}
+
+impl Repr for UniqueSlugs {
+    fn repr(&self) -> EcoString {
+        eco_format!("unique-slugs(len: {})", self.issued.len())
+    }
+}
+
+impl DynamicRepr for UniqueSlugs {}
+
+/// Transliterate a Latin letter with diacritics to its uppercase base
+/// letters.
+///
+/// Covers the Latin-1 Supplement and Latin Extended-A blocks as well as the
+/// Romanian letters with comma below. Since this only looks at the first
+/// character of a grapheme cluster, letters that are composed of a base
+/// letter and combining marks are reduced to their base letter anyway.
+fn transliterate(c: char) -> Option<&'static str> {
+    Some(match c {
+        'À'..='Å' | 'à'..='å' | 'Ā'..='ą' => "A",
+        'Æ' | 'æ' => "AE",
+        'Ç' | 'ç' | 'Ć'..='č' => "C",
+        'Ð' | 'ð' | 'Ď'..='đ' => "D",
+        'È'..='Ë' | 'è'..='ë' | 'Ē'..='ě' => "E",
+        'Ĝ'..='ģ' => "G",
+        'Ĥ'..='ħ' => "H",
+        'Ì'..='Ï' | 'ì'..='ï' | 'Ĩ'..='ı' => "I",
+        'Ĳ' | 'ĳ' => "IJ",
+        'Ĵ' | 'ĵ' => "J",
+        'Ķ'..='ĸ' => "K",
+        'Ĺ'..='ł' => "L",
+        'Ñ' | 'ñ' | 'Ń'..='ŋ' => "N",
+        'Ò'..='Ö' | 'Ø' | 'ò'..='ö' | 'ø' | 'Ō'..='ő' => "O",
+        'Œ' | 'œ' => "OE",
+        'Ŕ'..='ř' => "R",
+        'ß' => "SS",
+        'Ś'..='š' | 'ſ' | 'Ș' | 'ș' => "S",
+        'Þ' | 'þ' => "TH",
+        'Ţ'..='ŧ' | 'Ț' | 'ț' => "T",
+        'Ù'..='Ü' | 'ù'..='ü' | 'Ũ'..='ų' => "U",
+        'Ŵ' | 'ŵ' => "W",
+        'Ý' | 'ý' | 'ÿ' | 'Ŷ'..='Ÿ' => "Y",
+        'Ź'..='ž' => "Z",
+        _ => return None,
+    })
+}
@@ -60,7 +60,7 @@ pub struct ImageElem {
         let Spanned { v: path, span } =
             args.expect::<Spanned<EcoString>>("path to image file")?;
         let id = span.resolve_path(&path).at(span)?;
-        let data = engine.world.file(id).at(span)?;
+        let data = engine.file(id).at(span)?;
         path
     )]
     #[borrowed]
@@ -69,7 +69,14 @@ pub struct ImageElem {
     /// The raw file data.
     #[internal]
     #[required]
-    #[parse(Readable::Bytes(data))]
+    #[parse(
+        let data = Readable::Bytes(data);
+        if engine.sink.dry_run() {
+            let format = args.clone().named("format")?.unwrap_or_default();
+            check_image(&path, &data, format).at(args.span)?;
+        }
+        data
+    )]
     pub data: Readable,
 
     /// The image's format. Detected automatically by default.
@@ -115,6 +122,8 @@ impl ImageElem {
     /// ```
     #[func(title = "Decode Image")]
     pub fn decode(
+        /// The engine.
+        engine: &mut Engine,
         /// The call span of this function.
         span: Span,
         /// The data to decode as an image. Can be a string for SVGs.
@@ -135,6 +144,9 @@ impl ImageElem {
         #[named]
         fit: Option<ImageFit>,
     ) -> StrResult<Content> {
+        if engine.sink.dry_run() {
+            check_image("", &data, format.unwrap_or_default())?;
+        }
         let mut elem = ImageElem::new(EcoString::new(), data);
         if let Some(format) = format {
             elem.push_format(format);
@@ -283,6 +295,26 @@ fn determine_format(path: &str, data: &Readable) -> StrResult<ImageFormat> {
     })
 }
 
+/// Check an image without decoding it, as is done in a dry run.
+///
+/// Only the format and, for raster images, the header are validated. If the
+/// format can't be determined, nothing is checked, as it may still be
+/// configured with a set rule.
+fn check_image(path: &str, data: &Readable, format: Smart<ImageFormat>) -> StrResult<()> {
+    let format = match format {
+        Smart::Custom(format) => format,
+        Smart::Auto => match determine_format(path, data) {
+            Ok(format) => format,
+            Err(_) => return Ok(()),
+        },
+    };
+
+    match format {
+        ImageFormat::Raster(format) => RasterImage::check(&data.clone().into(), format),
+        ImageFormat::Vector(_) => Ok(()),
+    }
+}
+
 /// How an image should adjust itself to a given area,
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
 pub enum ImageFit {
@@ -63,6 +63,18 @@ impl RasterImage {
         Ok(Self(Arc::new(Repr { data, format, dynamic, icc, dpi })))
     }
 
+    /// Check that the data starts with a valid header of the format without
+    /// decoding the pixel data.
+    pub fn check(data: &Bytes, format: RasterFormat) -> StrResult<()> {
+        let cursor = io::Cursor::new(data);
+        match format {
+            RasterFormat::Jpg => JpegDecoder::new(cursor).map(drop),
+            RasterFormat::Png => PngDecoder::new(cursor).map(drop),
+            RasterFormat::Gif => GifDecoder::new(cursor).map(drop),
+        }
+        .map_err(format_image_error)
+    }
+
     /// The raw image data.
     pub fn data(&self) -> &Bytes {
         &self.0.data
@@ -284,4 +296,24 @@ mod tests {
         test("images/tiger.jpg", RasterFormat::Jpg, 72.0);
         test("images/graph.png", RasterFormat::Png, 144.0);
     }
+
+    #[test]
+    fn test_image_check() {
+        let mut png = vec![];
+        image::RgbImage::new(16, 16)
+            .write_to(&mut std::io::Cursor::new(&mut png), image::ImageOutputFormat::Png)
+            .unwrap();
+
+        // Corrupt the pixel data, but keep the header intact.
+        let start = png.windows(4).position(|w| w == b"IDAT").unwrap() + 4;
+        png[start..start + 8].fill(0xFF);
+        let bytes = Bytes::from(png);
+        assert!(RasterImage::check(&bytes, RasterFormat::Png).is_ok());
+        assert!(RasterImage::new(bytes, RasterFormat::Png).is_err());
+
+        // A broken header is detected, though.
+        let error =
+            RasterImage::check(&Bytes::from_static(b"\x89PNG"), RasterFormat::Png);
+        assert!(error.unwrap_err().starts_with("failed to decode image"));
+    }
 }
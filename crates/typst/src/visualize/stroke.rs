@@ -2,8 +2,8 @@ use ecow::EcoString;
 
 use crate::diag::{HintedStrResult, SourceResult};
 use crate::foundations::{
-    cast, dict, func, scope, ty, Args, Cast, Dict, Fold, FromValue, NoneValue, Repr,
-    Resolve, Smart, StyleChain, Value,
+    cast, dict, func, scope, ty, Args, Cast, Dict, DynamicRepr, Fold, FromValue,
+    NoneValue, Repr, Resolve, Smart, StyleChain, Value,
 };
 use crate::layout::{Abs, Length};
 use crate::utils::{Numeric, Scalar};
@@ -330,6 +330,8 @@ impl<T: Numeric + Repr> Repr for Stroke<T> {
     }
 }
 
+impl DynamicRepr for Stroke {}
+
 impl<T: Numeric + Fold> Fold for Stroke<T> {
     fn fold(self, outer: Self) -> Self {
         Self {
@@ -0,0 +1,67 @@
+//! Benchmarks for Typst code that is expected to be fast.
+//!
+//! Run them with `cargo bench --workspace --bench benches`.
+
+#[allow(unused)] // only the default world is used here
+mod world;
+
+use std::time::{Duration, Instant};
+
+use comemo::Track;
+use typst::eval::{eval_string, EvalMode};
+use typst::foundations::{Scope, Value};
+use typst::syntax::{Source, Span};
+use typst::World;
+
+use crate::world::TestWorld;
+
+/// How many elements the benchmarks build.
+const ELEMENTS: usize = 100_000;
+
+fn main() {
+    let world = TestWorld::new(Source::detached(""), false, false);
+
+    let push = run(
+        &world,
+        "array push",
+        &format!(
+            "let array = ()
+             for i in range({ELEMENTS}) {{ array.push(i) }}
+             array"
+        ),
+    );
+
+    let builder = run(
+        &world,
+        "builder push",
+        &format!(
+            "let b = builder()
+             for i in range({ELEMENTS}) {{ b.push(i) }}
+             b.finish()"
+        ),
+    );
+
+    println!("builder / array push: {:.2}", builder.as_secs_f64() / push.as_secs_f64());
+}
+
+/// Evaluate the code once, check that it builds the expected array, and print
+/// how long it took.
+fn run(world: &TestWorld, name: &str, code: &str) -> Duration {
+    let start = Instant::now();
+    let value = eval_string(
+        (world as &dyn World).track(),
+        code,
+        Span::detached(),
+        EvalMode::Code,
+        Scope::new(),
+    )
+    .unwrap_or_else(|errors| panic!("{name} failed: {errors:?}"));
+    let elapsed = start.elapsed();
+
+    let Value::Array(array) = value else { panic!("{name} didn't build an array") };
+    assert_eq!(array.len(), ELEMENTS);
+    assert_eq!(array.last(), Ok(Value::Int(ELEMENTS as i64 - 1)));
+
+    println!("{name}: {elapsed:?}");
+    elapsed
+}
@@ -25,6 +25,12 @@ pub struct Test {
     pub source: Source,
     pub notes: Vec<Note>,
     pub large: bool,
+    pub strict: bool,
+    pub pedantic: bool,
+    pub chunked: bool,
+    pub scoped: bool,
+    pub dry_run: bool,
+    pub prefetch: bool,
 }
 
 impl Display for Test {
@@ -253,6 +259,17 @@ impl<'a> Parser<'a> {
 
             let text = self.s.from(start);
             let large = text.starts_with("// LARGE");
+            let strict = text.starts_with("// STRICT");
+            let pedantic = text.starts_with("// PEDANTIC");
+            let header = |flag| {
+                text.lines()
+                    .map_while(|line| line.strip_prefix("// "))
+                    .any(|line| line == flag)
+            };
+            let chunked = header("CHUNKED");
+            let scoped = header("SCOPED");
+            let dry_run = header("DRY-RUN");
+            let prefetch = header("PREFETCH");
             if large {
                 self.collector.large.insert(name.clone());
             }
@@ -280,7 +297,19 @@ impl<'a> Parser<'a> {
                 }
             }
 
-            self.collector.tests.push(Test { pos, name, source, notes, large });
+            self.collector.tests.push(Test {
+                pos,
+                name,
+                source,
+                notes,
+                large,
+                strict,
+                pedantic,
+                chunked,
+                scoped,
+                dry_run,
+                prefetch,
+            });
         }
     }
 
@@ -2,14 +2,17 @@ use std::fmt::Write;
 use std::ops::Range;
 use std::path::Path;
 
+use comemo::Track;
 use ecow::eco_vec;
 use tiny_skia as sk;
-use typst::diag::{SourceDiagnostic, Warned};
-use typst::foundations::Smart;
+use typst::diag::{SourceDiagnostic, SourceResult, Warned};
+use typst::engine::{Route, Sink, Traced};
+use typst::foundations::{Module, Smart};
 use typst::layout::{Abs, Frame, FrameItem, Page, Transform};
 use typst::model::Document;
+use typst::utils::hash128;
 use typst::visualize::Color;
-use typst::WorldExt;
+use typst::{World, WorldExt};
 
 use crate::collect::{FileSize, NoteKind, Test};
 use crate::world::TestWorld;
@@ -62,7 +65,7 @@ impl<'a> Runner<'a> {
     fn new(test: &'a Test) -> Self {
         Self {
             test,
-            world: TestWorld::new(test.source.clone()),
+            world: TestWorld::new(test.source.clone(), test.strict, test.pedantic),
             seen: vec![false; test.notes.len()],
             result: TestResult {
                 errors: String::new(),
@@ -90,6 +93,18 @@ impl<'a> Runner<'a> {
         }
 
         self.check_document(doc.as_ref());
+        if self.test.chunked {
+            self.check_chunking();
+        }
+        if self.test.scoped {
+            self.check_scope_only();
+        }
+        if self.test.dry_run {
+            self.check_dry_run(&errors);
+        }
+        if self.test.prefetch {
+            self.check_prefetch(doc.as_ref(), &errors, &warnings);
+        }
 
         for error in &errors {
             self.check_diagnostic(NoteKind::Error, error);
@@ -105,6 +120,79 @@ impl<'a> Runner<'a> {
         self.result
     }
 
+    /// Check that evaluating the top-level markup in cached chunks results in
+    /// the same module as evaluating it directly.
+    fn check_chunking(&mut self) {
+        let unchunked = eval_main(&self.world, true).ok().map(|module| hash128(&module));
+        let chunked = eval_main(&self.world.clone().chunked(), true)
+            .ok()
+            .map(|module| hash128(&module));
+        if chunked != unchunked {
+            log!(self, "chunked evaluation resulted in a different module");
+        }
+    }
+
+    /// Check that evaluating the main file only for its definitions results in
+    /// the same bindings as evaluating it fully.
+    fn check_scope_only(&mut self) {
+        let Ok(module) = eval_main(&self.world, true) else { return };
+        let scoped = eval_main(&self.world, false);
+        if scoped.map(|scoped| hash128(scoped.scope())) != Ok(hash128(module.scope())) {
+            log!(self, "scope-only evaluation resulted in different bindings");
+        }
+    }
+
+    /// Check that a dry run only reports errors that compilation reports, too.
+    fn check_dry_run(&mut self, errors: &[SourceDiagnostic]) {
+        let Err(checked) = typst::check(&self.world).output else { return };
+        for error in &checked {
+            if !errors
+                .iter()
+                .any(|other| other.span == error.span && other.message == error.message)
+            {
+                log!(self, "dry run reported an error that compilation didn't");
+                log!(self, "  {}", error.message);
+            }
+        }
+    }
+
+    /// Check that prefetching the imported files results in the same output and
+    /// diagnostics as compiling without it.
+    fn check_prefetch(
+        &mut self,
+        document: Option<&Document>,
+        errors: &[SourceDiagnostic],
+        warnings: &[SourceDiagnostic],
+    ) {
+        let Warned { output, warnings: prefetched_warnings } =
+            typst::compile(&self.world.clone().prefetching());
+        let (prefetched, prefetched_errors) = match output {
+            Ok(doc) => (Some(doc), eco_vec![]),
+            Err(errors) => (None, errors),
+        };
+
+        let pages = |document: Option<&Document>| {
+            document.map(|doc| {
+                doc.pages.iter().map(|page| hash128(&page.frame)).collect::<Vec<_>>()
+            })
+        };
+        if pages(document) != pages(prefetched.as_ref()) {
+            log!(self, "prefetching resulted in a different document");
+        }
+
+        let diagnostics = |diags: &[SourceDiagnostic]| {
+            diags
+                .iter()
+                .map(|diag| (diag.span, diag.message.clone()))
+                .collect::<Vec<_>>()
+        };
+        if diagnostics(errors) != diagnostics(&prefetched_errors)
+            || diagnostics(warnings) != diagnostics(&prefetched_warnings)
+        {
+            log!(self, "prefetching resulted in different diagnostics");
+        }
+    }
+
     /// Handle errors that weren't annotated.
     fn handle_not_annotated(&mut self) {
         if !self.not_annotated.is_empty() {
@@ -437,3 +525,18 @@ fn to_sk_transform(transform: &Transform) -> sk::Transform {
         ty.to_pt() as f32,
     )
 }
+
+/// Evaluate the main file of a world, fully or only for its definitions.
+fn eval_main(world: &TestWorld, content: bool) -> SourceResult<Module> {
+    let world = world as &dyn World;
+    let traced = Traced::default();
+    let mut sink = Sink::new();
+    let eval = if content { typst::eval::eval } else { typst::eval::eval_scope };
+    eval(
+        world.track(),
+        traced.track(),
+        sink.track_mut(),
+        Route::default().track(),
+        &world.main(),
+    )
+}
@@ -8,19 +8,26 @@ use std::sync::OnceLock;
 use once_cell::sync::Lazy;
 use parking_lot::Mutex;
 use typst::diag::{bail, FileError, FileResult, StrResult};
-use typst::foundations::{func, Bytes, Datetime, NoneValue, Repr, Smart, Value};
+use typst::foundations::{
+    func, Bytes, Datetime, NativeFunc, NoneValue, Repr, Smart, Value,
+};
 use typst::layout::{Abs, Margin, PageElem};
+use typst::loading::DirEntry;
 use typst::syntax::{FileId, Source};
 use typst::text::{Font, FontBook, TextElem, TextSize};
 use typst::utils::LazyHash;
 use typst::visualize::Color;
-use typst::{Library, World};
+use typst::{Library, LibraryBuilder, World};
 
 /// A world that provides access to the tests environment.
 #[derive(Clone)]
 pub struct TestWorld {
     main: Source,
     base: &'static TestBase,
+    strict: bool,
+    pedantic: bool,
+    chunked: bool,
+    prefetch: bool,
 }
 
 impl TestWorld {
@@ -28,15 +35,46 @@ impl TestWorld {
     ///
     /// This is cheap because the shared base for all test runs is lazily
     /// initialized just once.
-    pub fn new(source: Source) -> Self {
+    pub fn new(source: Source, strict: bool, pedantic: bool) -> Self {
         static BASE: Lazy<TestBase> = Lazy::new(TestBase::default);
-        Self { main: source, base: &*BASE }
+        Self {
+            main: source,
+            base: &*BASE,
+            strict,
+            pedantic,
+            chunked: false,
+            prefetch: false,
+        }
+    }
+
+    /// The same world, but with a library that evaluates top-level markup in
+    /// cached chunks. Only supported with the default library.
+    pub fn chunked(self) -> Self {
+        assert!(!self.strict && !self.pedantic);
+        Self { chunked: true, ..self }
+    }
+
+    /// The same world, but with a library that evaluates the imported files in
+    /// parallel before compilation. Only supported with the default library.
+    pub fn prefetching(self) -> Self {
+        assert!(!self.strict && !self.pedantic);
+        Self { prefetch: true, ..self }
     }
 }
 
 impl World for TestWorld {
     fn library(&self) -> &LazyHash<Library> {
-        &self.base.library
+        if self.strict {
+            &self.base.strict_library
+        } else if self.pedantic {
+            &self.base.pedantic_library
+        } else if self.chunked {
+            &self.base.chunked_library
+        } else if self.prefetch {
+            &self.base.prefetch_library
+        } else {
+            &self.base.library
+        }
     }
 
     fn book(&self) -> &LazyHash<FontBook> {
@@ -66,6 +104,10 @@ impl World for TestWorld {
     fn today(&self, _: Option<i64>) -> Option<Datetime> {
         Some(Datetime::from_ymd(1970, 1, 1).unwrap())
     }
+
+    fn list(&self, id: FileId) -> Option<FileResult<Vec<DirEntry>>> {
+        Some(system_path(id).and_then(|path| list(&path)))
+    }
 }
 
 impl TestWorld {
@@ -82,6 +124,10 @@ impl TestWorld {
 /// Shared foundation of all test worlds.
 struct TestBase {
     library: LazyHash<Library>,
+    strict_library: LazyHash<Library>,
+    pedantic_library: LazyHash<Library>,
+    chunked_library: LazyHash<Library>,
+    prefetch_library: LazyHash<Library>,
     book: LazyHash<FontBook>,
     fonts: Vec<Font>,
     slots: Mutex<HashMap<FileId, FileSlot>>,
@@ -95,7 +141,17 @@ impl Default for TestBase {
             .collect();
 
         Self {
-            library: LazyHash::new(library()),
+            library: LazyHash::new(library(Library::builder())),
+            strict_library: LazyHash::new(library(Library::builder().with_strict(true))),
+            pedantic_library: LazyHash::new(library(
+                Library::builder().with_pedantic(true),
+            )),
+            chunked_library: LazyHash::new(library(
+                Library::builder().with_chunked(true),
+            )),
+            prefetch_library: LazyHash::new(library(
+                Library::builder().with_prefetch(true),
+            )),
             book: LazyHash::new(FontBook::from_fonts(&fonts)),
             fonts,
             slots: Mutex::new(HashMap::new()),
@@ -168,12 +224,30 @@ fn read(path: &Path) -> FileResult<Cow<'static, [u8]>> {
     }
 }
 
+/// List the entries of a directory.
+fn list(path: &Path) -> FileResult<Vec<DirEntry>> {
+    let f = |e| FileError::from_io(e, path);
+    let mut entries = vec![];
+    for entry in fs::read_dir(path).map_err(f)? {
+        let entry = entry.map_err(f)?;
+        entries.push(DirEntry {
+            name: entry.file_name().to_string_lossy().into(),
+            is_dir: entry.file_type().map_err(f)?.is_dir(),
+        });
+    }
+    Ok(entries)
+}
+
 /// The extended standard library for testing.
-fn library() -> Library {
+fn library(builder: LibraryBuilder) -> Library {
     // Set page width to 120pt with 10pt margins, so that the inner page is
     // exactly 100pt wide. Page height is unbounded and font size is 10pt so
     // that it multiplies to nice round numbers.
-    let mut lib = Library::default();
+    let mut lib = builder
+        .with_function("test", test::func())
+        .with_function("test-repr", test_repr::func())
+        .with_function("print", print::func())
+        .build();
 
     #[func]
     fn test(lhs: Value, rhs: Value) -> StrResult<NoneValue> {
@@ -205,10 +279,7 @@ fn library() -> Library {
         NoneValue
     }
 
-    // Hook up helpers into the global scope.
-    lib.global.scope_mut().define_func::<test>();
-    lib.global.scope_mut().define_func::<test_repr>();
-    lib.global.scope_mut().define_func::<print>();
+    // Hook up colors into the global scope.
     lib.global
         .scope_mut()
         .define("conifer", Color::from_u8(0x9f, 0xEB, 0x52, 0xFF));
@@ -62,8 +62,25 @@
 }
 
 --- spread-dict-into-array ---
-// Error: 9-17 cannot spread dictionary into array
-#(1, 2, ..(a: 1))
+// Spreading a dictionary yields its key-value pairs in order.
+#test((1, ..(b: 2, a: 3)), (1, ("b", 2), ("a", 3)))
+#test((..(:)), ())
+
+--- spread-str-into-array ---
+// Spreading a string yields its grapheme clusters.
+#test((..""), ())
+#test((.."abc", "d"), ("a", "b", "c", "d"))
+#test((.."🏳️‍🌈a̐"), ("🏳️‍🌈", "a̐"))
+
+--- spread-args-into-array ---
+// Error: 19-25 cannot spread arguments into array
+// Hint: 19-25 use `.pos()` to spread the positional arguments
+#let f(..args) = (..args)
+#f(1, 2)
+
+--- spread-content-into-array ---
+// Error: 3-9 cannot spread content into array
+#(..[ab])
 
 --- array-len ---
 // Test the `len` method.
@@ -127,27 +144,77 @@
 #test(range(4), (0, 1, 2, 3))
 #test(range(1, 4), (1, 2, 3))
 #test(range(-4, 2), (-4, -3, -2, -1, 0, 1))
-#test(range(10, 5), ())
+#test(range(5, 5), ())
 #test(range(10, step: 3), (0, 3, 6, 9))
 #test(range(1, 4, step: 1), (1, 2, 3))
 #test(range(1, 8, step: 2), (1, 3, 5, 7))
 #test(range(5, 2, step: -1), (5, 4, 3))
 #test(range(10, 0, step: -3), (10, 7, 4, 1))
 
+--- array-range-descending ---
+#test(range(3, 0, step: -1), (3, 2, 1))
+#test(range(-1, -4, step: -2), (-1, -3))
+#test(range(3, 0, step: -1, inclusive: true), (3, 2, 1, 0))
+
+--- array-range-inclusive ---
+#test(range(1, 4, inclusive: true), (1, 2, 3, 4))
+#test(range(0, 7, step: 3, inclusive: true), (0, 3, 6))
+#test(range(2, 2, inclusive: true), (2,))
+#test(range(9223372036854775806, 9223372036854775807, inclusive: true).len(), 2)
+
+--- array-range-wrong-direction ---
+// Error: 2-14 step does not move from the start towards the end
+// Hint: 2-14 use a negative step to count down
+#range(10, 5)
+
+--- array-range-wrong-direction-negative ---
+// Error: 20-22 step does not move from the start towards the end
+// Hint: 20-22 use a positive step to count up
+#range(0, 5, step: -1)
+
+--- array-range-float-step ---
+#test(range(0.0, 1.0, step: 0.25), (0.0, 0.25, 0.5, 0.75))
+#test(range(0, 1, step: 0.25, inclusive: true), (0.0, 0.25, 0.5, 0.75, 1.0))
+#test(range(1, 0, step: -0.5), (1.0, 0.5))
+#test(range(0, 1, step: 0.3), (0.0, 0.3, 0.6, 0.8999999999999999))
+#test(range(0.5, 3), (0.5, 1.5, 2.5))
+#test(range(0, 1, step: 0.1, inclusive: true).last(), 1.0)
+#test(range(0, 1, step: 0.1).at(3), 3 * 0.1)
+
+--- array-range-float-count ---
+#test(range(0, 1, count: 4), (0.0, 0.25, 0.5, 0.75))
+#test(range(0, 1, count: 5, inclusive: true), (0.0, 0.25, 0.5, 0.75, 1.0))
+#test(range(0, 0.3, count: 4, inclusive: true).last(), 0.3)
+#test(range(0, 1, count: 1, inclusive: true), (0.0,))
+#test(range(0, 1, count: 0), ())
+
+--- array-range-float-cap ---
+// Error: 22-25 range would contain more than 1000000 values
+// Hint: 22-25 use a larger step
+#range(0, 1e6, step: 0.5)
+
+--- array-range-count-cap ---
+// Error: 21-28 range would contain more than 1000000 values
+#range(0, 1, count: 1000001)
+
+--- array-range-step-and-count ---
+// Error: 20-23 cannot specify both step and count
+#range(0, 1, step: 0.1, count: 10)
+
 --- array-range-end-missing ---
 // Error: 2-9 missing argument: end
 #range()
 
---- array-range-float-invalid ---
-// Error: 11-14 expected integer, found float
-#range(1, 2.0)
+--- array-range-bad-type ---
+// Error: 11-16 expected integer or float, found string
+#range(1, "two")
 
 --- array-range-bad-step-type ---
-// Error: 17-22 expected integer, found string
+// Error: 17-22 expected integer or float, found string
 #range(4, step: "one")
 
 --- array-range-step-zero ---
-// Error: 18-19 number must not be zero
+// Error: 18-19 step must not be zero
 #range(10, step: 0)
 
 --- array-bad-method-lvalue ---
@@ -213,6 +280,107 @@
 #let numbers = ()
 #numbers.insert()
 
+--- array-splice ---
+// Test the `splice` method.
+#{
+  let array = (0, 1, 2, 3, 4)
+  array.splice(1, 3, ("a", "b", "c"))
+  test(array, (0, "a", "b", "c", 3, 4))
+  array.splice(1, 4, ("x",))
+  test(array, (0, "x", 3, 4))
+  array.splice(-2, -1)
+  test(array, (0, "x", 4))
+  array.splice(3, 3, (5, 6))
+  test(array, (0, "x", 4, 5, 6))
+  array.splice(0, 0, (-1,))
+  test(array, (-1, 0, "x", 4, 5, 6))
+}
+
+--- array-splice-full-range ---
+#{
+  let array = (1, 2, 3)
+  array.splice(0, 3, ())
+  test(array, ())
+  array.splice(0, 0, (4, 5))
+  test(array, (4, 5))
+}
+
+--- array-splice-out-of-bounds ---
+// Error: 3:3-3:27 array index out of bounds (index: 4, len: 3)
+#{
+  let array = (1, 2, 3)
+  array.splice(1, 4, (0,))
+}
+
+--- array-splice-reversed ---
+// Error: 3:3-3:21 array splice end is before start (start: 2, end: 1)
+#{
+  let array = (1, 2, 3)
+  array.splice(2, 1)
+}
+
+--- array-truncate ---
+// Test the `truncate` method.
+#{
+  let array = (1, 2, 3, 4, 5)
+  array.truncate(5)
+  test(array, (1, 2, 3, 4, 5))
+  array.truncate(3)
+  test(array, (1, 2, 3))
+  array.truncate(-1)
+  test(array, (1, 2))
+  array.truncate(0)
+  test(array, ())
+}
+
+--- array-truncate-out-of-bounds ---
+// Error: 3:3-3:20 array index out of bounds (index: 4, len: 3)
+#{
+  let array = (1, 2, 3)
+  array.truncate(4)
+}
+
+--- array-retain ---
+// Test the `retain` method.
+#{
+  let array = (7, 3, 2, 5, 1)
+  array.retain(x => x < 5)
+  test(array, (3, 2, 1))
+  array.retain(calc.even)
+  test(array, (2,))
+  array.retain(x => false)
+  test(array, ())
+}
+
+--- array-retain-nested ---
+#{
+  let data = (values: (1, 2, 3, 4))
+  data.values.retain(calc.odd)
+  test(data, (values: (1, 3)))
+}
+
+--- array-retain-not-bool ---
+#{
+  let array = (1, 2, 3)
+  // Error: 16-17 expected boolean, found integer
+  array.retain(x => x)
+}
+
+--- array-retain-error ---
+#{
+  let array = (1, 2, 3)
+  // Error: 33-40 panicked
+  array.retain(x => if x == 2 { panic() } else { true })
+}
+
+--- array-retain-captured ---
+#let array = (1, 2, 3)
+#let f() = {
+  // Error: 3-8 variables from outside the function are read-only and cannot be modified
+  array.retain(calc.odd)
+}
+#f()
+
 --- array-slice ---
 // Test the `slice` method.
 #test((1, 2, 3, 4).slice(2), (3, 4))
@@ -249,6 +417,49 @@
 #test(().map(x => x * 2), ())
 #test((2, 3).map(x => x * 2), (4, 6))
 
+--- array-field ---
+// Test the `field` method.
+#test(().field("a"), ())
+#test(((a: 1), (a: 2, b: 3)).field("a"), (1, 2))
+#test(((a: (1, 2)), (a: none)).field("a"), ((1, 2), none))
+#test(((a: 1, b: 2),).field("b"), (2,))
+
+--- array-field-with-default ---
+#test(((a: 1), (b: 2)).field("a", default: 0), (1, 0))
+#test(((b: 1), (b: 2)).field("a", default: none), (none, none))
+#test(((a: 1), (:)).field("a", default: (x: 1)), (1, (x: 1)))
+#test(().field("a", default: 5), ())
+
+--- array-field-heterogeneous ---
+#let rows = ((name: "A", age: 3), (name: "B"), (age: 5, name: "C"))
+#test(rows.field("name"), ("A", "B", "C"))
+#test(rows.field("age", default: auto), (3, auto, 5))
+
+--- array-field-missing-key ---
+// Error: 2-37 dictionary at index 2 does not contain key "a" and no default value was specified
+#((a: 1), (a: 2), (b: 3)).field("a")
+
+--- array-field-missing-key-first ---
+// Error: 2-21 dictionary at index 0 does not contain key "key" and no default value was specified
+#((:),).field("key")
+
+--- array-field-not-dict ---
+// Error: 2-32 expected dictionary at index 1, found integer
+#((a: 1), 2, (a: 3)).field("a")
+
+--- array-field-not-dict-with-default ---
+// Error: 2-41 expected dictionary at index 1, found array
+#((a: 1), (1, 2)).field("a", default: 0)
+
+--- array-field-not-dict-before-missing-key ---
+// Items are type-checked before any key is looked up.
+// Error: 2-34 expected dictionary at index 2, found string
+#((b: 1), (a: 2), "a").field("a")
+
+--- array-field-bad-key-type ---
+// Error: 18-19 expected string, found integer
+#((a: 1),).field(1)
+
 --- array-fold ---
 // Test the `fold` method.
 #test(().fold("hi", grid), "hi")
@@ -292,10 +503,14 @@
 
 --- array-join-bad-values ---
 // Error: 2-22 cannot join boolean with boolean
+// Hint: 2-22 use `str` or `repr` to convert values, or `joined` to join them with a separator
+// Hint: 2-22 unlike joining, the `+` operator can also add numbers
 #(true, false).join()
 
 --- array-join-bad-separator ---
 // Error: 2-20 cannot join string with integer
+// Hint: 2-20 use `str` or `repr` to convert values, or `joined` to join them with a separator
+// Hint: 2-20 unlike joining, the `+` operator can also add numbers
 #("a", "b").join(1)
 
 --- array-join-content ---
@@ -531,3 +746,72 @@
 --- array-reduce-unexpected-argument ---
 // Error: 19-21 unexpected argument
 #(1, 2, 3).reduce(() => none)
+
+--- array-group-by ---
+#let people = (
+  (name: "Anna", city: "Berlin"),
+  (name: "Ben", city: "Paris"),
+  (name: "Clara", city: "Berlin"),
+  (name: "Dan", city: "Rome"),
+)
+#let groups = people.group-by(p => p.city)
+#test(groups.keys(), ("Berlin", "Paris", "Rome"))
+#test(groups.Berlin.map(p => p.name), ("Anna", "Clara"))
+#test(groups.Paris.map(p => p.name), ("Ben",))
+#test(().group-by(x => x), (:))
+
+--- array-group-by-int-and-bool-keys ---
+#test((1, 2, 3, 4, 5).group-by(calc.odd), ("true": (1, 3, 5), "false": (2, 4)))
+#test((1, 12, 7, 15).group-by(x => calc.rem(x, 2)), ("1": (1, 7, 15), "0": (12,)))
+#test((1, "1").group-by(x => x), ("1": (1, "1")))
+
+--- array-group-by-bad-key ---
+// Error: 25-26 expected string, integer, or boolean as group key, found array
+// Hint: 25-26 the key function returned this for the item at index 1
+#("a", (1, 2)).group-by(x => x)
+
+--- array-group-by-key-error ---
+// Error: 23-29 cannot add integer and string
+#(1, 2).group-by(x => x + "")
+
+--- array-group-by-key-error-trace ---
+// An error in a key function that is defined elsewhere is traced back
+// through the `group-by` call.
+#let key(x) = x + ""
+#let result = catch(() => (1, 2).group-by(key))
+#test(result.message, "cannot add integer and string")
+#test(result.trace, ("error occurred in this call of function `group-by`",))
+
+// An error in an imported function that the key function calls is traced
+// through both calls.
+#import "/tests/suite/scripting/module.typ": push
+#let key(x) = push(x)
+#let result = catch(() => ("a",).group-by(key))
+#test(result.message, "cannot add string and integer")
+#test(result.trace, (
+  "error occurred in this call of function `push`",
+  "error occurred in this call of function `group-by`",
+))
+
+--- array-chunk-by ---
+#let dates = (
+  datetime(year: 2024, month: 1, day: 3),
+  datetime(year: 2024, month: 1, day: 20),
+  datetime(year: 2024, month: 2, day: 1),
+  datetime(year: 2024, month: 3, day: 9),
+  datetime(year: 2024, month: 3, day: 30),
+)
+#let months = dates.chunk-by(d => d.month())
+#test(months.len(), 3)
+#test(months.map(c => c.len()), (2, 1, 2))
+#test(months.map(c => c.first().month()), (1, 2, 3))
+
+--- array-chunk-by-not-adjacent ---
+#test((1, 1, 2, 1).chunk-by(x => x), ((1, 1), (2,), (1,)))
+#test((1, 3, 2, 4, 5).chunk-by(calc.odd), ((1, 3), (2, 4), (5,)))
+#test(().chunk-by(x => x), ())
+#test((1,).chunk-by(x => x), ((1,),))
+
+--- array-chunk-by-key-error ---
+// Error: 23-29 cannot add integer and string
+#(1, 2).chunk-by(x => x + "")
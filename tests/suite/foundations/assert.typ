@@ -33,6 +33,58 @@
 // Error: 2-57 inequality assertion failed: must be different from 11
 #assert.ne(11, 11, message: "must be different from 11")
 
+--- assert-eq-fail-dict ---
+// Test the differences of dictionaries.
+// Error: 2-51 equality assertion failed: value (a: 1, b: 2, c: 3) was not equal to (a: 1, b: 3, d: 4)
+// Hint: 2-51 at `.b`: 2 is not equal to 3
+// Hint: 2-51 key `c` only exists in the left value
+// Hint: 2-51 key `d` only exists in the right value
+#assert.eq((a: 1, b: 2, c: 3), (a: 1, b: 3, d: 4))
+
+--- assert-eq-fail-array ---
+// Test the differences of arrays.
+// Error: 2-30 equality assertion failed: value (1, 2, 3) was not equal to (1, 5)
+// Hint: 2-30 the arrays have lengths 3 and 2
+// Hint: 2-30 at `.at(1)`: 2 is not equal to 5
+#assert.eq((1, 2, 3), (1, 5))
+
+--- assert-eq-fail-str ---
+// Test the differences of strings.
+// Error: 2-29 equality assertion failed: value "hello" was not equal to "help!"
+// Hint: 2-29 the strings differ from grapheme 3 on: "lo" and "p!"
+#assert.eq("hello", "help!")
+
+--- assert-eq-fail-nested ---
+// Test the differences of deeply nested values.
+// Error: 2-97 equality assertion failed: value (list: ((name: "a", "my tags": (1, 2)),)) was not equal to (list: ((name: "a", "my tags": (1, 3)),))
+// Hint: 2-97 at `.list.at(0).at("my tags").at(1)`: 2 is not equal to 3
+#assert.eq((list: ((name: "a", "my tags": (1, 2)),)), (list: ((name: "a", "my tags": (1, 3)),)))
+
+--- assert-eq-fail-message-func ---
+// Test a message computed by a function.
+// Error: 2-44 equality assertion failed: computed
+#assert.eq(1, 2, message: () => "computed")
+
+--- assert-eq-fail-message-func-invalid ---
+// Test a message function returning something else than a string.
+// Error: 27-29 expected string, found integer
+#assert.eq(1, 2, message: () => 1)
+
+--- assert-ne-fail-message-func ---
+// Test a message computed by a function.
+// Error: 2-44 inequality assertion failed: same
+#assert.ne("a", "a", message: () => "same")
+
+--- assert-eq-message-func-lazy ---
+// The message function is only called on failure.
+#assert.eq(1, 1, message: () => panic("called"))
+#assert.ne(1, 2, message: () => panic("called"))
+
+--- assert-eq-int-float ---
+// Test that integers and floats compare like with `==`.
+#assert.eq(1, 1.0)
+#assert.eq((a: (1, 2)), (a: (1.0, 2.0)))
+
 --- assert-success ---
 // Test successful assertions.
 #assert(5 > 3)
@@ -0,0 +1,83 @@
+// Test array and string builders.
+
+--- builder-basic ---
+#let b = builder()
+#test(b.len(), 0)
+#b.push(1)
+#b.push("two")
+#b.extend((3, 4))
+#test(b.len(), 4)
+#test(repr(b), "builder(array, len: 4)")
+#test(b.finish(), (1, "two", 3, 4))
+
+--- builder-str ---
+#let s = builder(str)
+#s.push-str("Hello")
+#s.push-str(", ")
+#s.push-str("world!")
+#test(s.len(), 13)
+#test(s.finish(), "Hello, world!")
+
+--- builder-many ---
+// Build a large array in a loop.
+#let b = builder()
+#for i in range(100000) {
+  b.push(i)
+}
+#let array = b.finish()
+#test(array.len(), 100000)
+#test(array.last(), 99999)
+
+--- builder-extend-with-finished ---
+#let a = builder()
+#a.push(1)
+#a.push(2)
+#let b = builder()
+#b.push(0)
+#b.extend(a.finish())
+#test(b.finish(), (0, 1, 2))
+
+--- builder-copy-is-independent ---
+#let a = builder()
+#a.push(1)
+#let b = a
+#b.push(2)
+#test(a.finish(), (1,))
+#test(b.finish(), (1, 2))
+
+--- builder-use-after-finish ---
+#let b = builder()
+#b.push(1)
+#b.finish()
+// Error: 2-11 builder was already finished
+#b.push(2)
+
+--- builder-len-after-finish ---
+#let b = builder(str)
+#b.finish()
+// Error: 2-9 builder was already finished
+#b.len()
+
+--- builder-captured ---
+#let b = builder()
+#let f() = {
+  // Error: 3-4 variables from outside the function are read-only and cannot be modified
+  b.push(1)
+}
+#f()
+
+--- builder-push-to-str ---
+#let s = builder(str)
+// Error: 2-13 cannot push to a string builder
+// Hint: 2-13 use `push-str` instead
+#s.push("a")
+
+--- builder-push-str-to-array ---
+#let b = builder()
+// Error: 2-17 cannot push a string to an array builder
+// Hint: 2-17 use `push` instead
+#b.push-str("a")
+
+--- builder-bad-kind ---
+// Error: 2-21 cannot build a value of type dictionary
+#builder(dictionary)
@@ -94,6 +94,129 @@
 #test(calc.max(-3, 11), 11)
 #test(calc.min("hi"), "hi")
 
+--- calc-clamp ---
+// Test the `clamp` function.
+#for (args, expected) in (
+  ((5, 0, 10), 5),
+  ((5, 6, 10), 6),
+  ((5, 0, 4), 4),
+  ((5, 0.0, 4), 4.0),
+  ((-1.5, 0, 1), 0.0),
+  ((5pt, 1pt, 3pt), 3pt),
+  ((1cm, 1pt, 1in), 1cm),
+  ((2em, 1em, 3em), 2em),
+  ((150%, 0%, 100%), 100%),
+  ((-10deg, 0deg, 90deg), 0deg),
+) {
+  test(calc.clamp(..args), expected)
+}
+
+#test(type(calc.clamp(5, 0, 4)), int)
+#test(type(calc.clamp(5, 0, 4.0)), float)
+
+--- calc-clamp-bad-range ---
+// Error: 23-26 max must be greater than or equal to min
+#calc.clamp(1pt, 3pt, 2pt)
+
+--- calc-clamp-em ---
+// Error: 13-16 cannot compare 1em with 1pt
+#calc.clamp(1em, 1pt, 2pt)
+
+--- calc-clamp-mismatch ---
+// Error: 13-16 cannot compare length and integer
+#calc.clamp(1pt, 0, 2)
+
+--- calc-lerp ---
+// Test the `lerp` function.
+#for (args, expected) in (
+  ((0, 10, 0.5), 5.0),
+  ((0, 10, 50%), 5.0),
+  ((1.0, 3.0, 0.25), 1.5),
+  ((0, 10, 0), 0.0),
+  ((0, 10, 1.5), 15.0),
+  ((0, 10, -1), -10.0),
+  ((0pt, 10pt, 0.5), 5pt),
+  ((2pt, 4pt, 50%), 3pt),
+  ((1em, 3em, 0.5), 2em),
+  ((0%, 100%, 0.25), 25%),
+  ((10deg, 20deg, 0), 10deg),
+  ((1fr, 3fr, 0.5), 2fr),
+) {
+  test(calc.lerp(..args), expected)
+}
+
+--- calc-lerp-color ---
+#test(calc.lerp(red, blue, 0.3), color.mix((red, 70%), (blue, 30%)))
+#test(calc.lerp(red, blue, 0), color.mix((red, 1), (blue, 0)))
+#test(calc.lerp(red, blue, 100%), color.mix((red, 0), (blue, 1)))
+
+--- calc-lerp-color-extrapolate ---
+// Error: 23-26 cannot extrapolate between colors
+// Hint: 23-26 the interpolation factor must lie between 0% and 100%
+#calc.lerp(red, blue, 1.5)
+
+--- calc-lerp-mismatch ---
+// Error: 2-24 cannot subtract integer from length
+#calc.lerp(1, 2pt, 0.5)
+
+--- calc-lerp-bad-factor ---
+// Error: 18-21 expected float or ratio, found length
+#calc.lerp(1, 2, 1pt)
+
+--- calc-map ---
+// Test the `map` function.
+#for (args, expected) in (
+  ((5, 0, 10, 100, 200), 150.0),
+  ((15, 0, 10, 0pt, 1pt), 1.5pt),
+  ((-5, 0, 10, 0pt, 1pt), -0.5pt),
+  ((2, 0, 4, 10, 0), 5.0),
+  ((2.5, 5, 0, 0, 1), 0.5),
+  ((3pt, 2pt, 4pt, 0%, 100%), 50%),
+  ((45deg, 0deg, 90deg, 0, 2), 1.0),
+  ((50%, 0%, 100%, 0pt, 10pt), 5pt),
+) {
+  test(calc.map(..args), expected)
+}
+
+#test(calc.map(2, 0, 4, red, blue), calc.lerp(red, blue, 0.5))
+
+--- calc-map-clamp ---
+#test(calc.map(15, 0, 10, 0pt, 1pt, clamp: true), 1pt)
+#test(calc.map(-5, 0, 10, 0pt, 1pt, clamp: true), 0pt)
+#test(calc.map(5, 0, 10, 0pt, 1pt, clamp: true), 0.5pt)
+#test(calc.map(15, 10, 0, 0, 1, clamp: true), 0.0)
+#test(calc.map(20, 0, 10, red, blue, clamp: true), calc.lerp(red, blue, 1))
+
+--- calc-map-empty-range ---
+// Error: 2-25 in-min and in-max must not be equal (both are 5)
+#calc.map(1, 5, 5, 0, 1)
+
+--- calc-map-empty-range-mixed ---
+// Error: 2-37 in-min and in-max must not be equal (both are 2pt)
+#calc.map(1pt, 2pt, 2pt + 0em, 0, 1)
+
+--- calc-map-em ---
+// Error: 2-38 cannot map a length with non-zero em units (`1pt + 1em`)
+// Hint: 2-38 use `length.abs` instead to ignore its em component
+#calc.map(1em + 1pt, 0pt, 10pt, 0, 1)
+
+--- calc-map-mismatch ---
+// Error: 2-28 cannot subtract integer from length
+#calc.map(1pt, 0, 10, 0, 1)
+
+--- calc-smoothstep ---
+// Test the `smoothstep` function.
+#test(range(5).map(x => calc.smoothstep(0, 4, x)), (0.0, 0.15625, 0.5, 0.84375, 1.0))
+#test(calc.smoothstep(0, 1, -3), 0.0)
+#test(calc.smoothstep(0, 1, 5), 1.0)
+#test(calc.smoothstep(0pt, 2pt, 1pt), 0.5)
+#test(calc.smoothstep(1, 0, 0.5), 0.5)
+#test(calc.smoothstep(1, 0, 0.25), 0.84375)
+
+--- calc-smoothstep-empty-range ---
+// Error: 2-28 edge0 and edge1 must not be equal (both are 1)
+#calc.smoothstep(1, 1.0, 0)
+
 --- calc-pow-log-exp-ln ---
 // Test the `pow`, `log`, `exp`, and `ln` functions.
 #test(calc.pow(10, 0), 1)
@@ -136,6 +259,40 @@
 // Error: 2-18 the result is too large
 #1.bit-lshift(64)
 
+--- calc-bit-boundaries ---
+// Test the bitwise functions at the boundaries of the integer range.
+#let min = -9223372036854775807 - 1
+#let max = 9223372036854775807
+#test(min.bit-not(), max)
+#test(max.bit-not(), min)
+#test(min.bit-and(max), 0)
+#test(min.bit-and(-1), min)
+#test(min.bit-or(max), -1)
+#test(min.bit-xor(-1), max)
+#test(max.bit-xor(max), 0)
+#test(1.bit-lshift(62), 4611686018427387904)
+#test((-1).bit-lshift(63), min)
+#test((-3).bit-lshift(2), -12)
+#test(max.bit-lshift(0), max)
+#test(min.bit-rshift(63), -1)
+#test(min.bit-rshift(64), -1)
+#test(min.bit-rshift(63, logical: true), 1)
+#test(max.bit-rshift(62), 1)
+#test(max.bit-rshift(63), 0)
+
+--- calc-bit-lshift-into-sign ---
+// Error: 2-18 the result is too large
+#1.bit-lshift(63)
+
+--- calc-bit-lshift-negative-overflow ---
+// Error: 2-21 the result is too large
+#(-2).bit-lshift(63)
+
+--- calc-bit-lshift-max ---
+#let max = 9223372036854775807
+// Error: 2-19 the result is too large
+#max.bit-lshift(1)
+
 --- calc-bit-lshift-negative ---
 // Error: 15-17 number must be at least zero
 #1.bit-lshift(-1)
@@ -144,6 +301,33 @@
 // Error: 15-17 number must be at least zero
 #1.bit-rshift(-1)
 
+--- calc-bit-functions ---
+// Test the bitwise functions of the calc module.
+#let min = -9223372036854775807 - 1
+#let max = 9223372036854775807
+#test(calc.bit-not(64), -65)
+#test(calc.bit-not(min), max)
+#test(calc.bit-and(128, 192), 128)
+#test(calc.bit-and(min, -1), min)
+#test(calc.bit-or(-50, 3), -49)
+#test(calc.bit-or(min, max), -1)
+#test(calc.bit-xor(-1, -7), 6)
+#test(calc.bit-xor(min, -1), max)
+#test(calc.bit-lshift(32, 2), 128)
+#test(calc.bit-lshift(-1, 63), min)
+#test(calc.bit-rshift(-7, 2), -2)
+#test(calc.bit-rshift(min, 64), -1)
+#test(calc.bit-rshift(-7, 61, logical: true), 7)
+#test(calc.bit-rshift(min, 64, logical: true), 0)
+
+--- calc-bit-lshift-function-too-large ---
+// Error: 2-24 the result is too large
+#calc.bit-lshift(1, 63)
+
+--- calc-bit-lshift-function-negative ---
+// Error: 21-23 number must be at least zero
+#calc.bit-lshift(1, -1)
+
 --- calc-pow-zero-to-power-of-zero ---
 // Error: 2-16 zero to the power of zero is undefined
 #calc.pow(0, 0)
@@ -0,0 +1,24 @@
+--- call-stack ---
+#let inner() = call-stack()
+#let middle() = inner()
+#let outer() = {
+  middle()
+}
+#let stack = outer()
+#test(stack.map(frame => frame.name), ("inner", "middle", "outer"))
+#test(stack.map(frame => frame.line), (2, 4, 6))
+#test(stack.at(0).file, "/tests/suite/foundations/call-stack.typ")
+
+--- call-stack-top-level ---
+#test(call-stack(), ())
+
+--- call-stack-unnamed ---
+#let stack = (() => call-stack())()
+#test(stack.map(frame => frame.name), (none,))
+
+--- call-stack-after-return ---
+// Frames are removed once their call returns.
+#let deep(n) = if n == 0 { call-stack() } else { deep(n - 1) }
+#test(deep(3).len(), 4)
+#let shallow() = call-stack()
+#test(shallow().map(frame => frame.name), ("shallow",))
@@ -0,0 +1,49 @@
+// Test the canonical representation of values.
+
+--- canonical-repr-primitives ---
+#test(canonical-repr(none), "none")
+#test(canonical-repr(auto), "auto")
+#test(canonical-repr(true), "true")
+#test(canonical-repr(-3), "-3")
+#test(canonical-repr(<intro>), "<intro>")
+
+--- canonical-repr-float ---
+#test(canonical-repr(1.0), "1.0")
+#test(canonical-repr(-0.1), "-0.1")
+#test(canonical-repr(1e21), "1e21")
+#test(canonical-repr(1.5e-7), "1.5e-7")
+#test(canonical-repr(1 / 3), "0.3333333333333333")
+#test(canonical-repr(calc.nan), "calc.nan")
+#test(canonical-repr(calc.inf), "calc.inf")
+#test(canonical-repr(-calc.inf), "-calc.inf")
+
+--- canonical-repr-str ---
+#test(canonical-repr("hi"), "\"hi\"")
+#test(canonical-repr("a\"b\\c"), "\"a\\\"b\\\\c\"")
+#test(canonical-repr("1\n2\r3\t4"), "\"1\\n2\\r3\\t4\"")
+#test(canonical-repr("\u{7}"), "\"\\u{7}\"")
+#test(canonical-repr("ü 🏳️‍🌈"), "\"ü 🏳️‍🌈\"")
+
+--- canonical-repr-collections ---
+#test(canonical-repr(()), "()")
+#test(canonical-repr((1,)), "(1,)")
+#test(canonical-repr((1, 2.0, "x")), "(1, 2.0, \"x\")")
+#test(canonical-repr((:)), "(:)")
+#test(canonical-repr((b: 1, a: (2,))), "(b: 1, a: (2,))")
+#test(canonical-repr(("b c": none)), "(\"b c\": none)")
+#test(
+  canonical-repr(range(30)),
+  "(" + range(30).map(str).join(", ") + ")",
+)
+
+--- canonical-repr-content ---
+#test(canonical-repr([a]), "text(text: \"a\")")
+#test(
+  canonical-repr([a *b*]),
+  "sequence(children: (text(text: \"a\"), space(), strong(body: text(text: \"b\"))))",
+)
+
+--- canonical-repr-round-trip ---
+#let value = (a: (1, 2.5, calc.inf), "b c": ("\n", none, auto, false, (:)))
+#test(eval(canonical-repr(value)), value)
+#test(canonical-repr(eval(canonical-repr(value))), canonical-repr(value))
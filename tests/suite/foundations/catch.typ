@@ -0,0 +1,71 @@
+// Test catching errors.
+
+--- catch-ok ---
+#test(catch(() => 1 + 2), (ok: true, value: 3))
+#test(catch(() => none), (ok: true, value: none))
+
+--- catch-error ---
+#let result = catch(() => int("nope"))
+#test(result.ok, false)
+#test(result.message, "invalid integer: nope")
+#test(result.trace, ())
+#test(result.keys(), ("ok", "message", "trace"))
+
+--- catch-trace ---
+#let inner() = int("nope")
+#let outer() = inner()
+#let result = catch(() => outer())
+#test(result.message, "invalid integer: nope")
+#test(result.trace, (
+  "error occurred in this call of function `inner`",
+  "error occurred in this call of function `outer`",
+))
+
+--- catch-return ---
+#test(catch(() => { return 1; 2 }), (ok: true, value: 1))
+
+--- catch-nested ---
+#let result = catch(() => {
+  let inner = catch(() => 1 / 0)
+  test(inner.ok, false)
+  test(inner.message, "cannot divide by zero")
+  "recovered"
+})
+#test(result, (ok: true, value: "recovered"))
+
+--- catch-nested-failure ---
+#let result = catch(() => {
+  let inner = catch(() => 1 / 0)
+  inner.value
+})
+#test(result.ok, false)
+#test(result.message, "dictionary does not contain key \"value\"")
+
+--- catch-loop ---
+#let results = range(3).map(i => catch(() => 1 / (i - 1)))
+#test(results.map(r => r.ok), (true, false, true))
+
+--- catch-panic ---
+// Error: 14-27 panicked with: "boom"
+#catch(() => panic("boom"))
+
+--- catch-panic-nested ---
+// Error: 26-33 panicked
+#catch(() => catch(() => panic()))
+
+--- catch-break ---
+// Error: 34-39 cannot break outside of loop
+#for i in range(3) { catch(() => break) }
+
+--- catch-continue ---
+// Error: 34-42 cannot continue outside of loop
+#for i in range(3) { catch(() => continue) }
+
+--- catch-recursion ---
+// Error: 15-21 maximum function call depth exceeded
+#let rec(n) = rec(n) + 1
+#catch(() => rec(1))
+
+--- catch-not-function ---
+// Error: 8-9 expected function, found integer
+#catch(1)
@@ -130,3 +130,188 @@
 --- content-try-to-access-internal-field ---
 // Error: 9-15 hide does not have field "hidden"
 #hide[].hidden
+
+--- content-rewrite ---
+#let doc = [
+  = Intro
+  This is *important* and _nice_.
+]
+#let out = doc.rewrite((
+  heading: it => [Section: #it.body],
+  strong: it => it.body,
+  emph: [great],
+))
+#test(out.children.map(c => c.func()).contains(heading), false)
+#test(out.children.at(1), [Section: Intro])
+#test(out.children.at(5), [important])
+#test(out.children.at(9), [great])
+
+--- content-rewrite-remove ---
+#let out = [a #box[b] #box[c] d].rewrite((box: none))
+#test(out.children.map(c => c.func()).contains(box), false)
+#test(out.children.filter(c => c.func() == text), ([a], [d]))
+
+--- content-rewrite-nested-field ---
+#let out = block(box(strong[x]), fill: red).rewrite((strong: it => it.body))
+#test(out, block(box[x], fill: red))
+
+--- content-rewrite-bottom-up ---
+#let out = strong(emph[x]).rewrite((
+  emph: it => [E],
+  strong: it => {
+    assert.eq(it.body, [E])
+    [S]
+  },
+))
+#test(out, [S])
+
+--- content-rewrite-keeps-label ---
+#let out = [#box(strong[x])<lbl>].rewrite((strong: it => it.body))
+#test(out.label, <lbl>)
+
+--- content-rewrite-keeps-location ---
+#show heading: it => {
+  let out = it.rewrite((strong: it => it.body))
+  test(out.location(), it.location())
+  test(out.body, [A] + [ ] + [b])
+  out
+}
+
+= A *b*
+
+--- content-rewrite-unknown-element ---
+// Error: 16-34 unknown element: headin
+// Hint: 16-34 did you mean `heading`?
+#[= A].rewrite((headin: it => it))
+
+--- content-rewrite-bad-rule ---
+// Error: 20-31 expected function, content, or none for strong, found integer
+#strong[A].rewrite((strong: 1))
+
+--- content-rewrite-closure-not-content ---
+// Error: 29-31 expected content, found integer
+#strong[A].rewrite((strong: it => 1))
+
+--- content-space-predicates ---
+#test([ a].starts-with-space(), true)
+#test([a ].starts-with-space(), false)
+#test([a ].ends-with-space(), true)
+#test([a].ends-with-space(), false)
+#test(text("a\n").ends-with-space(), true)
+#test([a #parbreak()].ends-with-space(), true)
+#test([a \ ].ends-with-space(), true)
+#test(text(red)[a ].ends-with-space(), true)
+#test([a #[]].ends-with-space(), true)
+#test([].starts-with-space(), false)
+#test([].ends-with-space(), false)
+
+--- content-space-predicates-shallow ---
+// The checks don't look into other elements.
+#test([*a *].ends-with-space(), false)
+
+--- content-excerpt-styled ---
+#let body = [This is *rather long* text]
+#test(body.excerpt(words: 3), [This is *rather*…])
+#test(body.excerpt(words: 3, ellipsis: [ (more)]), [This is *rather* (more)])
+#test(body.excerpt(words: 4), [This is *rather long*…])
+
+--- content-excerpt-styled-wrapper ---
+#let body = text(red)[a b c]
+#let cut = body.excerpt(words: 2)
+#test(cut.children.len(), 2)
+#test(cut.children.first().child, [a b])
+#test(cut.children.last(), […])
+
+--- content-excerpt-exact ---
+// Nothing is dropped, so no ellipsis is added.
+#let body = [This is *rather long* text]
+#test(body.excerpt(words: 5), body)
+#test(body.excerpt(words: 10), body)
+#test([Hello world ].excerpt(words: 2), [Hello world])
+#test([Hello world].excerpt(chars: 11), [Hello world])
+
+--- content-excerpt-word-boundary ---
+// Cuts prefer word boundaries, but a single long word is cut hard.
+#test([Hello world].excerpt(chars: 8), [Hello] + […])
+#test([#"Hello world"].excerpt(chars: 8), [#"Hello"] + […])
+#test([This is *rather long* text].excerpt(chars: 10), [This is] + […])
+#test([Incomprehensibilities].excerpt(chars: 5), [Incom] + […])
+
+--- content-excerpt-atomic ---
+// Equations and raw text are kept or dropped as a whole.
+#test([Let $x$ be].excerpt(words: 2), [Let $x$] + […])
+#test([Let $x$ be].excerpt(words: 1), [Let] + […])
+#test([Let $x$ be].excerpt(chars: 4), [Let] + […])
+// Raw elements from different places are never equal, so compare the reprs.
+#test(repr([A `raw` text].excerpt(words: 2)), repr([A `raw`] + […]))
+#test([A #image("/assets/images/tiger.jpg")].excerpt(words: 1), [A] + […])
+
+--- content-excerpt-block-after-cut ---
+#let body = [Intro text #parbreak() #heading[Heading]]
+#test(body.excerpt(words: 2), [Intro text] + […])
+#test(body.excerpt(words: 1), [Intro] + […])
+
+--- content-excerpt-no-limit ---
+// Error: 2-15 expected either `words` or `chars`
+#[a].excerpt()
+
+--- content-sequence-flattened ---
+#test([a #[b *c*] d].children, ([a], [ ], [b], [ ], strong[c], [ ], [d]))
+#test($x#[y *z*]$.body.children, ([x], [y], [ ], strong[z]))
+
+--- content-sequence-labelled-kept ---
+#let c = [a #[b c]<l> d]
+#test(c.children.len(), 5)
+#test(c.children.at(2).label, <l>)
+#test(c.children.at(2), [b c])
+
+--- content-too-large ---
+#let body = [x]
+// Error: 2:10-2:23 content is too large to lay out
+// Hint: 2:10-2:23 it has more than 5000000 elements
+// Hint: 2:10-2:23 content that contains itself repeatedly, like `body = [#body #body]` in a loop, grows exponentially
+#for _ in range(23) {
+  body = [#body #body]
+}
+#body
+
+--- content-split-at-pagebreak ---
+#let body = [A #pagebreak() B #pagebreak() C]
+#test(body.split-at(pagebreak), ([A ], [ B ], [ C]))
+#test([#pagebreak()A].split-at(pagebreak), ([], [A]))
+
+--- content-split-at-keep ---
+#let body = [A#pagebreak()B#pagebreak()C]
+#test(body.split-at(pagebreak, keep: "none"), ([A], [B], [C]))
+#test(
+  body.split-at(pagebreak, keep: "start"),
+  ([A], pagebreak() + [B], pagebreak() + [C]),
+)
+#test(
+  body.split-at(pagebreak, keep: "end"),
+  ([A] + pagebreak(), [B] + pagebreak(), [C]),
+)
+
+--- content-split-at-label ---
+#let body = [A#metadata(none)<next>B]
+#test(body.split-at(<next>), ([A], [B]))
+
+--- content-split-at-styled ---
+#let body = [A #text(red)[B#pagebreak()C] D]
+#test(body.split-at(pagebreak), (body,))
+
+#let chunks = body.split-at(pagebreak, recursive: true)
+#test(chunks.len(), 2)
+#test(chunks.at(0).children.slice(0, 2), ([A], [ ]))
+#test(chunks.at(0).children.last().child, [B])
+#test(chunks.at(1).children.first().child, [C])
+#test(chunks.at(1).children.slice(1), ([ ], [D]))
+
+--- content-split-at-no-marker ---
+#let body = [A *B* C]
+#test(body.split-at(pagebreak), (body,))
+#test([].split-at(pagebreak), ([],))
+
+--- content-split-at-bad-keep ---
+// Error: 31-37 expected "none", "start", or "end"
+#[].split-at(pagebreak, keep: "both")
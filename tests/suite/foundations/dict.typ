@@ -61,10 +61,24 @@
   test((..(a: 1), b: 2), (a: 1, b: 2))
 }
 
+--- spread-into-dict-order ---
+#let dict = (b: 2, a: 1)
+#test((a: 0, ..dict).keys(), ("a", "b"))
+#test((..dict, a: 3, c: 4), (b: 2, a: 3, c: 4))
+#test((..dict, a: 3).keys(), ("b", "a"))
+#test((x: 0, ..dict).keys(), ("x", "b", "a"))
+
 --- spread-array-into-dict ---
 // Error: 3-11 cannot spread array into dictionary
+// Hint: 3-11 use `.to-dict()` to turn an array of pairs into a dictionary
 #(..(1, 2), a: 1)
 
+--- spread-args-into-dict ---
+// Error: 19-25 cannot spread arguments into dictionary
+// Hint: 19-25 use `.named()` to spread the named arguments
+#let f(..args) = (..args, a: 1)
+#f(b: 2)
+
 --- dict-at-lvalue ---
 // Test lvalue and rvalue access.
 #{
@@ -118,7 +132,8 @@
 #{
   let dict = (:)
   // Error: 3-9 dictionary does not contain key "b"
-  // Hint: 3-9 use `insert` to add or update values
+  // Hint: 3-9 the dictionary is empty
+  // Hint: 3-9 use `insert` or assign with `=` before updating a key with `+=`
   dict.b += 1
 }
 
@@ -240,10 +255,48 @@
 #{
   let dict = (a: 1)
   // Error: 3-9 dictionary does not contain key "b"
-  // Hint: 3-9 use `insert` to add or update values
+  // Hint: 3-9 the dictionary contains the key "a"
+  // Hint: 3-9 use `insert` or assign with `=` before updating a key with `+=`
   dict.b += 9
 }
 
+--- dict-syntax-compound-assign ---
+#{
+  let dict = (a: 6)
+  dict.a += 2
+  dict.a -= 4
+  dict.a *= 3
+  dict.a /= 2
+  test(dict, (a: 6.0))
+}
+
+--- dict-syntax-missing-sub-assign ---
+#{
+  let dict = (a: 1, b: 2, c: 3, d: 4)
+  // Error: 3-9 dictionary does not contain key "e"
+  // Hint: 3-9 the dictionary contains the keys "a", "b", "c", ..
+  // Hint: 3-9 use `insert` or assign with `=` before updating a key with `-=`
+  dict.e -= 1
+}
+
+--- dict-syntax-missing-mul-assign ---
+#{
+  let dict = (a: 1, b: 2)
+  // Error: 3-9 dictionary does not contain key "c"
+  // Hint: 3-9 the dictionary contains the keys "a", "b"
+  // Hint: 3-9 use `insert` or assign with `=` before updating a key with `*=`
+  dict.c *= 2
+}
+
+--- dict-syntax-missing-div-assign ---
+#{
+  let dict = (:)
+  // Error: 3-13 dictionary does not contain key "count"
+  // Hint: 3-13 the dictionary is empty
+  // Hint: 3-13 use `insert` or assign with `=` before updating a key with `/=`
+  dict.count /= 2
+}
+
 --- issue-3232-dict-unexpected-keys-sides ---
 // Confusing "expected relative length or dictionary, found dictionary"
 // Error: 16-58 unexpected keys "unexpected" and "unexpected-too"
@@ -264,3 +317,184 @@
 --- issue-3232-dict-empty ---
 #block(outset: (:), [Hi]) // Ok
 #box(radius: (:), [Hi]) // Ok
+
+--- dict-sorted ---
+#let dict = (c: 1, a: 3, b: 2)
+#test(dict.sorted(), (a: 3, b: 2, c: 1))
+#test(dict.sorted().keys(), ("a", "b", "c"))
+#test(dict.sorted(key: (k, v) => v).keys(), ("c", "b", "a"))
+#test(dict.keys(), ("c", "a", "b"))
+#test((:).sorted(), (:))
+
+--- dict-sorted-key-function-error ---
+// Error: 2-41 cannot compare string and integer
+#(a: 1, b: "x").sorted(key: (k, v) => v)
+
+--- dict-keys-sorted ---
+#test((b: 1, c: 2, a: 3).keys-sorted(), ("a", "b", "c"))
+#test((:).keys-sorted(), ())
+
+--- dict-update ---
+#let dict = (count: 1)
+#dict.update("count", x => x + 1)
+#test(dict, (count: 2))
+#dict.update("other", x => x, default: 5)
+#test(dict, (count: 2, other: 5))
+#dict.update("missing", x => x)
+#test(dict.missing, none)
+
+--- dict-update-nested ---
+#let data = (inner: (count: 0))
+#for i in range(3) {
+  data.inner.update("count", x => x + i)
+}
+#test(data.inner.count, 3)
+
+--- dict-update-error-leaves-dict-unchanged ---
+#let dict = (count: 1)
+// Error: 28-35 cannot add integer and string
+#dict.update("count", x => x + "a")
+#test(dict, (count: 1))
+
+--- dict-update-temporary ---
+// Error: 3-9 cannot mutate a temporary value
+#((a: 1)).update("a", x => x + 1)
+
+--- dict-update-temporary-state ---
+// States and counters have a non-mutating `update` method.
+#state("dict-update", 0).update(1)
+#context test(state("dict-update").get(), 1)
+
+--- dict-call-builder-method ---
+// Methods of other types don't mutate dictionaries.
+#let dict = (finish: () => 1)
+// Error: 7-13 type dictionary has no method `finish`
+// Hint: 7-13 to call the function stored in the dictionary, surround the field access with parentheses, e.g. `(dict.finish)(..)`
+#dict.finish()
+
+--- dict-deep-merge ---
+#let defaults = (
+  font: "Libertinus Serif",
+  colors: (text: black, link: (normal: blue, visited: purple)),
+  margins: (1cm, 2cm),
+)
+#let merged = defaults.deep-merge((colors: (link: (visited: red)), size: 11pt))
+#test(merged, (
+  font: "Libertinus Serif",
+  colors: (text: black, link: (normal: blue, visited: red)),
+  margins: (1cm, 2cm),
+  size: 11pt,
+))
+#test(merged.keys(), ("font", "colors", "margins", "size"))
+
+// The base is left unchanged and merging can be called on the type.
+#test(defaults.colors.link.visited, purple)
+#test(dictionary.deep-merge((a: (b: 1)), (a: (c: 2))), (a: (b: 1, c: 2)))
+
+// Adding replaces nested dictionaries as a whole.
+#test((defaults + (colors: (text: red))).colors, (text: red))
+#test(defaults.deep-merge((colors: (text: red))).colors.link.normal, blue)
+
+--- dict-deep-merge-empty ---
+#test((:).deep-merge((a: 1)), (a: 1))
+#test((a: (b: 1)).deep-merge((:)), (a: (b: 1)))
+#test((a: (b: 1)).deep-merge((a: (:))), (a: (b: 1)))
+
+--- dict-deep-merge-auto ---
+#let base = (size: 10pt, colors: (text: black))
+#test(base.deep-merge((size: auto, colors: (text: auto))), base)
+#test(base.deep-merge((size: auto, new: auto)), (size: 10pt, colors: (text: black), new: auto))
+#test(
+  base.deep-merge((size: auto, colors: auto), auto-keeps-base: false),
+  (size: auto, colors: auto),
+)
+
+--- dict-deep-merge-none ---
+#let base = (size: 10pt, colors: (text: black, link: blue))
+#test(base.deep-merge((size: none)), (size: none, colors: (text: black, link: blue)))
+#test(base.deep-merge((size: none, missing: none), none-removes: true), (colors: (text: black, link: blue)))
+#test(base.deep-merge((colors: (link: none)), none-removes: true), (size: 10pt, colors: (text: black)))
+#test(base.deep-merge((colors: none), none-removes: true), (size: 10pt))
+
+--- dict-deep-merge-none-and-auto ---
+#let base = (a: 1, b: 2, c: 3)
+#let other = (a: none, b: auto)
+#test(base.deep-merge(other), (a: none, b: 2, c: 3))
+#test(base.deep-merge(other, none-removes: true), (b: 2, c: 3))
+#test(base.deep-merge(other, auto-keeps-base: false), (a: none, b: auto, c: 3))
+#test(
+  base.deep-merge(other, none-removes: true, auto-keeps-base: false),
+  (b: auto, c: 3),
+)
+
+--- dict-deep-merge-arrays ---
+#let base = (fonts: ("Libertinus Serif",), nested: (list: (1, 2)))
+#let other = (fonts: ("Noto Sans",), nested: (list: (3,)))
+#test(base.deep-merge(other), other)
+#test(base.deep-merge(other, arrays: "replace"), other)
+#test(
+  base.deep-merge(other, arrays: "concat"),
+  (fonts: ("Libertinus Serif", "Noto Sans"), nested: (list: (1, 2, 3))),
+)
+#test(base.deep-merge((fonts: "Noto Sans"), arrays: "concat").fonts, "Noto Sans")
+
+--- dict-deep-merge-conflicting-types ---
+#let base = (colors: (text: black), size: 10pt)
+#test(base.deep-merge((colors: red, size: (value: 11pt))), (colors: red, size: (value: 11pt)))
+#test(base.deep-merge((colors: none, size: auto), strict: true), (colors: none, size: 10pt))
+#test(base.deep-merge((size: 11pt), strict: true), (colors: (text: black), size: 11pt))
+
+--- dict-deep-merge-strict ---
+// Error: 2-83 cannot merge dictionary with color at `theme.colors`
+#(theme: (colors: (text: black))).deep-merge((theme: (colors: red)), strict: true)
+
+--- dict-deep-merge-strict-scalar ---
+// Error: 2-76 cannot merge length with dictionary at `"font size"`
+#("font size": 10pt).deep-merge(("font size": (value: 11pt)), strict: true)
+
+--- dict-deep-merge-reference ---
+// Compare against a merge written in Typst.
+#let reference(a, b, concat: false) = {
+  for (key, value) in b {
+    let base = a.at(key, default: none)
+    if type(base) == dictionary and type(value) == dictionary {
+      a.insert(key, reference(base, value, concat: concat))
+    } else if concat and type(base) == array and type(value) == array {
+      a.insert(key, base + value)
+    } else {
+      a.insert(key, value)
+    }
+  }
+  a
+}
+
+#let fixtures = (
+  ((:), (:)),
+  ((a: 1, b: 2), (b: 3, c: 4)),
+  ((a: (b: (c: 1, d: 2))), (a: (b: (d: 3, e: 4), f: 5))),
+  ((a: (b: 1), c: (1, 2)), (a: 2, c: (3,))),
+  ((a: 1, list: (1, 2)), (a: (b: 1), list: (x: 3))),
+  ((x: (y: (z: (w: (v: 1)))), keep: "yes"), (x: (y: (z: (w: (u: 2)), t: 3)))),
+  ((fonts: ("A", "B"), sizes: (body: 10pt)), (fonts: ("C",), sizes: (body: 11pt, title: 20pt))),
+)
+
+#for (a, b) in fixtures {
+  test(a.deep-merge(b), reference(a, b))
+  test(a.deep-merge(b, arrays: "concat"), reference(a, b, concat: true))
+}
+
+--- dict-deep-merge-too-deep ---
+#let nest(n) = {
+  let d = (:)
+  for _ in range(n) {
+    d = (a: d)
+  }
+  d
+}
+#test(nest(256).deep-merge(nest(256)), nest(256))
+// Error: 2-33 cannot merge dictionaries nested more than 256 levels deep
+#nest(258).deep-merge(nest(258))
+
+--- dict-deep-merge-bad-arrays ---
+// Error: 33-38 expected "replace" or "concat"
+#(a: 1).deep-merge((:), arrays: "zip")
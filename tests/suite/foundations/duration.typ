@@ -101,3 +101,50 @@
 #test(duration(minutes: 20) < duration(minutes: 10), false)
 #test(duration(minutes: 20) <= duration(minutes: 10), false)
 #test(duration(minutes: 20) == duration(minutes: 10), false)
+
+--- duration-mixed-precision ---
+// Test shifting dates, times, and datetimes by various durations.
+#let date = datetime(day: 1, month: 1, year: 2000)
+#let time = datetime(hour: 12, minute: 0, second: 0)
+#let both = datetime(day: 1, month: 1, year: 2000, hour: 12, minute: 0, second: 0)
+#test(date + duration(days: 1, hours: 24), datetime(day: 3, month: 1, year: 2000))
+#test(date - duration(hours: 48), datetime(day: 30, month: 12, year: 1999))
+#test(time + duration(hours: 13), datetime(hour: 1, minute: 0, second: 0))
+#test(time - duration(days: 1), time)
+#test(
+  both + duration(hours: 36),
+  datetime(day: 3, month: 1, year: 2000, hour: 0, minute: 0, second: 0),
+)
+#test(both - duration(days: 1, hours: 1) - both, duration(hours: -25))
+#test(duration(days: 1) * 1.5, duration(hours: 36))
+#test(2 * duration(hours: 1) + duration(minutes: 30), duration(minutes: 150))
+
+--- duration-date-sub-day ---
+// Error: 3-62 a date can only be shifted by whole days
+// Hint: 3-62 use a datetime with a time of day to shift by less than a day
+#(datetime(day: 1, month: 1, year: 2000) + duration(hours: 1))
+
+--- duration-date-sub-day-subtract ---
+// Error: 3-64 a date can only be shifted by whole days
+// Hint: 3-64 use a datetime with a time of day to shift by less than a day
+#(datetime(day: 1, month: 1, year: 2000) - duration(seconds: 1))
+
+--- duration-constructor-overflow ---
+// Error: 2-38 duration is too large
+#duration(weeks: 9223372036854775807)
+
+--- duration-add-overflow ---
+// Error: 3-64 duration is too large
+#(duration(seconds: 9223372036854775807) + duration(seconds: 1))
+
+--- duration-multiply-overflow ---
+// Error: 3-29 duration is too large
+#(duration(weeks: 1) * 1e300)
+
+--- duration-divide-overflow ---
+// Error: 3-30 duration is too large
+#(duration(weeks: 1) / 1e-300)
+
+--- duration-datetime-overflow ---
+// Error: 3-65 datetime is out of range
+#(datetime(day: 1, month: 1, year: 9999) + duration(weeks: 1000))
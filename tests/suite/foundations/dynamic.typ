@@ -0,0 +1,76 @@
+// Test how dynamic values are converted to strings and displayed.
+
+--- dynamic-repr ---
+#test(repr(left + top), "left + top")
+#test(repr(rtl), "rtl")
+#test(repr(regex("a+")), "regex(\"a+\")")
+#test(repr(counter(page)), "counter(page)")
+#test(repr(state("x", 1)), "state(\"x\", 1)")
+#test(repr(heading.where(level: 1)), "heading.where(level: 1)")
+#test(repr(builder(str)), "builder(str, len: 0)")
+#test(repr(unique-slugs()), "unique-slugs(len: 0)")
+#context test(repr(here()), "..")
+
+--- dynamic-str ---
+#test(str(left + top), "left + top")
+#test(str(bottom), "bottom")
+#test(str(rtl), "rtl")
+#test(str(regex("a+")), "a+")
+
+--- dynamic-str-counter ---
+// Error: 6-19 cannot convert counter to string
+#str(counter(page))
+
+--- dynamic-str-state ---
+// Error: 6-19 cannot convert state to string
+#str(state("x", 1))
+
+--- dynamic-str-selector ---
+// Error: 6-29 cannot convert selector to string
+#str(heading.where(level: 1))
+
+--- dynamic-str-stroke ---
+// Error: 6-17 cannot convert stroke to string
+#str(stroke(red))
+
+--- dynamic-str-builder ---
+// Error: 6-15 cannot convert builder to string
+#str(builder())
+
+--- dynamic-str-unique-slugs ---
+// Error: 6-20 cannot convert unique slugs to string
+#str(unique-slugs())
+
+--- dynamic-str-csl-style ---
+// Error: 6-49 cannot convert csl style to string
+#str(bibliography("/assets/bib/works.bib").style)
+
+--- dynamic-str-location ---
+// Error: 14-20 cannot convert location to string
+#context str(here())
+
+--- dynamic-display ---
+// Dynamic values are displayed as their syntax-highlighted representation.
+#let values = (
+  top + left,
+  rtl,
+  regex("a+"),
+  counter(page),
+  state("x", 1),
+  heading.where(level: 1),
+  stroke(red),
+  builder(),
+  unique-slugs(),
+  bibliography("/assets/bib/works.bib").style,
+)
+#for value in values {
+  let shown = [#value]
+  test(shown.func(), raw)
+  test(shown.text, repr(value))
+  test(shown.lang, "typc")
+}
+#context {
+  let shown = [#here()]
+  test(shown.func(), raw)
+  test(shown.text, "..")
+}
@@ -10,6 +10,20 @@
 #eval("_Hello" + " World!_", mode: "markup") \
 #eval("RR_1^NN", mode: "math", scope: (RR: math.NN, NN: math.RR))
 
+--- eval-scope-markup ---
+// Test markup that references a function from the scope.
+#let greet(name) = [Hello #name!]
+#test(eval("#greet[World]", mode: "markup", scope: (greet: greet)), greet[World])
+
+--- eval-scope-round-trip ---
+// Test that values from the scope are passed through unchanged.
+#let f(x) = x * 2
+#test(eval("f", scope: (f: f)), f)
+#test(eval("f(2)", scope: (f: f)), 4)
+#test(eval("c", scope: (c: [*Hi*])), [*Hi*])
+#test(eval("a", scope: (a: (1, (2, 3)))), (1, (2, 3)))
+#test(eval("abc", mode: "math", scope: (abc: $a + b$)).body, $a + b$)
+
 --- eval-syntax-error-1 ---
 // Error: 7-12 expected pattern
 #eval("let")
@@ -23,6 +37,15 @@ Interacting
 Blue #move(dy: -0.15em)[🌊]
 ```
 
+--- eval-query ---
+// Evaluated code sees the document from within a context expression.
+#show heading: none
+= Alpha
+= Beta
+#context test(eval("query(heading)"), query(heading))
+#context test(eval("query(heading).map(it => it.body)"), ([Alpha], [Beta]))
+#context test(eval("here()"), here())
+
 --- eval-runtime-error ---
 // Error: 7-17 cannot continue outside of loop
 #eval("continue")
@@ -52,3 +75,8 @@ _Tiger!_
 #eval(mode: "math", "f(a) = cases(a + b\, space space x >= 3,a + b\, space space x = 5)")
 
 $f(a) = cases(a + b\, space space x >= 3,a + b\, space space x = 5)$
+
+--- eval-import-relative ---
+// Imports in evaluated code are resolved relative to the calling file.
+#let name = eval("import \"../scripting/modules/chap1.typ\": name; name")
+#test(name, "Klaus")
@@ -64,3 +64,36 @@
 #(-0987654321.0) \
 #(-3.14) \
 #(4.0 - 8.0)
+
+--- float-from-bytes ---
+#test(float.from-bytes(bytes((0, 0, 0, 0, 0, 0, 240, 63))), 1.0)
+#test(float.from-bytes(bytes((63, 240, 0, 0, 0, 0, 0, 0)), endian: "big"), 1.0)
+#test(float.from-bytes(bytes((0, 0, 128, 63))), 1.0)
+#test(float.from-bytes(bytes((192, 0, 0, 0)), endian: "big"), -2.0)
+#test(float.is-nan(float.from-bytes(bytes((0, 0, 192, 127)))), true)
+
+--- float-to-bytes ---
+#test(1.0.to-bytes(), bytes((0, 0, 0, 0, 0, 0, 240, 63)))
+#test(1.0.to-bytes(endian: "big"), bytes((63, 240, 0, 0, 0, 0, 0, 0)))
+#test(1.0.to-bytes(size: 4), bytes((0, 0, 128, 63)))
+#test((-0.0).to-bytes(endian: "big"), bytes((128, 0, 0, 0, 0, 0, 0, 0)))
+#test(0.0.to-bytes(endian: "big"), bytes((0, 0, 0, 0, 0, 0, 0, 0)))
+
+--- float-bytes-round-trip ---
+#for value in (0.0, -0.0, 1.5, calc.inf, -calc.inf, 1e308, 5e-324) {
+  for endian in ("big", "little") {
+    let bytes = value.to-bytes(endian: endian)
+    test(float.from-bytes(bytes, endian: endian).to-bytes(endian: endian), bytes)
+  }
+}
+// The bit pattern of a NaN with a payload is preserved.
+#let nan = bytes((1, 0, 0, 0, 0, 0, 248, 127))
+#test(float.from-bytes(nan).to-bytes(), nan)
+
+--- float-from-bytes-bad-length ---
+// Error: 2-33 expected 4 or 8 bytes, found 2
+#float.from-bytes(bytes((0, 0)))
+
+--- float-to-bytes-bad-size ---
+// Error: 2-23 size must be 4 or 8, found 2
+#1.0.to-bytes(size: 2)
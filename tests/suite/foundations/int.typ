@@ -71,3 +71,55 @@
 --- number-invalid-suffix ---
 // Error: 2-4 invalid number suffix: u
 #1u
+
+--- int-from-bytes ---
+#test(int.from-bytes(bytes((1, 2))), 513)
+#test(int.from-bytes(bytes((1, 2)), endian: "big"), 258)
+#test(int.from-bytes(bytes((255,))), -1)
+#test(int.from-bytes(bytes((255,)), signed: false), 255)
+#test(int.from-bytes(bytes((255, 255)), signed: false), 65535)
+#test(int.from-bytes(bytes((0, 0, 0, 128)), endian: "little"), -2147483648)
+#test(int.from-bytes(bytes((0, 0, 0, 128)), signed: false), 2147483648)
+#test(int.from-bytes(bytes((0, 0, 0, 0, 0, 0, 0, 128))), -9223372036854775807 - 1)
+#test(int.from-bytes(bytes((127, 255, 255, 255, 255, 255, 255, 255)), endian: "big"), 9223372036854775807)
+
+--- int-to-bytes ---
+#test(258.to-bytes(size: 2), bytes((2, 1)))
+#test(258.to-bytes(size: 2, endian: "big"), bytes((1, 2)))
+#test(255.to-bytes(size: 1), bytes((255,)))
+#test((-1).to-bytes(size: 1), bytes((255,)))
+#test((-128).to-bytes(size: 1), bytes((128,)))
+#test(1.to-bytes(), bytes((1, 0, 0, 0, 0, 0, 0, 0)))
+#test(1.to-bytes(size: 4, endian: "big"), bytes((0, 0, 0, 1)))
+#test((-2).to-bytes(size: 4), bytes((254, 255, 255, 255)))
+
+--- int-bytes-round-trip ---
+#for value in (0, 1, -1, 255, -9223372036854775807 - 1, 9223372036854775807) {
+  for endian in ("big", "little") {
+    test(int.from-bytes(value.to-bytes(endian: endian), endian: endian), value)
+  }
+}
+#for size in (1, 2, 4) {
+  let max = int.from-bytes(bytes((255,) * size), signed: false)
+  test(int.from-bytes(max.to-bytes(size: size), signed: false), max)
+}
+
+--- int-from-bytes-bad-length ---
+// Error: 2-34 expected 1, 2, 4, or 8 bytes, found 3
+#int.from-bytes(bytes((1, 2, 3)))
+
+--- int-from-bytes-too-large ---
+// Error: 2-50 number too large
+#int.from-bytes(bytes((255,) * 8), signed: false)
+
+--- int-to-bytes-bad-size ---
+// Error: 2-21 size must be 1, 2, 4, or 8, found 3
+#1.to-bytes(size: 3)
+
+--- int-to-bytes-overflow ---
+// Error: 2-23 256 is out of range for 8 bits
+#256.to-bytes(size: 1)
+
+--- int-to-bytes-underflow ---
+// Error: 2-26 -129 is out of range for 8 bits
+#(-129).to-bytes(size: 1)
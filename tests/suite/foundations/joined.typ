@@ -0,0 +1,34 @@
+--- joined-strings ---
+#test(joined("a", "b", "c"), "a b c")
+#test(joined("a", "b", separator: ", "), "a, b")
+#test(joined("a", "b", separator: none), "ab")
+#test(joined("a"), "a")
+
+--- joined-content ---
+#test(joined("a", [b]), "a " + [b])
+#test(joined([a], "b", separator: [ -- ]), [a] + [ -- ] + "b")
+
+--- joined-empty ---
+#test(joined(), none)
+#test(joined(separator: ", "), none)
+
+--- joined-bad-value ---
+// Error: 2-21 cannot join content with integer
+// Hint: 2-21 use `str` or `repr` to convert values, or `joined` to join them with a separator
+// Hint: 2-21 unlike joining, the `+` operator can also add numbers
+#joined("a", [b], 1)
+
+--- smart-join-spaces ---
+#test(smart-join([a], [b], [c]).children, ([a], [ ], [b], [ ], [c]))
+#test(smart-join([a ], [b]).children, ([a ], [b]))
+#test(smart-join([a], [ b]).children, ([a], [ b]))
+#test(smart-join("a", "b").children, ([a], [ ], [b]))
+#test(smart-join("a ", "b").children.len(), 2)
+
+--- smart-join-parbreak ---
+#test(smart-join([a], parbreak(), [b]).children, ([a], parbreak(), [b]))
+
+--- smart-join-empty ---
+#test(smart-join(), [])
+#test(smart-join([a]), [a])
+#test(smart-join([a], [], none, [b]).children, ([a], [ ], [b]))
@@ -29,6 +29,7 @@ The end.
 This is a thing #[that <last>] happened.
 
 --- label-dynamic-show-set ---
+// CHUNKED
 // Test abusing dynamic labels for styling.
 #show <red>: set text(red)
 #show <blue>: set text(blue)
@@ -36,6 +37,7 @@ This is a thing #[that <last>] happened.
 *A* *B* <red> *C* #label("bl" + "ue") *D*
 
 --- label-after-parbreak ---
+// CHUNKED
 // Test that label ignores parbreak.
 #show <hide>: none
 
@@ -74,3 +76,23 @@ _Visible_
 // Hint: 7-7 labels can only be applied in markup mode
 // Hint: 7-7 try wrapping your code in a markup block (`[ ]`)
 #{ [A] <a> }
+
+--- label-duplicate ---
+// PEDANTIC
+// Only the second place a label is attached to is reported.
+= A <a>
+// Warning: 5-8 label <a> is attached to more than one element
+// Hint: 5-8 it was first attached at tests/suite/foundations/label.typ:3:5
+// Hint: 5-8 references to it are ambiguous
+= B <a>
+= C <a>
+
+--- label-duplicate-same-place ---
+// PEDANTIC
+// Attaching a label in the same place repeatedly is fine.
+#for i in range(3) [X <x>]
+
+--- label-duplicate-off ---
+// Without pedantic lints, duplicate labels are fine.
+= A <a>
+= B <a>
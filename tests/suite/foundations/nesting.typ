@@ -0,0 +1,73 @@
+// Test operations on very deeply nested values.
+
+--- nesting-deep-equality ---
+#let a = ()
+#let b = ()
+#for i in range(100000) {
+  a = (a,)
+  b = (b,)
+}
+#test(a, b)
+#test(a <= b, true)
+#test(a == (b,), false)
+
+--- nesting-deep-dict-equality ---
+#let a = (:)
+#let b = (:)
+#for i in range(100000) {
+  a = (x: a)
+  b = (x: b)
+}
+#test(a, b)
+
+--- nesting-deep-hash ---
+// Calling a closure hashes its arguments for memoization.
+#let deep = ()
+#for i in range(100000) { deep = (deep,) }
+#let f(x) = type(x)
+#test(f(deep), array)
+
+--- nesting-deep-repr ---
+#let deep = ()
+#for i in range(100000) { deep = (deep,) }
+// Error: 2-12 value is too deeply nested
+#repr(deep)
+
+--- nesting-deep-json-encode ---
+#let deep = ()
+#for i in range(100000) { deep = (deep,) }
+// Error: 14-18 value is too deeply nested
+#json.encode(deep)
+
+--- nesting-deep-yaml-encode ---
+#let deep = ()
+#for i in range(100000) { deep = (deep,) }
+// Error: 14-18 value is too deeply nested
+#yaml.encode(deep)
+
+--- nesting-deep-cbor-encode ---
+#let deep = ()
+#for i in range(100000) { deep = (deep,) }
+// Error: 14-18 value is too deeply nested
+#cbor.encode(deep)
+
+--- nesting-deep-toml-encode ---
+#let deep = (:)
+#for i in range(100000) { deep = (a: deep) }
+// Error: 14-18 value is too deeply nested
+#toml.encode(deep)
+
+--- nesting-shallow-roundtrip ---
+#let value = ()
+#for i in range(100) { value = (value,) }
+#test(eval(repr(value)), value)
+#test(json.decode(json.encode(value, pretty: false)), value)
+#test(cbor.decode(cbor.encode(value)), value)
+
+--- nesting-deep-drop ---
+#let n = {
+  let deep = ()
+  for i in range(100000) { deep = (deep,) }
+  1
+}
+#test(n, 1)
@@ -9,6 +9,10 @@
   bytes("value3-value1-value2"),
 )
 
+--- plugin-from-bytes ---
+#let p = plugin(read("/assets/plugins/hello.wasm", encoding: none))
+#test(p.hello(), bytes("Hello from wasm!!!"))
+
 --- plugin-wrong-number-of-arguments ---
 #let p = plugin("/assets/plugins/hello.wasm")
 
@@ -31,7 +35,7 @@
 --- plugin-panic ---
 #let p = plugin("/assets/plugins/hello.wasm")
 
-// Error: 2-16 plugin panicked: wasm `unreachable` instruction executed
+// Error: 2-16 plugin function `will_panic` panicked: wasm `unreachable` instruction executed
 #p.will_panic()
 
 --- plugin-out-of-bounds-read ---
@@ -45,3 +49,27 @@
 
 // Error: 2-27 plugin tried to write out of bounds: pointer 0x40000000 is out of bounds for write of length 3
 #p.write_oob(bytes("xyz"))
+
+--- plugin-trap-and-memory-limit ---
+// A tiny module exporting the functions `trap` and `grow`, which execute
+// `unreachable` and try to grow the memory by 20000 pages, respectively.
+// Growing fails, so `grow` returns -1.
+#let p = plugin(bytes((
+  0, 97, 115, 109, 1, 0, 0, 0, 1, 5, 1, 96, 0, 1, 127, 3, 3, 2, 0, 0, 5, 3, 1,
+  0, 1, 7, 24, 3, 6, 109, 101, 109, 111, 114, 121, 2, 0, 4, 116, 114, 97, 112,
+  0, 0, 4, 103, 114, 111, 119, 0, 1, 10, 14, 2, 3, 0, 0, 11, 8, 0, 65, 160, 156,
+  1, 64, 0, 11,
+)))
+
+#test(
+  catch(() => p.trap()).message,
+  "plugin function `trap` panicked: wasm `unreachable` instruction executed",
+)
+#test(
+  catch(() => p.grow()).message,
+  "plugin function `grow` exceeded its memory limit",
+)
+
+--- plugin-invalid-module ---
+// Error: 17-31 failed to load WebAssembly module (unexpected end-of-file (at offset 0x0))
+#let p = plugin(bytes((0, 97)))
@@ -0,0 +1,57 @@
+--- methods-of-builtin-type ---
+#let methods = methods-of(array)
+#let names = methods.map(method => method.name)
+#test(names.contains("push"), true)
+#test(names.contains("len"), true)
+#test(names.contains("range"), false)
+#test(methods-of("array"), methods)
+#test(methods-of("dictionary"), methods-of(dictionary))
+
+#let len = methods.find(method => method.name == "len")
+#test(len, (name: "len", params: (), returns: "int"))
+
+#let join = methods.find(method => method.name == "join")
+#test(join.params, (
+  (name: "separator", positional: true, named: false, required: false, variadic: false),
+  (name: "last", positional: false, named: true, required: false, variadic: false),
+))
+
+--- methods-of-unknown-type ---
+// Error: 13-19 unknown type: "nope"
+#methods-of("nope")
+
+--- fields-of-builtin-type ---
+#test(fields-of(length), ("em", "abs"))
+#test(fields-of("alignment"), ("x", "y"))
+#test(fields-of(int), ())
+
+--- params-of-native-func ---
+#test(params-of(calc.pow).map(param => param.name), ("base", "exponent"))
+
+#let (values, separator) = params-of(joined)
+#test(values.variadic, true)
+#test(separator, (
+  name: "separator",
+  positional: false,
+  named: true,
+  required: false,
+  variadic: false,
+))
+
+--- params-of-closure ---
+#let f(a, (b, c), key: 1, ..rest) = none
+#test(params-of(f), (
+  (name: "a", positional: true, named: false, required: true, variadic: false),
+  (name: "(b, c)", positional: true, named: false, required: true, variadic: false),
+  (name: "key", positional: false, named: true, required: false, variadic: false),
+  (name: "rest", positional: true, named: false, required: false, variadic: true),
+))
+#test(params-of(f.with(1)), params-of(f))
+#test(params-of((..) => none).first().name, none)
+#test(params-of(() => none), ())
+
+--- path-of ---
+#import "../scripting/module.typ"
+#test(path-of(module), "/tests/suite/scripting/module.typ")
+#test(path-of(module), module.path)
+#test(path-of(calc), none)
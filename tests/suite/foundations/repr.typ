@@ -47,7 +47,11 @@
 
 // Functions.
 #let f(x) = x
+// Warning: 2-3 this is a function; did you mean to call it?
+// Hint: 2-3 to call it, write `#f(..)`
 #f \
+// Warning: 2-6 this is a function; did you mean to call it?
+// Hint: 2-6 to apply it to content, write `#rect[..]`
 #rect \
 #(() => none)
 
@@ -100,7 +100,8 @@
 #test("z", "Hello".at(5, default: "z"))
 
 --- string-at-not-a-char-boundary ---
-// Error: 2-14 string index 2 is not a character boundary
+// The test runner shows backslashes in messages as forward slashes.
+// Error: 2-14 string index 2 is not a character boundary in "🏳/u{fe0f}/u{200d}🌈" (valid boundaries near 2: 0, 4)
 #"🏳️‍🌈".at(2)
 
 --- string-at-out-of-bounds ---
@@ -118,9 +119,38 @@
 #test("abc🏡def".slice(-3, -1), "de")
 
 --- string-slice-not-a-char-boundary ---
-// Error: 2-21 string index -1 is not a character boundary
+// The test runner shows backslashes in messages as forward slashes.
+// Error: 2-21 string index -1 is not a character boundary in "🏳/u{fe0f}/u{200d}🌈" (valid boundaries near 13: 10, 14)
 #"🏳️‍🌈".slice(0, -1)
 
+--- string-slice-not-a-char-boundary-naive ---
+// Error: 2-21 string index 3 is not a character boundary in "naïve" (valid boundaries near 3: 2, 4)
+#"naïve".slice(0, 3)
+
+--- string-slice-not-a-char-boundary-long ---
+// Error: 2-21 string index 1 is not a character boundary (valid boundaries near 1: 0, 2)
+#("ä" * 30).slice(1)
+
+--- string-index-consistency ---
+// Indices returned by one method are valid input to the others.
+#for s in ("naïve", "🏳️‍🌈 über", "a\u{0300}bc", "日本語") {
+  for pat in s.clusters() {
+    let i = s.position(pat)
+    test(s.at(i), pat)
+    test(s.slice(i, count: pat.len()), pat)
+    let m = s.match(pat)
+    test(m.start, i)
+    test(s.slice(m.start, m.end), pat)
+  }
+  for m in s.matches(regex(".")) {
+    test(s.slice(m.start, m.end), m.text)
+    test(s.slice(m.start - s.len(), count: m.text.len()), m.text)
+  }
+  test(s.slice(0, s.len()), s)
+  test(s.slice(s.len()), "")
+  test(s.at(s.len(), default: none), none)
+}
+
 --- string-clusters ---
 // Test the `clusters` and `codepoints` methods.
 #test("abc".clusters(), ("a", "b", "c"))
@@ -310,6 +340,104 @@
 #test("abc".rev(), "cba")
 #test("ax̂e".rev(), "ex̂a")
 
+--- string-format ---
+#test(format("plain"), "plain")
+#test(format("{}-{}", 1, "a"), "1-a")
+#test(format("{1}{0}{1}", "a", "b"), "bab")
+#test(format("{} {0} {}", "a", "b"), "a a b")
+#test(format("{name}: {n:04}", name: "id", n: 3), "id: 0003")
+#test(format("{{}} {{{}}}", 1), "{} {1}")
+#test(format("{}, {}, {}, {}", 1.0, sym.arrow, <lbl>, version(1, 2)), "1, →, lbl, 1.2")
+
+--- string-format-numbers ---
+#test(format("{:.2} {} {:.0}", 3.14159, 2.5, 7.8), "3.14 2.5 8")
+#test(format("{:+} {:+} {} {}", 5, -5, -1.5, 0), "+5 −5 −1.5 0")
+#test(format("{:05} {:+06.1} {:03}", -42, 2.26, 1234), "−0042 +002.3 1234")
+#test(format("{:+}", calc.inf), "+inf")
+
+--- string-format-padding ---
+#test(format("[{:5}] [{:5}]", "ab", 12), "[ab   ] [   12]")
+#test(format("[{:^6}] [{:>4}] [{:<4}]", "ab", "x", 7), "[  ab  ] [   x] [7   ]")
+#test(format("[{:^5}]", "abc"), "[ abc ]")
+#test(format("[{:2}]", "abc"), "[abc]")
+
+--- string-format-padding-unicode ---
+// The width is measured in grapheme clusters.
+#test(format("[{:3}]", "e\u{301}"), "[e\u{301}  ]")
+#test(format("[{:>3}]", "🏳️‍🌈"), "[  🏳️‍🌈]")
+#test(format("[{:^5}]", "日本"), "[ 日本  ]")
+#test(format("{:+04}", -1), "−001")
+
+--- string-format-missing-positional ---
+// Error: 9-16 placeholder `{}` has no matching argument
+// Hint: 9-16 1 positional argument was given
+#format("{} {}", 1)
+
+--- string-format-missing-index ---
+// Error: 9-14 placeholder `{2}` has no matching argument
+// Hint: 9-14 2 positional arguments were given
+#format("{2}", 1, 2)
+
+--- string-format-missing-named ---
+// Error: 9-17 placeholder `{name}` has no matching argument
+// Hint: 9-17 pass it as a named argument like `name: ..`
+#format("{name}", nme: 1)
+
+--- string-format-unexpected-positional ---
+// Error: 18-19 unexpected argument
+// Hint: 18-19 the format string has no placeholder for it
+#format("{}", 1, 2)
+
+--- string-format-unexpected-named ---
+// Error: 18-26 unexpected argument: extra
+// Hint: 18-26 the format string has no placeholder `{extra}`
+#format("{}", 1, extra: 2)
+
+--- string-format-precision-int ---
+// Error: 18-19 precision is only supported for floats
+// Hint: 18-19 convert the integer with `float` to display decimal places
+#format("{:.2}", 3)
+
+--- string-format-sign-string ---
+// Error: 17-20 a sign is only supported for numbers
+#format("{:+}", "a")
+
+--- string-format-zero-string ---
+// Error: 18-21 zero padding is only supported for numbers
+#format("{:05}", "a")
+
+--- string-format-bad-type ---
+// Error: 15-21 expected integer, float, version, bytes, label, type, or string, found array
+#format("{}", (1, 2))
+
+--- string-format-bad-spec ---
+// Error: 9-15 invalid format spec `x`
+// Hint: 9-15 a spec consists of an alignment (`<`, `^`, or `>`), a sign (`+`), zero padding (`0`), a width, and a precision (`.2`), in this order
+#format("{:x}", 1)
+
+--- string-format-huge-width ---
+// Error: 9-20 format width must be at most 1000
+#format("{:100000}", 1)
+
+--- string-format-huge-precision ---
+// Error: 9-38 format precision must be at most 100
+#format("{:.99999999999999999999999}", 1.5)
+
+--- string-format-invalid-placeholder ---
+// Error: 9-16 invalid placeholder `{a b}`
+// Hint: 9-16 placeholders contain an index, a name, or nothing, optionally followed by a colon and a format spec
+#format("{a b}", 1)
+
+--- string-format-unmatched-brace ---
+// Error: 9-16 unmatched closing brace in format string
+// Hint: 9-16 use `}}` to insert a literal closing brace
+#format("a } b")
+
+--- string-format-unclosed ---
+// Error: 9-16 unclosed placeholder in format string
+// Hint: 9-16 use `{{` to insert a literal opening brace
+#format("a { b")
+
 --- string-unclosed ---
 // Error: 2-2:1 unclosed string
 #"hello\"
@@ -11,6 +11,47 @@
 #test(int in "integers or strings", true)
 #test(str in "integers or strings", true)
 
+--- type-string-comparison-deprecated ---
+// Warning: 7-28 comparing a type with a string is deprecated
+// Hint: 7-28 compare with the type itself instead, like `int`
+#test(type(10) == "integer", true)
+
+// Warning: 7-22 comparing a type with a string is deprecated
+// Hint: 7-22 compare with the type itself instead, like `str`
+#test("string" != str, false)
+
+--- type-comparison ---
+#test(type(10) == int, true)
+#test(type("a") != int, true)
+#test(type(type(1)) == type, true)
+
+--- type-dispatch ---
+#let describe(x) = {
+  let handlers = (
+    (int, x => "number " + str(x)),
+    (str, x => "text " + x),
+  )
+  for (ty, handler) in handlers {
+    if type(x) == ty { return handler(x) }
+  }
+  "other"
+}
+#test(describe(1), "number 1")
+#test(describe("a"), "text a")
+#test(describe(none), "other")
+
+--- isinstance ---
+#test(isinstance(1, int), true)
+#test(isinstance(1, float), false)
+#test(isinstance(1.5, int, float), true)
+#test(isinstance("a", int), false)
+#test(isinstance(int, type), true)
+#test(isinstance(none), false)
+
+--- isinstance-not-a-type ---
+// Error: 16-25 expected type, found string
+#isinstance(1, "integer")
+
 --- issue-3110-type-constructor ---
 // Let the error message report the type name.
 // Error: 2-9 type content does not have a constructor
@@ -0,0 +1,26 @@
+--- warn ---
+// Warning: 2-17 careful
+#warn("careful")
+
+--- warn-with-values ---
+// Warning: 2-40 logo is missing: "logo.svg", 2
+#warn("logo is missing", "logo.svg", 2)
+
+--- warn-in-loop ---
+// Each distinct message is only reported once.
+#for i in range(5) {
+  // Warning: 3-60 parity: "even"
+  // Warning: 3-60 parity: "odd"
+  warn("parity", if calc.even(i) { "even" } else { "odd" })
+}
+
+--- warn-continues ---
+// Compilation continues normally after a warning.
+// Warning: 11-24 first
+#let x = {warn("first"); 1}
+#test(x + 1, 2)
+
+--- warn-strict ---
+// STRICT
+// Error: 2-17 careful
+#warn("careful")
@@ -0,0 +1,50 @@
+// Test grouping content with layout hints.
+
+--- group-construct ---
+#let g = group(keep: true)[A][B]
+#test(g.func(), group)
+#test(g.keep, true)
+#test(g.children, ([A], [B]))
+#test(g.has("breakable"), false)
+#test(group(breakable: false, [A]).breakable, false)
+#test(group().children, ())
+
+--- group-keep-with ---
+#test([A].keep-with[B], group(keep: true)[A][B])
+#test([A].keep-with([B]).children, ([A], [B]))
+
+--- group-show-rule ---
+#show group: it => {
+  test(it.keep, true)
+  test(it.children, ([A], [B]))
+  none
+}
+#group(keep: true)[A][B]
+
+--- group-show-rule-set ---
+#set group(breakable: false)
+#show group: it => {
+  test(it.breakable, false)
+  none
+}
+#group[A]
+
+--- group-transparent ---
+// A group is laid out like its children.
+#context test(measure(group[Hello][World]), measure[HelloWorld])
+#let doc = [Intro #group(strong[A], [B])]
+#test(
+  doc.rewrite((strong: it => it.body)),
+  [Intro #group([A], [B])],
+)
+
+--- group-equality ---
+#test(group[A][B], group[A][B])
+#test(group(keep: true)[A], group(keep: true)[A])
+#test(group[A] != group(keep: true)[A], true)
+#test(group[A] != group[A][B], true)
+#test(group[A] != [A], true)
+
+--- group-bad-child ---
+// Error: 8-9 expected content, found integer
+#group(1)
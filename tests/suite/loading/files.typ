@@ -0,0 +1,32 @@
+// Test the `files` function.
+
+--- files-star ---
+#test(files("j*.typ"), ("json.typ",))
+#test(files("./?ml.typ"), ("./xml.typ",))
+#test(files("/tests/suite/loading/y*"), ("/tests/suite/loading/yaml.typ",))
+
+--- files-recursive ---
+#test(files("../loading/t*.typ", recursive: true), ("../loading/toml.typ",))
+#let all = files("/tests/suite/*.typ", recursive: true)
+#test(all.contains("/tests/suite/loading/files.typ"), true)
+#test(all, all.sorted())
+
+--- files-no-matches ---
+#test(files("*.pdf"), ())
+
+--- files-escapes-root ---
+// Error: 8-27 pattern escapes the project root
+#files("../../../../*.typ")
+
+--- files-wildcard-in-directory ---
+// Error: 8-17 wildcards are only supported in the last path component
+// Hint: 8-17 use `recursive: true` to also list files in subdirectories
+#files("*/a.typ")
+
+--- files-directory-not-found ---
+// Error: 8-19 file not found (searched at tests/suite/loading/missing)
+#files("missing/*")
+
+--- files-no-file-name ---
+// Error: 8-18 pattern must end with a file name
+#files("loading/")
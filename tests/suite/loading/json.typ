@@ -14,3 +14,42 @@
 // but not overflow
 #let bignum = json("/assets/data/big-number.json")
 #bignum
+
+--- json-dates ---
+#let data = `{"created": "2024-03-01", "at": "12:30:00", "sent": "2024-03-01T08:15:00"}`.text
+#test(json.decode(data).created, "2024-03-01")
+#test(json.decode(data, dates: none), json.decode(data))
+#let parsed = json.decode(data, dates: auto)
+#test(parsed.created, datetime(year: 2024, month: 3, day: 1))
+#test(parsed.at, datetime(hour: 12, minute: 30, second: 0))
+#test(parsed.sent, datetime(year: 2024, month: 3, day: 1, hour: 8, minute: 15, second: 0))
+#test(json.decode(`{"title": "2024"}`.text, dates: auto).title, "2024")
+
+--- json-dates-keys ---
+#let data = `{"created": "2024-03-01", "id": "2024-03-02", "log": [{"updated": "2024-03-03"}], "tags": {"updated": ["2024-03-04"]}}`.text
+#let parsed = json.decode(data, dates: ("created", "updated"))
+#test(parsed.created, datetime(year: 2024, month: 3, day: 1))
+#test(parsed.id, "2024-03-02")
+#test(parsed.log.at(0).updated, datetime(year: 2024, month: 3, day: 3))
+#test(parsed.tags.updated, (datetime(year: 2024, month: 3, day: 4),))
+
+--- json-big-ints ---
+#let data = `{"small": 42, "id": 12345678901234567890}`.text
+#test(json.decode(data).small, 42)
+#test(type(json.decode(data).id), float)
+#test(json.decode(data).id, 12345678901234567890.0)
+#test(json.decode(data, big-ints: "float"), json.decode(data))
+#test(json.decode(data, big-ints: "string"), (small: 42, id: "12345678901234567890"))
+#test(json.decode(`{"small": 42}`.text, big-ints: "error"), (small: 42))
+
+--- json-big-ints-error ---
+// Error: 14-73 integer 12345678901234567890 at `items.at(1).id` does not fit into 64 bits
+#json.decode(`{"items": [{"id": 1}, {"id": 12345678901234567890}]}`.text, big-ints: "error")
+
+--- json-big-ints-error-root ---
+// Error: 14-36 integer 12345678901234567890 does not fit into 64 bits
+#json.decode("12345678901234567890", big-ints: "error")
+
+--- json-big-ints-bad-mode ---
+// Error: 48-54 expected "float", "string", or "error"
+#json.decode("12345678901234567890", big-ints: "text")
@@ -15,3 +15,21 @@
 --- yaml-invalid ---
 // Error: 7-30 failed to parse YAML (did not find expected ',' or ']' at line 2 column 1, while parsing a flow sequence at line 1 column 18)
 #yaml("/assets/data/bad.yaml")
+
+--- yaml-timestamp ---
+// Timestamps are always parsed, plain strings only if asked for.
+#let data = "tagged: !timestamp 2024-03-01\nplain: 2024-03-02\nother: !custom text"
+#test(yaml.decode(data).tagged, datetime(year: 2024, month: 3, day: 1))
+#test(yaml.decode(data).plain, "2024-03-02")
+#test(yaml.decode(data).other, "text")
+#test(yaml.decode(data, dates: auto).plain, datetime(year: 2024, month: 3, day: 2))
+#test(yaml.decode(data, dates: ("tagged",)).plain, "2024-03-02")
+
+--- yaml-big-ints ---
+#let data = "id: 12345678901234567890"
+#test(type(yaml.decode(data).id), float)
+#test(yaml.decode(data, big-ints: "string").id, "12345678901234567890")
+
+--- yaml-big-ints-error ---
+// Error: 14-43 integer 12345678901234567890 at `ids.at(0)` does not fit into 64 bits
+#yaml.decode("ids: [12345678901234567890]", big-ints: "error")
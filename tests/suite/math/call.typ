@@ -65,6 +65,40 @@ $ sin(1) $
 // Error: 8-9 expected content, found integer
 $ sin(#1) $
 
+--- math-call-named-args-non-func ---
+// Error: 9-16 named arguments are only allowed when calling a function; `pi` is a symbol
+$ pi(a, size: 1) $
+
+--- math-call-named-args-non-func-field ---
+// Error: 13-17 named arguments are only allowed when calling a function; `pi.alt` is a symbol
+$ pi.alt(x, y: 1) $
+
+--- math-call-unknown-error ---
+#set math.equation(unknown-calls: "error")
+// Error: 3-5 `pi` is a symbol, not a function
+// Hint: 3-5 to display the parentheses, escape the opening one like `pi\(`
+// Hint: 3-5 or define a function named `pi`
+$ pi(a, b) $
+
+--- math-call-unknown-error-field ---
+#set math.equation(unknown-calls: "error")
+// Error: 2-8 `pi.alt` is a symbol, not a function
+// Hint: 2-8 to display the parentheses, escape the opening one like `pi.alt\(`
+// Hint: 2-8 or define a function named `pi.alt`
+$pi.alt(x)$
+
+--- math-call-unknown-error-ops-and-accents ---
+// Operators and accents are unaffected.
+#set math.equation(unknown-calls: "error")
+#context test(measure($sin(x) + arrow(v) + hat(a) + pi\(x)$).width > 0pt, true)
+
+--- math-call-unknown-render ---
+#let width(it) = measure(it).width
+#context test(width($pi(a, b)$) > width($pi$), true)
+
+--- math-call-named-args-func ---
+#test($vec(1, 2, delim: "[")$.body.delim, ("[", "]"))
+
 --- math-call-pass-to-box ---
 // When passing to a function, we lose the italic styling if we wrap the content
 // in a non-math function unless it's already nested in some math element (lr,
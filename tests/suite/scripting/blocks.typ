@@ -56,6 +56,8 @@
 #{
   [A]
   // Error: 3-4 cannot join content with integer
+  // Hint: 3-4 use `str` or `repr` to convert values, or `joined` to join them with a separator
+  // Hint: 3-4 unlike joining, the `+` operator can also add numbers
   1
   [B]
 }
@@ -141,3 +143,17 @@
 
 // Error: 2-3 unknown variable: x
 #x
+
+--- block-nesting-too-deep ---
+// Error: 7-33 maximum nesting depth exceeded
+#eval("#[" * 50000 + "]" * 50000, mode: "markup")
+
+--- parenthesized-nesting-too-deep ---
+// Error: 7-38 maximum nesting depth exceeded
+#eval("(" * 50000 + "1" + ")" * 50000)
+
+--- block-nesting-deep ---
+// Deep, but not too deep nesting works.
+#test(eval("(" * 200 + "1" + ")" * 200), 1)
+#test(eval("{" * 200 + "1" + "}" * 200), 1)
+#test(eval("(" * 200 + "1" + ",)" * 200).flatten(), (1,))
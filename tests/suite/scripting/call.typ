@@ -11,6 +11,8 @@
 #f(1)[2](3)
 
 // Don't parse this as a function.
+// Warning: 2-6 this is a function; did you mean to call it?
+// Hint: 2-6 to call it, write `#test(..)`
 #test (it)
 
 #let f(body) = body
@@ -23,6 +25,32 @@
 #g([A], [B])
 #g()[A][B]
 
+--- call-trailing-content-closure ---
+// Trailing content blocks are passed to closures as positional arguments,
+// after the parenthesized ones and in order.
+#let f(body) = body
+#test(f[a], [a])
+#test(f()[a], f([a]))
+
+#let pair(a, b) = (a, b)
+#test(pair[a][b], ([a], [b]))
+#test(pair([a])[b], ([a], [b]))
+
+#let wrap(fill: none, ..args) = (fill, args.pos())
+#test(wrap(1, fill: red)[a][b], (red, (1, [a], [b])))
+
+--- call-trailing-content-closure-too-many ---
+#let f(body) = body
+// Error: 6-9 unexpected argument
+// Hint: 6-9 the function takes 1 positional argument, but 2 were given, 2 of them as trailing content blocks
+#f[a][b]
+
+--- call-trailing-content-closure-too-many-mixed ---
+#let pair(a, b) = (a, b)
+// Error: 14-17 unexpected argument
+// Hint: 14-17 the function takes 2 positional arguments, but 4 were given, 3 of them as trailing content blocks
+#pair([a])[b][c][d]
+
 --- call-aliased-function ---
 // Call function assigned to variable.
 #let alias = type
@@ -171,8 +199,37 @@
 
 --- call-args-spread-string-invalid ---
 // Error: 11-19 cannot spread string
+// Hint: 11-19 use `.clusters()` to spread the string's characters
 #calc.min(.."nope")
 
+--- call-args-spread-dict ---
+// Spreading a dictionary into arguments yields named arguments.
+#let f(..args) = args
+#test(f(..(a: 1, b: 2)).named(), (a: 1, b: 2))
+#test(f(..(a: 1, b: 2)).pos(), ())
+
+--- call-args-positional-after-named ---
+// PEDANTIC
+#let f(..args) = none
+// Warning: 16-22 positional argument after named argument
+// Hint: 16-22 move it before the named arguments or give it a name
+#f(width: 1cm, [body], height: 2cm)
+
+--- call-args-positional-after-named-exempt ---
+// PEDANTIC
+// Purely positional or named arguments, spreads, and trailing content blocks
+// are fine.
+#let f(..args) = none
+#f(1, 2)
+#f(a: 1, b: 2)
+#f(a: 1, ..(1, 2))
+#f(a: 1)[body]
+
+--- call-args-positional-after-named-off ---
+// Without pedantic lints, the order of arguments doesn't matter.
+#let f(..args) = none
+#f(width: 1cm, [body], height: 2cm)
+
 --- call-args-content-block-unclosed ---
 // Error: 6-7 unclosed delimiter
 #func[`a]`
@@ -202,6 +259,27 @@
 // Error: 7-8 unclosed delimiter
 #{func(}
 
+--- call-uncalled-element-in-markup ---
+// Warning: 15-21 this is a function; did you mean to call it?
+// Hint: 15-21 to apply it to content, write `#strong[..]`
+#let body = [#strong]
+
+--- call-uncalled-func-in-markup ---
+// Warning: 15-23 this is a function; did you mean to call it?
+// Hint: 15-23 to call it, write `#calc.abs(..)`
+#let body = [#calc.abs]
+
+--- call-called-func-in-markup ---
+// Calls and deliberate representations don't warn.
+#let f() = none
+#let body = [#strong[x] #f() #repr(strong) #(strong)]
+
+--- call-uncalled-func-in-markup-strict ---
+// STRICT
+// Error: 15-21 this is a function; did you mean to call it?
+// Hint: 15-21 to apply it to content, write `#strong[..]`
+#let body = [#strong]
+
 --- call-args-unclosed-string ---
 // Error: 6-7 unclosed delimiter
 // Error: 1:7-2:1 unclosed string
@@ -44,6 +44,32 @@
   test(greet("Typst"), "Hi, Typst!")
 }
 
+--- closure-capture-in-loop ---
+// Each instantiation of the same closure captures the values at that time.
+#{
+  let offset = 10
+  let fs = ()
+  for i in range(3) {
+    fs.push(x => x + i + offset)
+    offset += 1
+  }
+  test(fs.map(f => f(0)), (10, 12, 14))
+}
+
+--- closure-capture-in-loop-math ---
+#{
+  let fs = ()
+  for xy in range(2) {
+    fs.push(() => $xy$)
+  }
+  test(fs.map(f => f().body), ([0], [1]))
+}
+
+--- closure-capture-eval ---
+// Closures in evaluated strings share the span of the call.
+#let fs = ("() => a", "() => b").map(code => eval(code, scope: (a: 1, b: 2)))
+#test(fs.map(f => f()), (1, 2))
+
 --- closure-shadows-outer-var ---
 // Redefined variable.
 #{
@@ -126,6 +152,7 @@
   let f(x) = x + 1
 
   // Error: 8-13 unexpected argument
+  // Hint: 8-13 the function takes 1 positional argument, but 3 were given
   f(1, "two", () => x)
 }
 
@@ -221,3 +248,37 @@
 #let x = 1
 #let c = [#(x) => (1, 2)]
 #test(c.children.last(), [(1, 2)]))
+
+--- closure-transparent ---
+// A transparent function is called just like the function it was made from,
+// only its internals are left out of error traces.
+#let fig = ((..args) => figure(..args, kind: image)).transparent()
+#test(type(fig), function)
+#fig([A], caption: [Image])
+
+// Error: 16-22 expected length, found string
+#fig([A], gap: "wide")
+
+--- closure-transparent-trace ---
+// A transparent wrapper adds no frame to the trace.
+#let fig = ((..args) => figure(..args, kind: image)).transparent()
+#let result = catch(() => fig([A], gap: "wide"))
+#test(result.message, "expected length, found string")
+#test(result.trace.len(), 0)
+
+--- closure-transparent-trace-opaque ---
+// A wrapper that isn't transparent keeps its frame.
+#let fig = (..args) => figure(..args, kind: image)
+#let result = catch(() => fig([A], gap: "wide"))
+#test(result.trace.len(), 1)
+
+--- closure-transparent-trace-nested ---
+// Nested transparent wrappers collapse fully.
+#let inner = ((..args) => figure(..args)).transparent()
+#let fig = ((..args) => inner(..args, kind: image)).transparent()
+#test(catch(() => fig([A], gap: "wide")).trace.len(), 0)
+
+// A wrapper around a transparent one that isn't transparent itself keeps
+// its own frame only.
+#let fig = (..args) => inner(..args, kind: image)
+#test(catch(() => fig([A], gap: "wide")).trace.len(), 1)
@@ -168,6 +168,16 @@
 #let (..a) = (:)
 #test(a, (:))
 
+--- destructuring-let-dict-with-sink-shared ---
+// The sink keeps the order of the remaining keys and the destructured
+// dictionary stays intact.
+#let dict = (d: 4, a: 1, c: 3, b: 2)
+#let (c, a: x, ..rest) = dict
+#test(rest.keys(), ("d", "b"))
+#test(dict.keys(), ("d", "a", "c", "b"))
+#let (..copy) = dict
+#test(copy.keys(), dict.keys())
+
 --- destructuring-let-dict-with-unnamed-sink ---
 // Destructuring with unnamed sink.
 #let (a, ..) = (a: 1, b: 2)
@@ -214,6 +224,84 @@
 // Error: 7-11 cannot destructure named pattern from an array
 #let (a: a, b) = (1, 2, 3)
 
+--- destructuring-let-dict-returned-missing-key ---
+// Error: 14-17 dictionary does not contain key "txt"
+// Hint: 14-17 `match` returns a dictionary with the keys `start`, `end`, `text`, and `captures`
+#let (start, txt) = "abc".match("b")
+
+--- destructuring-let-dict-returned-named-missing-key ---
+#context {
+  // Error: 15-20 dictionary does not contain key "hight"
+  // Hint: 15-20 `measure` returns a dictionary with the keys `width` and `height`
+  let (width, hight: h) = measure[A]
+}
+
+--- destructuring-assign-dict-returned-missing-key ---
+#{
+  let (start, end) = (0, 0)
+  // Error: 11-13 dictionary does not contain key "ed"
+  // Hint: 11-13 `match` returns a dictionary with the keys `start`, `end`, `text`, and `captures`
+  (start, ed) = "abc".match("b")
+}
+
+--- destructuring-let-dict-returned-indirect ---
+// Only the result of the call itself is known to stem from the function.
+#let m = "abc".match("b")
+// Error: 14-17 dictionary does not contain key "txt"
+#let (start, txt) = m
+
+--- destructuring-let-dict-returned ---
+#let (start, end, text: t) = "abc".match("b")
+#test((start, end, t), (1, 2, "b"))
+
+--- destructuring-let-args ---
+// Destructure positional and named arguments.
+#let args = arguments(1, 2, fill: red, 3, stroke: blue)
+#let (a, _, fill: f, ..rest) = args
+#test(a, 1)
+#test(f, red)
+#test(rest, arguments(3, stroke: blue))
+
+--- destructuring-let-args-with-sink-in-middle ---
+#let (first, ..middle, last) = arguments(1, 2, 3, 4, key: "value")
+#test((first, last), (1, 4))
+#test(middle.pos(), (2, 3))
+#test(middle.named(), (key: "value"))
+
+--- destructuring-let-args-forward ---
+// Destructured arguments can be spread back into a call.
+#let g(..args) = args
+#let f(..args) = {
+  let (body, size: _, ..rest) = args
+  g(body, ..rest)
+}
+#test(f([A], size: 10pt, fill: red), arguments([A], fill: red))
+
+--- destructuring-let-args-from-sink ---
+#let f(..args) = {
+  let (x, ..) = args
+  x
+}
+#test(f(1, 2, three: 3), 1)
+
+--- destructuring-let-args-not-enough ---
+// Error: 10-11 not enough positional arguments to destructure
+// Hint: 10-11 the provided arguments have 1 positional argument
+#let (a, b) = arguments(1)
+
+--- destructuring-let-args-not-enough-spread ---
+// Error: 13-15 not enough positional arguments to destructure
+// Hint: 13-15 the provided arguments have 2 positional arguments
+#let (a, b, .., c) = arguments(1, 2)
+
+--- destructuring-let-args-missing-named ---
+// Error: 10-14 missing argument: fill
+#let (a, fill: f) = arguments(1, stroke: red)
+
+--- destructuring-let-args-unexpected ---
+// Error: 26-35 unexpected argument: fill
+#let (a,) = arguments(1, fill: red)
+
 --- destructuring-during-loop-continue ---
 // Test continue while destructuring.
 // Should output "one = I \ two = II \ one = I".
@@ -368,3 +456,12 @@
 // Error: 6-12 too many elements to destructure
 // Hint: 6-12 the provided array has a length of 3, but the pattern expects 2 elements
 #for (x, y) in ((1,2,3), (4,5,6)) {}
+
+--- destructuring-multiline-span ---
+// Destructuring errors point at the binding that failed.
+#let (
+  a,
+  // Error: 7-8 not enough elements to destructure
+  // Hint: 7-8 the provided array has a length of 1
+  (b, c),
+) = (1, (2,))
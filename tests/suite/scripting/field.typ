@@ -2,8 +2,14 @@
 
 --- field-function ---
 // Test fields on function scopes.
+// Warning: 2-11 this is a function; did you mean to call it?
+// Hint: 2-11 to apply it to content, write `#enum.item[..]`
 #enum.item
+// Warning: 2-11 this is a function; did you mean to call it?
+// Hint: 2-11 to call it, write `#assert.eq(..)`
 #assert.eq
+// Warning: 2-11 this is a function; did you mean to call it?
+// Hint: 2-11 to call it, write `#assert.ne(..)`
 #assert.ne
 
 --- field-normal-function-invalid ---
@@ -101,6 +101,44 @@
 // Hint: 7-8 destructuring patterns must be wrapped in parentheses
 #for k, in () {}
 
+--- for-loop-assign-to-variable ---
+// Error: 23-24 cannot reassign the loop variable
+// Hint: 23-24 bind a new variable with let if needed
+#for x in (1, 2, 3) { x = 10 }
+
+--- for-loop-destructuring-assign-to-variable ---
+// Error: 29-30 cannot reassign the loop variable
+// Hint: 29-30 bind a new variable with let if needed
+#for (a, b) in ((1, 2),) { (a, b) = (b, a) }
+
+--- for-loop-compound-assign-to-variable ---
+// Compound assignments feed back into the same iteration.
+#let out = ()
+#for x in (1, 2, 3) {
+  x += 10
+  out.push(x)
+}
+#test(out, (11, 12, 13))
+
+--- for-loop-mutate-variable ---
+// Mutating the value of a loop variable is fine.
+#let out = ()
+#for d in ((a: 1), (a: 2)) {
+  d.a = d.a * 10
+  d.insert("b", 0)
+  out.push(d)
+}
+#test(out, ((a: 10, b: 0), (a: 20, b: 0)))
+
+--- for-loop-shadow-variable ---
+#let out = ()
+#for x in (1, 2) {
+  let x = x * 2
+  x = x + 1
+  out.push(x)
+}
+#test(out, (3, 5))
+
 --- for-loop-incomplete ---
 // Error: 5 expected pattern
 #for
@@ -1,6 +1,8 @@
 // Test function and module imports.
 
 --- import-basic ---
+// SCOPED
+// PREFETCH
 // Test basic syntax and semantics.
 
 // Test that this will be overwritten.
@@ -227,16 +229,19 @@
 #import "/assets/images/rhino.png"
 
 --- import-item-not-found ---
+// PREFETCH
 // Unresolved import.
 // Error: 23-35 unresolved import
 #import "module.typ": non_existing
 
 --- import-cyclic ---
+// PREFETCH
 // Cyclic import of this very file.
 // Error: 9-23 cyclic import
 #import "./import.typ"
 
 --- import-cyclic-in-other-file ---
+// PREFETCH
 // Cyclic import in other file.
 #import "./modules/cycle1.typ": *
 
@@ -321,11 +326,44 @@ This is never reached.
 #import "@test/adder:0.1.0": add
 #test(add(2, 8), 10)
 
+--- import-module-path ---
+#import "module.typ"
+#test(module.path, "/tests/suite/scripting/module.typ")
+
+--- import-module-path-package ---
+#import "@test/adder:0.1.0"
+#test(adder.path, "@test/adder:0.1.0/lib.typ")
+
+--- import-module-path-builtin ---
+#test(calc.path, none)
+#test(eval("sys").path, none)
+
+--- import-module-content ---
+// SCOPED
+#import "module.typ"
+#test(module.content, include "module.typ")
+#test(calc.content, [])
+
+--- import-module-functions-only ---
+// SCOPED
+#import "module.typ"
+#let utils = module.functions-only()
+#test(utils.content, [])
+#test(include utils, [])
+#test(utils.item(1, 2), 3)
+#test(utils.b, module.b)
+#test(module.content == [], false)
+
 --- import-from-package-required-compiler-version ---
 // Test too high required compiler version.
 // Error: 9-29 package requires typst 1.0.0 or newer (current version is VERSION)
 #import "@test/future:0.1.0": future
 
+--- import-from-package-entrypoint-missing ---
+// Error: 9-29 file not found (searched at tests/packages/broken-0.1.0/missing.typ)
+// Hint: 9-29 the file is named as the `entrypoint` in the manifest of @test/broken:0.1.0
+#import "@test/broken:0.1.0"
+
 --- import-from-package-namespace-invalid-1 ---
 // Error: 9-13 `@` is not a valid package namespace
 #import "@@": *
@@ -377,3 +415,32 @@ This is never reached.
 --- import-from-file-package-lookalike ---
 // Error: 9-28 file not found (searched at tests/suite/scripting/#test/mypkg:1.0.0)
 #import "#test/mypkg:1.0.0": *
+
+--- import-unused ---
+// PEDANTIC
+// Only the unused item is reported.
+// Warning: 26-27 unused import: c
+#import "module.typ": a, c
+#test(a, none)
+
+--- import-unused-wildcard ---
+// PEDANTIC
+// Wildcard imports are exempt.
+#import "module.typ": *
+
+--- import-used-in-closure ---
+// PEDANTIC
+// Capturing an imported item counts as using it.
+#import "module.typ": a
+#let f() = a
+
+--- import-unused-in-block ---
+// PEDANTIC
+#{
+  // Warning: 24-25 unused import: c
+  import "module.typ": c
+}
+
+--- import-unused-off ---
+// Without pedantic lints, unused imports are fine.
+#import "module.typ": c
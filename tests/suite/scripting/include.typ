@@ -1,6 +1,7 @@
 // Test module includes.
 
 --- include-file ---
+// PREFETCH
 #set page(width: 200pt)
 
 = Document
@@ -30,3 +31,45 @@
 --- include-semicolon-or-linebreak ---
 // Error: 18 expected semicolon or line break
 #include "hi.typ" Hi
+
+--- include-array ---
+#let chapters = ("chap1", "chap2").map(c => "modules/" + c + ".typ")
+#test(
+  (include chapters).children,
+  (include "modules/chap1.typ", include "modules/chap2.typ"),
+)
+
+--- include-array-mixed ---
+#import "modules/chap1.typ" as chap1
+#test(
+  (include (chap1, "modules/chap2.typ")).children,
+  (include chap1, include "modules/chap2.typ"),
+)
+
+--- include-array-empty ---
+#test(include (), [])
+
+--- include-array-not-found ---
+// Error: 10-52 file not found (searched at tests/suite/scripting/modules/chap3.typ)
+// Hint: 10-52 error occurred while including item 1 ("modules/chap3.typ")
+#include ("modules/chap1.typ", "modules/chap3.typ")
+
+--- include-array-cyclic ---
+// PREFETCH
+// Error: 10-46 cyclic import
+// Hint: 10-46 error occurred while including item 1 ("include.typ")
+#include ("modules/chap1.typ", "include.typ")
+
+--- include-array-invalid-item ---
+// Error: 10-34 expected path or module, found integer
+// Hint: 10-34 error occurred while including item 1 (2)
+#include ("modules/chap1.typ", 2)
+
+--- include-invalid-type ---
+// Error: 10-11 expected path, module, or array, found integer
+#include 1
+
+--- include-cyclic-in-show-rule ---
+// Error: 24-37 cyclic import
+// Hint: 24-37 the cycle passes through a show rule defined at tests/suite/scripting/include.typ:3:8
+#show: it => { include "include.typ"; it }
@@ -158,3 +158,70 @@ Three
 // Error: 21-24 expected pattern, found keyword `let`
 // Hint: 21-24 keyword `let` is not allowed as an identifier; try `let_` instead
 #let (context, foo, let) = (5, 6, 7)
+
+--- let-unknown-variable-suggest-std ---
+// Error: 2-7 unknown variable: textt
+// Hint: 2-7 did you mean `text`?
+#textt("a")
+
+--- let-unknown-variable-suggest-swapped ---
+// Error: 2-6 unknown variable: emhp
+// Hint: 2-6 did you mean `emph`?
+#emhp[..]
+
+--- let-unknown-variable-suggest-local ---
+#let value = 1
+// Error: 2-7 unknown variable: valeu
+// Hint: 2-7 did you mean `value`?
+#valeu
+
+--- let-unknown-variable-suggest-math ---
+// Error: 3-8 unknown variable: alpah
+// Hint: 3-8 did you mean `alpha`?
+$ alpah $
+
+--- let-shadow-std ---
+// PEDANTIC
+// Warning: 6-10 variable `text` shadows the standard library
+// Hint: 6-10 this shadows the standard library function `text`
+// Hint: 6-10 rename the variable or access the original as `std.text`
+#let text = "hi"
+
+// Warning: 6-11 variable `table` shadows the standard library
+// Hint: 6-11 this shadows the standard library function `table`
+// Hint: 6-11 rename the variable or access the original as `std.table`
+#let table(x) = x
+
+--- let-shadow-std-nested ---
+// PEDANTIC
+// Inside closures, in destructuring patterns, and in loops.
+// Warning: 18-22 variable `rect` shadows the standard library
+// Hint: 18-22 this shadows the standard library function `rect`
+// Hint: 18-22 rename the variable or access the original as `std.rect`
+#let f() = { let rect = 1; rect }
+#f()
+
+// Warning: 11-14 variable `red` shadows the standard library
+// Hint: 11-14 this shadows the standard library color `red`
+// Hint: 11-14 rename the variable or access the original as `std.red`
+#let (a, (red, b)) = (1, (2, 3))
+
+// Warning: 6-9 variable `box` shadows the standard library
+// Hint: 6-9 this shadows the standard library function `box`
+// Hint: 6-9 rename the variable or access the original as `std.box`
+#for box in (1, 2) {}
+
+--- let-shadow-std-exempt ---
+// PEDANTIC
+// Names the library doesn't export, parameters, and captured variables are
+// fine.
+#let greeting = 1
+#let f(text) = text
+#f(1)
+#let x = 1
+#let g() = x
+#g()
+
+--- let-shadow-std-off ---
+// Without pedantic lints, shadowing is fine.
+#let text = 1
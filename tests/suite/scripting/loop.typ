@@ -140,3 +140,85 @@
     for _ in range(3) [B]
   )
 }
+
+--- loop-break-label ---
+// Break out of two loops at once.
+#let pairs = ()
+#for <outer> x in range(3) {
+  for y in range(3) {
+    if x * y == 2 { break <outer> }
+    pairs.push((x, y))
+  }
+}
+#test(pairs, ((0, 0), (0, 1), (0, 2), (1, 0), (1, 1)))
+
+--- loop-continue-label ---
+#let out = ()
+#let i = 0
+#while <outer> i < 3 {
+  i += 1
+  for c in "abc" {
+    if c == "b" { continue <outer> }
+    out.push(str(i) + c)
+  }
+  out.push("unreachable")
+}
+#test(out, ("1a", "2a", "3a"))
+
+--- loop-break-label-innermost ---
+// Without a label, the innermost loop is targeted. A label that names the
+// innermost loop behaves the same way.
+#let out = ""
+#for <a> x in "ab" {
+  for <b> y in "xy" {
+    out += x + y
+    break <b>
+  }
+  for y in "xy" {
+    out += y
+    break
+  }
+}
+#test(out, "axxbxx")
+
+--- loop-label-shadowed ---
+// The innermost loop with the label is targeted.
+#let out = ""
+#for <l> x in "ab" {
+  for <l> y in "xy" {
+    out += x + y
+    break <l>
+  }
+}
+#test(out, "axbx")
+
+--- loop-label-join ---
+#let out = for <outer> x in range(3) {
+  for y in range(3) {
+    if y == 2 { continue <outer> }
+    str(x) + str(y)
+  }
+}
+#test(out, "000110112021")
+
+--- loop-break-label-unknown ---
+#for x in range(3) {
+  for y in range(3) {
+    // Error: 5-18 no enclosing loop is labelled <inner>
+    break <inner>
+  }
+}
+
+--- loop-continue-label-outside-of-loop ---
+// Error: 12-28 no enclosing loop is labelled <outer>
+#let x = { continue <outer> }
+
+--- loop-break-label-in-function ---
+// A loop label can't be targeted from within a function.
+#for <outer> x in range(3) {
+  let f() = {
+    // Error: 5-18 no enclosing loop is labelled <outer>
+    break <outer>
+  }
+  f()
+}
@@ -12,6 +12,37 @@
   test(matrix, (((1,), (2,)), ((3, 5), (4,))))
 }
 
+--- method-mutating-field-chain ---
+// Test mutating a value reached through a chain of fields.
+#{
+  let d = (items: (1,), inner: (a: 1), list: (1, 2, 3))
+  d.items.push(2)
+  d.inner.insert("b", 2)
+  test(d.list.remove(0), 1)
+  test(d, (items: (1, 2), inner: (a: 1, b: 2), list: (2, 3)))
+
+  let deep = (a: (b: (c: ())))
+  deep.a.b.c.push("x")
+  deep.a.b.c.push("y")
+  test(deep.a.b.c, ("x", "y"))
+}
+
+--- method-mutating-field-chain-missing ---
+#{
+  let d = (:)
+  // Error: 3-10 dictionary does not contain key "items"
+  // Hint: 3-10 use `insert` to add or update values
+  d.items.push(1)
+}
+
+--- method-mutating-field-chain-captured ---
+#let d = (items: ())
+#let f() = {
+  // Error: 3-4 variables from outside the function are read-only and cannot be modified
+  d.items.push(1)
+}
+#f()
+
 --- method-multiline ---
 // Test multiline chain in code block.
 #{
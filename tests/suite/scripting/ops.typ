@@ -278,6 +278,35 @@
 // Not in handles precedence.
 #test(-1 not in (1, 2, 3), true)
 
+--- ops-in-dict ---
+#let dict = (a: 1, b: (c: 2))
+#test("a" in dict, true)
+#test("c" in dict, false)
+#test("c" not in dict, true)
+#test("c" in dict.b, true)
+#test("a" in dict.b, false)
+#test("b" not in dict.b, true)
+
+--- ops-in-dict-non-string ---
+// Error: 3-4 cannot apply 'in' to integer and dictionary
+// Hint: 3-4 dictionary keys are always strings
+#(1 in (a: 1))
+
+--- ops-not-in-dict-content ---
+// Error: 3-6 cannot apply 'not in' to content and dictionary
+// Hint: 3-6 dictionary keys are always strings
+#([a] not in (a: 1))
+
+--- ops-in-str-content ---
+// Error: 3-8 cannot apply 'in' to content and string
+// Hint: 3-8 only strings and regular expressions can be searched for in a string
+#([abc] in "abc")
+
+--- ops-in-content ---
+// Error: 12-17 cannot apply 'in' to string and content
+// Hint: 12-17 only strings, arrays, and dictionaries can contain values
+#("abc" in [abc])
+
 --- ops-precedence-parentheses ---
 // Parentheses override precedence.
 #test((1), 1)
@@ -326,6 +355,22 @@
 // Error: 3-9 cannot apply 'not' to array
 #(not ())
 
+--- ops-and-non-boolean-lhs ---
+// Error: 3-4 expected boolean, found integer
+#(1 and panic())
+
+--- ops-or-non-boolean-lhs ---
+// Error: 3-4 expected boolean, found integer
+#(1 or panic())
+
+--- ops-and-non-boolean-rhs ---
+// Error: 12-15 expected boolean, found string
+#(true and "a")
+
+--- ops-or-non-boolean-rhs ---
+// Error: 12-16 expected boolean, found none
+#(false or none)
+
 --- ops-compare-relative-length-and-ratio ---
 // Error: 3-19 cannot compare relative length and ratio
 #(30% + 1pt <= 40%)
@@ -463,3 +508,47 @@
 // (since then it doesn't resolve to the standard library version anymore).
 #let rect = ""
 #(rect = "hi")
+
+--- ops-constant-repeated ---
+// Constant expressions give the same result every time they are evaluated.
+#let results = ()
+#for i in range(3) {
+  results.push(2 * 3.5 / 7 + -(1 - 4))
+  results.push("a" + "b" in "abc")
+}
+#test(results, (4.0, true) * 3)
+
+--- ops-constant-overflow-repeated ---
+// Errors in constant expressions are reported every time.
+#for i in range(2) {
+  // Error: 3-26 value is too large
+  9223372036854775807 + 1
+}
+
+--- ops-binary-multiline-span ---
+// Errors in multi-line binary operations point at the operator.
+// Error: 4:9-4:10 cannot add integer and string
+#let total = (
+  1 + 2,
+  3,
+).len() + "4"
+
+--- ops-assign-multiline-span ---
+#let x = 1
+// Error: 2:5-2:7 cannot add integer and array
+#{
+  x += (
+    1,
+  )
+}
+
+--- ops-join-multiline-span ---
+// Join failures point at the value that failed to join.
+#{
+  let x = 1
+  x
+  // Error: 3-9 cannot join integer with array
+  // Hint: 3-9 use `str` or `repr` to convert values, or `joined` to join them with a separator
+  // Hint: 3-9 unlike joining, the `+` operator can also add numbers
+  (2, 3)
+}
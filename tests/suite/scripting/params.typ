@@ -67,3 +67,13 @@
 --- issue-1351-parameter-dictionary ---
 // Error: 17-22 expected pattern, found string
 #let foo((test: "bar")) = {}
+
+--- params-sink-provided ---
+#let f(..args) = {
+  let size = args.named().at("size", default: auto)
+  if args.provided("size") { repr(size) } else { "omitted" }
+}
+#test(f(), "omitted")
+#test(f(size: auto), "auto")
+#test(f(size: 5), "5")
+#test(f(5), "omitted")
@@ -33,9 +33,44 @@
 #while [nope] [nope]
 
 --- while-loop-condition-always-true ---
-// Error: 8-25 condition is always true
+// Warning: 8-25 condition is always true
+// Hint: 8-25 the loop can only end if a variable in the condition changes
+// Error: 2-28 loop seems to be infinite
 #while 2 < "hello".len() {}
 
+--- while-loop-condition-always-true-strict ---
+// STRICT
+// In strict mode, the lint fails the loop right away.
+// Error: 8-13 condition is always true
+// Hint: 8-13 the loop can only end if a variable in the condition changes
+#while 1 < 2 {}
+
+--- while-loop-condition-unmodified ---
+#let i = 0
+#let n = 3
+// Warning: 8-13 condition is always true
+// Hint: 8-13 the loop can only end if a variable in the condition changes
+// Error: 2-27 loop seems to be infinite
+#while i < n { let i = 1 }
+
+--- while-loop-condition-mutating-method ---
+#let items = ()
+#while items.len() < 3 { items.push(items.len()) }
+#test(items, (0, 1, 2))
+
+--- while-loop-condition-mutated ---
+#let (a, b) = (0, 1)
+#while a < 5 { (a, b) = (b, a + b) }
+#test(a, 5)
+
+#let i = 0
+#while { i += 1; i < 3 } {}
+#test(i, 3)
+
+#let d = (count: 0)
+#while d.count < 2 { d.count += 1 }
+#test(d, (count: 2))
+
 --- while-loop-limit ---
 // Error: 2:2-2:24 loop seems to be infinite
 #let i = 1
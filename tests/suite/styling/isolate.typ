@@ -0,0 +1,67 @@
+// Test style isolation.
+
+--- isolate-basic ---
+#set text(red)
+#context test(text.fill, red)
+#isolate(context test(text.fill, black))
+#context test(text.fill, red)
+
+--- isolate-sibling ---
+#set text(red)
+#let fragment = context test(text.fill, black)
+#isolate(fragment)
+#box(context test(text.fill, red))
+
+--- isolate-nested-set ---
+// Set rules within the isolated content still apply.
+#set text(red)
+#isolate[
+  #set text(blue)
+  #context test(text.fill, blue)
+]
+
+--- isolate-document-defaults ---
+// The document's default styles are not isolated.
+#set text(size: 20pt)
+#isolate(context test(text.size, 10pt))
+
+--- isolate-explicit-property ---
+#set text(red)
+#isolate(text(green, context test(text.fill, green)))
+
+--- isolate-only ---
+#set text(red)
+#set par(leading: 1em)
+#isolate(only: (text,), {
+  context test(text.fill, black)
+  context test(par.leading, 1em)
+})
+
+--- isolate-only-multiple ---
+#set text(red)
+#set par(leading: 1em)
+#set list(indent: 2em)
+#isolate(only: (text, par), {
+  context test(text.fill, black)
+  context test(par.leading, 0.65em)
+  context test(list.indent, 2em)
+})
+
+--- isolate-nested ---
+#set text(red)
+#set par(leading: 1em)
+#isolate(only: (par,), {
+  context test(text.fill, red)
+  context test(par.leading, 0.65em)
+  isolate(context test(text.fill, black))
+})
+
+--- isolate-show-rule ---
+// Show rules are not isolated.
+#show heading: it => context test(text.fill, black)
+#set text(red)
+#isolate[= Heading]
+
+--- isolate-only-not-element ---
+// Error: 16-27 expected element
+#isolate(only: (calc.abs,))[]
@@ -94,3 +94,169 @@ A #box(rect(fill: yellow, inset: 5pt, rect())) B
 // when there are recursive show rules.
 #show enum: set text(blue)
 #enum(numbering: "(a)", [A], enum[B])
+
+--- set-multiple-targets ---
+#set (heading, figure)(numbering: "I")
+#context test(heading.numbering, "I")
+#context test(figure.numbering, "I")
+
+--- set-multiple-targets-three ---
+#set (list, enum, terms)(
+  tight: false,
+  spacing: 1em,
+)
+#context test((list.tight, enum.tight, terms.tight), (false, false, false))
+#context test((list.spacing, enum.spacing, terms.spacing), (1em, 1em, 1em))
+
+--- set-multiple-targets-field-access ---
+#set (math.vec, math.mat)(delim: "[")
+#context test((math.vec.delim, math.mat.delim), (("[", "]"), ("[", "]")))
+
+--- set-multiple-targets-if ---
+#set (list, enum)(tight: false) if false
+#context test((list.tight, enum.tight), (true, true))
+#set (list, enum)(tight: false) if true
+#context test((list.tight, enum.tight), (false, false))
+
+--- set-multiple-targets-bad-argument ---
+// Error: 22-37 unexpected argument: numbering
+// Hint: 22-37 error occurred while setting `list`
+#set (heading, list)(numbering: "1.")
+
+--- set-multiple-targets-not-element ---
+// Error: 16-24 only element functions can be used in set rules
+#set (heading, calc.abs)(numbering: "1.")
+
+--- set-multiple-targets-empty ---
+// Error: 7 expected identifier
+#set ()(numbering: "1.")
+
+--- set-group-if ---
+#set (list(tight: false), text(size: 12pt)) if false
+#context test((list.tight, text.size), (true, 10pt))
+#set (list(tight: false), text(size: 12pt)) if true
+#context test((list.tight, text.size), (false, 12pt))
+
+--- set-group-field-access ---
+#set (heading(numbering: "I"), math.vec(delim: "["))
+#context test((heading.numbering, math.vec.delim), ("I", ("[", "]")))
+
+--- set-group-bad-argument ---
+// Error: 38-53 unexpected argument: numbering
+// Hint: 38-53 error occurred while setting `list`
+#set (heading(numbering: "1."), list(numbering: "1."))
+
+--- set-group-missing-arguments ---
+// Error: 32-36 missing arguments for `list`
+// Hint: 32-36 in a group of set rules, each element needs its own arguments
+// Hint: 32-36 to leave it unchanged, remove it from the group
+#set (heading(numbering: "I"), list)
+
+--- set-if-body-lone-true ---
+#let two-column = true
+// Warning: 18-38 set rule has no effect
+// Hint: 18-38 set rules only apply until the end of the block they are in
+// Hint: 18-38 to apply it to everything after the conditional, write `set text(..) if condition` instead
+#if two-column { set text(size: 12pt) }
+#context test(text.size, 10pt)
+
+--- set-if-body-lone-false ---
+// Warning: 14-34 set rule has no effect
+// Hint: 14-34 set rules only apply until the end of the block they are in
+// Hint: 14-34 to apply it to everything after the conditional, write `set text(..) if condition` instead
+#if false [ #set text(size: 12pt) ]
+#context test(text.size, 10pt)
+
+--- set-if-body-lone-nested ---
+// Warning: 31-75 set rule has no effect
+// Hint: 31-75 set rules only apply until the end of the block they are in
+// Hint: 31-75 to apply it to everything after the conditional, write `set (..) if condition` instead
+#if true { if false {} else { set (list(tight: false), enum(tight: false)) } }
+#context test(list.tight, true)
+
+--- set-if-body-not-lone ---
+#if true {
+  set text(size: 12pt)
+  context test(text.size, 12pt)
+}
+#{
+  if true { set text(size: 12pt) }
+}
+#set text(size: 12pt) if true
+#context test(text.size, 12pt)
+
+--- set-in-loop-merged ---
+// Set rules in a loop don't wrap each iteration in a separate style wrapper.
+#let items = for i in range(3) {
+  set text(red)
+  [#i]
+}
+#test(items.has("children"), false)
+#test(items.child, [#0#1#2])
+
+--- set-in-loop-merged-joined ---
+#let styled(i) = {
+  set text(red)
+  [#i]
+}
+#let items = [a] + styled(0) + styled(1) + ([b] + styled(2) + styled(3))
+#test(items.children.len(), 4)
+#test(items.children.at(1).child, [#0#1])
+#test(items.children.at(3).child, [#2#3])
+
+--- set-in-loop-interleaved ---
+// Differently styled items must stay separate and in order.
+#let items = for i in range(4) {
+  if calc.even(i) {
+    set text(red)
+    [#i]
+  } else {
+    set text(blue)
+    [#i]
+  }
+}
+#test(items.children.len(), 4)
+#test(items.children.map(it => it.child), ([0], [1], [2], [3]))
+
+--- set-in-loop-show-not-merged ---
+#let items = for i in range(2) {
+  show "1": "one"
+  [#i]
+}
+#test(items.children.len(), 2)
+
+--- default-of ---
+#test(default-of(text, "size"), 11pt)
+#test(default-of(text, "fill"), black)
+#test(default-of(heading, "numbering"), none)
+#test(default-of(heading, "level"), auto)
+#test(default-of(heading, "offset"), 0)
+#test(default-of(par, "leading"), 0.65em)
+#test(default-of(par, "justify"), false)
+#test(default-of(strong, "delta"), 300)
+
+--- default-of-ignores-set-rules ---
+#set text(size: 14pt)
+#set strong(delta: 100)
+#test(default-of(text, "size"), 11pt)
+#test(default-of(strong, "delta"), 300)
+#context test(default-of(text, "size"), 11pt)
+
+--- defaults-of ---
+#test(defaults-of(strong), (delta: 300))
+#test(defaults-of(heading).numbering, none)
+#test(defaults-of(heading).keys().contains("body"), false)
+
+--- default-of-unknown-field ---
+// Error: 21-27 strong has no settable field `size`
+// Hint: 21-27 settable fields are `delta`
+#default-of(strong, "size")
+
+--- default-of-required-field ---
+// Error: 21-27 strong has no settable field `body`
+// Hint: 21-27 settable fields are `delta`
+#default-of(strong, "body")
+
+--- default-of-not-element ---
+// Error: 13-21 expected element
+#default-of(calc.abs, "x")
@@ -25,6 +25,7 @@ Where is
 my heading?
 
 --- show-selector-realistic ---
+// CHUNKED
 // Test integrated example.
 #show heading: it => block({
   set text(10pt)
@@ -100,7 +101,7 @@ Hey
 #show red: []
 
 --- show-selector-in-expression ---
-// Error: 7-25 show is only allowed directly in code and content blocks
+// Error: 3-25 cannot add integer and styles
 #(1 + show heading: none)
 
 --- show-bare-basic ---
@@ -132,8 +133,8 @@ Forest
 Ignored
 
 --- show-bare-in-expression ---
-// Error: 4-19 show is only allowed directly in code and content blocks
-#((show: body => 2) * body)
+// Error: 3-24 cannot multiply styles with integer
+#((show: body => 2) * 2)
 
 --- show-bare-missing-colon-closure ---
 // Error: 6 expected colon
@@ -149,6 +150,7 @@ Ignored
 = Heading
 
 --- show-multiple-rules ---
+// CHUNKED
 // Test more recipes down the chain.
 #show list: scale.with(origin: left, x: 80%)
 #show heading: []
@@ -159,6 +161,7 @@ Ignored
 = Nope
 
 --- show-rule-in-function ---
+// CHUNKED
 // Test show rule in function.
 #let starwars(body) = {
   show list: it => block({
@@ -260,3 +263,88 @@ I am *strong*, I am _emphasized_, and I am #[special<special>].
 
 = Hello
 *strong*
+
+--- show-label-after-labelled-content ---
+#metadata(none) <intro>
+// Warning: 2-32 show rule does not apply to earlier content labelled <intro>
+// Hint: 2-32 move the show rule before the labelled content
+// Hint: 2-32 or use `show: show-everywhere` to apply it to the whole file
+#show <intro>: it => panic("x")
+
+--- show-everywhere-label ---
+#show: show-everywhere
+#metadata(none) <intro>
+// Error: 22-38 panicked with: "hoisted"
+#show <intro>: it => panic("hoisted")
+
+--- show-everywhere-in-function ---
+// Show rules in functions can't be hoisted out of the function.
+#let f() = [#show: show-everywhere; #show <intro>: it => panic("x")]
+#metadata(none) <intro>
+#f()
+
+--- show-function-index ---
+#let log = state("log", ())
+#show emph: (it, info) => log.update(l => l + (calc.even(info.index),))
+#{
+  emph[a]
+  emph[b]
+  emph[c]
+}
+#context test(log.final(), (true, false, true))
+
+--- show-function-index-single-param ---
+#let log = state("log", ())
+#show emph: (it, ..args) => log.update(l => l + (args.pos().len(),))
+#show strong: it => log.update(l => l + (it.body.text,))
+#{
+  emph[a]
+  strong[b]
+  emph[c]
+}
+#context test(log.final(), (0, "b", 0))
+
+--- show-function-index-independent ---
+#let log = state("log", ())
+#show emph: (it, info) => log.update(l => l + ("emph " + str(info.index),)) + it.body
+#show strong: (it, info) => log.update(l => l + ("strong " + str(info.index),))
+#{
+  emph(strong[a])
+  strong[b]
+  emph[]
+  emph(strong[c])
+}
+#context test(log.final(), (
+  "emph 0", "strong 0", "strong 1", "emph 1", "emph 2", "strong 2",
+))
+
+--- show-function-index-regex ---
+#let log = state("log", ())
+#show regex("."): (it, info) => log.update(l => l + ((it.text, info.index),))
+#{ "ab" }
+#context test(log.final(), (("a", 0), ("b", 1)))
+
+--- show-in-expression-value ---
+#let rule = show heading: emph
+#test(str(type(rule)), "styles")
+
+--- show-scoped-selector ---
+#let shout(body) = scoped(show "a": upper, body)
+#let shouted = shout[a]
+#test(shouted.child, [a])
+#test(shouted.has("styles"), true)
+
+--- show-scoped-bare ---
+#test(scoped(show: strong, [a]), strong[a])
+#test(scoped(body => emph(body), [a]), emph[a])
+#test(scoped([b], [a]), [b])
+
+--- show-scoped-from-function ---
+// The rule doesn't leak out of the scoped content.
+#let rule(suffix) = show: it => it + suffix
+#let items = scoped(rule[!], [a]) + [b]
+#test(items, [a] + [!] + [b])
+
+--- show-scoped-bad-type ---
+// Error: 9-10 expected styles, content, or function, found integer
+#scoped(1, [a])
@@ -35,4 +35,74 @@
 
 --- symbol-unknown-modifier ---
 // Error: 13-20 unknown symbol modifier
+// Hint: 13-20 available modifiers: grin, angry, red, anguish, astonish, bandage, beam, blank, clown, cold, concern, cool, cover, cowboy, cry, devil, smile, frown, diagonal, disguise, dizzy, dotted, down, sweat, drool, explode, eyeroll, friendly, fear, fever, flush, slight, frust, goofy, halo, happy, heart, hearts, heat, hug, inv, joy, kiss, blush, lick, lie, mask, meh, melt, money, monocle, nausea, nerd, neutral, open, party, peek, plead, relief, rofl, sad, salute, shock, shush, skeptic, sleep, sleepy, tear, smirk, sneeze, speak, not, squint, stars, straight, suffer, surprise, symbols, withheld, teeth, think, tired, tongue, wink, triumph, unhappy, vomit, weary, woozy, worry, wow, yawn, zip
 #emoji.face.garbage
+
+--- symbol-unknown-modifier-chained ---
+// Error: 21-27 unknown symbol modifier
+// Hint: 21-27 available modifiers: bar, long, not, l
+#sym.arrow.r.double.wiggly
+
+--- symbol-unknown-modifier-exhausted ---
+#let envelope = symbol("🖂", ("stamped", "🖃"), ("stamped.pen", "🖆"))
+// Error: 23-26 unknown symbol modifier
+// Hint: 23-26 no further modifiers are available
+#envelope.stamped.pen.fly
+
+--- symbol-unknown-modifier-math ---
+// Error: 17-23 unknown symbol modifier
+// Hint: 17-23 available modifiers: bar, long, not, l
+$arrow.r.double.wiggly$
+
+--- symbol-modifier-order ---
+#test(str(sym.arrow.double.r), str(sym.arrow.r.double))
+#test(str(sym.arrow.long.double.r.bar), str(sym.arrow.r.double.long.bar))
+#test(sym.arrow.double.has("r"), true)
+#test(sym.arrow.has("double.long.r"), sym.arrow.has("r.long.double"))
+
+--- symbol-has ---
+#test(sym.arrow.has("r"), true)
+#test(sym.arrow.has("r.double"), true)
+#test(sym.arrow.r.has("double"), true)
+#test(sym.arrow.has(""), true)
+#test(sym.arrow.has("wiggly"), false)
+#test(sym.arrow.r.has("wiggly"), false)
+#test(sym.arrow.r.has("r.wiggly"), false)
+#test(symbol("x").has("r"), false)
+
+--- symbol-variants ---
+#let variants = sym.arrow.r.variants()
+#test(variants.first(), (name: "r", codepoint: "→"))
+#test(variants.at(1), (name: "r.long.bar", codepoint: "⟼"))
+#test(variants.all(v => "r" in v.name.split(".")), true)
+#test(sym.arrow.variants().len() > variants.len(), true)
+
+--- symbol-variants-constructor ---
+#let envelope = symbol("🖂", ("stamped", "🖃"), ("stamped.pen", "🖆"))
+#test(envelope.variants(), (
+  (name: "", codepoint: "🖂"),
+  (name: "stamped", codepoint: "🖃"),
+  (name: "stamped.pen", codepoint: "🖆"),
+))
+#test(envelope.pen.variants().map(v => v.name), ("stamped.pen",))
+#test(symbol("x").variants(), ((name: "", codepoint: "x"),))
+
+--- symbol-get ---
+#test(sym.arrow.get("r"), sym.arrow.r)
+#test(sym.arrow.get("r.double"), sym.arrow.r.double)
+#test(sym.arrow.get("double.r"), sym.arrow.double.r)
+#test(sym.arrow.r.get("double"), sym.arrow.r.double)
+#test(sym.arrow.get(""), sym.arrow)
+#for variant in sym.arrow.variants() {
+  test(str(sym.arrow.get(variant.name)), variant.codepoint)
+}
+
+--- symbol-get-unknown ---
+// Error: 2-26 symbol has no variant "r.doble"
+// Hint: 2-26 did you mean `r.double`?
+#sym.arrow.get("r.doble")
+
+--- symbol-get-unknown-modified ---
+// Error: 2-26 symbol has no variant "doble"
+// Hint: 2-26 did you mean `double`?
+#sym.arrow.r.get("doble")
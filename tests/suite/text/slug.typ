@@ -0,0 +1,110 @@
+// Test slugs and unique slug tracking.
+
+--- slug-basic ---
+#test(slug("Hello World"), "hello-world")
+#test(slug("  Hello,   World!  "), "hello-world")
+#test(slug("Hello World", lowercase: false), "Hello-World")
+#test(slug("Hello World", separator: "_"), "hello_world")
+#test(slug("Hello World", separator: ""), "helloworld")
+#test(slug("Chapter 1.2.3"), "chapter-1-2-3")
+#test(slug(""), "")
+#test(slug("!?"), "")
+
+--- slug-content ---
+#test(slug[= Über *uns*], "uber-uns")
+#test(slug[Some #emph[emphasized] text], "some-emphasized-text")
+
+--- slug-diacritics ---
+#test(slug("Héllo Wörld"), "hello-world")
+#test(slug("Ærøskøbing"), "aeroskobing")
+#test(slug("Ærøskøbing", lowercase: false), "AEroskobing")
+#test(slug("Straße"), "strasse")
+#test(slug("Łódź Żółć"), "lodz-zolc")
+#test(slug("Œuvre Þing"), "oeuvre-thing")
+#test(slug("Ștefan Țară"), "stefan-tara")
+#test(slug("Crème Brûlée", lowercase: false), "Creme-Brulee")
+
+--- slug-combining-marks ---
+// Decomposed letters are reduced to their base letter.
+#test(slug("Cafe\u{301} nai\u{308}ve"), "cafe-naive")
+
+--- slug-emoji ---
+#test(slug("I ❤️ Typst 🎉"), "i-typst")
+#test(slug("🎉🎉🎉"), "")
+
+--- slug-other-scripts ---
+#test(slug("日本語 ガイド"), "日本語-ガイド")
+#test(slug("Привет Мир"), "привет-мир")
+#test(slug("Привет Мир", lowercase: false), "Привет-Мир")
+#test(slug("日本語 ガイド", ascii: true), "")
+#test(slug("Typst 入門 guide", ascii: true), "typst-guide")
+#test(slug("a日b", ascii: true), "a-b")
+
+--- slug-max-length ---
+#test(slug("a" * 100).len(), 64)
+#test(slug("hello world", max-length: 5), "hello")
+#test(slug("hello world", max-length: 6), "hello")
+#test(slug("hello world", max-length: 7), "hello-w")
+#test(slug("hello world", max-length: none), "hello-world")
+#test(slug("hello world", max-length: 0), "")
+
+--- slug-max-length-grapheme ---
+// Truncation never splits a grapheme cluster.
+#test(slug("α\u{301}β", max-length: 1), "α\u{301}")
+#test(slug("α\u{301}β", max-length: 2), "α\u{301}β")
+
+--- slug-max-length-separator ---
+#test(slug("ab cd", separator: "--", max-length: 3), "ab")
+#test(slug("ab cd", separator: "--", max-length: 4), "ab")
+
+--- slug-bad-input ---
+// Error: 7-9 expected string or content, found integer
+#slug(12)
+
+--- unique-slugs-basic ---
+#let slugs = unique-slugs()
+#test(slugs.len(), 0)
+#test(slugs.issue("intro"), "intro")
+#test(slugs.issue("intro"), "intro-2")
+#test(slugs.issue("intro"), "intro-3")
+#test(slugs.issue("outro"), "outro")
+#test(slugs.len(), 4)
+#test(slugs.contains("intro-2"), true)
+#test(slugs.contains("intro-4"), false)
+#test(repr(slugs), "unique-slugs(len: 4)")
+
+--- unique-slugs-taken-suffix ---
+// A suffix is skipped if the suffixed slug was already issued.
+#let slugs = unique-slugs()
+#test(slugs.issue("a-2"), "a-2")
+#test(slugs.issue("a"), "a")
+#test(slugs.issue("a"), "a-3")
+#test(slugs.issue("a-2"), "a-2-2")
+
+--- unique-slugs-separator ---
+#let slugs = unique-slugs(separator: "_")
+#slugs.issue("intro")
+#test(slugs.issue("intro"), "intro_2")
+
+--- unique-slugs-headings ---
+#let slugs = unique-slugs()
+#let ids = ()
+#for title in ("Introduction", "Usage", "Introduction", "Usage!") {
+  ids.push(slugs.issue(slug(title)))
+}
+#test(ids, ("introduction", "usage", "introduction-2", "usage-2"))
+
+--- unique-slugs-copy-is-independent ---
+#let a = unique-slugs()
+#a.issue("x")
+#let b = a
+#test(b.issue("x"), "x-2")
+#test(a.issue("x"), "x-2")
+
+--- unique-slugs-captured ---
+#let slugs = unique-slugs()
+#let f() = {
+  // Error: 3-8 variables from outside the function are read-only and cannot be modified
+  slugs.issue("a")
+}
+#f()
@@ -77,10 +77,12 @@ A #box(image("/assets/images/tiger.jpg", height: 1cm, width: 80%)) B
 #image("path/does/not/exist")
 
 --- image-bad-format ---
+// DRY-RUN
 // Error: 2-22 unknown image format
 #image("./image.typ")
 
 --- image-bad-svg ---
+// DRY-RUN
 // Error: 2-33 failed to parse SVG (found closing tag 'g' instead of 'style' in line 4)
 #image("/assets/images/bad.svg")
 
@@ -104,6 +106,11 @@ A #box(image("/assets/images/tiger.jpg", height: 1cm, width: 80%)) B
 // Error: 2-91 failed to decode image (Format error decoding Png: Invalid PNG signature.)
 #image.decode(read("/assets/images/tiger.jpg", encoding: none), format: "png", width: 80%)
 
+--- image-specify-wrong-format ---
+// DRY-RUN
+// Error: 2-54 failed to decode image (Format error decoding Png: Invalid PNG signature.)
+#image("/tests/suite/loading/csv.typ", format: "png")
+
 --- issue-870-image-rotation ---
 // Ensure that EXIF rotation is applied.
 // https://github.com/image-rs/image/issues/1045